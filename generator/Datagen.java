@@ -1,3 +1,4 @@
+import com.mojang.serialization.DynamicOps;
 import com.mojang.serialization.Lifecycle;
 import net.minecraft.SharedConstants;
 import net.minecraft.commands.Commands;
@@ -8,8 +9,12 @@ import net.minecraft.core.HolderSet;
 import net.minecraft.core.IdMap;
 import net.minecraft.core.MappedRegistry;
 import net.minecraft.core.Registry;
+import net.minecraft.core.RegistryAccess;
 import net.minecraft.core.registries.BuiltInRegistries;
 import net.minecraft.core.registries.Registries;
+import net.minecraft.nbt.NbtIo;
+import net.minecraft.nbt.NbtOps;
+import net.minecraft.nbt.Tag;
 import net.minecraft.network.protocol.PacketType;
 import net.minecraft.network.protocol.configuration.ConfigurationProtocols;
 import net.minecraft.network.protocol.game.GameProtocols;
@@ -19,6 +24,7 @@ import net.minecraft.network.protocol.status.StatusProtocols;
 import net.minecraft.server.WorldLoader;
 import net.minecraft.server.WorldStem;
 import net.minecraft.server.dedicated.DedicatedServerProperties;
+import net.minecraft.resources.RegistryDataLoader;
 import net.minecraft.server.packs.repository.ServerPacksSource;
 import net.minecraft.server.permissions.LevelBasedPermissionSet;
 import net.minecraft.util.Util;
@@ -47,6 +53,8 @@ import net.minecraft.world.level.validation.ContentValidationException;
 import net.minecraft.world.level.validation.DirectoryValidator;
 import net.minecraft.world.phys.AABB;
 
+import java.io.ByteArrayOutputStream;
+import java.io.DataOutputStream;
 import java.io.IOException;
 import java.nio.file.Files;
 import java.nio.file.Path;
@@ -151,23 +159,47 @@ public final class Datagen {
         item(b);
         Files.writeString(Path.of("item.txt"), b.toString());
 
-        // var access = worldStem.registries().compositeAccess();
+        var access = worldStem.registries().compositeAccess();
 
-        // b.setLength(0);
-        // tags(b, access.lookupOrThrow(Registries.BLOCK));
-        // Files.writeString(Path.of("block_tags.txt"), b.toString());
+        b.setLength(0);
+        tags(b, access.lookupOrThrow(Registries.BLOCK));
+        tags(b, access.lookupOrThrow(Registries.ITEM));
+        tags(b, access.lookupOrThrow(Registries.ENTITY_TYPE));
+        tags(b, access.lookupOrThrow(Registries.FLUID));
+        Files.writeString(Path.of("tags.txt"), b.toString());
 
-        // b.setLength(0);
-        // tags(b, access.lookupOrThrow(Registries.ITEM));
-        // Files.writeString(Path.of("item_tags.txt"), b.toString());
+        b.setLength(0);
+        registry_data(b, access);
+        Files.writeString(Path.of("registry_data.txt"), b.toString());
+    }
 
-        // b.setLength(0);
-        // tags(b, access.lookupOrThrow(Registries.ENTITY_TYPE));
-        // Files.writeString(Path.of("entity_tags.txt"), b.toString());
+    private static void registry_data(final StringBuilder b, final RegistryAccess access) throws IOException {
+        var ops = access.createSerializationContext(NbtOps.INSTANCE);
+        for (var data : RegistryDataLoader.SYNCHRONIZED_REGISTRIES) {
+            write_registry_data(b, ops, access, data);
+        }
+    }
 
-        // b.setLength(0);
-        // tags(b, access.lookupOrThrow(Registries.GAME_EVENT));
-        // Files.writeString(Path.of("game_event_tags.txt"), b.toString());
+    private static <T> void write_registry_data(
+        final StringBuilder b,
+        final DynamicOps<Tag> ops,
+        final RegistryAccess access,
+        final RegistryDataLoader.RegistryData<T> data
+    ) throws IOException {
+        var registry = access.lookupOrThrow(data.key());
+        writeHead(b, data.key().identifier().getPath(), "nbt", registry.size());
+        var out = new ByteArrayOutputStream();
+        for (final T t : registry) {
+            b.append(Objects.requireNonNull(registry.getKey(t)));
+            b.append(NL);
+            out.reset();
+            NbtIo.writeAnyTag(data.elementCodec().encodeStart(ops, t).getOrThrow(), new DataOutputStream(out));
+            for (final byte x : out.toByteArray()) {
+                b.append(ih(x & 0xff));
+                b.append(SP);
+            }
+            b.append(NL);
+        }
     }
 
     private static <E> void tags(final StringBuilder b, final Registry<E> registryLookup) {
@@ -175,6 +207,7 @@ public final class Datagen {
         @SuppressWarnings("unchecked")
         HolderSet.Named<E>[] a = registryLookup.listTags().toArray(HolderSet.Named[]::new);
         Arrays.sort(a, Comparator.comparing(j -> j.key().location().getPath()));
+        writeHead(b, registryLookup.key().identifier().getPath(), "tag", a.length);
         for (final HolderSet.Named<E> tag : a) {
             b.append(tag.key().location().getPath());
             b.append(NL);
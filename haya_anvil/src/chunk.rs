@@ -0,0 +1,664 @@
+//! The `sections` format of chunk roots, as saved since 1.18.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+use haya_ident::{Ident, MINECRAFT, parse_ident};
+use haya_nbt::{CompoundTag, ListTag, StringTag, Tag};
+use haya_palette::{BiomeContainer, BlockContainer, LIGHT_LEN};
+use minecraft_data::{
+    block, block_entity_type, block_state, block_state_property_key, block_state_property_value,
+    block_state_props,
+};
+use mser::{Error, ErrorKind};
+
+const MIN_BLOCK_BITS: usize = 4;
+
+/// A chunk decoded from its root compound.
+#[derive(Clone)]
+pub struct Chunk {
+    /// `DataVersion`, the data version of the game that saved the chunk.
+    pub data_version: i32,
+    pub x: i32,
+    pub z: i32,
+    /// `yPos`, the lowest section of the world.
+    pub y: Option<i32>,
+    pub sections: Vec<Section>,
+    /// Long arrays keyed by the names of `HeightmapType`.
+    pub heightmaps: Vec<(StringTag, Vec<i64>)>,
+    pub block_entities: Vec<BlockEntity>,
+    /// Entries of the root not listed above, such as `Status` and ticks.
+    pub rest: CompoundTag,
+    /// Biome names missing from the registry, which were read as plains.
+    /// Ignored when encoding.
+    pub unknown_biomes: Vec<StringTag>,
+}
+
+#[derive(Clone)]
+pub struct Section {
+    pub y: i8,
+    pub block_states: BlockContainer,
+    pub biomes: BiomeContainer,
+    pub block_light: Option<Box<[u8; LIGHT_LEN]>>,
+    pub sky_light: Option<Box<[u8; LIGHT_LEN]>>,
+}
+
+#[derive(Clone)]
+pub struct BlockEntity {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub ty: block_entity_type,
+    /// The compound without `id`, `x`, `y` and `z`.
+    pub data: CompoundTag,
+}
+
+impl Chunk {
+    /// Decodes a chunk root. `biomes` is the biome registry in ID order.
+    ///
+    /// Unknown blocks become air, unknown biomes become plains and unknown
+    /// block entities are dropped, as in vanilla. The names of unknown
+    /// biomes are kept in `unknown_biomes`. Sections without `block_states`
+    /// or `biomes` are filled with air and the first biome.
+    pub fn from_nbt(mut root: CompoundTag, biomes: &[Ident]) -> Result<Self, Error> {
+        let data_version = match root.find_remove("DataVersion") {
+            Some(Tag::Int(x)) => x,
+            _ => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let x = int(root.find_remove("xPos"))?;
+        let z = int(root.find_remove("zPos"))?;
+        let y = match root.find_remove("yPos") {
+            Some(tag) => Some(int(Some(tag))?),
+            None => None,
+        };
+        let mut unknown_biomes = Vec::new();
+        let sections = match root.find_remove("sections") {
+            Some(Tag::List(ListTag::Compound(list))) => list
+                .into_iter()
+                .map(|section| Section::from_nbt(section, biomes, &mut unknown_biomes))
+                .collect::<Result<_, _>>()?,
+            Some(Tag::List(ListTag::None)) | None => Vec::new(),
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let heightmaps = match root.find_remove("Heightmaps") {
+            Some(Tag::Compound(maps)) => maps
+                .into_inner()
+                .into_iter()
+                .filter_map(|(k, v)| match v {
+                    Tag::LongArray(data) => Some((k, data)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let block_entities = match root.find_remove("block_entities") {
+            Some(Tag::List(ListTag::Compound(list))) => {
+                let mut out = Vec::with_capacity(list.len());
+                for data in list {
+                    if let Some(entity) = BlockEntity::from_nbt(data)? {
+                        out.push(entity);
+                    }
+                }
+                out
+            }
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            data_version,
+            x,
+            z,
+            y,
+            sections,
+            heightmaps,
+            block_entities,
+            rest: root,
+            unknown_biomes,
+        })
+    }
+
+    /// Encodes the chunk root. `biomes` is the biome registry in ID order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a section holds a biome ID outside of `biomes`.
+    pub fn to_nbt(&self, biomes: &[Ident]) -> CompoundTag {
+        let mut root = CompoundTag::with_capacity(self.rest.len() + 7);
+        root.push(
+            StringTag::from_utf8("DataVersion"),
+            Tag::Int(self.data_version),
+        );
+        root.push(StringTag::from_utf8("xPos"), Tag::Int(self.x));
+        root.push(StringTag::from_utf8("zPos"), Tag::Int(self.z));
+        if let Some(y) = self.y {
+            root.push(StringTag::from_utf8("yPos"), Tag::Int(y));
+        }
+        for (k, v) in self.rest.iter() {
+            root.push(k.clone(), v.clone());
+        }
+        let sections = self.sections.iter().map(|x| x.to_nbt(biomes)).collect();
+        root.push(
+            StringTag::from_utf8("sections"),
+            Tag::List(ListTag::Compound(sections)),
+        );
+        let mut maps = CompoundTag::with_capacity(self.heightmaps.len());
+        for (k, v) in &self.heightmaps {
+            maps.push(k.clone(), Tag::LongArray(v.clone()));
+        }
+        root.push(StringTag::from_utf8("Heightmaps"), Tag::Compound(maps));
+        let entities = self
+            .block_entities
+            .iter()
+            .map(BlockEntity::to_nbt)
+            .collect();
+        root.push(
+            StringTag::from_utf8("block_entities"),
+            Tag::List(ListTag::Compound(entities)),
+        );
+        root
+    }
+
+    /// The heightmap named `name`, such as `MOTION_BLOCKING`.
+    pub fn heightmap(&self, name: &str) -> Option<&[i64]> {
+        self.heightmaps
+            .iter()
+            .find(|(k, _)| &**k == name)
+            .map(|(_, v)| &v[..])
+    }
+}
+
+impl Section {
+    /// Biome names missing from `biomes` are read as plains and pushed to
+    /// `unknown_biomes`.
+    pub fn from_nbt(
+        mut tag: CompoundTag,
+        biomes: &[Ident],
+        unknown_biomes: &mut Vec<StringTag>,
+    ) -> Result<Self, Error> {
+        let y = match tag.find("Y") {
+            Some(Tag::Byte(x)) => *x,
+            _ => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let block_states = match tag.find_remove("block_states") {
+            Some(Tag::Compound(x)) => read_blocks(x)?,
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+            None => BlockContainer::new(block::air.state_default().id()),
+        };
+        let biome_container = match tag.find_remove("biomes") {
+            Some(Tag::Compound(x)) => read_biomes(x, biomes, unknown_biomes)?,
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+            None => BiomeContainer::new(0),
+        };
+        Ok(Self {
+            y,
+            block_states,
+            biomes: biome_container,
+            block_light: light(tag.find_remove("BlockLight"))?,
+            sky_light: light(tag.find_remove("SkyLight"))?,
+        })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the section holds a biome ID outside of `biomes`.
+    pub fn to_nbt(&self, biomes: &[Ident]) -> CompoundTag {
+        let mut tag = CompoundTag::with_capacity(5);
+        tag.push(StringTag::from_utf8("Y"), Tag::Byte(self.y));
+
+        let (ids, data) = write_container(&self.block_states, MIN_BLOCK_BITS);
+        let palette = ids
+            .into_iter()
+            .map(|id| match block_state::new(id) {
+                Some(state) => block_to_nbt(state),
+                None => block_to_nbt(block::air.state_default()),
+            })
+            .collect();
+        tag.push(
+            StringTag::from_utf8("block_states"),
+            Tag::Compound(container(ListTag::Compound(palette), data)),
+        );
+
+        let (ids, biome_data) = write_container(&self.biomes, 1);
+        let names = ids
+            .into_iter()
+            .map(|id| ident_to_nbt(biomes[id as usize]))
+            .collect();
+        tag.push(
+            StringTag::from_utf8("biomes"),
+            Tag::Compound(container(ListTag::String(names), biome_data)),
+        );
+
+        for (name, light) in [
+            ("BlockLight", &self.block_light),
+            ("SkyLight", &self.sky_light),
+        ] {
+            if let Some(x) = light {
+                let bytes = x.iter().map(|&b| b as i8).collect();
+                tag.push(StringTag::from_utf8(name), Tag::ByteArray(bytes));
+            }
+        }
+        tag
+    }
+}
+
+impl BlockEntity {
+    fn from_nbt(mut data: CompoundTag) -> Result<Option<Self>, Error> {
+        let kind = match data.find_remove("id") {
+            Some(Tag::String(id)) => match parse_ident(id.as_bytes()) {
+                Some(x) if x.namespace().is_none() => x.path().parse::<block_entity_type>().ok(),
+                _ => None,
+            },
+            _ => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let x = int(data.find_remove("x"))?;
+        let y = int(data.find_remove("y"))?;
+        let z = int(data.find_remove("z"))?;
+        Ok(kind.map(|ty| Self { x, y, z, ty, data }))
+    }
+
+    fn to_nbt(&self) -> CompoundTag {
+        let mut tag = CompoundTag::with_capacity(self.data.len() + 4);
+        tag.push(
+            StringTag::from_utf8("id"),
+            Tag::String(StringTag::from_owned(
+                format!("{MINECRAFT}:{}", self.ty.name()).into_boxed_str(),
+            )),
+        );
+        tag.push(StringTag::from_utf8("x"), Tag::Int(self.x));
+        tag.push(StringTag::from_utf8("y"), Tag::Int(self.y));
+        tag.push(StringTag::from_utf8("z"), Tag::Int(self.z));
+        for (k, v) in self.data.iter() {
+            tag.push(k.clone(), v.clone());
+        }
+        tag
+    }
+}
+
+fn int(tag: Option<Tag>) -> Result<i32, Error> {
+    match tag {
+        Some(Tag::Int(x)) => Ok(x),
+        _ => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
+
+fn light(tag: Option<Tag>) -> Result<Option<Box<[u8; LIGHT_LEN]>>, Error> {
+    match tag {
+        Some(Tag::ByteArray(x)) if x.len() == LIGHT_LEN => {
+            let mut out = Box::new([0; LIGHT_LEN]);
+            for (a, b) in out.iter_mut().zip(x) {
+                *a = b as u8;
+            }
+            Ok(Some(out))
+        }
+        None => Ok(None),
+        Some(_) => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
+
+fn read_blocks(mut tag: CompoundTag) -> Result<BlockContainer, Error> {
+    let entries = match tag.find_remove("palette") {
+        Some(Tag::List(ListTag::Compound(x))) if !x.is_empty() => x,
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    let palette = entries
+        .iter()
+        .map(|x| block_from_nbt(x).map(block_state::id))
+        .collect::<Result<Vec<_>, _>>()?;
+    read_container(&palette, tag.find("data"), MIN_BLOCK_BITS)
+}
+
+fn read_biomes(
+    mut tag: CompoundTag,
+    biomes: &[Ident],
+    unknown: &mut Vec<StringTag>,
+) -> Result<BiomeContainer, Error> {
+    let names = match tag.find_remove("palette") {
+        Some(Tag::List(ListTag::String(x))) if !x.is_empty() => x,
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    let plains = biomes
+        .iter()
+        .position(|x| Some(*x) == parse_ident(b"plains"));
+    let mut palette = Vec::with_capacity(names.len());
+    for name in names {
+        let ident = parse_ident(name.as_bytes());
+        let id = match biomes.iter().position(|x| Some(*x) == ident) {
+            Some(id) => id,
+            None => {
+                unknown.push(name);
+                plains.ok_or_else(|| Error::new(ErrorKind::Invalid))?
+            }
+        };
+        palette.push(id as u16);
+    }
+    read_container(&palette, tag.find("data"), 1)
+}
+
+/// Resolves a `{Name, Properties}` compound. Properties the block does not
+/// have are ignored and missing ones take their default value.
+fn block_from_nbt(tag: &CompoundTag) -> Result<block_state, Error> {
+    let name = match tag.find("Name") {
+        Some(Tag::String(x)) => x,
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    let kind =
+        match parse_ident(name.as_bytes()).map(|x| (x.namespace().is_none(), x.path().parse())) {
+            Some((true, Ok(x))) => x,
+            _ => return Ok(block::air.state_default()),
+        };
+    let mut buf = [(
+        block_state_property_key::age,
+        block_state_property_value::d_0,
+    ); 16];
+    let mut len = 0;
+    if let Some(Tag::Compound(props)) = tag.find("Properties") {
+        for (k, tag_value) in props.iter() {
+            let Tag::String(v) = tag_value else {
+                continue;
+            };
+            if let (Ok(key), Ok(val), Some(slot)) = (k.parse(), v.parse(), buf.get_mut(len)) {
+                *slot = (key, val);
+                len += 1;
+            }
+        }
+    }
+    Ok(block_state::parse(kind, &mut buf[..len]))
+}
+
+fn block_to_nbt(state: block_state) -> CompoundTag {
+    let mut tag = CompoundTag::with_capacity(2);
+    tag.push(
+        StringTag::from_utf8("Name"),
+        Tag::String(StringTag::from_owned(
+            format!("{MINECRAFT}:{}", state.to_block().name()).into_boxed_str(),
+        )),
+    );
+    let mut buf = [(
+        block_state_property_key::age,
+        block_state_property_value::d_0,
+    ); 16];
+    let props = block_state_props(state, &mut buf);
+    if !props.is_empty() {
+        let mut map = CompoundTag::with_capacity(props.len());
+        for (k, v) in props {
+            map.push(
+                StringTag::from_utf8(k.name()),
+                Tag::String(StringTag::from_utf8(v.name())),
+            );
+        }
+        tag.push(StringTag::from_utf8("Properties"), Tag::Compound(map));
+    }
+    tag
+}
+
+fn ident_to_nbt(ident: Ident) -> StringTag {
+    let namespace = ident.namespace().unwrap_or(MINECRAFT);
+    StringTag::from_owned(format!("{namespace}:{}", ident.path()).into_boxed_str())
+}
+
+fn container(palette: ListTag, data: Vec<i64>) -> CompoundTag {
+    let mut tag = CompoundTag::with_capacity(2);
+    tag.push(StringTag::from_utf8("palette"), Tag::List(palette));
+    if !data.is_empty() {
+        tag.push(StringTag::from_utf8("data"), Tag::LongArray(data));
+    }
+    tag
+}
+
+/// Bits per entry of a saved palette with `len` entries.
+const fn bits(len: usize, min: usize) -> usize {
+    if len <= 1 {
+        0
+    } else {
+        let b = (usize::BITS - (len - 1).leading_zeros()) as usize;
+        if b < min { min } else { b }
+    }
+}
+
+/// Unpacks saved entries, which unlike in the network format never span
+/// two longs.
+fn read_container<const P: usize, const L: usize, const H: usize>(
+    palette: &[u16],
+    tag: Option<&Tag>,
+    min_bits: usize,
+) -> Result<haya_palette::PalettedContainer<u16, P, L, H>, Error> {
+    let mut out = haya_palette::PalettedContainer::new(palette[0]);
+    let b = bits(palette.len(), min_bits);
+    if b == 0 {
+        return Ok(out);
+    }
+    let data = match tag {
+        Some(Tag::LongArray(x)) => x,
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    let per_long = 64 / b;
+    if data.len() != L.div_ceil(per_long) {
+        return Err(Error::new(ErrorKind::Invalid));
+    }
+    let mask = (1u64 << b) - 1;
+    for index in 0..L {
+        let long = data[index / per_long] as u64;
+        let entry = (long >> (index % per_long * b)) & mask;
+        match palette.get(entry as usize) {
+            Some(&x) => {
+                out.set(index, x);
+            }
+            None => return Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+    Ok(out)
+}
+
+/// The sorted palette and packed data of a container.
+fn write_container<const P: usize, const L: usize, const H: usize>(
+    container: &haya_palette::PalettedContainer<u16, P, L, H>,
+    min_bits: usize,
+) -> (Vec<u16>, Vec<i64>) {
+    let values = (0..L)
+        .map(|i| unsafe { *container.get_unchecked(i) })
+        .collect::<Vec<_>>();
+    let mut palette = values.clone();
+    palette.sort_unstable();
+    palette.dedup();
+    let b = bits(palette.len(), min_bits);
+    if b == 0 {
+        return (palette, Vec::new());
+    }
+    let per_long = 64 / b;
+    let mut data = alloc::vec![0i64; L.div_ceil(per_long)];
+    for (index, value) in values.iter().enumerate() {
+        let entry = palette.binary_search(value).unwrap_or(0) as u64;
+        data[index / per_long] |= (entry << (index % per_long * b)) as i64;
+    }
+    (palette, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(name: &str, props: &[(&str, &str)]) -> block_state {
+        let mut buf = [(
+            block_state_property_key::age,
+            block_state_property_value::d_0,
+        ); 16];
+        for (slot, (k, v)) in buf.iter_mut().zip(props) {
+            *slot = (k.parse().unwrap(), v.parse().unwrap());
+        }
+        block_state::parse(name.parse().unwrap(), &mut buf[..props.len()])
+    }
+
+    #[test]
+    fn test_chunk_fixture() {
+        let region = crate::Region::read(include_bytes!("../testdata/r.0.0.mca")).unwrap();
+        assert_eq!(region.iter().count(), 1);
+        let chunk = region.get(1, 2).unwrap();
+        assert_eq!(chunk.compression, crate::Compression::Zlib);
+        let root = chunk.decode().unwrap().1;
+        let read = Chunk::from_nbt(root, &[Ident::new("plains").unwrap()]).unwrap();
+        assert_eq!(
+            (read.data_version, read.x, read.z, read.y),
+            (4671, 1, 2, Some(-4))
+        );
+        assert_eq!(
+            read.heightmap("MOTION_BLOCKING").map(<[i64]>::len),
+            Some(37)
+        );
+        assert!(read.block_entities.is_empty());
+        let section = &read.sections[0];
+        let bedrock = block::bedrock.state_default().id();
+        unsafe {
+            assert_eq!(*section.block_states.get_unchecked(0), bedrock);
+            assert_eq!(
+                *section.block_states.get_unchecked(3),
+                state("oak_log", &[("axis", "z")]).id()
+            );
+            assert_eq!(*section.block_states.get_unchecked(4095), bedrock);
+            assert_eq!(*section.biomes.get_unchecked(0), 0);
+        }
+    }
+
+    #[test]
+    fn test_chunk() {
+        let biomes = [
+            Ident::new("plains").unwrap(),
+            Ident::new("desert").unwrap(),
+            Ident::new("forest").unwrap(),
+        ];
+        let stone = block::stone.state_default().id();
+        let stairs = state("oak_stairs", &[("facing", "east"), ("half", "top")]);
+
+        let mut blocks = BlockContainer::new(stone);
+        blocks.set(1, stairs.id());
+        let mut mixed = BlockContainer::new(stone);
+        for i in 0..20 {
+            mixed.set(i * 200, block::stone.state_index() + i as u16 * 50);
+        }
+        let mut biome = BiomeContainer::new(1);
+        biome.set(63, 2);
+        let mut rest = CompoundTag::new();
+        rest.push(
+            StringTag::from_utf8("Status"),
+            Tag::String(StringTag::from_utf8("minecraft:full")),
+        );
+        let mut data = CompoundTag::new();
+        data.push(StringTag::from_utf8("Lock"), Tag::Byte(0));
+        let chunk = Chunk {
+            data_version: 4671,
+            x: 3,
+            z: -2,
+            y: Some(-4),
+            sections: alloc::vec![
+                Section {
+                    y: -4,
+                    block_states: blocks,
+                    biomes: biome,
+                    block_light: None,
+                    sky_light: Some(Box::new([0xf0; LIGHT_LEN])),
+                },
+                Section {
+                    y: -3,
+                    block_states: mixed,
+                    biomes: BiomeContainer::new(0),
+                    block_light: None,
+                    sky_light: None,
+                },
+            ],
+            heightmaps: alloc::vec![(StringTag::from_utf8("MOTION_BLOCKING"), alloc::vec![7; 37])],
+            block_entities: alloc::vec![BlockEntity {
+                x: 48,
+                y: -60,
+                z: -31,
+                ty: block_entity_type::chest,
+                data,
+            }],
+            rest,
+            unknown_biomes: Vec::new(),
+        };
+
+        let root = chunk.to_nbt(&biomes);
+        let Some(Tag::List(ListTag::Compound(sections))) = root.find("sections") else {
+            panic!();
+        };
+        let Some(Tag::Compound(states)) = sections[0].find("block_states") else {
+            panic!();
+        };
+        let Some(Tag::List(ListTag::Compound(palette))) = states.find("palette") else {
+            panic!();
+        };
+        assert_eq!(palette.len(), 2);
+        let Some(Tag::Compound(props)) = palette[1].find("Properties") else {
+            panic!();
+        };
+        assert!(matches!(props.find("facing"), Some(Tag::String(x)) if &**x == "east"));
+        assert!(matches!(states.find("data"), Some(Tag::LongArray(x)) if x.len() == 256));
+        let Some(Tag::Compound(biome_tag)) = sections[0].find("biomes") else {
+            panic!();
+        };
+        assert!(matches!(biome_tag.find("data"), Some(Tag::LongArray(x)) if x.len() == 1));
+
+        let read = Chunk::from_nbt(root, &biomes).unwrap();
+        assert_eq!(
+            (read.data_version, read.x, read.z, read.y),
+            (4671, 3, -2, Some(-4))
+        );
+        assert_eq!(read.heightmap("MOTION_BLOCKING"), Some(&[7; 37][..]));
+        assert!(read.rest.find("Status").is_some());
+        let entity = &read.block_entities[0];
+        assert_eq!((entity.x, entity.y, entity.z), (48, -60, -31));
+        assert!(entity.ty == block_entity_type::chest);
+        assert!(matches!(entity.data.find("Lock"), Some(Tag::Byte(0))));
+        let section = &read.sections[0];
+        unsafe {
+            assert_eq!(*section.block_states.get_unchecked(0), stone);
+            assert_eq!(*section.block_states.get_unchecked(1), stairs.id());
+            assert_eq!(*section.biomes.get_unchecked(0), 1);
+            assert_eq!(*section.biomes.get_unchecked(63), 2);
+            for i in 0..4096 {
+                assert_eq!(
+                    read.sections[1].block_states.get_unchecked(i),
+                    chunk.sections[1].block_states.get_unchecked(i)
+                );
+            }
+        }
+        assert!(section.block_light.is_none());
+        assert_eq!(section.sky_light.as_deref(), Some(&[0xf0; LIGHT_LEN]));
+        assert_eq!(read.sections[1].biomes.palette(), &[0]);
+
+        // Unknown blocks become air and invalid properties keep defaults.
+        let mut entry = CompoundTag::new();
+        entry.push(
+            StringTag::from_utf8("Name"),
+            Tag::String(StringTag::from_utf8("mod:thing")),
+        );
+        assert_eq!(
+            block_from_nbt(&entry).unwrap().id(),
+            block::air.state_default().id()
+        );
+        let mut entry = block_to_nbt(stairs);
+        if let Some(Tag::Compound(props)) = entry.find_mut("Properties") {
+            props.push(
+                StringTag::from_utf8("half"),
+                Tag::String(StringTag::from_utf8("sideways")),
+            );
+        }
+        assert_eq!(block_from_nbt(&entry).unwrap().id(), stairs.id());
+
+        // Unknown biomes become plains and are reported.
+        let read = Chunk::from_nbt(chunk.to_nbt(&biomes), &biomes[..1]).unwrap();
+        assert_eq!(read.sections[0].biomes.palette(), &[0]);
+        assert_eq!(
+            read.unknown_biomes.iter().map(|x| &**x).collect::<Vec<_>>(),
+            ["minecraft:desert", "minecraft:forest"]
+        );
+        assert!(Chunk::from_nbt(chunk.to_nbt(&biomes), &biomes[1..]).is_err());
+
+        let mut bad = chunk.to_nbt(&biomes);
+        if let Some(Tag::List(ListTag::Compound(sections))) = bad.find_mut("sections")
+            && let Some(Tag::Compound(states)) = sections[0].find_mut("block_states")
+            && let Some(Tag::LongArray(data)) = states.find_mut("data")
+        {
+            data.pop();
+        }
+        assert!(Chunk::from_nbt(bad, &biomes).is_err());
+    }
+}
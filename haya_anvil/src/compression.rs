@@ -0,0 +1,173 @@
+use crate::lz4;
+use alloc::vec::Vec;
+use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, decompress_to_vec_zlib_with_limit};
+use mser::{Error, ErrorKind};
+
+const COMPRESSION_LEVEL: u8 = 6;
+/// Maximum length of a decompressed chunk.
+const MAX_CHUNK_LEN: usize = 1 << 26;
+
+const GZIP_MAGIC: [u8; 3] = [0x1f, 0x8b, 8];
+const FHCRC: u8 = 2;
+const FEXTRA: u8 = 4;
+const FNAME: u8 = 8;
+const FCOMMENT: u8 = 16;
+
+/// Compression type of a chunk in a region file.
+///
+/// Type 127, a compressor named by the chunk itself, is not supported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Compression {
+    Gzip = 1,
+    Zlib = 2,
+    None = 3,
+    Lz4 = 4,
+}
+
+impl Compression {
+    pub const fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Gzip),
+            2 => Some(Self::Zlib),
+            3 => Some(Self::None),
+            4 => Some(Self::Lz4),
+            _ => None,
+        }
+    }
+
+    pub const fn id(self) -> u8 {
+        self as u8
+    }
+
+    pub fn compress(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Gzip => {
+                let mut out = Vec::with_capacity(data.len() / 2 + 18);
+                out.extend_from_slice(&GZIP_MAGIC);
+                out.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0xff]);
+                out.extend_from_slice(&compress_to_vec(data, COMPRESSION_LEVEL));
+                out.extend_from_slice(&crc32(data).to_le_bytes());
+                out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                out
+            }
+            Self::Zlib => compress_to_vec_zlib(data, COMPRESSION_LEVEL),
+            Self::None => Vec::from(data),
+            Self::Lz4 => {
+                let mut out = Vec::with_capacity(data.len() / 2 + 42);
+                lz4::compress(data, &mut out);
+                out
+            }
+        }
+    }
+
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Gzip => gzip_decompress(data),
+            Self::Zlib => decompress_to_vec_zlib_with_limit(data, MAX_CHUNK_LEN)
+                .map_err(|_| Error::new(ErrorKind::Invalid)),
+            Self::None => {
+                if data.len() > MAX_CHUNK_LEN {
+                    Err(Error::new(ErrorKind::TooLong))
+                } else {
+                    Ok(Vec::from(data))
+                }
+            }
+            Self::Lz4 => lz4::decompress(data, MAX_CHUNK_LEN),
+        }
+    }
+}
+
+fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    if data.len() < 18 || data[..3] != GZIP_MAGIC {
+        return Err(Error::new(ErrorKind::Invalid));
+    }
+    let flags = data[3];
+    let mut header = &data[10..data.len() - 8];
+    if flags & FEXTRA != 0 {
+        let Some(&[lo, hi]) = header.get(..2) else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        let len = u16::from_le_bytes([lo, hi]) as usize + 2;
+        header = header
+            .get(len..)
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof))?;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = header
+                .iter()
+                .position(|&b| b == 0)
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof))?;
+            header = &header[end + 1..];
+        }
+    }
+    if flags & FHCRC != 0 {
+        header = header
+            .get(2..)
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof))?;
+    }
+    let out = decompress_to_vec_with_limit(header, MAX_CHUNK_LEN)
+        .map_err(|_| Error::new(ErrorKind::Invalid))?;
+    let trailer = &data[data.len() - 8..];
+    if trailer[..4] != crc32(&out).to_le_bytes() || trailer[4..] != (out.len() as u32).to_le_bytes()
+    {
+        return Err(Error::new(ErrorKind::Invalid));
+    }
+    Ok(out)
+}
+
+const CRC32: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |c, &b| {
+        CRC32[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        let data = b"minecraft:stone minecraft:stone minecraft:dirt";
+        for c in [
+            Compression::Gzip,
+            Compression::Zlib,
+            Compression::None,
+            Compression::Lz4,
+        ] {
+            assert_eq!(Compression::from_id(c.id()), Some(c));
+            assert_eq!(c.decompress(&c.compress(data)).unwrap(), data);
+        }
+        // A header carrying the original file name.
+        let mut named = Compression::Gzip.compress(data);
+        named[3] = FNAME;
+        named.splice(10..10, *b"chunk\0");
+        assert_eq!(Compression::Gzip.decompress(&named).unwrap(), data);
+        let len = named.len();
+        named[len - 5] ^= 1;
+        assert!(Compression::Gzip.decompress(&named).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+#![no_std]
+#![warn(clippy::shadow_reuse, clippy::use_self)]
+
+extern crate alloc;
+
+mod chunk;
+mod compression;
+mod lz4;
+mod region;
+
+pub use self::chunk::{BlockEntity, Chunk, Section};
+pub use self::compression::Compression;
+pub use self::region::{Region, RegionChunk, SECTOR_SIZE, external_name, region_name, region_pos};
+pub use haya_palette::LIGHT_LEN;
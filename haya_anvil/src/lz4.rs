@@ -0,0 +1,304 @@
+//! The `LZ4Block` stream of lz4-java, which vanilla uses for compression
+//! type 4, and the LZ4 block format inside it.
+
+use alloc::vec::Vec;
+use mser::{Error, ErrorKind};
+
+const MAGIC: &[u8; 8] = b"LZ4Block";
+const HEADER_LEN: usize = 21;
+const METHOD_RAW: u8 = 0x10;
+const METHOD_LZ4: u8 = 0x20;
+const BLOCK_SIZE: usize = 1 << 16;
+/// `32 - clz(BLOCK_SIZE - 1) - 10`, stored in the low bits of the token.
+const LEVEL: u8 = 6;
+const SEED: u32 = 0x9747_b28c;
+
+const MIN_MATCH: usize = 4;
+const LAST_LITERALS: usize = 5;
+const MF_LIMIT: usize = 12;
+const HASH_LOG: u32 = 12;
+
+pub(crate) fn compress(data: &[u8], out: &mut Vec<u8>) {
+    for block in data.chunks(BLOCK_SIZE) {
+        let start = out.len();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&[0; HEADER_LEN - 8]);
+        compress_block(block, out);
+        let mut len = out.len() - start - HEADER_LEN;
+        let method = if len >= block.len() {
+            out.truncate(start + HEADER_LEN);
+            out.extend_from_slice(block);
+            len = block.len();
+            METHOD_RAW
+        } else {
+            METHOD_LZ4
+        };
+        let header = &mut out[start + 8..start + HEADER_LEN];
+        header[0] = method | LEVEL;
+        header[1..5].copy_from_slice(&(len as u32).to_le_bytes());
+        header[5..9].copy_from_slice(&(block.len() as u32).to_le_bytes());
+        header[9..13].copy_from_slice(&checksum(block).to_le_bytes());
+    }
+    out.extend_from_slice(MAGIC);
+    out.push(METHOD_RAW | LEVEL);
+    out.extend_from_slice(&[0; HEADER_LEN - 9]);
+}
+
+pub(crate) fn decompress(mut data: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        let Some((header, rest)) = data.split_first_chunk::<HEADER_LEN>() else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        if header[..8] != *MAGIC {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+        let compressed = u32_le(header, 9) as usize;
+        let len = u32_le(header, 13) as usize;
+        let check = u32_le(header, 17);
+        if len == 0 {
+            return if compressed == 0 {
+                Ok(out)
+            } else {
+                Err(Error::new(ErrorKind::Invalid))
+            };
+        }
+        if len > BLOCK_SIZE || len > limit - out.len() {
+            return Err(Error::new(ErrorKind::TooLong));
+        }
+        let Some((block, next)) = rest.split_at_checked(compressed) else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        data = next;
+        let start = out.len();
+        match header[8] & 0xf0 {
+            METHOD_RAW if compressed == len => out.extend_from_slice(block),
+            METHOD_LZ4 => decompress_block(block, &mut out, len)?,
+            _ => return Err(Error::new(ErrorKind::Invalid)),
+        }
+        if checksum(&out[start..]) != check {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+    }
+    Ok(out)
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    xxhash32(data, SEED) & 0x0fff_ffff
+}
+
+fn u32_le(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+fn compress_block(src: &[u8], out: &mut Vec<u8>) {
+    let mut table = [0u32; 1 << HASH_LOG];
+    let mut anchor = 0;
+    let mut i = 0;
+    if src.len() > MF_LIMIT {
+        let limit = src.len() - MF_LIMIT;
+        while i < limit {
+            let seq = u32_le(src, i);
+            let h = (seq.wrapping_mul(2654435761) >> (32 - HASH_LOG)) as usize;
+            let candidate = table[h] as usize;
+            table[h] = i as u32;
+            if candidate < i && i - candidate <= u16::MAX as usize && u32_le(src, candidate) == seq
+            {
+                let max = src.len() - LAST_LITERALS - i;
+                let mut len = MIN_MATCH;
+                while len < max && src[candidate + len] == src[i + len] {
+                    len += 1;
+                }
+                sequence(out, &src[anchor..i], Some((i - candidate, len)));
+                i += len;
+                anchor = i;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    sequence(out, &src[anchor..], None);
+}
+
+fn sequence(out: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    let match_len = match matched {
+        Some((_, len)) => len - MIN_MATCH,
+        None => 0,
+    };
+    out.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literals.len() >= 15 {
+        length(out, literals.len() - 15);
+    }
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            length(out, match_len - 15);
+        }
+    }
+}
+
+fn length(out: &mut Vec<u8>, mut n: usize) {
+    while n >= 255 {
+        out.push(255);
+        n -= 255;
+    }
+    out.push(n as u8);
+}
+
+fn decompress_block(src: &[u8], out: &mut Vec<u8>, len: usize) -> Result<(), Error> {
+    let start = out.len();
+    let end = start + len;
+    let mut i = 0;
+    loop {
+        let token = *src
+            .get(i)
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof))?;
+        i += 1;
+        let literals = extend(src, &mut i, (token >> 4) as usize)?;
+        let Some(x) = src.get(i..i + literals) else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        if literals > end - out.len() {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+        out.extend_from_slice(x);
+        i += literals;
+        if i == src.len() {
+            break;
+        }
+        let Some(&[lo, hi]) = src.get(i..i + 2) else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        i += 2;
+        let offset = u16::from_le_bytes([lo, hi]) as usize;
+        let matched = extend(src, &mut i, (token & 15) as usize)? + MIN_MATCH;
+        if offset == 0 || offset > out.len() - start || matched > end - out.len() {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+        let from = out.len() - offset;
+        for k in from..from + matched {
+            let b = out[k];
+            out.push(b);
+        }
+    }
+    if out.len() == end {
+        Ok(())
+    } else {
+        Err(Error::new(ErrorKind::Invalid))
+    }
+}
+
+fn extend(src: &[u8], i: &mut usize, mut n: usize) -> Result<usize, Error> {
+    if n == 15 {
+        loop {
+            let b = *src
+                .get(*i)
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof))?;
+            *i += 1;
+            n += b as usize;
+            if b != 255 {
+                break;
+            }
+        }
+    }
+    Ok(n)
+}
+
+const P1: u32 = 2654435761;
+const P2: u32 = 2246822519;
+const P3: u32 = 3266489917;
+const P4: u32 = 668265263;
+const P5: u32 = 374761393;
+
+fn xxhash32(data: &[u8], seed: u32) -> u32 {
+    let round = |acc: u32, at: usize| {
+        acc.wrapping_add(u32_le(data, at).wrapping_mul(P2))
+            .rotate_left(13)
+            .wrapping_mul(P1)
+    };
+    let mut i = 0;
+    let mut h = if data.len() >= 16 {
+        let mut v = [
+            seed.wrapping_add(P1).wrapping_add(P2),
+            seed.wrapping_add(P2),
+            seed,
+            seed.wrapping_sub(P1),
+        ];
+        while i + 16 <= data.len() {
+            for (k, acc) in v.iter_mut().enumerate() {
+                *acc = round(*acc, i + 4 * k);
+            }
+            i += 16;
+        }
+        v[0].rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18))
+    } else {
+        seed.wrapping_add(P5)
+    };
+    h = h.wrapping_add(data.len() as u32);
+    while i + 4 <= data.len() {
+        h = h.wrapping_add(u32_le(data, i).wrapping_mul(P3));
+        h = h.rotate_left(17).wrapping_mul(P4);
+        i += 4;
+    }
+    for &b in &data[i..] {
+        h = h.wrapping_add((b as u32).wrapping_mul(P5));
+        h = h.rotate_left(11).wrapping_mul(P1);
+    }
+    h ^= h >> 15;
+    h = h.wrapping_mul(P2);
+    h ^= h >> 13;
+    h = h.wrapping_mul(P3);
+    h ^ (h >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lz4() {
+        assert_eq!(xxhash32(b"", 0), 0x02cc_5d05);
+        assert_eq!(xxhash32(b"abc", 0), 0x32d1_53ff);
+        assert_eq!(
+            xxhash32(b"Nobody inspects the spammish repetition", 0),
+            0xe229_3b2f
+        );
+
+        let mut data = Vec::new();
+        for i in 0..100_000u32 {
+            data.extend_from_slice(&(i % 300).to_le_bytes());
+        }
+        let mut out = Vec::new();
+        compress(&data, &mut out);
+        assert!(out.len() < data.len() / 2);
+        assert_eq!(decompress(&out, usize::MAX).unwrap(), data);
+        assert!(decompress(&out, 1000).is_err());
+
+        let mut noise = Vec::new();
+        let mut x = 1u32;
+        for _ in 0..5000 {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            noise.push(x as u8);
+        }
+        out.clear();
+        compress(&noise, &mut out);
+        assert_eq!(out[8] & 0xf0, METHOD_RAW);
+        assert_eq!(decompress(&out, usize::MAX).unwrap(), noise);
+        out[HEADER_LEN] ^= 1;
+        assert!(decompress(&out, usize::MAX).is_err());
+
+        // Hand-built `LZ4Block` stream, see `testdata/README.md`.
+        let text = (0..6000u32)
+            .map(|i| alloc::format!("minecraft:block_{};", i * i % 97))
+            .collect::<alloc::string::String>();
+        let stream = include_bytes!("../testdata/stream.lz4");
+        assert_eq!(decompress(stream, usize::MAX).unwrap(), text.as_bytes());
+        assert!(decompress(&stream[..stream.len() - 1], usize::MAX).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::Compression;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use haya_nbt::{CompoundNamed, NbtLimits};
+use mser::{Error, ErrorKind, Reader, Write};
+
+pub const SECTOR_SIZE: usize = 4096;
+const CHUNKS: usize = 1024;
+const HEADER_LEN: usize = 2 * SECTOR_SIZE;
+const EXTERNAL: u8 = 0x80;
+/// Largest number of sectors a chunk can occupy in the region file itself.
+const MAX_SECTORS: usize = 255;
+/// Length prefix and compression type preceding the chunk data.
+const CHUNK_HEADER_LEN: usize = 5;
+
+/// Region coordinates of the file holding a chunk.
+pub const fn region_pos(chunk_x: i32, chunk_z: i32) -> (i32, i32) {
+    (chunk_x >> 5, chunk_z >> 5)
+}
+
+/// `r.<x>.<z>.mca`, the file name of a region.
+pub fn region_name(region_x: i32, region_z: i32) -> String {
+    format!("r.{region_x}.{region_z}.mca")
+}
+
+/// `c.<x>.<z>.mcc`, the file holding a chunk too large for its region.
+pub fn external_name(chunk_x: i32, chunk_z: i32) -> String {
+    format!("c.{chunk_x}.{chunk_z}.mcc")
+}
+
+/// A compressed chunk stored in a region file.
+#[derive(Clone)]
+pub struct RegionChunk {
+    /// Seconds since the epoch at which the chunk was last saved.
+    pub timestamp: u32,
+    pub compression: Compression,
+    /// Whether the data lives in the `.mcc` file named by [`external_name`].
+    ///
+    /// [`Region::read`] leaves [`data`](Self::data) empty for such chunks.
+    pub external: bool,
+    pub data: Vec<u8>,
+}
+
+impl RegionChunk {
+    /// Compresses a chunk root, moving it to an external file if it would
+    /// not fit in 255 sectors.
+    pub fn encode(root: &CompoundNamed, compression: Compression, timestamp: u32) -> Self {
+        let len = root.len_s();
+        let mut raw = Vec::with_capacity(len);
+        unsafe {
+            mser::write_unchecked(raw.as_mut_ptr(), root);
+            raw.set_len(len);
+        }
+        let data = compression.compress(&raw);
+        Self {
+            timestamp,
+            compression,
+            external: data.len() + CHUNK_HEADER_LEN > MAX_SECTORS * SECTOR_SIZE,
+            data,
+        }
+    }
+
+    /// Decompresses the chunk root, which for external chunks requires the
+    /// contents of the `.mcc` file in [`data`](Self::data).
+    pub fn decode(&self) -> Result<CompoundNamed, Error> {
+        let raw = self.compression.decompress(&self.data)?;
+        let mut reader = Reader::new(&raw);
+        let root = CompoundNamed::read_limited(&mut reader, NbtLimits::UNLIMITED)?;
+        reader.finish()?;
+        Ok(root)
+    }
+}
+
+/// The 32x32 chunks of an `.mca` file.
+#[derive(Clone)]
+pub struct Region {
+    chunks: Box<[Option<RegionChunk>]>,
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Region {
+    pub fn new() -> Self {
+        Self {
+            chunks: (0..CHUNKS).map(|_| None).collect(),
+        }
+    }
+
+    /// Parses a region file. An empty file is an empty region.
+    pub fn read(data: &[u8]) -> Result<Self, Error> {
+        let mut region = Self::new();
+        if data.is_empty() {
+            return Ok(region);
+        }
+        let Some((locations, rest)) = data.split_first_chunk::<SECTOR_SIZE>() else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        let Some(timestamps) = rest.first_chunk::<SECTOR_SIZE>() else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        for (index, slot) in region.chunks.iter_mut().enumerate() {
+            let location = be(locations, index * 4);
+            if location == 0 {
+                continue;
+            }
+            let offset = (location >> 8) as usize * SECTOR_SIZE;
+            let sectors = (location & 0xff) as usize;
+            if offset < HEADER_LEN || sectors == 0 {
+                return Err(Error::new(ErrorKind::Invalid));
+            }
+            let Some(chunk) = data.get(offset..) else {
+                return Err(Error::new(ErrorKind::UnexpectedEof));
+            };
+            let Some((header, body)) = chunk.split_first_chunk::<CHUNK_HEADER_LEN>() else {
+                return Err(Error::new(ErrorKind::UnexpectedEof));
+            };
+            let len = be(header, 0) as usize;
+            if len == 0 || len + 4 > sectors * SECTOR_SIZE {
+                return Err(Error::new(ErrorKind::Invalid));
+            }
+            let compression = match Compression::from_id(header[4] & !EXTERNAL) {
+                Some(x) => x,
+                None => return Err(Error::new(ErrorKind::Discriminant)),
+            };
+            let external = header[4] & EXTERNAL != 0;
+            let payload = if external {
+                Vec::new()
+            } else {
+                match body.get(..len - 1) {
+                    Some(x) => Vec::from(x),
+                    None => return Err(Error::new(ErrorKind::UnexpectedEof)),
+                }
+            };
+            *slot = Some(RegionChunk {
+                timestamp: be(timestamps, index * 4),
+                compression,
+                external,
+                data: payload,
+            });
+        }
+        Ok(region)
+    }
+
+    /// Serializes the region with chunks packed in index order.
+    ///
+    /// Only a stub is written for external chunks; their data belongs in the
+    /// file named by [`external_name`].
+    pub fn write(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        out.resize(start + HEADER_LEN, 0);
+        let mut sector = HEADER_LEN / SECTOR_SIZE;
+        for (index, slot) in self.chunks.iter().enumerate() {
+            let Some(chunk) = slot else {
+                continue;
+            };
+            let external =
+                chunk.external || chunk.data.len() + CHUNK_HEADER_LEN > MAX_SECTORS * SECTOR_SIZE;
+            let data: &[u8] = if external { &[] } else { &chunk.data };
+            let len = CHUNK_HEADER_LEN + data.len();
+            let sectors = len.div_ceil(SECTOR_SIZE);
+            let location = ((sector as u32) << 8) | sectors as u32;
+            let at = start + index * 4;
+            out[at..at + 4].copy_from_slice(&location.to_be_bytes());
+            out[at + SECTOR_SIZE..at + SECTOR_SIZE + 4]
+                .copy_from_slice(&chunk.timestamp.to_be_bytes());
+            out.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+            out.push(chunk.compression.id() | if external { EXTERNAL } else { 0 });
+            out.extend_from_slice(data);
+            out.resize(out.len() + sectors * SECTOR_SIZE - len, 0);
+            sector += sectors;
+        }
+    }
+
+    /// The chunk at `x`, `z`, taken modulo 32.
+    pub fn get(&self, x: i32, z: i32) -> Option<&RegionChunk> {
+        self.chunks[index(x, z)].as_ref()
+    }
+
+    pub fn get_mut(&mut self, x: i32, z: i32) -> Option<&mut RegionChunk> {
+        self.chunks[index(x, z)].as_mut()
+    }
+
+    pub fn insert(&mut self, x: i32, z: i32, chunk: RegionChunk) -> Option<RegionChunk> {
+        self.chunks[index(x, z)].replace(chunk)
+    }
+
+    pub fn remove(&mut self, x: i32, z: i32) -> Option<RegionChunk> {
+        self.chunks[index(x, z)].take()
+    }
+
+    /// Present chunks with their coordinates inside the region.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32, &RegionChunk)> {
+        self.chunks.iter().enumerate().filter_map(|(i, chunk)| {
+            chunk
+                .as_ref()
+                .map(|x| ((i & 31) as i32, (i >> 5) as i32, x))
+        })
+    }
+}
+
+const fn index(x: i32, z: i32) -> usize {
+    (x & 31) as usize | ((z & 31) as usize) << 5
+}
+
+fn be(data: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use haya_nbt::{CompoundTag, StringTag, Tag};
+
+    fn root(status: &str, filler: usize) -> CompoundNamed {
+        let mut c = CompoundTag::new();
+        c.push(StringTag::from_utf8("DataVersion"), Tag::Int(4671));
+        c.push(
+            StringTag::from_utf8("Status"),
+            Tag::String(StringTag::from_utf8(status)),
+        );
+        let mut x = 1u64;
+        let noise = (0..filler)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as i64
+            })
+            .collect();
+        c.push(StringTag::from_utf8("noise"), Tag::LongArray(noise));
+        CompoundNamed(StringTag::new(), c)
+    }
+
+    fn status(root: &CompoundNamed) -> &str {
+        match root.1.find("Status") {
+            Some(Tag::String(x)) => x,
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_region() {
+        let mut region = Region::new();
+        region.insert(
+            0,
+            0,
+            RegionChunk::encode(&root("full", 0), Compression::Zlib, 7),
+        );
+        region.insert(
+            31,
+            1,
+            RegionChunk::encode(&root("features", 2000), Compression::Gzip, 8),
+        );
+        region.insert(
+            -1,
+            -1,
+            RegionChunk::encode(&root("empty", 10), Compression::Lz4, 9),
+        );
+        let large = RegionChunk::encode(&root("noise", 140_000), Compression::None, 10);
+        assert!(large.external);
+        let mcc = large.data.clone();
+        region.insert(5, 5, large);
+
+        let mut file = Vec::new();
+        region.write(&mut file);
+        assert_eq!(file.len() % SECTOR_SIZE, 0);
+        // Header and one sector each, except four for the gzip chunk.
+        assert_eq!(file.len() / SECTOR_SIZE, 2 + 1 + 4 + 1 + 1);
+        assert_eq!(&file[..4], &[0, 0, 2, 1]);
+        assert_eq!(&file[SECTOR_SIZE..SECTOR_SIZE + 4], &[0, 0, 0, 7]);
+
+        let mut read = Region::read(&file).unwrap();
+        assert_eq!(read.iter().count(), 4);
+        let chunk = read.get(32, 64).unwrap();
+        assert_eq!(chunk.timestamp, 7);
+        assert_eq!(status(&chunk.decode().unwrap()), "full");
+        let chunk = read.get(31, 1).unwrap();
+        assert_eq!(chunk.compression, Compression::Gzip);
+        assert_eq!(status(&chunk.decode().unwrap()), "features");
+        assert_eq!(
+            status(&read.get(31, 31).unwrap().decode().unwrap()),
+            "empty"
+        );
+        let chunk = read.get_mut(5, 5).unwrap();
+        assert!(chunk.external && chunk.data.is_empty());
+        chunk.data = mcc;
+        assert_eq!(status(&chunk.decode().unwrap()), "noise");
+        assert!(read.remove(0, 0).is_some());
+        assert!(read.get(0, 0).is_none());
+
+        assert_eq!(Region::read(&[]).unwrap().iter().count(), 0);
+        assert!(Region::read(&file[..100]).is_err());
+        file[HEADER_LEN + 4] = 9;
+        assert!(Region::read(&file).is_err());
+        assert_eq!(region_pos(-1, 33), (-1, 1));
+        assert_eq!(region_name(-1, 1), "r.-1.1.mca");
+        assert_eq!(external_name(5, -3), "c.5.-3.mcc");
+    }
+}
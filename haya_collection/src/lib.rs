@@ -5,7 +5,7 @@ extern crate alloc;
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use mser::{Error, Read, Reader, V21, Write, Writer, read_v21_len};
+use mser::{Error, Read, Reader, Skip, V21, Write, Writer, read_v21_len, skip_n};
 
 pub enum List<'a, T: 'a, const MAX: usize = { usize::MAX }> {
     Borrowed(&'a [T]),
@@ -32,6 +32,12 @@ impl<T: core::fmt::Debug, const MAX: usize> core::fmt::Debug for List<'_, T, MAX
     }
 }
 
+impl<T: PartialEq, const MAX: usize> PartialEq for List<'_, T, MAX> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
 impl<'a, T, const MAX: usize> alloc::borrow::Borrow<[T]> for List<'a, T, MAX> {
     fn borrow(&self) -> &[T] {
         match self {
@@ -98,14 +104,25 @@ impl<'a, T: Read<'a>, const MAX: usize> Read<'a> for List<'a, T, MAX> {
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
         let len = read_v21_len(buf, MAX)?;
         let mut vec = Vec::with_capacity(capacity_fix(len));
-        for _ in 0..len {
-            vec.push(T::read(buf)?);
+        for i in 0..len {
+            match T::read(buf) {
+                Ok(x) => vec.push(x),
+                Err(e) => return Err(e.with_index(i)),
+            }
         }
         Ok(Self::Owned(vec))
     }
 }
 
-#[derive(Clone, Debug)]
+impl<T: Skip, const MAX: usize> Skip for List<'_, T, MAX> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let len = read_v21_len(buf, MAX)?;
+        skip_n::<T>(buf, len)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Map<'a, K: 'a, V: 'a, const MAX: usize = { usize::MAX }>(pub List<'a, (K, V), MAX>);
 
 impl<'a, K: Write + 'a, V: Write + 'a, const MAX: usize> Write for Map<'a, K, V, MAX> {
@@ -135,16 +152,29 @@ impl<'a, K: Read<'a>, V: Read<'a>, const MAX: usize> Read<'a> for Map<'a, K, V,
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
         let len = read_v21_len(buf, MAX)?;
         let mut vec = Vec::with_capacity(capacity_fix(len));
-        for _ in 0..len {
-            let k = K::read(buf)?;
-            let v = V::read(buf)?;
+        for i in 0..len {
+            let k = match K::read(buf) {
+                Ok(x) => x,
+                Err(e) => return Err(e.with_index(i)),
+            };
+            let v = match V::read(buf) {
+                Ok(x) => x,
+                Err(e) => return Err(e.with_index(i)),
+            };
             vec.push((k, v));
         }
         Ok(Self(List::Owned(vec)))
     }
 }
 
-#[derive(Clone, Debug)]
+impl<K: Skip, V: Skip, const MAX: usize> Skip for Map<'_, K, V, MAX> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        List::<'_, (K, V), MAX>::skip(buf)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
 pub enum Cow<'a, T> {
     Borrowed(&'a T),
     Owned(Box<T>),
@@ -174,3 +204,12 @@ impl<'a, T: Read<'a>> Read<'a> for Cow<'a, T> {
         Ok(Self::Owned(Box::new(T::read(buf)?)))
     }
 }
+
+impl<T: Skip> Skip for Cow<'_, T> {
+    const SIZE: Option<usize> = T::SIZE;
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        T::skip(buf)
+    }
+}
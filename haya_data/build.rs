@@ -6,7 +6,7 @@ use nested::ZString;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use std::env::var_os;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const V21MAX: usize = 0x1FFFFF;
 const V7MAX: usize = 0x7F;
@@ -86,16 +86,30 @@ fn main() {
     let pac = data.len() - pac_len..data.len();
 
     let s = core::str::from_utf8(&data).unwrap();
-    let block_names = registries(&mut w, &s[reg], &mut gen_hash);
-    registries(&mut w, &s[pac], &mut gen_hash);
+    let block_names = registries(&mut w, &s[reg.clone()], &mut gen_hash);
+    registries(&mut w, &s[pac.clone()], &mut gen_hash);
 
     item(&mut w, &s[ite]);
     entity(&mut w, &s[ent]);
 
-    let (bs_repr, bl_props, bs_size) = block_state(&mut w, &s[blo], &mut gen_hash, &block_names);
+    let (bs_repr, bl_props, bs_size) =
+        block_state(&mut w, &s[blo.clone()], &mut gen_hash, &block_names);
     fluid_state(&mut w, &s[flu], bs_repr, &bl_props, &bs_size);
+    let cur = Snapshot::new(&s[reg], &s[pac], &s[blo]);
+    versions(&mut w, &path.join("versions"), &cur);
+
+    let tag = std::fs::read_to_string(path.join("tags.txt")).unwrap_or_default();
+    tags(&mut w, &tag, &mut gen_hash);
+
+    let reg_data = std::fs::read_to_string(path.join("registry_data.txt")).unwrap_or_default();
+    registry_data(&mut w, &reg_data);
 
     std::fs::write(out.join("data.rs"), w).unwrap();
+
+    w = String::new();
+    let testdata = path.with_file_name("testdata").join("versions");
+    snapshots(&mut w, &testdata, &cur);
+    std::fs::write(out.join("test_versions.rs"), w).unwrap();
 }
 
 fn version(w: &mut String, data: &str) {
@@ -1181,6 +1195,365 @@ fn entity(w: &mut String, data: &str) {
     *w += ";\n";
 }
 
+const NO_ID: u32 = u32::MAX;
+
+/// ID tables of a data snapshot, keyed by name.
+struct Snapshot<'a> {
+    registries: Vec<(&'a str, Vec<&'a str>)>,
+    packets: Vec<(&'a str, Vec<&'a str>)>,
+    blocks: Vec<&'a str>,
+    /// Block and `name[key=value,...]` of each block state.
+    states: Vec<(u32, String)>,
+    /// Default block state of each block.
+    defaults: Vec<u32>,
+}
+
+impl<'a> Snapshot<'a> {
+    fn new(reg: &'a str, pac: &'a str, blo: &str) -> Self {
+        let registries = sections(reg);
+        let packets = sections(pac);
+        let blocks = registries
+            .iter()
+            .find(|x| x.0 == "block")
+            .expect("block registry")
+            .1
+            .clone();
+        let (states, defaults) = state_names(blo, &blocks);
+        Self {
+            registries,
+            packets,
+            blocks,
+            states,
+            defaults,
+        }
+    }
+
+    fn registry(&self, name: &str) -> &[&'a str] {
+        match self.registries.iter().find(|x| x.0 == name) {
+            Some(x) => &x.1,
+            None => &[],
+        }
+    }
+
+    fn packet(&self, name: &str) -> &[&'a str] {
+        match self.packets.iter().find(|x| x.0 == name) {
+            Some(x) => &x.1,
+            None => &[],
+        }
+    }
+}
+
+fn sections(data: &str) -> Vec<(&str, Vec<&str>)> {
+    let mut iter = data.split('\n');
+    let mut out = Vec::new();
+    while let Some(x) = iter.next() {
+        if x.is_empty() {
+            break;
+        }
+        let (name, size, _) = head(Some(x), "");
+        out.push((name, iter.by_ref().take(size).collect()));
+    }
+    out
+}
+
+fn state_names(data: &str, blocks: &[&str]) -> (Vec<(u32, String)>, Vec<u32>) {
+    let mut iter = data.split('\n');
+    let (_, size, _) = head(iter.next(), "block_state_property_key");
+    let keys = iter.by_ref().take(size).collect::<Vec<_>>();
+    let (_, size, _) = head(iter.next(), "block_state_property_value");
+    let vals = iter.by_ref().take(size).collect::<Vec<_>>();
+    let (_, size, _) = head(iter.next(), "block_state_property");
+    let kv = iter
+        .by_ref()
+        .take(size)
+        .map(|x| hex_line(x).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let (_, size, _) = head(iter.next(), "block_state_properties");
+    let props = iter
+        .by_ref()
+        .take(size)
+        .map(|x| {
+            if x.is_empty() {
+                Vec::new()
+            } else {
+                hex_line(x).collect::<Vec<_>>()
+            }
+        })
+        .collect::<Vec<_>>();
+    let (_, size, _) = head(iter.next(), "block_state");
+    let block_props = read_rl(size, &mut iter).collect::<Vec<_>>();
+    let (_, size, _) = head(iter.next(), "block_to_default_block_state");
+    let block_default = read_rl(size, &mut iter).collect::<Vec<_>>();
+    assert_eq!(block_props.len(), blocks.len());
+
+    let mut states = Vec::new();
+    let mut defaults = Vec::with_capacity(blocks.len());
+    let mut values = Vec::new();
+    for (block, &name) in blocks.iter().enumerate() {
+        let block_prop = &props[block_props[block] as usize];
+        let count = block_prop
+            .iter()
+            .map(|&x| kv[x as usize].len() - 1)
+            .product::<usize>();
+        defaults.push(states.len() as u32 + block_default[block]);
+        for offset in 0..count {
+            values.clear();
+            let mut rest = offset;
+            for &index in block_prop.iter().rev() {
+                let prop = &kv[index as usize];
+                let len = prop.len() - 1;
+                values.push((keys[prop[0] as usize], vals[prop[1 + rest % len] as usize]));
+                rest /= len;
+            }
+            values.sort_unstable();
+            let mut key = name.to_owned();
+            if !values.is_empty() {
+                key.push('[');
+                for (index, &(k, v)) in values.iter().enumerate() {
+                    if index != 0 {
+                        key.push(',');
+                    }
+                    key += k;
+                    key.push('=');
+                    key += v;
+                }
+                key.push(']');
+            }
+            states.push((block as u32, key));
+        }
+    }
+    (states, defaults)
+}
+
+fn name_index<'a>(names: &[&'a str]) -> HashMap<&'a str, u32> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| (n, i as u32))
+        .collect()
+}
+
+fn remap_names(old: &[&str], cur: &[&str]) -> (Vec<u32>, Vec<u32>) {
+    let (old_index, cur_index) = (name_index(old), name_index(cur));
+    let to = old
+        .iter()
+        .map(|n| cur_index.get(n).copied().unwrap_or(NO_ID))
+        .collect();
+    let from = cur
+        .iter()
+        .map(|n| old_index.get(n).copied().unwrap_or(NO_ID))
+        .collect();
+    (to, from)
+}
+
+/// Maps block states by name, falling back to the default state of the
+/// same block when a property combination does not exist in the other version.
+fn remap_states(old: &Snapshot, cur: &Snapshot) -> (Vec<u32>, Vec<u32>) {
+    let one_way = |a: &Snapshot, b: &Snapshot| {
+        let states = b
+            .states
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.1.as_str(), i as u32))
+            .collect::<HashMap<&str, u32>>();
+        let blocks = name_index(&b.blocks);
+        a.states
+            .iter()
+            .map(|(block, key)| match states.get(key.as_str()) {
+                Some(&x) => x,
+                None => match blocks.get(a.blocks[*block as usize]) {
+                    Some(&x) => b.defaults[x as usize],
+                    None => NO_ID,
+                },
+            })
+            .collect()
+    };
+    (one_way(old, cur), one_way(cur, old))
+}
+
+fn remap(w: &mut String, field: &str, (to, from): (Vec<u32>, Vec<u32>)) {
+    *w += field;
+    *w += ": Remap::table(";
+    list(w, to.into_iter());
+    *w += ", ";
+    list(w, from.into_iter());
+    *w += "),\n";
+}
+
+const PACKETS: [&str; 9] = [
+    "serverbound/handshake",
+    "clientbound/status",
+    "serverbound/status",
+    "clientbound/login",
+    "serverbound/login",
+    "clientbound/configuration",
+    "serverbound/configuration",
+    "clientbound/play",
+    "serverbound/play",
+];
+
+/// Emits a module with ID mappings for every snapshot under `generated/versions`.
+fn versions(w: &mut String, path: &Path, cur: &Snapshot) {
+    let mut protocols = snapshots(w, path, cur);
+    protocols.sort_unstable_by(|x, y| y.cmp(x));
+    *w += "pub static VERSIONS: &[&Version] = &[&CURRENT_VERSION";
+    for proto in protocols {
+        *w += ", &v";
+        write(w, proto);
+        *w += "::VERSION";
+    }
+    *w += "];\n";
+}
+
+/// Emits a `v<protocol>` module for each snapshot directory under `path`
+/// and returns their protocol versions.
+fn snapshots(w: &mut String, path: &Path, cur: &Snapshot) -> Vec<u32> {
+    let mut dirs = match std::fs::read_dir(path) {
+        Ok(dir) => dir
+            .map(|x| x.unwrap().path())
+            .filter(|x| x.is_dir())
+            .collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+    dirs.sort_unstable();
+    let mut protocols = Vec::new();
+    for dir in &dirs {
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        let version = read("version.txt");
+        let mut lines = version.split('\n');
+        let name = lines.next().unwrap();
+        let proto = parse_u32(lines.next().unwrap());
+        let (reg2, pac2, blo2) = (
+            read("registries.txt"),
+            read("packet.txt"),
+            read("block_state.txt"),
+        );
+        let old = Snapshot::new(&reg2, &pac2, &blo2);
+
+        *w += "pub mod v";
+        write(w, proto);
+        *w += " {\nuse super::Remap;\n";
+        *w += "pub const NAME_VERSION: &str = \"";
+        *w += name;
+        *w += "\";\n";
+        *w += "pub const PROTOCOL_VERSION: u32 = ";
+        write(w, proto);
+        *w += ";\n";
+        *w += "#[allow(clippy::large_const_arrays)]\n";
+        *w += "pub static VERSION: super::Version = super::Version {\n";
+        *w += "name: NAME_VERSION,\nprotocol: PROTOCOL_VERSION,\n";
+        for packet in PACKETS {
+            remap(
+                w,
+                &packet.replace('/', "_"),
+                remap_names(old.packet(packet), cur.packet(packet)),
+            );
+        }
+        for registry in ["item", "entity_type"] {
+            remap(
+                w,
+                registry,
+                remap_names(old.registry(registry), cur.registry(registry)),
+            );
+        }
+        remap(w, "block_state", remap_states(&old, cur));
+        *w += "};\n}\n";
+        protocols.push(proto);
+    }
+    protocols
+}
+
+fn tags(w: &mut String, data: &str, gen_hash: &mut GenerateHash) {
+    let mut iter = data.split('\n');
+    let mut registries = Vec::new();
+    let mut ids = Vec::new();
+    while let Some(x) = iter.next() {
+        if x.is_empty() {
+            break;
+        }
+        let (registry, size, repr) = head(Some(x), "");
+        if size == 0 {
+            continue;
+        }
+        let mut names = Vec::with_capacity(size);
+        ids.clear();
+        for _ in 0..size {
+            names.push(iter.next().unwrap());
+            ids.push(hex_line(iter.next().unwrap()).collect::<Vec<_>>());
+        }
+        let name = format!("{registry}_tag");
+        enum_head(w, repr, &name);
+        for &tag in &names {
+            kw_prefix(w, &tag.replace('/', "_"));
+            *w += ",\n";
+        }
+        *w += "}\n";
+        impl_common(w, &name, repr, size, 0);
+        impl_name(w, gen_hash, repr, &names, &name);
+
+        *w += "impl ";
+        *w += &name;
+        *w += " {\n";
+        *w += "#[allow(clippy::large_const_arrays)]\nconst IDS: &[&[u32]; ";
+        write(w, size);
+        *w += "] = &[\n";
+        for x in &ids {
+            list(w, x.iter().copied());
+            *w += ",\n";
+        }
+        *w += "];\n";
+        *w += "#[inline]\n#[must_use]\npub const fn ids(self) -> &'static [u32] {\n";
+        *w += "Self::IDS[self as usize]\n}\n";
+        *w += "#[inline]\n#[must_use]\npub const fn contains(self, x: ";
+        *w += registry;
+        *w += ") -> bool {\n";
+        *w += "crate::tag_contains(self.ids(), x as u32)\n}\n";
+        *w += "}\n";
+        *w += "impl Tag for ";
+        *w += &name;
+        *w += " {\nconst REGISTRY: &'static str = \"";
+        *w += registry;
+        *w += "\";\n#[inline]\nfn name(self) -> &'static str {\nSelf::name(self)\n}\n";
+        *w += "#[inline]\nfn ids(self) -> &'static [u32] {\nSelf::ids(self)\n}\n}\n";
+        registries.push((registry, name));
+    }
+    *w += "pub static TAG_REGISTRIES: &[TagRegistry] = &[";
+    for (registry, name) in registries {
+        *w += "TagRegistry { registry: \"";
+        *w += registry;
+        *w += "\", names: ";
+        *w += &name;
+        *w += "::N, ids: ";
+        *w += &name;
+        *w += "::IDS },\n";
+    }
+    *w += "];\n";
+}
+
+fn registry_data(w: &mut String, data: &str) {
+    let mut iter = data.split('\n');
+    *w += "#[allow(clippy::large_const_arrays)]\n";
+    *w += "pub static REGISTRY_DATA: &[SyncedRegistry] = &[\n";
+    while let Some(x) = iter.next() {
+        if x.is_empty() {
+            break;
+        }
+        let (registry, size, _) = head(Some(x), "");
+        *w += "SyncedRegistry { registry: \"";
+        *w += registry;
+        *w += "\", entries: &[\n";
+        for _ in 0..size {
+            *w += "(\"";
+            *w += iter.next().unwrap();
+            *w += "\", ";
+            list(w, hex_line(iter.next().unwrap()).map(|x| x as u8));
+            *w += "),\n";
+        }
+        *w += "] },\n";
+    }
+    *w += "];\n";
+}
+
 fn head<'a>(raw: Option<&'a str>, expected: &str) -> (&'a str, usize, Repr) {
     let raw2 = raw.expect("EOF");
     let Some(first) = raw2.strip_prefix(';') else {
@@ -1268,7 +1641,7 @@ fn from_str(n: &str) -> Result<Self, Self::Err> {
         *w += "::core::option::Option::Some(x) => unsafe { ::core::result::Result::Ok(::core::mem::transmute::<";
         *w += repr.to_int();
         *w += ", Self>(x)) },
-::core::option::Option::None => ::core::result::Result::Err(::mser::Error),
+::core::option::Option::None => ::core::result::Result::Err(::mser::Error::new(::mser::ErrorKind::Invalid)),
 }";
     } else {
         *w += "unsafe {\n";
@@ -1280,7 +1653,7 @@ fn from_str(n: &str) -> Result<Self, Self::Err> {
             write(w, i);
             *w += ",\n";
         }
-        *w += "_ => return ::core::result::Result::Err(::mser::Error),\n";
+        *w += "_ => return ::core::result::Result::Err(::mser::Error::new(::mser::ErrorKind::Invalid)),\n";
         *w += "}\n)\n)\n}";
     }
     *w += "\n}\n}\n";
@@ -1644,8 +2017,9 @@ fn impl_codec_struct(w: &mut String, name: &str, size: usize, repr: Repr) {
     *w += repr.to_int();
     *w += "))\n";
     *w += "} else {\n";
-    *w += "::core::result::Result::Err(::mser::Error)\n";
+    *w += "::core::result::Result::Err(n.error(::mser::ErrorKind::Discriminant))\n";
     *w += "}\n}\n}\n";
+    impl_skip(w, name, size);
 }
 
 fn impl_codec(w: &mut String, name: &str, size: usize, repr: Repr) {
@@ -1698,8 +2072,30 @@ fn impl_codec(w: &mut String, name: &str, size: usize, repr: Repr) {
     *w += repr.to_int();
     *w += ")) }\n";
     *w += "} else {\n";
-    *w += "::core::result::Result::Err(::mser::Error)\n";
+    *w += "::core::result::Result::Err(n.error(::mser::ErrorKind::Discriminant))\n";
     *w += "}\n}\n}\n";
+    impl_skip(w, name, size);
+}
+
+/// Ids are only advanced over, a one byte id is not checked against `size`.
+fn impl_skip(w: &mut String, name: &str, size: usize) {
+    *w += "impl ::mser::Skip for ";
+    *w += name;
+    *w += " {\n";
+    if size <= V7MAX {
+        *w += "const SIZE: ::core::option::Option<usize> = ::core::option::Option::Some(1);\n";
+    }
+    *w += "#[inline]\n";
+    *w += "fn skip(n: &mut ::mser::Reader<'_>) -> ::core::result::Result<(), ::mser::Error> {\n";
+    if size <= V7MAX {
+        *w += "n.read_array::<1>()?;\n";
+        *w += "::core::result::Result::Ok(())\n";
+    } else if size <= V21MAX {
+        *w += "<::mser::V21 as ::mser::Skip>::skip(n)\n";
+    } else {
+        *w += "<::mser::V32 as ::mser::Skip>::skip(n)\n";
+    }
+    *w += "}\n}\n";
 }
 
 fn write(w: &mut String, f: impl core::fmt::Display) {
@@ -594,10 +594,200 @@ const fn hash64(n: &[u8], seed: u64) -> u64 {
     h
 }
 
+/// ID mapping between a protocol version and the current one.
+#[derive(Clone, Copy, Debug)]
+pub struct Remap {
+    len: u32,
+    to_current: &'static [u32],
+    from_current: &'static [u32],
+}
+
+impl Remap {
+    pub const NONE: u32 = u32::MAX;
+
+    const fn identity(len: u32) -> Self {
+        Self {
+            len,
+            to_current: &[],
+            from_current: &[],
+        }
+    }
+
+    /// Builds a mapping from the IDs of a version to the current ones and
+    /// back, with [`Remap::NONE`] for IDs missing from the other side.
+    #[must_use]
+    pub const fn table(to_current: &'static [u32], from_current: &'static [u32]) -> Self {
+        Self {
+            len: to_current.len() as u32,
+            to_current,
+            from_current,
+        }
+    }
+
+    /// Maps an ID of this version to the current version.
+    #[must_use]
+    pub fn to_current(self, id: u32) -> Option<u32> {
+        if id >= self.len {
+            None
+        } else if self.to_current.is_empty() {
+            Some(id)
+        } else {
+            Some(self.to_current[id as usize]).filter(|&x| x != Self::NONE)
+        }
+    }
+
+    /// Maps an ID of the current version to this version.
+    #[must_use]
+    pub fn from_current(self, id: u32) -> Option<u32> {
+        if self.from_current.is_empty() {
+            Some(id).filter(|&x| x < self.len)
+        } else {
+            self.from_current
+                .get(id as usize)
+                .copied()
+                .filter(|&x| x != Self::NONE)
+        }
+    }
+
+    /// Maps an ID of this version to the version of `to`.
+    #[must_use]
+    pub fn translate(self, to: Self, id: u32) -> Option<u32> {
+        to.from_current(self.to_current(id)?)
+    }
+}
+
+/// IDs of a supported protocol version.
+///
+/// Block states missing from the other version map to the default state of
+/// the same block.
+#[derive(Clone, Copy, Debug)]
+pub struct Version {
+    pub name: &'static str,
+    pub protocol: u32,
+    pub serverbound_handshake: Remap,
+    pub clientbound_status: Remap,
+    pub serverbound_status: Remap,
+    pub clientbound_login: Remap,
+    pub serverbound_login: Remap,
+    pub clientbound_configuration: Remap,
+    pub serverbound_configuration: Remap,
+    pub clientbound_play: Remap,
+    pub serverbound_play: Remap,
+    pub item: Remap,
+    pub entity_type: Remap,
+    pub block_state: Remap,
+}
+
+pub static CURRENT_VERSION: Version = Version {
+    name: NAME_VERSION,
+    protocol: PROTOCOL_VERSION,
+    serverbound_handshake: Remap::identity(serverbound_handshake::MAX as u32 + 1),
+    clientbound_status: Remap::identity(clientbound_status::MAX as u32 + 1),
+    serverbound_status: Remap::identity(serverbound_status::MAX as u32 + 1),
+    clientbound_login: Remap::identity(clientbound_login::MAX as u32 + 1),
+    serverbound_login: Remap::identity(serverbound_login::MAX as u32 + 1),
+    clientbound_configuration: Remap::identity(clientbound_configuration::MAX as u32 + 1),
+    serverbound_configuration: Remap::identity(serverbound_configuration::MAX as u32 + 1),
+    clientbound_play: Remap::identity(clientbound_play::MAX as u32 + 1),
+    serverbound_play: Remap::identity(serverbound_play::MAX as u32 + 1),
+    item: Remap::identity(item::MAX as u32 + 1),
+    entity_type: Remap::identity(entity_type::MAX as u32 + 1),
+    block_state: Remap::identity(block_state::MAX as u32 + 1),
+};
+
+impl Version {
+    /// Looks up a version by protocol number.
+    #[must_use]
+    pub fn get(protocol: u32) -> Option<&'static Self> {
+        VERSIONS.iter().copied().find(|x| x.protocol == protocol)
+    }
+}
+
+macro_rules! version_ids {
+    ($($name:ident, $id:ident;)+) => {
+        impl Version {
+            $(
+                #[doc = concat!("Decodes a `", stringify!($name), "` ID of this version.")]
+                #[must_use]
+                pub fn $name(&self, id: u32) -> Option<$name> {
+                    $name::new(self.$name.to_current(id)?.try_into().ok()?)
+                }
+
+                #[doc = concat!("Encodes a `", stringify!($name), "` as an ID of this version.")]
+                #[must_use]
+                pub fn $id(&self, x: $name) -> Option<u32> {
+                    self.$name.from_current(x.id() as u32)
+                }
+            )+
+        }
+    };
+}
+
+version_ids! {
+    clientbound_configuration, clientbound_configuration_id;
+    serverbound_configuration, serverbound_configuration_id;
+    clientbound_play, clientbound_play_id;
+    serverbound_play, serverbound_play_id;
+    item, item_id;
+    entity_type, entity_type_id;
+    block_state, block_state_id;
+}
+
+/// Tags of a registry, as sent in the `update_tags` packet.
+#[derive(Clone, Copy, Debug)]
+pub struct TagRegistry {
+    pub registry: &'static str,
+    pub names: &'static [&'static str],
+    /// Sorted IDs of the entries of each tag.
+    pub ids: &'static [&'static [u32]],
+}
+
+/// A generated tag such as `block_tag::mineable_pickaxe`.
+pub trait Tag: Copy {
+    const REGISTRY: &'static str;
+
+    fn name(self) -> &'static str;
+
+    /// Sorted IDs of the entries of this tag.
+    fn ids(self) -> &'static [u32];
+}
+
+#[allow(dead_code)]
+const fn tag_contains(ids: &[u32], x: u32) -> bool {
+    let mut lo = 0;
+    let mut hi = ids.len();
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if ids[mid] == x {
+            return true;
+        } else if ids[mid] < x {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    false
+}
+
+/// Default entries of a registry synchronised during configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct SyncedRegistry {
+    pub registry: &'static str,
+    /// Entry identifiers, including their namespace, and their unnamed
+    /// network NBT.
+    pub entries: &'static [(&'static str, &'static [u8])],
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    mod fixture {
+        use super::*;
+
+        include!(concat!(env!("OUT_DIR"), "/test_versions.rs"));
+    }
+
     #[test]
     fn test_parse() {
         assert_eq!(game_event::block_activate.name(), "block_activate");
@@ -737,4 +927,75 @@ mod tests {
         assert!(block::dispenser.state_default().opaque_full_cube().unwrap());
         assert_eq!(block::fire.state_default().opacity().unwrap(), 0);
     }
+
+    #[test]
+    fn test_version() {
+        let v = Version::get(PROTOCOL_VERSION).unwrap();
+        assert_eq!(v.name, NAME_VERSION);
+        assert_eq!(v.item(item::MAX as u32 + 1), None);
+        assert_eq!(v.item_id(item::stone), Some(item::stone as u32));
+        let s = block::oak_log.state_default();
+        assert_eq!(v.block_state(s.id() as u32), Some(s));
+        assert_eq!(
+            v.clientbound_play
+                .translate(v.clientbound_play, clientbound_play::animate as u32),
+            Some(clientbound_play::animate as u32)
+        );
+    }
+
+    #[test]
+    fn test_version_remap() {
+        const NONE: u32 = Remap::NONE;
+        assert_eq!((item::stone as u32, item::granite as u32), (1, 2));
+        // An older version listing granite before stone, with an item that
+        // no longer exists and none of the newer items.
+        let old = Version {
+            name: "old",
+            protocol: PROTOCOL_VERSION - 1,
+            item: Remap::table(&[0, 2, 1, NONE], &[0, 2, 1]),
+            ..CURRENT_VERSION
+        };
+        assert_eq!(old.item(1), Some(item::granite));
+        assert_eq!(old.item(3), None);
+        assert_eq!(old.item(4), None);
+        assert_eq!(old.item_id(item::stone), Some(2));
+        assert_eq!(old.item_id(item::diorite), None);
+        assert_eq!(old.item.translate(CURRENT_VERSION.item, 2), Some(1));
+        assert_eq!(CURRENT_VERSION.item.translate(old.item, 2), Some(1));
+        assert_eq!(old.item.translate(old.item, 3), None);
+    }
+
+    #[test]
+    fn test_version_snapshot() {
+        // testdata/versions/fixture: stone and granite swapped, an item that
+        // was removed since, and an oak log without `axis=z`.
+        let old = &fixture::v1::VERSION;
+        assert_eq!(old.name, "fixture");
+        assert_eq!(old.item(1), Some(item::granite));
+        assert_eq!(old.item(2), Some(item::stone));
+        assert_eq!(old.item(3), None);
+        assert_eq!(old.item_id(item::diorite), None);
+        assert_eq!(old.entity_type(0), Some(entity_type::pig));
+
+        let log = |axis| {
+            let offset = oak_log::new().with_axis(axis).encode() as raw_block_state;
+            block_state::new(block::oak_log.state_index() + offset).unwrap()
+        };
+        assert_eq!(old.block_state(1), Some(block::granite.state_default()));
+        assert_eq!(old.block_state(3), Some(log(prop_axis_x_y_z::x)));
+        assert_eq!(old.block_state(4), Some(log(prop_axis_x_y_z::y)));
+        assert_eq!(old.block_state_id(log(prop_axis_x_y_z::z)), Some(4));
+        assert_eq!(old.block_state_id(block::diorite.state_default()), None);
+
+        assert_eq!(
+            old.clientbound_login.to_current(1),
+            Some(clientbound_login::login_finished as u32)
+        );
+        assert_eq!(
+            old.clientbound_login
+                .from_current(clientbound_login::cookie_request as u32),
+            None
+        );
+        assert_eq!(old.clientbound_play.to_current(0), None);
+    }
 }
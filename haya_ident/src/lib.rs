@@ -7,7 +7,7 @@ use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
 use core::str::from_utf8_unchecked;
 use haya_str::HayaStr;
-use mser::{ByteArray, Error, Read, Reader, V21, Write, Writer};
+use mser::{ByteArray, Error, ErrorKind, Read, Reader, Skip, V21, Write, Writer};
 
 pub const MINECRAFT: &str = "minecraft";
 
@@ -79,6 +79,13 @@ pub struct ResourceKey<'a> {
     pub identifier: Ident<'a>,
 }
 
+impl Skip for ResourceKey<'_> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        Ident::skip(buf)
+    }
+}
+
 impl Write for ResourceKey<'_> {
     unsafe fn write(&self, w: &mut Writer) {
         unsafe { self.identifier.write(w) }
@@ -155,11 +162,19 @@ impl<'a> Read<'a> for Ident<'a> {
         let identifier = ByteArray::<32767>::read(buf)?.0;
         match parse_ident(identifier) {
             Some(Ident { namespace, path }) => Ok(Self { namespace, path }),
-            None => Err(Error),
+            None => Err(buf.error(ErrorKind::Invalid)),
         }
     }
 }
 
+/// Skipping only checks the length, the identifier itself is not parsed.
+impl Skip for Ident<'_> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        ByteArray::<32767>::skip(buf)
+    }
+}
+
 impl Write for Ident<'_> {
     unsafe fn write(&self, w: &mut Writer) {
         unsafe {
@@ -229,11 +244,18 @@ enum Inner {
     Full { namespace: Box<str>, path: Box<str> },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct TagKey<'a> {
     pub location: Ident<'a>,
 }
 
+impl Skip for TagKey<'_> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        Ident::skip(buf)
+    }
+}
+
 impl Write for TagKey<'_> {
     unsafe fn write(&self, w: &mut Writer) {
         unsafe { self.location.write(w) }
@@ -1,7 +1,12 @@
 #![no_std]
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
-use mser::{Error, Read, Reader, V21, V32, Write, Writer};
+extern crate alloc;
+
+mod shape;
+
+pub use self::shape::{Aabb, Axis, BlockHitResult, VoxelShape, collide_boxes};
+use mser::{Error, Read, Reader, Skip, V21, V32, Write, Writer};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Vec3 {
@@ -362,7 +367,7 @@ pub struct BlockPos {
     pub z: i32,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct BlockPosPacked(pub i64);
 
 impl Write for BlockPosPacked {
@@ -383,6 +388,16 @@ impl<'a> Read<'a> for BlockPosPacked {
     }
 }
 
+impl Skip for BlockPosPacked {
+    const SIZE: Option<usize> = Some(8);
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        buf.read_array::<8>()?;
+        Ok(())
+    }
+}
+
 impl BlockPosPacked {
     #[must_use]
     pub const fn to_pos(self) -> BlockPos {
@@ -435,6 +450,16 @@ impl<'a> Read<'a> for ChunkPos {
     }
 }
 
+impl Skip for ChunkPos {
+    const SIZE: Option<usize> = Some(8);
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        buf.read_array::<8>()?;
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ChunkSectionPosPacked(i64);
 
@@ -545,7 +570,7 @@ impl core::ops::MulAssign for BlockPos {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(u8)]
 #[must_use]
 pub enum Direction {
@@ -0,0 +1,611 @@
+use crate::{BlockPos, Direction, Vec3};
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const EPSILON: f64 = 1.0E-7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    #[inline]
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Direction {
+    #[inline]
+    pub const fn axis(self) -> Axis {
+        match self {
+            Self::Down | Self::Up => Axis::Y,
+            Self::North | Self::South => Axis::Z,
+            Self::West | Self::East => Axis::X,
+        }
+    }
+
+    #[inline]
+    pub const fn is_positive(self) -> bool {
+        matches!(self, Self::Up | Self::South | Self::East)
+    }
+
+    #[inline]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Down => Self::Up,
+            Self::Up => Self::Down,
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+}
+
+/// Axis-aligned box stored as `[min_x, min_y, min_z, max_x, max_y, max_z]`,
+/// the layout of the shapes in `minecraft_data`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb(pub [f64; 6]);
+
+impl Aabb {
+    #[inline]
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self([min.x, min.y, min.z, max.x, max.y, max.z])
+    }
+
+    #[inline]
+    pub const fn min(&self, axis: Axis) -> f64 {
+        self.0[axis.index()]
+    }
+
+    #[inline]
+    pub const fn max(&self, axis: Axis) -> f64 {
+        self.0[axis.index() + 3]
+    }
+
+    #[must_use]
+    pub const fn move_by(self, x: f64, y: f64, z: f64) -> Self {
+        let [a, b, c, d, e, f] = self.0;
+        Self([a + x, b + y, c + z, d + x, e + y, f + z])
+    }
+
+    #[must_use]
+    pub fn expand_towards(self, delta: Vec3) -> Self {
+        let [mut a, mut b, mut c, mut d, mut e, mut f] = self.0;
+        if delta.x < 0.0 {
+            a += delta.x;
+        } else {
+            d += delta.x;
+        }
+        if delta.y < 0.0 {
+            b += delta.y;
+        } else {
+            e += delta.y;
+        }
+        if delta.z < 0.0 {
+            c += delta.z;
+        } else {
+            f += delta.z;
+        }
+        Self([a, b, c, d, e, f])
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.0[0] < other.0[3]
+            && self.0[3] > other.0[0]
+            && self.0[1] < other.0[4]
+            && self.0[4] > other.0[1]
+            && self.0[2] < other.0[5]
+            && self.0[5] > other.0[2]
+    }
+
+    pub fn contains(&self, p: Vec3) -> bool {
+        p.x >= self.0[0]
+            && p.x < self.0[3]
+            && p.y >= self.0[1]
+            && p.y < self.0[4]
+            && p.z >= self.0[2]
+            && p.z < self.0[5]
+    }
+
+    /// Entry point of the segment `from..to` into this box, as a fraction of
+    /// the segment and the face that was hit.
+    pub fn clip(&self, from: Vec3, to: Vec3) -> Option<(f64, Direction)> {
+        let mut t = 1.0;
+        let mut hit = None;
+        let delta = [to.x - from.x, to.y - from.y, to.z - from.z];
+        let start = [from.x, from.y, from.z];
+        for (axis, (neg, pos)) in [
+            (Direction::West, Direction::East),
+            (Direction::Down, Direction::Up),
+            (Direction::North, Direction::South),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (plane, dir) = if delta[axis] > EPSILON {
+                (self.0[axis], neg)
+            } else if delta[axis] < -EPSILON {
+                (self.0[axis + 3], pos)
+            } else {
+                continue;
+            };
+            let s = (plane - start[axis]) / delta[axis];
+            if s <= 0.0 || s >= t {
+                continue;
+            }
+            let inside = (0..3).filter(|&x| x != axis).all(|x| {
+                let v = start[x] + s * delta[x];
+                self.0[x] - EPSILON < v && v < self.0[x + 3] + EPSILON
+            });
+            if inside {
+                t = s;
+                hit = Some(dir);
+            }
+        }
+        hit.map(|dir| (t, dir))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BlockHitResult {
+    pub location: Vec3,
+    pub direction: Direction,
+    pub pos: BlockPos,
+    pub inside: bool,
+}
+
+/// Union of boxes relative to a block origin.
+#[derive(Clone, Debug)]
+pub struct VoxelShape<'a> {
+    boxes: Cow<'a, [[f64; 6]]>,
+}
+
+impl<'a> VoxelShape<'a> {
+    pub const EMPTY: VoxelShape<'static> = VoxelShape {
+        boxes: Cow::Borrowed(&[]),
+    };
+
+    pub const BLOCK: VoxelShape<'static> = VoxelShape {
+        boxes: Cow::Borrowed(&[[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]]),
+    };
+
+    /// Wraps boxes such as `block_state::collision_shape()`.
+    #[inline]
+    pub const fn new(boxes: &'a [[f64; 6]]) -> Self {
+        Self {
+            boxes: Cow::Borrowed(boxes),
+        }
+    }
+
+    #[inline]
+    pub fn boxes(&self) -> impl ExactSizeIterator<Item = Aabb> + '_ {
+        self.boxes.iter().map(|&x| Aabb(x))
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<Aabb> {
+        let mut iter = self.boxes.iter();
+        let mut b = *iter.next()?;
+        for x in iter {
+            for i in 0..3 {
+                b[i] = b[i].min(x[i]);
+                b[i + 3] = b[i + 3].max(x[i + 3]);
+            }
+        }
+        Some(Aabb(b))
+    }
+
+    pub fn min(&self, axis: Axis) -> f64 {
+        self.bounds().map_or(f64::INFINITY, |x| x.min(axis))
+    }
+
+    pub fn max(&self, axis: Axis) -> f64 {
+        self.bounds().map_or(f64::NEG_INFINITY, |x| x.max(axis))
+    }
+
+    #[must_use]
+    pub fn into_owned(self) -> VoxelShape<'static> {
+        VoxelShape {
+            boxes: Cow::Owned(self.boxes.into_owned()),
+        }
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &VoxelShape<'_>) -> VoxelShape<'static> {
+        join(&self.boxes, &other.boxes, |a, b| a || b)
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &VoxelShape<'_>) -> VoxelShape<'static> {
+        join(&self.boxes, &other.boxes, |a, b| a && b)
+    }
+
+    /// The part of `self` not covered by `other`.
+    #[must_use]
+    pub fn difference(&self, other: &VoxelShape<'_>) -> VoxelShape<'static> {
+        join(&self.boxes, &other.boxes, |a, b| a && !b)
+    }
+
+    pub fn intersects(&self, other: &VoxelShape<'_>) -> bool {
+        join_is_not_empty(&self.boxes, &other.boxes, |a, b| a && b)
+    }
+
+    /// Whether the shape is exactly the unit cube, nothing less or more.
+    pub fn is_full_block(&self) -> bool {
+        !join_is_not_empty(&Self::BLOCK.boxes, &self.boxes, |a, b| a != b)
+    }
+
+    /// The boxes touching the `direction` face, stretched over the whole
+    /// block along its axis.
+    #[must_use]
+    pub fn face(&self, direction: Direction) -> VoxelShape<'static> {
+        let axis = direction.axis().index();
+        let mut boxes = Vec::new();
+        for &x in self.boxes.iter() {
+            let touches = if direction.is_positive() {
+                (x[axis + 3] - 1.0).abs() < EPSILON
+            } else {
+                x[axis].abs() < EPSILON
+            };
+            if touches {
+                let mut y = x;
+                y[axis] = 0.0;
+                y[axis + 3] = 1.0;
+                boxes.push(y);
+            }
+        }
+        join(&boxes, &[], |a, _| a)
+    }
+
+    /// Whether the `direction` face of `self` is fully covered by the
+    /// opposite face of `neighbor`, as in `Shapes.blockOccludes`.
+    pub fn face_occluded_by(&self, neighbor: &VoxelShape<'_>, direction: Direction) -> bool {
+        if self.is_full_block() && neighbor.is_full_block() {
+            return true;
+        }
+        if neighbor.is_empty() {
+            return false;
+        }
+        let axis = direction.axis();
+        let (first, second) = if direction.is_positive() {
+            (self, neighbor)
+        } else {
+            (neighbor, self)
+        };
+        if (first.max(axis) - 1.0).abs() >= EPSILON || second.min(axis).abs() >= EPSILON {
+            return false;
+        }
+        let a = self.face(direction);
+        let b = neighbor.face(direction.opposite());
+        !join_is_not_empty(&a.boxes, &b.boxes, |x, y| x && !y)
+    }
+
+    /// Clamps the movement `delta` of `aabb` along `axis` so it stops at
+    /// this shape. `aabb` is relative to the shape origin.
+    pub fn collide(&self, axis: Axis, aabb: &Aabb, mut delta: f64) -> f64 {
+        if delta.abs() < EPSILON {
+            return 0.0;
+        }
+        let i = axis.index();
+        let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+        for x in self.boxes.iter() {
+            if x[j + 3] <= aabb.0[j] + EPSILON
+                || x[j] >= aabb.0[j + 3] - EPSILON
+                || x[k + 3] <= aabb.0[k] + EPSILON
+                || x[k] >= aabb.0[k + 3] - EPSILON
+            {
+                continue;
+            }
+            if delta > 0.0 && x[i] >= aabb.0[i + 3] - EPSILON {
+                delta = delta.min(x[i] - aabb.0[i + 3]);
+            } else if delta < 0.0 && x[i + 3] <= aabb.0[i] + EPSILON {
+                delta = delta.max(x[i + 3] - aabb.0[i]);
+            }
+        }
+        delta
+    }
+
+    /// Traces the segment `from..to` against this shape placed at `pos`.
+    pub fn clip(&self, from: Vec3, to: Vec3, pos: BlockPos) -> Option<BlockHitResult> {
+        if self.is_empty() {
+            return None;
+        }
+        let delta = Vec3 {
+            x: to.x - from.x,
+            y: to.y - from.y,
+            z: to.z - from.z,
+        };
+        if delta.x * delta.x + delta.y * delta.y + delta.z * delta.z < EPSILON {
+            return None;
+        }
+        let (x, y, z) = (pos.x as f64, pos.y as f64, pos.z as f64);
+        let start = Vec3 {
+            x: from.x + delta.x * 0.001,
+            y: from.y + delta.y * 0.001,
+            z: from.z + delta.z * 0.001,
+        };
+        if self.boxes().any(|b| b.move_by(x, y, z).contains(start)) {
+            return Some(BlockHitResult {
+                location: start,
+                direction: nearest(delta).opposite(),
+                pos,
+                inside: true,
+            });
+        }
+        let mut best: Option<(f64, Direction)> = None;
+        for b in self.boxes() {
+            if let Some((t, dir)) = b.move_by(x, y, z).clip(from, to)
+                && best.is_none_or(|(u, _)| t < u)
+            {
+                best = Some((t, dir));
+            }
+        }
+        best.map(|(t, direction)| BlockHitResult {
+            location: Vec3 {
+                x: from.x + delta.x * t,
+                y: from.y + delta.y * t,
+                z: from.z + delta.z * t,
+            },
+            direction,
+            pos,
+            inside: false,
+        })
+    }
+}
+
+/// Resolves `movement` of `aabb` against world-space boxes, in the axis order
+/// of `Entity.collideWithShapes`.
+pub fn collide_boxes(movement: Vec3, aabb: &Aabb, boxes: &[Aabb]) -> Vec3 {
+    let raw = boxes.iter().map(|x| x.0).collect::<Vec<_>>();
+    let shape = VoxelShape::new(&raw);
+    let mut aabb = *aabb;
+    let mut out = Vec3::ZERO;
+    let order = if movement.x.abs() < movement.z.abs() {
+        [Axis::Y, Axis::Z, Axis::X]
+    } else {
+        [Axis::Y, Axis::X, Axis::Z]
+    };
+    for axis in order {
+        let delta = match axis {
+            Axis::X => movement.x,
+            Axis::Y => movement.y,
+            Axis::Z => movement.z,
+        };
+        if delta == 0.0 {
+            continue;
+        }
+        let d = shape.collide(axis, &aabb, delta);
+        match axis {
+            Axis::X => {
+                out.x = d;
+                aabb = aabb.move_by(d, 0.0, 0.0);
+            }
+            Axis::Y => {
+                out.y = d;
+                aabb = aabb.move_by(0.0, d, 0.0);
+            }
+            Axis::Z => {
+                out.z = d;
+                aabb = aabb.move_by(0.0, 0.0, d);
+            }
+        }
+    }
+    out
+}
+
+fn nearest(v: Vec3) -> Direction {
+    let (ax, ay, az) = (v.x.abs(), v.y.abs(), v.z.abs());
+    if ax >= ay && ax >= az {
+        if v.x < 0.0 {
+            Direction::West
+        } else {
+            Direction::East
+        }
+    } else if ay >= az {
+        if v.y < 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        }
+    } else if v.z < 0.0 {
+        Direction::North
+    } else {
+        Direction::South
+    }
+}
+
+struct Grid {
+    coords: [Vec<f64>; 3],
+}
+
+impl Grid {
+    fn new(a: &[[f64; 6]], b: &[[f64; 6]]) -> Self {
+        let coords = [0, 1, 2].map(|axis| {
+            let mut v = a
+                .iter()
+                .chain(b)
+                .flat_map(|x| [x[axis], x[axis + 3]])
+                .collect::<Vec<_>>();
+            v.sort_unstable_by(f64::total_cmp);
+            v.dedup();
+            v
+        });
+        Self { coords }
+    }
+
+    fn size(&self) -> [usize; 3] {
+        self.coords.each_ref().map(|x| x.len().saturating_sub(1))
+    }
+
+    fn center(&self, i: usize, j: usize, k: usize) -> [f64; 3] {
+        let [x, y, z] = &self.coords;
+        [
+            (x[i] + x[i + 1]) * 0.5,
+            (y[j] + y[j + 1]) * 0.5,
+            (z[k] + z[k + 1]) * 0.5,
+        ]
+    }
+}
+
+fn covers(boxes: &[[f64; 6]], p: [f64; 3]) -> bool {
+    boxes.iter().any(|b| {
+        b[0] < p[0] && p[0] < b[3] && b[1] < p[1] && p[1] < b[4] && b[2] < p[2] && p[2] < b[5]
+    })
+}
+
+fn join_is_not_empty(a: &[[f64; 6]], b: &[[f64; 6]], op: impl Fn(bool, bool) -> bool) -> bool {
+    let grid = Grid::new(a, b);
+    let [nx, ny, nz] = grid.size();
+    for i in 0..nx {
+        for j in 0..ny {
+            for k in 0..nz {
+                let p = grid.center(i, j, k);
+                if op(covers(a, p), covers(b, p)) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Combines two shapes cell by cell and merges the result into boxes.
+fn join(a: &[[f64; 6]], b: &[[f64; 6]], op: impl Fn(bool, bool) -> bool) -> VoxelShape<'static> {
+    let grid = Grid::new(a, b);
+    let [nx, ny, nz] = grid.size();
+    let index = |i: usize, j: usize, k: usize| (i * ny + j) * nz + k;
+    let mut cells = vec![false; nx * ny * nz];
+    for i in 0..nx {
+        for j in 0..ny {
+            for k in 0..nz {
+                let p = grid.center(i, j, k);
+                cells[index(i, j, k)] = op(covers(a, p), covers(b, p));
+            }
+        }
+    }
+    let mut boxes = Vec::new();
+    for i in 0..nx {
+        for j in 0..ny {
+            for k in 0..nz {
+                if !cells[index(i, j, k)] {
+                    continue;
+                }
+                let mut k2 = k + 1;
+                while k2 < nz && cells[index(i, j, k2)] {
+                    k2 += 1;
+                }
+                let mut j2 = j + 1;
+                while j2 < ny && (k..k2).all(|z| cells[index(i, j2, z)]) {
+                    j2 += 1;
+                }
+                let mut i2 = i + 1;
+                while i2 < nx && (j..j2).all(|y| (k..k2).all(|z| cells[index(i2, y, z)])) {
+                    i2 += 1;
+                }
+                for x in i..i2 {
+                    for y in j..j2 {
+                        for z in k..k2 {
+                            cells[index(x, y, z)] = false;
+                        }
+                    }
+                }
+                let [xs, ys, zs] = &grid.coords;
+                boxes.push([xs[i], ys[j], zs[k], xs[i2], ys[j2], zs[k2]]);
+            }
+        }
+    }
+    VoxelShape {
+        boxes: Cow::Owned(boxes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLAB: &[[f64; 6]] = &[[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]];
+    const TOP: &[[f64; 6]] = &[[0.0, 0.5, 0.0, 1.0, 1.0, 1.0]];
+
+    #[test]
+    fn test_join() {
+        let slab = VoxelShape::new(SLAB);
+        let top = VoxelShape::new(TOP);
+        let full = slab.union(&top);
+        assert!(full.is_full_block());
+        assert_eq!(full.boxes().len(), 1);
+        assert!(!slab.is_full_block());
+        assert!(slab.intersection(&top).is_empty());
+        assert!(!slab.intersects(&top));
+        assert!(!VoxelShape::BLOCK.difference(&slab).is_full_block());
+        let tall = VoxelShape::new(&[[0.0, 0.0, 0.0, 1.0, 1.5, 1.0]]);
+        assert!(!tall.is_full_block());
+        assert!(!full.union(&tall).is_full_block());
+        assert_eq!(
+            VoxelShape::BLOCK.difference(&slab).bounds(),
+            Some(Aabb(TOP[0]))
+        );
+    }
+
+    #[test]
+    fn test_occlusion() {
+        let slab = VoxelShape::new(SLAB);
+        let block = VoxelShape::BLOCK;
+        assert!(slab.face_occluded_by(&block, Direction::Down));
+        assert!(!slab.face_occluded_by(&block, Direction::Up));
+        assert!(!block.face_occluded_by(&slab, Direction::North));
+        assert!(block.face_occluded_by(&block, Direction::East));
+        assert!(!block.face_occluded_by(&VoxelShape::EMPTY, Direction::East));
+    }
+
+    #[test]
+    fn test_collide() {
+        let slab = VoxelShape::new(SLAB);
+        let player = Aabb([0.2, 1.0, 0.2, 0.8, 2.8, 0.8]);
+        assert_eq!(slab.collide(Axis::Y, &player, -2.0), -0.5);
+        assert_eq!(slab.collide(Axis::Y, &player, 1.0), 1.0);
+        assert_eq!(slab.collide(Axis::X, &player, 1.0), 1.0);
+        let side = Aabb([1.0, 0.0, 0.2, 1.6, 1.8, 0.8]);
+        assert_eq!(slab.collide(Axis::X, &side, -0.5), 0.0);
+
+        let boxes = [Aabb(SLAB[0])];
+        let v = collide_boxes(
+            Vec3 {
+                x: 0.5,
+                y: -2.0,
+                z: 0.0,
+            },
+            &player,
+            &boxes,
+        );
+        assert_eq!((v.x, v.y, v.z), (0.5, -0.5, 0.0));
+
+        let hit = slab
+            .clip(
+                Vec3 {
+                    x: 0.5,
+                    y: 5.0,
+                    z: 0.5,
+                },
+                Vec3 {
+                    x: 0.5,
+                    y: -5.0,
+                    z: 0.5,
+                },
+                BlockPos { x: 0, y: 0, z: 0 },
+            )
+            .unwrap();
+        assert!(hit.direction == Direction::Up);
+        assert_eq!(hit.location.y, 0.5);
+        assert!(!hit.inside);
+    }
+}
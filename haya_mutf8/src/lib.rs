@@ -1,7 +1,7 @@
 #![no_std]
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
-use mser::{Error, Writer};
+use mser::{Error, ErrorKind, Writer};
 
 const CHAR_WIDTH: &[u8; 256] = &[
     // 1  2  3  4  5  6  7  8  9  A  B  C  D  E  F
@@ -120,28 +120,28 @@ pub fn decode_mutf8_len(mut bytes: &[u8]) -> Result<usize, Error> {
                     if sec == 0x80 {
                         len += 1;
                     } else {
-                        return Err(Error);
+                        return Err(Error::new(ErrorKind::Utf8));
                     }
                 } else {
-                    return Err(Error);
+                    return Err(Error::new(ErrorKind::Utf8));
                 }
             }
             0xC2..=0xDF => {
                 if let [sec, ref rest @ ..] = bytes[..] {
                     bytes = rest;
                     if sec as i8 >= -64 {
-                        return Err(Error);
+                        return Err(Error::new(ErrorKind::Utf8));
                     }
                     len += 2;
                 } else {
-                    return Err(Error);
+                    return Err(Error::new(ErrorKind::Utf8));
                 }
             }
             0xE0..=0xEF => match bytes[..] {
                 [sec, third, ref rest @ ..] => {
                     bytes = rest;
                     if sec & 0xC0 != 0x80 || third & 0xC0 != 0x80 {
-                        return Err(Error);
+                        return Err(Error::new(ErrorKind::Utf8));
                     }
                     match (byte, sec) {
                         (0xE0, 0xA0..=0xBF)
@@ -153,25 +153,25 @@ pub fn decode_mutf8_len(mut bytes: &[u8]) -> Result<usize, Error> {
                             [fourth, fifth, sixth, ref rest @ ..] => {
                                 bytes = rest;
                                 if fourth != 0xED {
-                                    return Err(Error);
+                                    return Err(Error::new(ErrorKind::Utf8));
                                 }
                                 match fifth {
                                     0xB0..=0xBF => (),
-                                    _ => return Err(Error),
+                                    _ => return Err(Error::new(ErrorKind::Utf8)),
                                 }
                                 if sixth & 0xC0 != 0x80 {
-                                    return Err(Error);
+                                    return Err(Error::new(ErrorKind::Utf8));
                                 }
                                 len += 4;
                             }
-                            _ => return Err(Error),
+                            _ => return Err(Error::new(ErrorKind::Utf8)),
                         },
-                        _ => return Err(Error),
+                        _ => return Err(Error::new(ErrorKind::Utf8)),
                     }
                 }
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Utf8)),
             },
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Utf8)),
         }
     }
     Ok(len)
@@ -189,7 +189,7 @@ pub unsafe fn decode_mutf8(bytes: &[u8], w: &mut Writer) -> Result<(), Error> {
             0xC0 => unsafe {
                 let sec = match bytes.get(index + 1) {
                     Some(&byte) => byte,
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Utf8)),
                 };
                 if sec == 0x80 {
                     w.write(bytes.get_unchecked(start..index));
@@ -197,27 +197,27 @@ pub unsafe fn decode_mutf8(bytes: &[u8], w: &mut Writer) -> Result<(), Error> {
                     index += 2;
                     start = index;
                 } else {
-                    return Err(Error);
+                    return Err(Error::new(ErrorKind::Utf8));
                 }
             },
             0xC2..=0xDF => {
                 let sec = match bytes.get(index + 1) {
                     Some(&byte) => byte,
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Utf8)),
                 };
                 if sec as i8 >= -64 {
-                    return Err(Error);
+                    return Err(Error::new(ErrorKind::Utf8));
                 }
                 index += 2;
             }
             0xE0..=0xEF => unsafe {
                 let sec = match bytes.get(index + 1) {
                     Some(&byte) if byte & 0xC0 == 0x80 => byte,
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Utf8)),
                 };
                 let third = match bytes.get(index + 2) {
                     Some(&byte) if byte & 0xC0 == 0x80 => byte,
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Utf8)),
                 };
                 match (byte, sec) {
                     (0xE0, 0xA0..=0xBF)
@@ -228,15 +228,15 @@ pub unsafe fn decode_mutf8(bytes: &[u8], w: &mut Writer) -> Result<(), Error> {
                     (0xED, 0xA0..=0xAF) => {
                         match bytes.get(index + 3) {
                             Some(0xED) => (),
-                            _ => return Err(Error),
+                            _ => return Err(Error::new(ErrorKind::Utf8)),
                         };
                         let fifth = match bytes.get(index + 4) {
                             Some(&x @ 0xB0..=0xBF) => x & 0x3F,
-                            _ => return Err(Error),
+                            _ => return Err(Error::new(ErrorKind::Utf8)),
                         };
                         let sixth = match bytes.get(index + 5) {
                             Some(&x) if x & 0xC0 == 0x80 => x & 0x3F,
-                            _ => return Err(Error),
+                            _ => return Err(Error::new(ErrorKind::Utf8)),
                         };
                         let s1 = 0xD000 | (u32::from(sec & 0x3F) << 6) | u32::from(third & 0x3F);
                         let s2 = 0xD000 | (u32::from(fifth) << 6) | u32::from(sixth);
@@ -251,10 +251,10 @@ pub unsafe fn decode_mutf8(bytes: &[u8], w: &mut Writer) -> Result<(), Error> {
                         index += 6;
                         start = index;
                     }
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Utf8)),
                 }
             },
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Utf8)),
         }
     }
 
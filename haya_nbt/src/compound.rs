@@ -1,6 +1,7 @@
-use crate::{CompoundTag, StringTag, Tag, read_tag};
+use crate::limits::Accounter;
+use crate::{CompoundTag, NbtLimits, StringTag, Tag, read_tag};
 use alloc::vec::Vec;
-use mser::{Error, Read, Reader, Write, Writer};
+use mser::{Error, ErrorKind, Read, Reader, Write, Writer};
 
 impl AsRef<[(StringTag, Tag)]> for CompoundTag {
     #[inline]
@@ -157,17 +158,26 @@ impl CompoundTag {
     }
 }
 
-impl Read<'_> for CompoundTag {
-    #[inline]
-    fn read(buf: &mut Reader) -> Result<Self, Error> {
-        match read_tag(buf, crate::ReadEntry::Compound(Self::new()), 512) {
+impl CompoundTag {
+    /// Reads an unnamed compound, charging it against `limits`.
+    pub fn read_limited(buf: &mut Reader, limits: NbtLimits) -> Result<Self, Error> {
+        let mut acc = Accounter::new(limits);
+        acc.compound(buf)?;
+        match read_tag(buf, crate::ReadEntry::Compound(Self::new()), &mut acc) {
             Ok(Tag::Compound(x)) => Ok(x),
-            Ok(_) => Err(Error),
+            Ok(_) => Err(buf.error(ErrorKind::Invalid)),
             Err(e) => Err(e),
         }
     }
 }
 
+impl Read<'_> for CompoundTag {
+    #[inline]
+    fn read(buf: &mut Reader) -> Result<Self, Error> {
+        Self::read_limited(buf, NbtLimits::NETWORK)
+    }
+}
+
 impl From<Vec<(StringTag, Tag)>> for CompoundTag {
     #[inline]
     fn from(value: Vec<(StringTag, Tag)>) -> Self {
@@ -2,20 +2,30 @@
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
 extern crate alloc;
+#[cfg(test)]
+extern crate self as haya_nbt;
 
 mod byte_array;
 mod compound;
 mod int_array;
+mod limits;
 mod list;
 mod long_array;
 mod number;
+mod skip;
 mod string;
 mod stringify;
+mod tag_ref;
 mod unicode;
 
 use self::byte_array::ByteArray;
 use self::int_array::IntArray;
+use self::limits::Accounter;
+pub use self::limits::NbtLimits;
 use self::long_array::LongArray;
+pub use self::tag_ref::{
+    CompoundIter, CompoundRef, IntArrayRef, ListIter, ListRef, LongArrayRef, StringRef, TagRef,
+};
 pub use self::unicode::{character, name};
 use alloc::boxed::Box;
 use alloc::string::String;
@@ -23,7 +33,7 @@ use alloc::vec;
 use alloc::vec::Vec;
 use haya_ident::{Identifier, parse_ident};
 use haya_str::HayaStr;
-use mser::{Error, Read, Reader, Write, Writer};
+use mser::{Error, ErrorKind, Read, Reader, Write, Writer};
 use uuid::Uuid;
 
 pub trait Deserialize: Sized {
@@ -34,6 +44,38 @@ pub trait Serialize {
     fn serialize(&self) -> Tag;
 }
 
+/// A value that always serializes to a compound, and can therefore be
+/// flattened into another one by the `NbtSerialize` derive.
+///
+/// ```compile_fail
+/// #[derive(mser_macro::NbtSerialize)]
+/// struct Loose {
+///     #[nbt(flatten)]
+///     rest: i64,
+/// }
+/// ```
+pub trait SerializeCompound: Serialize {
+    fn serialize_compound(&self) -> CompoundTag;
+}
+
+impl<T: Serialize + ?Sized> Serialize for Box<T> {
+    fn serialize(&self) -> Tag {
+        (**self).serialize()
+    }
+}
+
+impl<T: SerializeCompound + ?Sized> SerializeCompound for Box<T> {
+    fn serialize_compound(&self) -> CompoundTag {
+        (**self).serialize_compound()
+    }
+}
+
+impl<T: Deserialize> Deserialize for Box<T> {
+    fn deserialize(nbt: Tag) -> Result<Self, Error> {
+        T::deserialize(nbt).map(Self::new)
+    }
+}
+
 impl Serialize for StringTag {
     fn serialize(&self) -> Tag {
         Tag::String(self.clone())
@@ -44,7 +86,7 @@ impl Deserialize for StringTag {
     fn deserialize(nbt: Tag) -> Result<Self, Error> {
         match nbt {
             Tag::String(string_tag) => Ok(string_tag),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -64,7 +106,7 @@ impl Deserialize for bool {
             Tag::Long(x) => Ok(x != 0),
             Tag::Float(x) => Ok(x != 0.0),
             Tag::Double(x) => Ok(x != 0.0),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -105,9 +147,9 @@ impl Deserialize for Identifier {
         match nbt {
             Tag::String(s) => match parse_ident(s.as_bytes()) {
                 Some(x) => Ok(x.to_identifier()),
-                None => Err(Error),
+                None => Err(Error::new(ErrorKind::Invalid)),
             },
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -125,7 +167,7 @@ impl Deserialize for ListTag {
             Tag::ByteArray(x) => Ok(Self::Byte(x)),
             Tag::IntArray(x) => Ok(Self::Int(x)),
             Tag::LongArray(x) => Ok(Self::Long(x)),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -153,7 +195,7 @@ impl Deserialize for Vec<i8> {
         match nbt {
             Tag::ByteArray(x) => Ok(x),
             Tag::List(ListTag::Byte(x)) => Ok(x),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -163,7 +205,7 @@ impl Deserialize for Vec<i32> {
         match nbt {
             Tag::IntArray(x) => Ok(x),
             Tag::List(ListTag::Int(x)) => Ok(x),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -173,7 +215,7 @@ impl Deserialize for Vec<i64> {
         match nbt {
             Tag::LongArray(x) => Ok(x),
             Tag::List(ListTag::Long(x)) => Ok(x),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -211,7 +253,7 @@ impl Deserialize for Uuid {
         let vec = match nbt {
             Tag::IntArray(x) => x,
             Tag::List(ListTag::Int(x)) => x,
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         };
         match vec[..] {
             [a, b, c, d] => {
@@ -223,7 +265,7 @@ impl Deserialize for Uuid {
                     b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11, b12, b13, b14, b15, b16,
                 ]))
             }
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -237,7 +279,7 @@ impl Deserialize for i32 {
             Tag::Long(x) => Ok(x as Self),
             Tag::Float(x) => Ok(x as Self),
             Tag::Double(x) => Ok(x as Self),
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -248,6 +290,111 @@ impl Serialize for i32 {
     }
 }
 
+/// Integers accept any integer tag whose value fits, without truncation.
+macro_rules! integer {
+    ($($ty:ident => $tag:ident),*) => {
+        $(
+            impl Deserialize for $ty {
+                fn deserialize(nbt: Tag) -> Result<Self, Error> {
+                    let x = match nbt {
+                        Tag::Byte(x) => Self::try_from(x).ok(),
+                        Tag::Short(x) => Self::try_from(x).ok(),
+                        Tag::Int(x) => Self::try_from(x).ok(),
+                        Tag::Long(x) => Self::try_from(x).ok(),
+                        _ => None,
+                    };
+                    match x {
+                        Some(x) => Ok(x),
+                        None => Err(Error::new(ErrorKind::Invalid)),
+                    }
+                }
+            }
+
+            impl Serialize for $ty {
+                fn serialize(&self) -> Tag {
+                    Tag::$tag(*self)
+                }
+            }
+        )*
+    };
+}
+
+integer!(i8 => Byte, i16 => Short, i64 => Long);
+
+impl Deserialize for f32 {
+    fn deserialize(nbt: Tag) -> Result<Self, Error> {
+        match nbt {
+            Tag::Float(x) => Ok(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+}
+
+impl Serialize for f32 {
+    fn serialize(&self) -> Tag {
+        Tag::Float(*self)
+    }
+}
+
+impl Deserialize for f64 {
+    fn deserialize(nbt: Tag) -> Result<Self, Error> {
+        match nbt {
+            Tag::Float(x) => Ok(x as Self),
+            Tag::Double(x) => Ok(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+}
+
+impl Serialize for f64 {
+    fn serialize(&self) -> Tag {
+        Tag::Double(*self)
+    }
+}
+
+impl Serialize for CompoundTag {
+    fn serialize(&self) -> Tag {
+        Tag::Compound(self.clone())
+    }
+}
+
+impl SerializeCompound for CompoundTag {
+    fn serialize_compound(&self) -> CompoundTag {
+        self.clone()
+    }
+}
+
+impl Deserialize for CompoundTag {
+    fn deserialize(nbt: Tag) -> Result<Self, Error> {
+        match nbt {
+            Tag::Compound(x) => Ok(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+}
+
+/// `with` helpers for UUIDs stored as four ints, also accepting the
+/// hyphenated string form written by older versions.
+pub mod lenient_uuid {
+    use crate::{Deserialize, Serialize, Tag};
+    use mser::{Error, ErrorKind};
+    use uuid::Uuid;
+
+    pub fn serialize(id: &Uuid) -> Tag {
+        id.serialize()
+    }
+
+    pub fn deserialize(nbt: Tag) -> Result<Uuid, Error> {
+        match nbt {
+            Tag::String(x) => match Uuid::try_parse(&x) {
+                Ok(id) => Ok(id),
+                Err(_) => Err(Error::new(ErrorKind::Invalid)),
+            },
+            x => Uuid::deserialize(x),
+        }
+    }
+}
+
 impl Serialize for Tag {
     fn serialize(&self) -> Tag {
         self.clone()
@@ -284,16 +431,16 @@ pub struct RefStringTag<'a>(pub &'a str);
 #[derive(Clone, Copy)]
 pub struct RawStringTag<'a>(&'a str);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct CompoundTag(Vec<(StringTag, Tag)>);
 
 #[derive(Clone)]
 pub struct CompoundStringify(pub CompoundTag);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct StringTag(Inner);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 enum Inner {
     Thin(HayaStr),
     Heap(Box<str>),
@@ -302,7 +449,7 @@ enum Inner {
 #[derive(Clone, Copy)]
 pub struct ListInfo(pub TagType, pub u32);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum ListTag {
     None,
     Byte(Vec<i8>),
@@ -328,7 +475,7 @@ impl TagType {
             Self::Long => Ok(i64::read(buf)? != 0),
             Self::Float => Ok(f32::read(buf)? != 0.0),
             Self::Double => Ok(f64::read(buf)? != 0.0),
-            _ => Err(Error),
+            _ => Err(buf.error(ErrorKind::Invalid)),
         }
     }
 
@@ -338,7 +485,7 @@ impl TagType {
                 Ok(x) => Ok(x),
                 Err(e) => Err(e),
             },
-            _ => Err(Error),
+            _ => Err(buf.error(ErrorKind::Invalid)),
         }
     }
 
@@ -352,50 +499,66 @@ impl TagType {
                     Self::Int => unsafe {
                         Ok(list::int_list(
                             len2,
-                            buf.read_slice(len2.checked_mul(4).ok_or(Error)?)?,
+                            buf.read_slice(
+                                len2.checked_mul(4)
+                                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                            )?,
                         ))
                     },
-                    _ => Err(Error),
+                    _ => Err(buf.error(ErrorKind::Invalid)),
                 }
             }
-            _ => Err(Error),
+            _ => Err(buf.error(ErrorKind::Invalid)),
         }
     }
 
-    fn tag_no_rec(self, n: &mut Reader) -> Result<Tag, Error> {
-        Ok(match self {
+    fn tag_no_rec(self, n: &mut Reader, acc: &mut Accounter) -> Result<Tag, Error> {
+        let tag = match self {
             Self::Byte => Tag::from(i8::read(n)?),
             Self::Short => Tag::from(i16::read(n)?),
             Self::Int => Tag::from(i32::read(n)?),
             Self::Long => Tag::from(i64::read(n)?),
             Self::Float => Tag::from(f32::read(n)?),
             Self::Double => Tag::from(f64::read(n)?),
-            Self::ByteArray => Tag::from(ByteArray::read(n)?.0),
+            Self::ByteArray => {
+                acc.array(n, list::array_len(n)?, 1)?;
+                return Ok(Tag::from(ByteArray::read(n)?.0));
+            }
             Self::String => Tag::from(StringTag::read(n)?),
-            Self::IntArray => Tag::from(IntArray::read(n)?.0),
-            Self::LongArray => Tag::from(LongArray::read(n)?.0),
+            Self::IntArray => {
+                acc.array(n, list::array_len(n)?, 4)?;
+                return Ok(Tag::from(IntArray::read(n)?.0));
+            }
+            Self::LongArray => {
+                acc.array(n, list::array_len(n)?, 8)?;
+                return Ok(Tag::from(LongArray::read(n)?.0));
+            }
             Self::List => Tag::List(ListTag::None),
             Self::Compound => Tag::Compound(CompoundTag::new()),
             Self::End => Tag::End,
-        })
+        };
+        acc.tag(n, &tag)?;
+        Ok(tag)
     }
 }
 
 impl TagType {
     pub fn tag(self, n: &mut Reader) -> Result<Tag, Error> {
-        let t = match self.tag_no_rec(n)? {
-            Tag::List(ListTag::None) => {
-                let l = ListInfo::read(n)?;
-                match l.list_no_rec(n)? {
-                    ListTag::Compound(x) => ReadEntry::ListCompound(x, l.1),
-                    ListTag::List(x) => ReadEntry::ListList(x, 1),
-                    x => return Ok(Tag::List(x)),
-                }
-            }
+        self.tag_limited(n, NbtLimits::NETWORK)
+    }
+
+    pub fn tag_limited(self, n: &mut Reader, limits: NbtLimits) -> Result<Tag, Error> {
+        let mut acc = Accounter::new(limits);
+        let t = match self.tag_no_rec(n, &mut acc)? {
+            Tag::List(ListTag::None) => match read_list(n, &mut acc)? {
+                (ListTag::Compound(x), len) => ReadEntry::ListCompound(x, len),
+                (ListTag::List(x), len) => ReadEntry::ListList(x, len),
+                (x, _) => return Ok(Tag::List(x)),
+            },
             Tag::Compound(c) => ReadEntry::Compound(c),
             x => return Ok(x),
         };
-        read_tag(n, t, 512)
+        read_tag(n, t, &mut acc)
     }
 }
 
@@ -406,7 +569,7 @@ enum ReadEntry {
     ListList(Vec<ListTag>, u32),
 }
 
-fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag, Error> {
+fn read_tag(buf: &mut Reader, next: ReadEntry, acc: &mut Accounter) -> Result<Tag, Error> {
     let mut blocks = Vec::<ReadEntry>::with_capacity(4);
     let mut names = Vec::<StringTag>::with_capacity(4);
     blocks.push(next);
@@ -419,7 +582,7 @@ fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag,
                     Some(ReadEntry::Compound(mut c)) => {
                         let k = match names.pop() {
                             Some(x) => x,
-                            None => return Err(Error),
+                            None => return Err(buf.error(ErrorKind::Invalid)),
                         };
                         c.push(k, Tag::Compound(compound));
                         ReadEntry::Compound(c)
@@ -428,19 +591,20 @@ fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag,
                         l.push(compound);
                         ReadEntry::ListCompound(l, len)
                     }
-                    Some(ReadEntry::ListList(_, _)) => return Err(Error),
+                    Some(ReadEntry::ListList(_, _)) => return Err(buf.error(ErrorKind::Invalid)),
                     None => return Ok(Tag::Compound(compound)),
                 },
                 ty => {
                     let name = StringTag::read(buf)?;
-                    match ty.tag_no_rec(buf)? {
+                    acc.entry(buf, name.len())?;
+                    match ty.tag_no_rec(buf, acc)? {
                         Tag::Compound(c) => {
                             names.push(name);
                             blocks.push(ReadEntry::Compound(compound));
                             ReadEntry::Compound(c)
                         }
                         Tag::List(ListTag::None) => {
-                            read_list1(buf, &mut blocks, &mut names, compound, name)?
+                            read_list1(buf, acc, &mut blocks, &mut names, compound, name)?
                         }
                         t => {
                             compound.push(name, t);
@@ -452,13 +616,14 @@ fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag,
             ReadEntry::ListCompound(compounds, len) => {
                 if len != 0 {
                     blocks.push(ReadEntry::ListCompound(compounds, len - 1));
+                    acc.compound(buf)?;
                     ReadEntry::Compound(CompoundTag::new())
                 } else {
                     match blocks.pop() {
                         Some(ReadEntry::Compound(mut x)) => {
                             let k = match names.pop() {
                                 Some(x) => x,
-                                None => return Err(Error),
+                                None => return Err(buf.error(ErrorKind::Invalid)),
                             };
                             x.push(k, Tag::List(ListTag::Compound(compounds)));
                             ReadEntry::Compound(x)
@@ -467,20 +632,22 @@ fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag,
                             lists.push(ListTag::Compound(compounds));
                             ReadEntry::ListList(lists, len)
                         }
-                        Some(ReadEntry::ListCompound(_, _)) => return Err(Error),
+                        Some(ReadEntry::ListCompound(_, _)) => {
+                            return Err(buf.error(ErrorKind::Invalid));
+                        }
                         None => return Ok(Tag::List(ListTag::Compound(compounds))),
                     }
                 }
             }
             ReadEntry::ListList(lists, len) => {
                 if len != 0 {
-                    read_list2(buf, &mut blocks, lists, len)?
+                    read_list2(buf, acc, &mut blocks, lists, len)?
                 } else {
                     match blocks.pop() {
                         Some(ReadEntry::Compound(mut x)) => {
                             let k = match names.pop() {
                                 Some(x) => x,
-                                None => return Err(Error),
+                                None => return Err(buf.error(ErrorKind::Invalid)),
                             };
                             x.push(k, Tag::List(ListTag::List(lists)));
                             ReadEntry::Compound(x)
@@ -489,37 +656,44 @@ fn read_tag(buf: &mut Reader, next: ReadEntry, max_depth: usize) -> Result<Tag,
                             x.push(ListTag::List(lists));
                             ReadEntry::ListList(x, len)
                         }
-                        Some(ReadEntry::ListCompound(_, _)) => return Err(Error),
+                        Some(ReadEntry::ListCompound(_, _)) => {
+                            return Err(buf.error(ErrorKind::Invalid));
+                        }
                         None => return Ok(Tag::List(ListTag::List(lists))),
                     }
                 }
             }
         };
         blocks.push(next);
-        if max_depth == blocks.len() {
-            return Err(Error);
-        }
+        acc.depth(buf, blocks.len())?;
     }
 }
 
+#[inline]
+fn read_list(buf: &mut Reader<'_>, acc: &mut Accounter) -> Result<(ListTag, u32), Error> {
+    let l = ListInfo::read(buf)?;
+    let list = l.list_no_rec(buf, acc)?;
+    Ok((list, l.1))
+}
+
 #[inline]
 fn read_list2(
     buf: &mut Reader<'_>,
+    acc: &mut Accounter,
     blocks: &mut Vec<ReadEntry>,
     mut lists: Vec<ListTag>,
     len: u32,
 ) -> Result<ReadEntry, Error> {
-    let l = ListInfo::read(buf)?;
-    Ok(match l.list_no_rec(buf)? {
-        ListTag::List(x) => {
+    Ok(match read_list(buf, acc)? {
+        (ListTag::List(x), n) => {
             blocks.push(ReadEntry::ListList(lists, len - 1));
-            ReadEntry::ListList(x, l.1)
+            ReadEntry::ListList(x, n)
         }
-        ListTag::Compound(x) => {
+        (ListTag::Compound(x), n) => {
             blocks.push(ReadEntry::ListList(lists, len - 1));
-            ReadEntry::ListCompound(x, l.1)
+            ReadEntry::ListCompound(x, n)
         }
-        x => {
+        (x, _) => {
             lists.push(x);
             ReadEntry::ListList(lists, len - 1)
         }
@@ -529,24 +703,24 @@ fn read_list2(
 #[inline]
 fn read_list1(
     buf: &mut Reader<'_>,
+    acc: &mut Accounter,
     blocks: &mut Vec<ReadEntry>,
     names: &mut Vec<StringTag>,
     mut compound: CompoundTag,
     name: StringTag,
 ) -> Result<ReadEntry, Error> {
-    let l = ListInfo::read(buf)?;
-    Ok(match l.list_no_rec(buf)? {
-        ListTag::List(x) => {
+    Ok(match read_list(buf, acc)? {
+        (ListTag::List(x), n) => {
             names.push(name);
             blocks.push(ReadEntry::Compound(compound));
-            ReadEntry::ListList(x, l.1)
+            ReadEntry::ListList(x, n)
         }
-        ListTag::Compound(x) => {
+        (ListTag::Compound(x), n) => {
             names.push(name);
             blocks.push(ReadEntry::Compound(compound));
-            ReadEntry::ListCompound(x, l.1)
+            ReadEntry::ListCompound(x, n)
         }
-        x => {
+        (x, _) => {
             compound.push(name, Tag::List(x));
             ReadEntry::Compound(compound)
         }
@@ -685,13 +859,16 @@ fn write_tag(w: &mut Writer, next: WriteEntry) {
             let next = match blocks.pop().unwrap_unchecked() {
                 WriteEntry::Compound(compound) => match compound.split_first() {
                     Some((x, y)) => {
+                        x.1.id().write(w);
                         x.0.write(w);
                         match &x.1 {
-                            Tag::List(ListTag::Compound(cl)) => {
+                            Tag::List(l @ ListTag::Compound(cl)) => {
+                                l.list_info().write(w);
                                 blocks.push(WriteEntry::Compound(y));
                                 WriteEntry::ListCompound(cl)
                             }
-                            Tag::List(ListTag::List(ll)) => {
+                            Tag::List(l @ ListTag::List(ll)) => {
+                                l.list_info().write(w);
                                 blocks.push(WriteEntry::Compound(y));
                                 WriteEntry::ListList(ll)
                             }
@@ -730,13 +907,14 @@ fn write_tag(w: &mut Writer, next: WriteEntry) {
                 }
                 WriteEntry::ListList(lists) => {
                     if let Some((x, y)) = lists.split_first() {
-                        x.list_info().write(w);
                         match x {
                             ListTag::List(tag) => {
+                                x.list_info().write(w);
                                 blocks.push(WriteEntry::ListList(y));
                                 WriteEntry::ListList(tag)
                             }
                             ListTag::Compound(tag) => {
+                                x.list_info().write(w);
                                 blocks.push(WriteEntry::ListList(y));
                                 WriteEntry::ListCompound(tag)
                             }
@@ -768,13 +946,15 @@ fn len_tag(next: WriteEntry) -> usize {
             let next = match blocks.pop().unwrap_unchecked() {
                 WriteEntry::Compound(compound) => match compound.split_first() {
                     Some((x, y)) => {
-                        w += x.0.len_s();
+                        w += x.1.id().len_s() + x.0.len_s();
                         match &x.1 {
-                            Tag::List(ListTag::Compound(cl)) => {
+                            Tag::List(l @ ListTag::Compound(cl)) => {
+                                w += l.list_info().len_s();
                                 blocks.push(WriteEntry::Compound(y));
                                 WriteEntry::ListCompound(cl)
                             }
-                            Tag::List(ListTag::List(ll)) => {
+                            Tag::List(l @ ListTag::List(ll)) => {
+                                w += l.list_info().len_s();
                                 blocks.push(WriteEntry::Compound(y));
                                 WriteEntry::ListList(ll)
                             }
@@ -813,13 +993,14 @@ fn len_tag(next: WriteEntry) -> usize {
                 }
                 WriteEntry::ListList(lists) => {
                     if let Some((x, y)) = lists.split_first() {
-                        w += x.list_info().len_s();
                         match x {
                             ListTag::List(tag) => {
+                                w += x.list_info().len_s();
                                 blocks.push(WriteEntry::ListList(y));
                                 WriteEntry::ListList(tag)
                             }
                             ListTag::Compound(tag) => {
+                                w += x.list_info().len_s();
                                 blocks.push(WriteEntry::ListList(y));
                                 WriteEntry::ListCompound(tag)
                             }
@@ -848,7 +1029,7 @@ impl Read<'_> for TagType {
         if t <= 12 {
             unsafe { Ok(core::mem::transmute::<u8, Self>(t)) }
         } else {
-            Err(Error)
+            Err(buf.error(ErrorKind::Discriminant))
         }
     }
 }
@@ -865,7 +1046,7 @@ impl Write for TagType {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum Tag {
     Byte(i8),
     Short(i16),
@@ -918,30 +1099,40 @@ impl Write for Tag {
     unsafe fn write(&self, w: &mut Writer) {
         unsafe {
             self.id().write(w);
-            write_tag(
-                w,
-                match self {
-                    Self::List(ListTag::Compound(cl)) => WriteEntry::ListCompound(cl),
-                    Self::List(ListTag::List(ll)) => WriteEntry::ListList(ll),
-                    Self::List(list) => {
-                        write_list_no_rec(w, list);
-                        return;
-                    }
-                    Self::Compound(c) => WriteEntry::Compound(c.0.as_slice()),
-                    t => {
-                        write_no_rec(w, t);
-                        return;
-                    }
-                },
-            );
+            let e = match self {
+                Self::List(l @ ListTag::Compound(cl)) => {
+                    l.list_info().write(w);
+                    WriteEntry::ListCompound(cl)
+                }
+                Self::List(l @ ListTag::List(ll)) => {
+                    l.list_info().write(w);
+                    WriteEntry::ListList(ll)
+                }
+                Self::List(list) => {
+                    write_list_no_rec(w, list);
+                    return;
+                }
+                Self::Compound(c) => WriteEntry::Compound(c.0.as_slice()),
+                t => {
+                    write_no_rec(w, t);
+                    return;
+                }
+            };
+            write_tag(w, e);
         }
     }
 
     fn len_s(&self) -> usize {
-        let id = self.id().len_s();
+        let mut id = self.id().len_s();
         let e = match self {
-            Self::List(ListTag::Compound(cl)) => WriteEntry::ListCompound(cl),
-            Self::List(ListTag::List(ll)) => WriteEntry::ListList(ll),
+            Self::List(l @ ListTag::Compound(cl)) => {
+                id += l.list_info().len_s();
+                WriteEntry::ListCompound(cl)
+            }
+            Self::List(l @ ListTag::List(ll)) => {
+                id += l.list_info().len_s();
+                WriteEntry::ListList(ll)
+            }
             Self::List(list) => {
                 return id + len_list_no_rec(list);
             }
@@ -1098,6 +1289,12 @@ impl From<Vec<i64>> for Tag {
     }
 }
 
+impl Tag {
+    pub fn read_limited(buf: &mut Reader, limits: NbtLimits) -> Result<Self, Error> {
+        TagType::read(buf)?.tag_limited(buf, limits)
+    }
+}
+
 impl<'a> Read<'a> for Tag {
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
         TagType::read(buf)?.tag(buf)
@@ -1107,17 +1304,26 @@ impl<'a> Read<'a> for Tag {
 #[derive(Clone)]
 pub struct CompoundNamed(pub StringTag, pub CompoundTag);
 
-impl Read<'_> for CompoundNamed {
-    #[inline]
-    fn read(n: &mut Reader) -> Result<Self, Error> {
+impl CompoundNamed {
+    pub fn read_limited(n: &mut Reader, limits: NbtLimits) -> Result<Self, Error> {
         if matches!(TagType::read(n)?, TagType::Compound) {
-            Ok(Self(StringTag::read(n)?, CompoundTag::read(n)?))
+            Ok(Self(
+                StringTag::read(n)?,
+                CompoundTag::read_limited(n, limits)?,
+            ))
         } else {
-            Err(Error)
+            Err(n.error(ErrorKind::Invalid))
         }
     }
 }
 
+impl Read<'_> for CompoundNamed {
+    #[inline]
+    fn read(n: &mut Reader) -> Result<Self, Error> {
+        Self::read_limited(n, NbtLimits::NETWORK)
+    }
+}
+
 impl Write for CompoundNamed {
     #[inline]
     unsafe fn write(&self, w: &mut Writer) {
@@ -1133,3 +1339,115 @@ impl Write for CompoundNamed {
         1 + Write::len_s(&self.0) + Write::len_s(&self.1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mser_macro::{NbtDeserialize, NbtSerialize};
+
+    #[test]
+    fn test_nbt_derive() {
+        #[derive(NbtSerialize, NbtDeserialize)]
+        struct Beehive {
+            #[nbt(rename = "CustomName")]
+            custom_name: Option<StringTag>,
+            #[nbt(default)]
+            ticks: i32,
+            #[nbt(rename = "UUID", with = crate::lenient_uuid)]
+            owner: Uuid,
+            bee: Bee,
+            #[nbt(flatten)]
+            rest: CompoundTag,
+        }
+
+        #[derive(NbtSerialize, NbtDeserialize)]
+        #[nbt(tag = "type")]
+        enum Bee {
+            Angry { anger: i16 },
+            Sleeping,
+        }
+
+        #[derive(NbtSerialize, NbtDeserialize, PartialEq, Debug)]
+        enum Mood {
+            VeryHappy,
+            #[nbt(rename = "meh")]
+            Neutral,
+        }
+
+        let snbt =
+            r#"{UUID:"069a79f4-44e9-4726-a5be-fca90e38aaf5",bee:{type:"angry",anger:3s},x:1b}"#;
+        let tag = Tag::Compound(CompoundStringify::decode(snbt).unwrap().0);
+        let hive = Beehive::deserialize(tag).unwrap();
+        assert!(hive.custom_name.is_none());
+        assert_eq!(hive.ticks, 0);
+        assert_eq!(hive.owner.as_u128(), 0x069a79f444e94726a5befca90e38aaf5);
+        assert!(matches!(hive.bee, Bee::Angry { anger: 3 }));
+        assert!(matches!(hive.rest.find("x"), Some(Tag::Byte(1))));
+
+        let Tag::Compound(c) = hive.serialize() else {
+            panic!()
+        };
+        assert!(c.find("CustomName").is_none());
+        assert!(matches!(c.find("UUID"), Some(Tag::IntArray(x)) if x.len() == 4));
+        assert!(matches!(c.find("x"), Some(Tag::Byte(1))));
+        let again = Beehive::deserialize(Tag::Compound(c)).unwrap();
+        assert_eq!(again.owner, hive.owner);
+
+        let sleeping = Bee::Sleeping.serialize();
+        assert!(matches!(Bee::deserialize(sleeping), Ok(Bee::Sleeping)));
+        let bad = CompoundStringify::decode(r#"{UUID:[I;1,2,3,4],bee:{type:"flying"}}"#).unwrap();
+        assert!(Beehive::deserialize(Tag::Compound(bad.0)).is_err());
+
+        assert!(matches!(Mood::VeryHappy.serialize(), Tag::String(x) if &*x == "very_happy"));
+        let meh = Tag::String(StringTag::from_utf8("meh"));
+        assert_eq!(Mood::deserialize(meh).unwrap(), Mood::Neutral);
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(i8::deserialize(Tag::Int(-3)).unwrap(), -3);
+        assert!(i8::deserialize(Tag::Int(300)).is_err());
+        assert!(i16::deserialize(Tag::Float(1.0)).is_err());
+        assert_eq!(i64::deserialize(Tag::Byte(-1)).unwrap(), -1);
+        assert!(f32::deserialize(Tag::Double(0.5)).is_err());
+        assert_eq!(f64::deserialize(Tag::Float(0.5)).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_flatten_compound() {
+        #[derive(NbtSerialize)]
+        struct Outer {
+            name: StringTag,
+            #[nbt(flatten)]
+            inner: Box<Inner>,
+        }
+
+        #[derive(NbtSerialize)]
+        struct Inner {
+            level: i32,
+            #[nbt(flatten)]
+            bee: Bee,
+        }
+
+        #[derive(NbtSerialize)]
+        #[nbt(tag = "type")]
+        enum Bee {
+            Angry { anger: i16 },
+        }
+
+        let x = Outer {
+            name: StringTag::from_utf8("a"),
+            inner: Box::new(Inner {
+                level: 3,
+                bee: Bee::Angry { anger: 5 },
+            }),
+        };
+        let Tag::Compound(c) = x.serialize() else {
+            panic!()
+        };
+        assert_eq!(c.len(), 4);
+        assert!(matches!(c.find("level"), Some(Tag::Int(3))));
+        assert!(matches!(c.find("type"), Some(Tag::String(x)) if &**x == "angry"));
+        assert!(matches!(c.find("anger"), Some(Tag::Short(5))));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{ListTag, Tag, TagType};
+use mser::{Error, ErrorKind, Reader};
+
+/// Quotas applied while decoding, like vanilla's `NbtAccounter`.
+///
+/// Every decoded tag is charged the heap size vanilla estimates for it, so
+/// `max_bytes` bounds memory use rather than input length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NbtLimits {
+    pub max_depth: usize,
+    pub max_bytes: usize,
+}
+
+impl NbtLimits {
+    /// The quota for NBT received from the network.
+    pub const NETWORK: Self = Self {
+        max_depth: 512,
+        max_bytes: 2 * 1024 * 1024,
+    };
+
+    /// Depth limit only, for trusted input such as world files.
+    pub const UNLIMITED: Self = Self {
+        max_depth: 512,
+        max_bytes: usize::MAX,
+    };
+}
+
+impl Default for NbtLimits {
+    fn default() -> Self {
+        Self::NETWORK
+    }
+}
+
+const COMPOUND: usize = 48;
+const ENTRY: usize = 36;
+const LIST: usize = 37;
+const LIST_ELEMENT: usize = 4;
+const STRING: usize = 36;
+const ARRAY: usize = 24;
+
+pub(crate) struct Accounter {
+    pub(crate) max_depth: usize,
+    max_bytes: usize,
+    used: usize,
+}
+
+impl Accounter {
+    pub(crate) const fn new(limits: NbtLimits) -> Self {
+        Self {
+            max_depth: limits.max_depth,
+            max_bytes: limits.max_bytes,
+            used: 0,
+        }
+    }
+
+    pub(crate) fn account(&mut self, n: &Reader<'_>, bytes: usize) -> Result<(), Error> {
+        self.used = self.used.saturating_add(bytes);
+        if self.used > self.max_bytes {
+            Err(n.error(ErrorKind::TooLong))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn depth(&self, n: &Reader<'_>, depth: usize) -> Result<(), Error> {
+        if depth >= self.max_depth {
+            Err(n.error(ErrorKind::Invalid))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn compound(&mut self, n: &Reader<'_>) -> Result<(), Error> {
+        self.account(n, COMPOUND)
+    }
+
+    pub(crate) fn list_header(&mut self, n: &Reader<'_>) -> Result<(), Error> {
+        self.account(n, LIST)
+    }
+
+    /// The element slots of a list, excluding the elements.
+    pub(crate) fn slots(&mut self, n: &Reader<'_>, len: usize) -> Result<(), Error> {
+        self.account(n, len.saturating_mul(LIST_ELEMENT))
+    }
+
+    pub(crate) fn array(&mut self, n: &Reader<'_>, len: usize, width: usize) -> Result<(), Error> {
+        self.account(n, ARRAY.saturating_add(len.saturating_mul(width)))
+    }
+
+    /// A compound entry and its key, excluding the value.
+    pub(crate) fn entry(&mut self, n: &Reader<'_>, key_len: usize) -> Result<(), Error> {
+        self.account(n, ENTRY.saturating_add(string(key_len)))
+    }
+
+    /// A tag without its children, which are charged as they are read.
+    pub(crate) fn tag(&mut self, n: &Reader<'_>, tag: &Tag) -> Result<(), Error> {
+        self.account(
+            n,
+            match tag {
+                Tag::Byte(_) => 9,
+                Tag::Short(_) => 10,
+                Tag::Int(_) | Tag::Float(_) => 12,
+                Tag::Long(_) | Tag::Double(_) => 16,
+                Tag::String(x) => string(x.len()),
+                Tag::ByteArray(x) => return self.array(n, x.len(), 1),
+                Tag::IntArray(x) => return self.array(n, x.len(), 4),
+                Tag::LongArray(x) => return self.array(n, x.len(), 8),
+                Tag::List(_) => 0,
+                Tag::Compound(_) => COMPOUND,
+                Tag::End => 8,
+            },
+        )
+    }
+
+    /// A list of `len` elements of type `ty`, charged before it is allocated.
+    /// This includes the fixed size of each element; the contents of strings
+    /// and arrays, and lists and compounds, are charged as they are read.
+    pub(crate) fn list(&mut self, n: &Reader<'_>, ty: TagType, len: usize) -> Result<(), Error> {
+        self.list_header(n)?;
+        let element = match ty {
+            TagType::End | TagType::List | TagType::Compound => 0,
+            TagType::Byte => 9,
+            TagType::Short => 10,
+            TagType::Int | TagType::Float => 12,
+            TagType::Long | TagType::Double => 16,
+            TagType::String => STRING,
+            TagType::ByteArray | TagType::IntArray | TagType::LongArray => ARRAY,
+        };
+        self.account(n, len.saturating_mul(LIST_ELEMENT + element))
+    }
+
+    /// The contents of a string in a list, without its fixed size.
+    pub(crate) fn string_data(&mut self, n: &Reader<'_>, len: usize) -> Result<(), Error> {
+        self.account(n, len.saturating_mul(2))
+    }
+
+    /// The contents of an array in a list, without its fixed size.
+    pub(crate) fn array_data(
+        &mut self,
+        n: &Reader<'_>,
+        len: usize,
+        width: usize,
+    ) -> Result<(), Error> {
+        self.account(n, len.saturating_mul(width))
+    }
+
+    /// The elements of `list` unless they are lists or compounds.
+    pub(crate) fn elements(&mut self, n: &Reader<'_>, list: &ListTag) -> Result<(), Error> {
+        self.account(
+            n,
+            match list {
+                ListTag::None | ListTag::List(_) | ListTag::Compound(_) => 0,
+                ListTag::Byte(x) => 9 * x.len(),
+                ListTag::Short(x) => 10 * x.len(),
+                ListTag::Int(x) => 12 * x.len(),
+                ListTag::Float(x) => 12 * x.len(),
+                ListTag::Long(x) => 16 * x.len(),
+                ListTag::Double(x) => 16 * x.len(),
+                ListTag::String(x) => x.iter().map(|s| string(s.len())).sum(),
+                ListTag::ByteArray(x) => x.iter().map(|a| ARRAY + a.len()).sum(),
+                ListTag::IntArray(x) => x.iter().map(|a| ARRAY + 4 * a.len()).sum(),
+                ListTag::LongArray(x) => x.iter().map(|a| ARRAY + 8 * a.len()).sum(),
+            },
+        )
+    }
+}
+
+const fn string(len: usize) -> usize {
+    STRING.saturating_add(len.saturating_mul(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CompoundNamed, CompoundStringify, CompoundTag, ListTag, NbtLimits, StringTag, Tag,
+    };
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use mser::{Read, Reader, Write};
+
+    fn encode(tag: &impl Write) -> Vec<u8> {
+        let mut data = Vec::with_capacity(tag.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), tag);
+            data.set_len(tag.len_s());
+        }
+        data
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut root = CompoundTag::new();
+        root.push(StringTag::from_utf8("a"), Tag::IntArray(vec![0; 600_000]));
+        let data = encode(&CompoundNamed(StringTag::new(), root));
+        assert!(CompoundNamed::read(&mut Reader::new(&data)).is_err());
+        CompoundNamed::read_limited(&mut Reader::new(&data), NbtLimits::UNLIMITED).unwrap();
+
+        let small = NbtLimits {
+            max_depth: 512,
+            max_bytes: 1024,
+        };
+        let mut root = CompoundTag::new();
+        let items = vec![CompoundTag::new(); 32];
+        root.push(
+            StringTag::from_utf8("l"),
+            Tag::List(ListTag::Compound(items)),
+        );
+        let data = encode(&root);
+        assert!(CompoundTag::read(&mut Reader::new(&data)).is_ok());
+        assert!(CompoundTag::read_limited(&mut Reader::new(&data), small).is_err());
+
+        // Charged from the header alone, before any element is allocated.
+        let root = [9, 0, 1, b'l', 10, 0, 0, 0x80, 0, 0];
+        assert!(CompoundTag::read(&mut Reader::new(&root)).is_err());
+        let mut root = CompoundTag::new();
+        root.push(
+            StringTag::from_utf8("l"),
+            Tag::List(ListTag::Int(vec![0; 150_000])),
+        );
+        let data = encode(&root);
+        assert!(CompoundTag::read(&mut Reader::new(&data)).is_err());
+        CompoundTag::read_limited(&mut Reader::new(&data), NbtLimits::UNLIMITED).unwrap();
+
+        let mut list = ListTag::Int(vec![1]);
+        for _ in 0..600 {
+            list = ListTag::List(vec![list]);
+        }
+        let mut root = CompoundTag::new();
+        root.push(StringTag::from_utf8("l"), Tag::List(list));
+        let data = encode(&root);
+        assert!(CompoundTag::read(&mut Reader::new(&data)).is_err());
+
+        let mut s = String::from("{l:[");
+        for _ in 0..200 {
+            s.push_str("1,");
+        }
+        s.push_str("1]}");
+        assert!(CompoundStringify::decode(&s).is_ok());
+        assert!(CompoundStringify::decode_limited(&s, small).is_err());
+        let nested = "{a:".repeat(600);
+        assert!(CompoundStringify::decode(&nested).is_err());
+    }
+}
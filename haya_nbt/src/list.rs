@@ -1,7 +1,8 @@
+use crate::limits::Accounter;
 use crate::{ByteArray, CompoundTag, IntArray, ListInfo, ListTag, LongArray, StringTag, TagType};
 use alloc::vec::Vec;
 use haya_collection::capacity_fix;
-use mser::{Error, Read, Reader, Write, Writer};
+use mser::{Error, ErrorKind, Read, Reader, Write, Writer};
 
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -163,8 +164,9 @@ impl ListTag {
 }
 
 impl ListInfo {
-    pub(crate) fn list_no_rec(self, n: &mut Reader) -> Result<ListTag, Error> {
+    pub(crate) fn list_no_rec(self, n: &mut Reader, acc: &mut Accounter) -> Result<ListTag, Error> {
         let len = self.1 as usize;
+        acc.list(n, self.0, len)?;
         match self.0 {
             TagType::End => Ok(ListTag::None),
             TagType::Byte => Ok(ListTag::Byte(Vec::from(crate::byte_array::u8_to_i8_slice(
@@ -173,71 +175,107 @@ impl ListInfo {
             TagType::Short => unsafe {
                 Ok(ListTag::Short(short_list(
                     len,
-                    n.read_slice(len.checked_mul(2).ok_or(Error)?)?,
+                    n.read_slice(
+                        len.checked_mul(2)
+                            .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                    )?,
                 )))
             },
             TagType::Int => unsafe {
                 Ok(ListTag::Int(int_list(
                     len,
-                    n.read_slice(len.checked_mul(4).ok_or(Error)?)?,
+                    n.read_slice(
+                        len.checked_mul(4)
+                            .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                    )?,
                 )))
             },
             TagType::Long => unsafe {
                 Ok(ListTag::Long(long_list(
                     len,
-                    n.read_slice(len.checked_mul(8).ok_or(Error)?)?,
+                    n.read_slice(
+                        len.checked_mul(8)
+                            .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                    )?,
                 )))
             },
             TagType::Float => unsafe {
                 Ok(ListTag::Float(f32_list(
                     len,
-                    n.read_slice(len.checked_mul(4).ok_or(Error)?)?,
+                    n.read_slice(
+                        len.checked_mul(4)
+                            .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                    )?,
                 )))
             },
             TagType::Double => unsafe {
                 Ok(ListTag::Double(f64_list(
                     len,
-                    n.read_slice(len.checked_mul(8).ok_or(Error)?)?,
+                    n.read_slice(
+                        len.checked_mul(8)
+                            .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+                    )?,
                 )))
             },
             TagType::ByteArray => {
-                if len.checked_mul(4).ok_or(Error)? > n.len() {
-                    return Err(Error);
+                if len
+                    .checked_mul(4)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?
+                    > n.len()
+                {
+                    return Err(n.error(ErrorKind::UnexpectedEof));
                 }
                 let mut list = Vec::with_capacity(capacity_fix(len));
                 for _ in 0..len {
+                    acc.array_data(n, array_len(n)?, 1)?;
                     list.push(ByteArray::read(n)?.0);
                 }
                 Ok(ListTag::ByteArray(list))
             }
             TagType::String => {
-                if len.checked_mul(2).ok_or(Error)? > n.len() {
-                    return Err(Error);
+                if len
+                    .checked_mul(2)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?
+                    > n.len()
+                {
+                    return Err(n.error(ErrorKind::UnexpectedEof));
                 }
                 let mut list = Vec::with_capacity(capacity_fix(len));
                 for _ in 0..len {
-                    list.push(StringTag::read(n)?);
+                    let s = StringTag::read(n)?;
+                    acc.string_data(n, s.len())?;
+                    list.push(s);
                 }
                 Ok(ListTag::String(list))
             }
             TagType::List => Ok(ListTag::List(Vec::with_capacity(capacity_fix(len)))),
             TagType::Compound => Ok(ListTag::Compound(Vec::with_capacity(capacity_fix(len)))),
             TagType::IntArray => {
-                if len.checked_mul(4).ok_or(Error)? > n.len() {
-                    return Err(Error);
+                if len
+                    .checked_mul(4)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?
+                    > n.len()
+                {
+                    return Err(n.error(ErrorKind::UnexpectedEof));
                 }
                 let mut list = Vec::with_capacity(capacity_fix(len));
                 for _ in 0..len {
+                    acc.array_data(n, array_len(n)?, 4)?;
                     list.push(IntArray::read(n)?.0);
                 }
                 Ok(ListTag::IntArray(list))
             }
             TagType::LongArray => {
-                if len.checked_mul(4).ok_or(Error)? > n.len() {
-                    return Err(Error);
+                if len
+                    .checked_mul(4)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?
+                    > n.len()
+                {
+                    return Err(n.error(ErrorKind::UnexpectedEof));
                 }
                 let mut list = Vec::with_capacity(capacity_fix(len));
                 for _ in 0..len {
+                    acc.array_data(n, array_len(n)?, 8)?;
                     list.push(LongArray::read(n)?.0);
                 }
                 Ok(ListTag::LongArray(list))
@@ -246,6 +284,11 @@ impl ListInfo {
     }
 }
 
+/// Peeks the length prefix of an array, to charge it before it is read.
+pub(crate) fn array_len(n: &Reader) -> Result<usize, Error> {
+    Ok(u32::from_be_bytes(*n.peek_array::<4>()?) as usize)
+}
+
 pub(crate) unsafe fn long_list(len: usize, slice: &[u8]) -> Vec<i64> {
     debug_assert_eq!(len * 8, slice.len());
 
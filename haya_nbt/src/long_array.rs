@@ -1,5 +1,5 @@
 use alloc::vec::Vec;
-use mser::{Error, Read, Reader};
+use mser::{Error, ErrorKind, Read, Reader};
 
 #[derive(Clone)]
 pub(crate) struct LongArray(pub Vec<i64>);
@@ -7,7 +7,10 @@ pub(crate) struct LongArray(pub Vec<i64>);
 impl<'a> Read<'a> for LongArray {
     fn read(buf: &mut Reader<'a>) -> Result<Self, mser::Error> {
         let len = u32::read(buf)? as usize;
-        let data = buf.read_slice(len.checked_mul(8).ok_or(Error)?)?;
+        let data = buf.read_slice(
+            len.checked_mul(8)
+                .ok_or_else(|| Error::new(ErrorKind::TooLong))?,
+        )?;
         let mut vec = Vec::with_capacity(len);
         unsafe { copy_swap(len, data.as_ptr(), vec.as_mut_ptr()) }
         unsafe { vec.set_len(len) }
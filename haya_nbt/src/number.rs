@@ -2,7 +2,7 @@ use crate::TagPrimitive;
 use alloc::vec::Vec;
 use core::str::from_utf8_unchecked;
 use haya_str::hex_to_u8;
-use mser::Error;
+use mser::{Error, ErrorKind};
 
 #[derive(Clone, Copy)]
 enum Suffix {
@@ -40,14 +40,14 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
             return if dec_true_peek(rest)?.is_empty() {
                 Ok(TagPrimitive::Byte(1))
             } else {
-                Err(Error)
+                Err(Error::new(ErrorKind::Invalid))
             };
         }
         Some((b'f' | b'F', rest)) => {
             return if dec_false_peek(rest)?.is_empty() {
                 Ok(TagPrimitive::Byte(0))
             } else {
-                Err(Error)
+                Err(Error::new(ErrorKind::Invalid))
             };
         }
         _ => (),
@@ -78,11 +78,11 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
             Radix::Binary
         }
         [b'.' | b'0'..=b'9' | b'+' | b'-', ..] => Radix::Decimal,
-        _ => return Err(Error),
+        _ => return Err(Error::new(ErrorKind::Invalid)),
     };
     let (last, rest) = match n {
         [rest @ .., a] => (*a, rest),
-        _ => return Err(Error),
+        _ => return Err(Error::new(ErrorKind::Invalid)),
     };
     let suffix = match last {
         b'B' | b'b' => match rest[..] {
@@ -168,7 +168,7 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
     {
         let p = match n.split_first() {
             Some((x, y)) => (*x, y),
-            None => return Err(Error),
+            None => return Err(Error::new(ErrorKind::Invalid)),
         };
         let only_dig = if let Suffix::Auto = suffix {
             match p {
@@ -226,13 +226,13 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
         FloatParser::Double => unsafe {
             return match from_utf8_unchecked(m).parse() {
                 Ok(x) => Ok(TagPrimitive::Double(x)),
-                Err(_) => Err(Error),
+                Err(_) => Err(Error::new(ErrorKind::Invalid)),
             };
         },
         FloatParser::Float => unsafe {
             return match from_utf8_unchecked(m).parse() {
                 Ok(x) => Ok(TagPrimitive::Float(x)),
-                Err(_) => Err(Error),
+                Err(_) => Err(Error::new(ErrorKind::Invalid)),
             };
         },
         FloatParser::None => {}
@@ -274,7 +274,7 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
                         out = out.wrapping_mul(16).wrapping_add(d as u64);
                     }
                 }
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             }
             match suffix {
                 Suffix::UnsignedByte => Ok(TagPrimitive::Byte(out as u8 as i8)),
@@ -339,13 +339,17 @@ pub(crate) unsafe fn dec_num(mut n: &[u8], tmp: &mut Vec<u8>) -> Result<TagPrimi
             }
         }
     };
-    if m.is_empty() { ret } else { Err(Error) }
+    if m.is_empty() {
+        ret
+    } else {
+        Err(Error::new(ErrorKind::Invalid))
+    }
 }
 
 fn dec_true_peek(n: &[u8]) -> Result<&[u8], Error> {
     match n {
         [b'r' | b'R', b'u' | b'U', b'e' | b'E', rest @ ..] => Ok(rest),
-        _ => Err(Error),
+        _ => Err(Error::new(ErrorKind::Invalid)),
     }
 }
 
@@ -358,6 +362,6 @@ fn dec_false_peek(n: &[u8]) -> Result<&[u8], Error> {
             b'e' | b'E',
             rest @ ..,
         ] => Ok(rest),
-        _ => Err(Error),
+        _ => Err(Error::new(ErrorKind::Invalid)),
     }
 }
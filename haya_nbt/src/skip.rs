@@ -0,0 +1,146 @@
+use crate::{Tag, TagType};
+use mser::{Error, ErrorKind, Read, Reader, Skip};
+
+const MAX_DEPTH: usize = 512;
+
+#[derive(Clone, Copy)]
+enum Frame {
+    Compound,
+    List(TagType, u32),
+}
+
+impl TagType {
+    /// Advances past a payload of this type without allocating.
+    pub fn skip(self, buf: &mut Reader<'_>) -> Result<(), Error> {
+        let mut stack = [Frame::Compound; MAX_DEPTH];
+        let mut depth = 0;
+        let mut next = self.skip_no_rec(buf)?;
+        loop {
+            if let Some(frame) = next {
+                if depth == MAX_DEPTH {
+                    return Err(buf.error(ErrorKind::Invalid));
+                }
+                stack[depth] = frame;
+                depth += 1;
+            }
+            let frame = match depth.checked_sub(1) {
+                Some(x) => &mut stack[x],
+                None => return Ok(()),
+            };
+            next = match frame {
+                Frame::Compound => match Self::read(buf)? {
+                    Self::End => {
+                        depth -= 1;
+                        None
+                    }
+                    ty => {
+                        let len = u16::read(buf)?;
+                        buf.read_slice(len as usize)?;
+                        ty.skip_no_rec(buf)?
+                    }
+                },
+                Frame::List(ty, len) => {
+                    if *len == 0 {
+                        depth -= 1;
+                        None
+                    } else {
+                        *len -= 1;
+                        ty.skip_no_rec(buf)?
+                    }
+                }
+            };
+        }
+    }
+
+    fn skip_no_rec(self, buf: &mut Reader<'_>) -> Result<Option<Frame>, Error> {
+        let n = match self {
+            Self::End => 0,
+            Self::Byte => 1,
+            Self::Short => 2,
+            Self::Int | Self::Float => 4,
+            Self::Long | Self::Double => 8,
+            Self::String => u16::read(buf)? as usize,
+            Self::ByteArray => u32::read(buf)? as usize,
+            Self::IntArray => array_len(buf, 4)?,
+            Self::LongArray => array_len(buf, 8)?,
+            Self::List => {
+                let ty = Self::read(buf)?;
+                let len = u32::read(buf)?;
+                let size = match ty {
+                    Self::Byte => 1,
+                    Self::Short => 2,
+                    Self::Int | Self::Float => 4,
+                    Self::Long | Self::Double => 8,
+                    Self::End => 0,
+                    _ => return Ok(Some(Frame::List(ty, len))),
+                };
+                (len as usize)
+                    .checked_mul(size)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?
+            }
+            Self::Compound => return Ok(Some(Frame::Compound)),
+        };
+        buf.read_slice(n)?;
+        Ok(None)
+    }
+}
+
+fn array_len(buf: &mut Reader<'_>, size: usize) -> Result<usize, Error> {
+    (u32::read(buf)? as usize)
+        .checked_mul(size)
+        .ok_or_else(|| Error::new(ErrorKind::TooLong))
+}
+
+impl Skip for Tag {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        TagType::read(buf)?.skip(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompoundTag, ListTag, StringTag, Tag};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use mser::{Read, Reader, Skip, Write};
+
+    #[test]
+    fn test_skip() {
+        let mut inner = CompoundTag::new();
+        inner.push(
+            StringTag::from_utf8("id"),
+            Tag::String(StringTag::from_utf8("x")),
+        );
+        let mut compound = CompoundTag::new();
+        compound.push(
+            StringTag::from_utf8("items"),
+            Tag::List(ListTag::Compound(vec![inner.clone()])),
+        );
+        compound.push(
+            StringTag::from_utf8("lists"),
+            Tag::List(ListTag::List(vec![
+                ListTag::Int(vec![1, 2]),
+                ListTag::Compound(vec![inner]),
+            ])),
+        );
+        compound.push(StringTag::from_utf8("longs"), Tag::LongArray(vec![3; 4]));
+        let tag = Tag::Compound(compound);
+
+        let mut data = Vec::with_capacity(tag.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), &tag);
+            data.set_len(tag.len_s());
+        }
+        let mut r = Reader::new(&data);
+        Tag::skip(&mut r).unwrap();
+        assert!(r.is_empty());
+
+        let mut r = Reader::new(&data);
+        let read = Tag::read(&mut r).unwrap();
+        assert!(r.is_empty());
+        assert_eq!(read.len_s(), data.len());
+
+        let mut r = Reader::new(&data[..data.len() - 1]);
+        assert!(Tag::skip(&mut r).is_err());
+    }
+}
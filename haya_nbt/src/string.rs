@@ -2,7 +2,7 @@ use crate::{Error, Inner, RawStringTag, Read, RefStringTag, StringTag, Write, Wr
 use alloc::vec::Vec;
 use haya_mutf8::{as_mutf8_ascii, decode_mutf8, decode_mutf8_len, encode_mutf8, encode_mutf8_len};
 use haya_str::HayaStr;
-use mser::Reader;
+use mser::{ErrorKind, Reader};
 
 impl<'a> RawStringTag<'a> {
     pub const fn new(n: &'a [u8]) -> Option<Self> {
@@ -46,7 +46,7 @@ impl<'a> Read<'a> for RawStringTag<'a> {
         if let Some(x) = as_mutf8_ascii(data) {
             Ok(Self(x))
         } else {
-            Err(Error)
+            Err(buf.error(ErrorKind::Utf8))
         }
     }
 }
@@ -1,8 +1,9 @@
+use crate::limits::Accounter;
 use crate::list::ListPrimitive;
 use crate::number::dec_num;
 use crate::{
-    CompoundStringify, CompoundTag, Error, ListTag, Read as _, StringTag, Tag, TagArray,
-    TagPrimitive,
+    CompoundStringify, CompoundTag, Error, ErrorKind, ListTag, NbtLimits, Read as _, StringTag,
+    Tag, TagArray, TagPrimitive,
 };
 use alloc::vec;
 use alloc::vec::Vec;
@@ -24,7 +25,12 @@ impl From<CompoundTag> for CompoundStringify {
 impl CompoundStringify {
     #[inline]
     pub fn decode(n: &str) -> Result<Self, Error> {
-        unsafe { decode(&mut Reader::new(n.as_bytes()), 512).map(Self) }
+        Self::decode_limited(n, NbtLimits::NETWORK)
+    }
+
+    #[inline]
+    pub fn decode_limited(n: &str, limits: NbtLimits) -> Result<Self, Error> {
+        unsafe { decode(&mut Reader::new(n.as_bytes()), limits).map(Self) }
     }
 
     #[inline]
@@ -36,7 +42,7 @@ impl CompoundStringify {
 fn find_next_value<'a>(n: &mut Reader<'a>) -> Result<&'a [u8], Error> {
     let i = n.position(b",]} \n\t\r");
     if n.end_from(i) {
-        Err(Error)
+        Err(n.error(ErrorKind::Invalid))
     } else {
         unsafe { Ok(n.read_slice_from(i)) }
     }
@@ -58,14 +64,14 @@ fn dec_arr_peek(n: &mut Reader, tmp: &mut TBuf) -> Result<TagArray, Error> {
                 let value = find_next_value(n)?;
                 let a = match dec_num(value, tmp.next()) {
                     Ok(TagPrimitive::Byte(l)) => l,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 };
                 skip_ws(n);
                 vec.push(a);
                 match u8::read(n)? {
                     b']' => break,
                     b',' => continue,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 }
             }
             vec.shrink_to_fit();
@@ -85,14 +91,14 @@ fn dec_arr_peek(n: &mut Reader, tmp: &mut TBuf) -> Result<TagArray, Error> {
                 let value = find_next_value(n)?;
                 let a = match dec_num(value, tmp.next()) {
                     Ok(TagPrimitive::Int(l)) => l,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 };
                 skip_ws(n);
                 vec.push(a);
                 match u8::read(n)? {
                     b']' => break,
                     b',' => continue,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 }
             }
             vec.shrink_to_fit();
@@ -112,20 +118,20 @@ fn dec_arr_peek(n: &mut Reader, tmp: &mut TBuf) -> Result<TagArray, Error> {
                 let value = find_next_value(n)?;
                 let a = match dec_num(value, tmp.next()) {
                     Ok(TagPrimitive::Long(l)) => l,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 };
                 skip_ws(n);
                 vec.push(a);
                 match u8::read(n)? {
                     b']' => break,
                     b',' => continue,
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 }
             }
             vec.shrink_to_fit();
             Ok(TagArray::Long(vec))
         },
-        _ => Err(Error),
+        _ => Err(n.error(ErrorKind::Invalid)),
     }
 }
 
@@ -137,12 +143,13 @@ impl TBuf {
     }
 }
 
-unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error> {
+unsafe fn decode(n: &mut Reader, limits: NbtLimits) -> Result<CompoundTag, Error> {
     enum Bl {
         C(CompoundTag),
         L(ListTag),
     }
 
+    let mut acc = Accounter::new(limits);
     let mut tmp = TBuf(Vec::new());
     let mut names = Vec::<u8>::new();
     let mut blocks = vec![Bl::C(CompoundTag::new())];
@@ -151,16 +158,15 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
 
     skip_ws(n);
     if u8::read(n)? != b'{' {
-        return Err(Error);
+        return Err(n.error(ErrorKind::Invalid));
     }
+    acc.compound(n)?;
     loop {
         // step 1 or none
-        if blocks.len() == max_depth {
-            return Err(Error);
-        }
+        acc.depth(n, blocks.len())?;
         let mut bl = match blocks.pop() {
             Some(x) => x,
-            None => return Err(Error),
+            None => return Err(n.error(ErrorKind::Invalid)),
         };
         skip_ws(n);
 
@@ -185,13 +191,13 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
             match u8::read(n)? {
                 b'}' => on_end = true,
                 b',' => (),
-                _ => return Err(Error),
+                _ => return Err(n.error(ErrorKind::Invalid)),
             }
         } else {
             match u8::read(n)? {
                 b']' => on_end = true,
                 b',' => (),
-                _ => return Err(Error),
+                _ => return Err(n.error(ErrorKind::Invalid)),
             }
         }
         if !on_end {
@@ -250,7 +256,7 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                         names.truncate(new_len);
                         continue;
                     }
-                    _ => return Err(Error),
+                    _ => return Err(n.error(ErrorKind::Invalid)),
                 },
                 (Some(Bl::L(l)), Bl::C(x)) => {
                     if let ListTag::None = l {
@@ -260,7 +266,7 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                         vec.push(x);
                         continue;
                     } else {
-                        return Err(Error);
+                        return Err(n.error(ErrorKind::Invalid));
                     }
                 }
                 (Some(Bl::L(l)), Bl::L(x)) => {
@@ -271,15 +277,16 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                         vec.push(x);
                         continue;
                     } else {
-                        return Err(Error);
+                        return Err(n.error(ErrorKind::Invalid));
                     }
                 }
                 (None, Bl::C(x)) => return Ok(x),
-                _ => return Err(Error),
+                _ => return Err(n.error(ErrorKind::Invalid)),
             }
         }
         match bl {
             Bl::C(mut c) => {
+                let mark = names.len();
                 let k = match n.peek_byte()? {
                     b'\"' => {
                         unsafe { n.advance(1) }
@@ -292,7 +299,7 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                     _ => unsafe {
                         let x = n.position(b": \n\t\r");
                         if n.end_from(x) {
-                            return Err(Error);
+                            return Err(n.error(ErrorKind::Invalid));
                         }
                         let m = n.read_slice_from(x);
                         let a = names.len();
@@ -301,16 +308,18 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                     },
                 };
                 let kl = (k.len() as u32).to_le_bytes();
+                acc.entry(n, k.len())?;
                 skip_ws(n);
                 if b':' == n.peek_byte()? {
                     unsafe { n.advance(1) }
                 } else {
-                    return Err(Error);
+                    return Err(n.error(ErrorKind::Invalid));
                 }
                 skip_ws(n);
                 let t = match n.peek_byte()? {
                     b'{' => {
                         unsafe { n.advance(1) }
+                        acc.compound(n)?;
                         names.extend(kl);
                         blocks.push(Bl::C(c));
                         blocks.push(Bl::C(CompoundTag::new()));
@@ -324,6 +333,7 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                             Ok(TagArray::Int(x)) => Tag::IntArray(x),
                             Ok(TagArray::Long(x)) => Tag::LongArray(x),
                             Err(_) => {
+                                acc.list_header(n)?;
                                 names.extend(kl);
                                 blocks.push(Bl::C(c));
                                 blocks.push(Bl::L(ListTag::None));
@@ -350,13 +360,18 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                         }
                     },
                 };
+                acc.tag(n, &t)?;
                 unsafe {
                     c.push(StringTag::from_utf8(from_utf8_unchecked(k)), t);
                 }
+                names.truncate(mark);
+                blocks.push(Bl::C(c));
             }
             Bl::L(mut l) => match n.peek_byte()? {
                 b'{' => unsafe {
                     n.advance(1);
+                    acc.slots(n, 1)?;
+                    acc.compound(n)?;
                     if let ListTag::None = &l {
                         l = ListTag::Compound(Vec::new());
                     }
@@ -366,46 +381,51 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                 },
                 b'[' => unsafe {
                     n.advance(1);
+                    acc.slots(n, 1)?;
                     match dec_arr_peek(n, &mut tmp) {
                         Ok(arr) => {
                             match arr {
                                 TagArray::Byte(b) => {
+                                    acc.array(n, b.len(), 1)?;
                                     if let ListTag::None = &l {
                                         l = ListTag::ByteArray(Vec::new());
                                     }
                                     match &mut l {
                                         ListTag::ByteArray(x) => x.push(b),
-                                        _ => return Err(Error),
+                                        _ => return Err(n.error(ErrorKind::Invalid)),
                                     }
                                 }
                                 TagArray::Int(b) => {
+                                    acc.array(n, b.len(), 4)?;
                                     if let ListTag::None = &l {
                                         l = ListTag::IntArray(Vec::new());
                                     }
                                     match &mut l {
                                         ListTag::IntArray(x) => x.push(b),
-                                        _ => return Err(Error),
+                                        _ => return Err(n.error(ErrorKind::Invalid)),
                                     }
                                 }
                                 TagArray::Long(b) => {
+                                    acc.array(n, b.len(), 8)?;
                                     if let ListTag::None = &l {
                                         l = ListTag::LongArray(Vec::new());
                                     }
                                     match &mut l {
                                         ListTag::LongArray(x) => x.push(b),
-                                        _ => return Err(Error),
+                                        _ => return Err(n.error(ErrorKind::Invalid)),
                                     }
                                 }
                             }
                             blocks.push(Bl::L(l));
                         }
                         Err(_) => {
+                            acc.list_header(n)?;
                             match &l {
                                 ListTag::None => {
                                     l = ListTag::List(Vec::new());
                                 }
                                 ListTag::List(_) => (),
-                                _ => return Err(Error),
+                                _ => return Err(n.error(ErrorKind::Invalid)),
                             }
                             blocks.push(Bl::L(l));
                             blocks.push(Bl::L(ListTag::None));
@@ -441,6 +461,10 @@ unsafe fn decode(n: &mut Reader, max_depth: usize) -> Result<CompoundTag, Error>
                             ListTag::String(list)
                         }
                     };
+                    acc.slots(n, l.list_info().1 as usize)?;
+                    acc.elements(n, &l)?;
+                    // The closing bracket has been consumed.
+                    on_end = true;
                     blocks.push(Bl::L(l));
                 },
             },
@@ -472,7 +496,7 @@ unsafe fn dec_list_string(
                 unsafe { list.push(StringTag::from_utf8(from_utf8_unchecked(x))) }
             }
             b']' => return Ok(()),
-            _ => return Err(Error),
+            _ => return Err(n.error(ErrorKind::Invalid)),
         }
     }
 }
@@ -510,7 +534,7 @@ unsafe fn dec_list_primitive(
                             v.push(x as f64);
                             break 'l;
                         }
-                        _ => return Err(Error),
+                        _ => return Err(n.error(ErrorKind::Invalid)),
                     },
                     TagPrimitive::Double(x) => match &mut list {
                         ListPrimitive::Float(v) => {
@@ -521,7 +545,7 @@ unsafe fn dec_list_primitive(
                             v.push(x);
                             break 'l;
                         }
-                        _ => return Err(Error),
+                        _ => return Err(n.error(ErrorKind::Invalid)),
                     },
                 };
                 match &mut list {
@@ -534,7 +558,7 @@ unsafe fn dec_list_primitive(
                 }
             }
             b']' => return Ok(list),
-            _ => return Err(Error),
+            _ => return Err(n.error(ErrorKind::Invalid)),
         }
     }
 }
@@ -547,7 +571,7 @@ fn dec_quoted_str<'a>(n: &mut Reader, buf: &'a mut Vec<u8>, quote: u8) -> Result
     loop {
         let cur = n.memchr2(ESCAPE, quote);
         if n.end_from(cur) {
-            return Err(Error);
+            return Err(n.error(ErrorKind::Invalid));
         }
         unsafe { buf.extend(n.read_slice_from(cur)) }
         if n.read_byte()? != ESCAPE {
@@ -572,15 +596,15 @@ fn escape_quoted(n: &mut Reader) -> Result<char, Error> {
         b'n' => Ok('\n'),
         b'x' => match dec_char2(*n.read_array()?) {
             Some(x) => Ok(x),
-            None => Err(Error),
+            None => Err(n.error(ErrorKind::Invalid)),
         },
         b'u' => match dec_char4(*n.read_array()?) {
             Some(x) => Ok(x),
-            None => Err(Error),
+            None => Err(n.error(ErrorKind::Invalid)),
         },
         b'U' => match dec_char8(*n.read_array()?) {
             Some(x) => Ok(x),
-            None => Err(Error),
+            None => Err(n.error(ErrorKind::Invalid)),
         },
         b'N' => unsafe {
             if b'{' == n.peek_byte()? {
@@ -592,16 +616,16 @@ fn escape_quoted(n: &mut Reader) -> Result<char, Error> {
                     if let Some(x) = crate::unicode::character(name) {
                         Ok(x)
                     } else {
-                        Err(Error)
+                        Err(n.error(ErrorKind::Invalid))
                     }
                 } else {
-                    Err(Error)
+                    Err(n.error(ErrorKind::Invalid))
                 }
             } else {
-                Err(Error)
+                Err(n.error(ErrorKind::Invalid))
             }
         },
-        _ => Err(Error),
+        _ => Err(n.error(ErrorKind::Invalid)),
     }
 }
 
@@ -1015,3 +1039,23 @@ fn encode(buf: &mut Vec<u8>, n: &CompoundTag) {
         buf.push(b']');
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompoundStringify, ListTag, Tag};
+
+    #[test]
+    fn test_decode() {
+        let c = CompoundStringify::decode(
+            r#"{id:"minecraft:stone",Count:1b,l:[1,2],s:['a',"b"],tag:{a:{}},n:-3}"#,
+        )
+        .unwrap()
+        .0;
+        assert_eq!(c.len(), 6);
+        assert!(matches!(c.find("Count"), Some(Tag::Byte(1))));
+        assert!(matches!(c.find("l"), Some(Tag::List(ListTag::Int(x))) if x[..] == [1, 2]));
+        assert!(matches!(c.find("s"), Some(Tag::List(ListTag::String(x))) if x.len() == 2));
+        assert!(matches!(c.find("tag"), Some(Tag::Compound(x)) if x.len() == 1));
+        assert!(matches!(c.find("n"), Some(Tag::Int(-3))));
+    }
+}
@@ -0,0 +1,555 @@
+use crate::{CompoundTag, ListTag, StringTag, Tag, TagType};
+use alloc::vec::Vec;
+use haya_mutf8::as_mutf8_ascii;
+use mser::{Error, ErrorKind, Read, Reader, Skip, Write, Writer};
+
+/// A tag borrowed from its encoded form.
+///
+/// The structure is validated when read, so lists and compounds can be
+/// walked later without errors. Strings stay in modified UTF-8 until
+/// converted.
+#[derive(Clone, Copy, Debug)]
+pub enum TagRef<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(StringRef<'a>),
+    ByteArray(&'a [i8]),
+    IntArray(IntArrayRef<'a>),
+    LongArray(LongArrayRef<'a>),
+    List(ListRef<'a>),
+    Compound(CompoundRef<'a>),
+    End,
+}
+
+/// Length prefix followed by modified UTF-8.
+#[derive(Clone, Copy, Debug)]
+pub struct StringRef<'a>(&'a [u8]);
+
+/// Element type, length and elements.
+#[derive(Clone, Copy, Debug)]
+pub struct ListRef<'a>(&'a [u8]);
+
+/// Named entries up to and including the end tag.
+#[derive(Clone, Copy, Debug)]
+pub struct CompoundRef<'a>(&'a [u8]);
+
+impl TagType {
+    /// Borrows a payload of this type from `buf`.
+    pub fn tag_ref<'a>(self, buf: &mut Reader<'a>) -> Result<TagRef<'a>, Error> {
+        let start = buf.peek_slice(buf.len())?;
+        Ok(match self {
+            Self::End => TagRef::End,
+            Self::Byte => TagRef::Byte(i8::read(buf)?),
+            Self::Short => TagRef::Short(i16::read(buf)?),
+            Self::Int => TagRef::Int(i32::read(buf)?),
+            Self::Long => TagRef::Long(i64::read(buf)?),
+            Self::Float => TagRef::Float(f32::read(buf)?),
+            Self::Double => TagRef::Double(f64::read(buf)?),
+            Self::String => {
+                let len = u16::read(buf)? as usize;
+                buf.read_slice(len)?;
+                TagRef::String(StringRef(&start[..2 + len]))
+            }
+            Self::ByteArray => {
+                let len = u32::read(buf)? as usize;
+                let s = buf.read_slice(len)?;
+                TagRef::ByteArray(unsafe { core::slice::from_raw_parts(s.as_ptr().cast(), len) })
+            }
+            Self::IntArray | Self::LongArray => {
+                let size = if let Self::IntArray = self { 4 } else { 8 };
+                let len = (u32::read(buf)? as usize)
+                    .checked_mul(size)
+                    .ok_or_else(|| Error::new(ErrorKind::TooLong))?;
+                let s = buf.read_slice(len)?;
+                if size == 4 {
+                    TagRef::IntArray(IntArrayRef(s))
+                } else {
+                    TagRef::LongArray(LongArrayRef(s))
+                }
+            }
+            Self::List | Self::Compound => {
+                self.skip(buf)?;
+                let s = &start[..start.len() - buf.len()];
+                if let Self::List = self {
+                    TagRef::List(ListRef(s))
+                } else {
+                    TagRef::Compound(CompoundRef(s))
+                }
+            }
+        })
+    }
+}
+
+impl<'a> TagRef<'a> {
+    pub const fn id(&self) -> TagType {
+        match self {
+            Self::Byte(_) => TagType::Byte,
+            Self::Short(_) => TagType::Short,
+            Self::Int(_) => TagType::Int,
+            Self::Long(_) => TagType::Long,
+            Self::Float(_) => TagType::Float,
+            Self::Double(_) => TagType::Double,
+            Self::String(_) => TagType::String,
+            Self::ByteArray(_) => TagType::ByteArray,
+            Self::IntArray(_) => TagType::IntArray,
+            Self::LongArray(_) => TagType::LongArray,
+            Self::List(_) => TagType::List,
+            Self::Compound(_) => TagType::Compound,
+            Self::End => TagType::End,
+        }
+    }
+
+    pub const fn as_byte(self) -> Option<i8> {
+        match self {
+            Self::Byte(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_short(self) -> Option<i16> {
+        match self {
+            Self::Short(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_int(self) -> Option<i32> {
+        match self {
+            Self::Int(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_long(self) -> Option<i64> {
+        match self {
+            Self::Long(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_float(self) -> Option<f32> {
+        match self {
+            Self::Float(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_double(self) -> Option<f64> {
+        match self {
+            Self::Double(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_string(self) -> Option<StringRef<'a>> {
+        match self {
+            Self::String(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_byte_array(self) -> Option<&'a [i8]> {
+        match self {
+            Self::ByteArray(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_int_array(self) -> Option<IntArrayRef<'a>> {
+        match self {
+            Self::IntArray(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_long_array(self) -> Option<LongArrayRef<'a>> {
+        match self {
+            Self::LongArray(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_list(self) -> Option<ListRef<'a>> {
+        match self {
+            Self::List(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn as_compound(self) -> Option<CompoundRef<'a>> {
+        match self {
+            Self::Compound(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Decodes an owned copy.
+    pub fn to_tag(self) -> Result<Tag, Error> {
+        Ok(match self {
+            Self::Byte(x) => Tag::Byte(x),
+            Self::Short(x) => Tag::Short(x),
+            Self::Int(x) => Tag::Int(x),
+            Self::Long(x) => Tag::Long(x),
+            Self::Float(x) => Tag::Float(x),
+            Self::Double(x) => Tag::Double(x),
+            Self::String(x) => Tag::String(x.to_string_tag()?),
+            Self::ByteArray(x) => Tag::ByteArray(x.to_vec()),
+            Self::IntArray(x) => Tag::IntArray(x.iter().collect()),
+            Self::LongArray(x) => Tag::LongArray(x.iter().collect()),
+            Self::List(x) => Tag::List(x.to_list()?),
+            Self::Compound(x) => Tag::Compound(x.to_compound()?),
+            Self::End => Tag::End,
+        })
+    }
+
+    fn raw(&self) -> &'a [u8] {
+        match *self {
+            Self::String(x) => x.0,
+            Self::ByteArray(x) => unsafe {
+                core::slice::from_raw_parts(x.as_ptr().cast(), x.len())
+            },
+            Self::IntArray(x) => x.0,
+            Self::LongArray(x) => x.0,
+            Self::List(x) => x.0,
+            Self::Compound(x) => x.0,
+            _ => &[],
+        }
+    }
+}
+
+impl<'a> Read<'a> for TagRef<'a> {
+    fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
+        TagType::read(buf)?.tag_ref(buf)
+    }
+}
+
+impl Skip for TagRef<'_> {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        Tag::skip(buf)
+    }
+}
+
+impl Write for TagRef<'_> {
+    unsafe fn write(&self, w: &mut Writer) {
+        unsafe {
+            self.id().write(w);
+            match *self {
+                Self::Byte(x) => x.write(w),
+                Self::Short(x) => x.write(w),
+                Self::Int(x) => x.write(w),
+                Self::Long(x) => x.write(w),
+                Self::Float(x) => x.write(w),
+                Self::Double(x) => x.write(w),
+                Self::ByteArray(x) => {
+                    (x.len() as u32).write(w);
+                    w.write(self.raw());
+                }
+                Self::IntArray(x) => {
+                    (x.len() as u32).write(w);
+                    w.write(self.raw());
+                }
+                Self::LongArray(x) => {
+                    (x.len() as u32).write(w);
+                    w.write(self.raw());
+                }
+                _ => w.write(self.raw()),
+            }
+        }
+    }
+
+    fn len_s(&self) -> usize {
+        1 + match *self {
+            Self::Byte(_) => 1,
+            Self::Short(_) => 2,
+            Self::Int(_) | Self::Float(_) => 4,
+            Self::Long(_) | Self::Double(_) => 8,
+            Self::ByteArray(_) | Self::IntArray(_) | Self::LongArray(_) => 4 + self.raw().len(),
+            _ => self.raw().len(),
+        }
+    }
+}
+
+impl<'a> StringRef<'a> {
+    /// The encoded bytes without the length prefix.
+    pub fn as_bytes(self) -> &'a [u8] {
+        &self.0[2..]
+    }
+
+    /// Borrows the string when its encoding is the same in UTF-8.
+    pub fn as_str(self) -> Option<&'a str> {
+        as_mutf8_ascii(self.as_bytes())
+    }
+
+    pub fn to_string_tag(self) -> Result<StringTag, Error> {
+        StringTag::read(&mut Reader::new(self.0))
+    }
+}
+
+impl PartialEq<str> for StringRef<'_> {
+    fn eq(&self, other: &str) -> bool {
+        match self.as_str() {
+            Some(x) => x == other,
+            None => match self.to_string_tag() {
+                Ok(x) => &*x == other,
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+impl<'a> ListRef<'a> {
+    pub fn element(self) -> TagType {
+        unsafe { core::mem::transmute::<u8, TagType>(self.0[0]) }
+    }
+
+    pub fn len(self) -> usize {
+        u32::from_be_bytes([self.0[1], self.0[2], self.0[3], self.0[4]]) as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(self) -> ListIter<'a> {
+        ListIter {
+            element: self.element(),
+            remaining: self.len(),
+            buf: Reader::new(&self.0[5..]),
+        }
+    }
+
+    pub fn get(self, index: usize) -> Option<TagRef<'a>> {
+        self.iter().nth(index)
+    }
+
+    pub fn to_list(self) -> Result<ListTag, Error> {
+        match TagType::List.tag(&mut Reader::new(self.0))? {
+            Tag::List(x) => Ok(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+}
+
+impl<'a> IntoIterator for ListRef<'a> {
+    type Item = TagRef<'a>;
+    type IntoIter = ListIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct ListIter<'a> {
+    element: TagType,
+    remaining: usize,
+    buf: Reader<'a>,
+}
+
+impl<'a> Iterator for ListIter<'a> {
+    type Item = TagRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let tag = self.element.tag_ref(&mut self.buf).ok();
+        if tag.is_none() {
+            self.remaining = 0;
+        }
+        tag
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> CompoundRef<'a> {
+    /// Finds the first entry named `name`.
+    pub fn get(self, name: &str) -> Option<TagRef<'a>> {
+        self.iter().find(|(k, _)| *k == *name).map(|(_, v)| v)
+    }
+
+    pub fn iter(self) -> CompoundIter<'a> {
+        CompoundIter {
+            buf: Reader::new(self.0),
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0.first() == Some(&(TagType::End as u8))
+    }
+
+    pub fn to_compound(self) -> Result<CompoundTag, Error> {
+        match TagType::Compound.tag(&mut Reader::new(self.0))? {
+            Tag::Compound(x) => Ok(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+}
+
+impl<'a> IntoIterator for CompoundRef<'a> {
+    type Item = (StringRef<'a>, TagRef<'a>);
+    type IntoIter = CompoundIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct CompoundIter<'a> {
+    buf: Reader<'a>,
+}
+
+impl<'a> CompoundIter<'a> {
+    fn entry(&mut self) -> Result<Option<(StringRef<'a>, TagRef<'a>)>, Error> {
+        let ty = TagType::read(&mut self.buf)?;
+        if let TagType::End = ty {
+            return Ok(None);
+        }
+        let name = match TagType::String.tag_ref(&mut self.buf)? {
+            TagRef::String(x) => x,
+            _ => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        Ok(Some((name, ty.tag_ref(&mut self.buf)?)))
+    }
+}
+
+impl<'a> Iterator for CompoundIter<'a> {
+    type Item = (StringRef<'a>, TagRef<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        match self.entry() {
+            Ok(Some(x)) => Some(x),
+            _ => {
+                self.buf = Reader::new(&[]);
+                None
+            }
+        }
+    }
+}
+
+macro_rules! array_ref {
+    ($name:ident, $ty:ty, $size:literal) => {
+        #[derive(Clone, Copy, Debug)]
+        pub struct $name<'a>(&'a [u8]);
+
+        impl<'a> $name<'a> {
+            pub const fn len(self) -> usize {
+                self.0.len() / $size
+            }
+
+            pub const fn is_empty(self) -> bool {
+                self.0.is_empty()
+            }
+
+            pub fn get(self, index: usize) -> Option<$ty> {
+                let b = self.0.get(index * $size..index * $size + $size)?;
+                Some(<$ty>::from_be_bytes(b.try_into().ok()?))
+            }
+
+            pub fn iter(self) -> impl ExactSizeIterator<Item = $ty> + 'a {
+                self.0
+                    .chunks_exact($size)
+                    .map(|b| <$ty>::from_be_bytes(b.try_into().unwrap_or_default()))
+            }
+
+            pub fn to_vec(self) -> Vec<$ty> {
+                self.iter().collect()
+            }
+        }
+    };
+}
+
+array_ref!(IntArrayRef, i32, 4);
+array_ref!(LongArrayRef, i64, 8);
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompoundTag, ListTag, StringTag, Tag, TagRef};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use mser::{Read, Reader, Write};
+
+    fn encode(tag: &impl Write) -> Vec<u8> {
+        let mut data = Vec::with_capacity(tag.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), tag);
+            data.set_len(tag.len_s());
+        }
+        data
+    }
+
+    #[test]
+    fn test_tag_ref() {
+        let mut item = CompoundTag::new();
+        item.push(
+            StringTag::from_utf8("id"),
+            Tag::String(StringTag::from_utf8("stone")),
+        );
+        item.push(StringTag::from_utf8("count"), Tag::Int(3));
+        let mut root = CompoundTag::new();
+        root.push(
+            StringTag::from_utf8("name"),
+            Tag::String(StringTag::from_utf8("\0é")),
+        );
+        root.push(
+            StringTag::from_utf8("items"),
+            Tag::List(ListTag::Compound(vec![item; 2])),
+        );
+        root.push(StringTag::from_utf8("longs"), Tag::LongArray(vec![-1, 2]));
+        root.push(
+            StringTag::from_utf8("bytes"),
+            Tag::ByteArray(vec![-1, 2, 3]),
+        );
+        root.push(
+            StringTag::from_utf8("f"),
+            Tag::List(ListTag::Float(vec![0.5; 3])),
+        );
+        let data = encode(&Tag::Compound(root));
+
+        let mut r = Reader::new(&data);
+        let tag = TagRef::read(&mut r).unwrap();
+        assert!(r.is_empty());
+        let root = tag.as_compound().unwrap();
+        assert_eq!(root.iter().count(), 5);
+        let name = root.get("name").and_then(TagRef::as_string).unwrap();
+        assert!(name.as_str().is_none());
+        assert_eq!(&*name.to_string_tag().unwrap(), "\0é");
+        assert!(name == *"\0é");
+
+        let items = root.get("items").and_then(TagRef::as_list).unwrap();
+        assert_eq!(items.len(), 2);
+        for entry in items {
+            let item = entry.as_compound().unwrap();
+            assert_eq!(item.get("count").and_then(TagRef::as_int), Some(3));
+            assert_eq!(
+                item.get("id").and_then(TagRef::as_string).unwrap().as_str(),
+                Some("stone")
+            );
+            assert!(item.get("missing").is_none());
+        }
+        let longs = root.get("longs").and_then(TagRef::as_long_array).unwrap();
+        assert_eq!(longs.to_vec(), [-1, 2]);
+        assert_eq!(longs.get(1), Some(2));
+        assert_eq!(
+            root.get("bytes").and_then(TagRef::as_byte_array),
+            Some(&[-1, 2, 3][..])
+        );
+        let floats = root.get("f").and_then(TagRef::as_list).unwrap();
+        assert_eq!(floats.get(2).and_then(TagRef::as_float), Some(0.5));
+
+        assert_eq!(encode(&tag), data);
+        assert_eq!(encode(&tag.to_tag().unwrap()), data);
+        for (_, v) in root {
+            let owned = v.to_tag().unwrap();
+            assert_eq!(encode(&owned), encode(&v));
+        }
+        assert!(TagRef::read(&mut Reader::new(&data[..data.len() - 1])).is_err());
+    }
+}
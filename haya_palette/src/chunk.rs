@@ -1,13 +1,23 @@
-use crate::Biome;
+use crate::{Biome, BiomeContainer, ChunkColumn};
 use alloc::vec::Vec;
 use hashbrown::HashTable;
 use minecraft_data::{block, block_state};
-use mser::cold_path;
+use mser::{Error, ErrorKind, Read, Reader, cold_path};
 
 const BLOCK_PER_CHUNK: usize = 4 * 4 * 4;
 const INDIRECT4_PER_CHUNK: usize = BLOCK_PER_CHUNK / 2;
 const INDIRECT2_PER_CHUNK: usize = BLOCK_PER_CHUNK / 4;
-const INDEX_MASK: u64 = 0x3FFF_FFFF_FFFF_FFFF;
+
+// A cell is the 4x4x4 blocks sharing a biome. Its value in the table is
+// `kind << 62 | biome << 32 | index`, where `index` is into the pool of its
+// kind, or the block state itself for `SINGLE`.
+const DIRECT: u64 = 0;
+const INDIRECT4: u64 = 1;
+const INDIRECT2: u64 = 2;
+const SINGLE: u64 = 3;
+const KIND_SHIFT: u32 = 62;
+const BIOME_SHIFT: u32 = 32;
+const INDEX_MASK: u64 = 0xFFFF_FFFF;
 
 #[derive(Clone)]
 pub struct Direct<T: Copy> {
@@ -17,26 +27,32 @@ pub struct Direct<T: Copy> {
 #[derive(Clone)]
 pub struct Indirect4<T: Copy> {
     palette: [T; 16],
+    len: u8,
     data: [u8; INDIRECT4_PER_CHUNK],
 }
 
 #[derive(Clone)]
 pub struct Indirect2<T: Copy> {
     palette: [T; 4],
+    len: u8,
     data: [u8; INDIRECT2_PER_CHUNK],
 }
 
+/// Loaded blocks and biomes of a client's world, stored per 4x4x4 cell in
+/// the smallest of four layouts.
 #[derive(Clone)]
 pub struct ChunkCache {
-    pub direct: Vec<Direct<block_state>>,
-    pub indirect2: Vec<Indirect2<block_state>>,
-    pub indirect4: Vec<Indirect4<block_state>>,
-    pub biome: Vec<Biome>,
-    pub chunks: HashTable<(u64, u64)>,
-    pub direct_key: Vec<u32>,
-    pub indirect4_key: Vec<u32>,
-    pub indirect2_key: Vec<u32>,
-    pub single_key: Vec<u32>,
+    direct: Vec<Direct<block_state>>,
+    indirect4: Vec<Indirect4<block_state>>,
+    indirect2: Vec<Indirect2<block_state>>,
+    /// Cell key of each pool entry, so that freeing can move the last
+    /// entry into the hole.
+    direct_key: Vec<u64>,
+    indirect4_key: Vec<u64>,
+    indirect2_key: Vec<u64>,
+    cells: HashTable<(u64, u64)>,
+    min_y: i32,
+    height: u32,
 }
 
 impl Default for ChunkCache {
@@ -46,44 +62,326 @@ impl Default for ChunkCache {
 }
 
 impl ChunkCache {
+    /// An empty cache for the overworld.
     pub const fn new() -> Self {
         Self {
             direct: Vec::new(),
-            indirect2: Vec::new(),
             indirect4: Vec::new(),
-            biome: Vec::new(),
-            chunks: HashTable::new(),
+            indirect2: Vec::new(),
             direct_key: Vec::new(),
             indirect4_key: Vec::new(),
             indirect2_key: Vec::new(),
-            single_key: Vec::new(),
+            cells: HashTable::new(),
+            min_y: -64,
+            height: 384,
         }
     }
 
+    /// Clears the cache for a dimension whose `min_y` and `height` come from
+    /// its dimension type, as after `Login` or `Respawn`.
+    pub fn set_dimension(&mut self, min_y: i32, height: u32) {
+        self.clear();
+        self.min_y = min_y;
+        self.height = height;
+    }
+
+    pub fn clear(&mut self) {
+        self.direct.clear();
+        self.indirect4.clear();
+        self.indirect2.clear();
+        self.direct_key.clear();
+        self.indirect4_key.clear();
+        self.indirect2_key.clear();
+        self.cells.clear();
+    }
+
+    /// Releases memory left over from forgotten chunks.
+    pub fn shrink_to_fit(&mut self) {
+        self.direct.shrink_to_fit();
+        self.indirect4.shrink_to_fit();
+        self.indirect2.shrink_to_fit();
+        self.direct_key.shrink_to_fit();
+        self.indirect4_key.shrink_to_fit();
+        self.indirect2_key.shrink_to_fit();
+        self.cells.shrink_to_fit(|x| mix(x.0));
+    }
+
+    /// Number of loaded cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Loads the `data` of a `LevelChunkWithLight`, replacing the chunk if
+    /// it is already loaded.
+    pub fn insert_chunk(&mut self, x: i32, z: i32, data: &[u8]) -> Result<(), Error> {
+        let column = ChunkColumn::read(data, self.min_y, self.height)?;
+        self.insert_column(x, z, &column)
+    }
+
+    /// Loads a decoded column, which must span the dimension of the cache.
+    pub fn insert_column(&mut self, x: i32, z: i32, column: &ChunkColumn) -> Result<(), Error> {
+        if column.min_y() != self.min_y || column.sections.len() != (self.height >> 4) as usize {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+        self.remove_chunk(x, z);
+        let mut states = [block::air.state_default(); BLOCK_PER_CHUNK];
+        for (i, section) in column.sections.iter().enumerate() {
+            let section_y = (self.min_y >> 4) + i as i32;
+            for cell in 0..64 {
+                let (cx, cy, cz) = (cell & 3, cell >> 4, cell >> 2 & 3);
+                for (j, slot) in states.iter_mut().enumerate() {
+                    let index = (cy * 4 + (j >> 4)) << 8
+                        | (cz * 4 + (j >> 2 & 3)) << 4
+                        | (cx * 4 + (j & 3));
+                    let id = unsafe { *section.block_states.get_unchecked(index) };
+                    *slot = block_state::new(id).unwrap_or(block::air.state_default());
+                }
+                let biome = unsafe { *section.biomes.get_unchecked(cell) };
+                let key = pack(
+                    x * 4 + cx as i32,
+                    section_y * 4 + cy as i32,
+                    z * 4 + cz as i32,
+                );
+                let value = self.alloc(key, &states) | (biome as u64) << BIOME_SHIFT;
+                self.cells
+                    .insert_unique(mix(key), (key, value), |e| mix(e.0));
+            }
+        }
+        Ok(())
+    }
+
+    /// Unloads a chunk, as on `ForgetLevelChunk`.
+    pub fn remove_chunk(&mut self, x: i32, z: i32) {
+        let bottom = self.min_y >> 2;
+        for cy in bottom..bottom + (self.height >> 2) as i32 {
+            for cz in 0..4 {
+                for cx in 0..4 {
+                    let key = pack(x * 4 + cx, cy, z * 4 + cz);
+                    if let Ok(entry) = self.cells.find_entry(mix(key), |e| e.0 == key) {
+                        let ((_, value), _) = entry.remove();
+                        self.free(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces the biomes of a loaded chunk with the `data` of a
+    /// `ChunkBiomes` entry.
+    pub fn update_biomes(&mut self, x: i32, z: i32, data: &[u8]) -> Result<(), Error> {
+        let mut buf = Reader::new(data);
+        let count = (self.height >> 4) as usize;
+        let mut sections = Vec::with_capacity(count);
+        for _ in 0..count {
+            sections.push(BiomeContainer::read(&mut buf)?);
+        }
+        if !buf.is_empty() {
+            return Err(buf.error(ErrorKind::Invalid));
+        }
+        for (i, biomes) in sections.iter().enumerate() {
+            let section_y = (self.min_y >> 4) + i as i32;
+            for cell in 0..64 {
+                let key = pack(
+                    x * 4 + (cell & 3) as i32,
+                    section_y * 4 + (cell >> 4) as i32,
+                    z * 4 + (cell >> 2 & 3) as i32,
+                );
+                if let Some(e) = self.cells.find_mut(mix(key), |e| e.0 == key) {
+                    let biome = unsafe { *biomes.get_unchecked(cell) };
+                    e.1 = (e.1 & !(0xFFFF << BIOME_SHIFT)) | (biome as u64) << BIOME_SHIFT;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the changes of a `SectionBlocksUpdate`, packed as
+    /// `state << 12 | x << 8 | z << 4 | y`.
+    pub fn update_section(
+        &mut self,
+        section_x: i32,
+        section_y: i32,
+        section_z: i32,
+        changes: impl IntoIterator<Item = u64>,
+    ) {
+        for change in changes {
+            let Some(state) = u16::try_from(change >> 12).ok().and_then(block_state::new) else {
+                continue;
+            };
+            let pos = change as i32;
+            self.set_block(
+                section_x * 16 + (pos >> 8 & 15),
+                section_y * 16 + (pos & 15),
+                section_z * 16 + (pos >> 4 & 15),
+                state,
+            );
+        }
+    }
+
+    /// Sets a block, as on `BlockUpdate`, returning the previous state or
+    /// `None` if the chunk is not loaded.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, state: block_state) -> Option<block_state> {
+        let j = cell_index(x, y, z);
+        let key = pack(x >> 2, y >> 2, z >> 2);
+        let value = self.cells.find(mix(key), |e| e.0 == key)?.1;
+        let index = (value & INDEX_MASK) as usize;
+        let old = unsafe { self.get_unchecked(value, j) };
+        if old == state {
+            return Some(old);
+        }
+        unsafe {
+            match value >> KIND_SHIFT {
+                DIRECT => {
+                    *self
+                        .direct
+                        .get_unchecked_mut(index)
+                        .data
+                        .get_unchecked_mut(j) = state;
+                    return Some(old);
+                }
+                INDIRECT4 if self.indirect4.get_unchecked_mut(index).set(j, state) => {
+                    return Some(old);
+                }
+                INDIRECT2 if self.indirect2.get_unchecked_mut(index).set(j, state) => {
+                    return Some(old);
+                }
+                _ => {}
+            }
+        }
+        let mut states = [old; BLOCK_PER_CHUNK];
+        for (i, slot) in states.iter_mut().enumerate() {
+            *slot = unsafe { self.get_unchecked(value, i) };
+        }
+        states[j] = state;
+        self.free(value);
+        let kind = self.alloc(key, &states);
+        if let Some(e) = self.cells.find_mut(mix(key), |e| e.0 == key) {
+            e.1 = kind | (value & (0xFFFF << BIOME_SHIFT));
+        }
+        Some(old)
+    }
+
+    /// The block at `x`, `y`, `z`, or void air if its chunk is not loaded.
     pub fn get_block(&self, x: i32, y: i32, z: i32) -> block_state {
-        let j = ((x & 3) | ((y & 3) << 2) | ((z & 3) << 4)) as usize;
-        let chunk = pack(x >> 2, y >> 2, z >> 2);
-        let t = match self.chunks.find(mix(chunk), |x| x.0 == chunk) {
-            Some(t) => t.1,
+        let key = pack(x >> 2, y >> 2, z >> 2);
+        match self.cells.find(mix(key), |e| e.0 == key) {
+            Some(e) => unsafe { self.get_unchecked(e.1, cell_index(x, y, z)) },
             None => {
                 cold_path();
-                return block::void_air.state_default();
+                block::void_air.state_default()
             }
-        };
-        let n = (t & INDEX_MASK) as usize;
-        let ty = t >> 62;
+        }
+    }
+
+    /// The biome at block `x`, `y`, `z` if its chunk is loaded.
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Option<Biome> {
+        let key = pack(x >> 2, y >> 2, z >> 2);
+        self.cells
+            .find(mix(key), |e| e.0 == key)
+            .map(|e| Biome((e.1 >> BIOME_SHIFT) as u16))
+    }
+
+    /// # Safety
+    ///
+    /// `value` must be a cell of this cache and `j` less than 64.
+    unsafe fn get_unchecked(&self, value: u64, j: usize) -> block_state {
+        let n = (value & INDEX_MASK) as usize;
         unsafe {
-            match ty {
-                3 => block_state::new(n as u16).unwrap_unchecked(),
-                2 => self.indirect2.get_unchecked(n).get(j),
-                1 => self.indirect4.get_unchecked(n).get(j),
+            match value >> KIND_SHIFT {
+                SINGLE => block_state::new(n as u16).unwrap_unchecked(),
+                INDIRECT2 => self.indirect2.get_unchecked(n).get(j),
+                INDIRECT4 => self.indirect4.get_unchecked(n).get(j),
                 _ => *self.direct.get_unchecked(n).data.get_unchecked(j),
             }
         }
     }
+
+    /// Stores a cell in the smallest layout that fits, returning its kind
+    /// and index.
+    fn alloc(&mut self, key: u64, states: &[block_state; BLOCK_PER_CHUNK]) -> u64 {
+        let mut palette = [states[0]; 16];
+        let mut len = 1;
+        for &state in states {
+            if !palette[..len].contains(&state) {
+                if len == 16 {
+                    len += 1;
+                    break;
+                }
+                palette[len] = state;
+                len += 1;
+            }
+        }
+        match len {
+            1 => SINGLE << KIND_SHIFT | palette[0].id() as u64,
+            2..=4 => {
+                let mut cell = Indirect2 {
+                    palette: [palette[0], palette[1], palette[2], palette[3]],
+                    len: len as u8,
+                    data: [0; INDIRECT2_PER_CHUNK],
+                };
+                for (j, &state) in states.iter().enumerate() {
+                    cell.set(j, state);
+                }
+                self.indirect2.push(cell);
+                self.indirect2_key.push(key);
+                INDIRECT2 << KIND_SHIFT | (self.indirect2.len() - 1) as u64
+            }
+            5..=16 => {
+                let mut cell = Indirect4 {
+                    palette,
+                    len: len as u8,
+                    data: [0; INDIRECT4_PER_CHUNK],
+                };
+                for (j, &state) in states.iter().enumerate() {
+                    cell.set(j, state);
+                }
+                self.indirect4.push(cell);
+                self.indirect4_key.push(key);
+                INDIRECT4 << KIND_SHIFT | (self.indirect4.len() - 1) as u64
+            }
+            _ => {
+                self.direct.push(Direct { data: *states });
+                self.direct_key.push(key);
+                DIRECT << KIND_SHIFT | (self.direct.len() - 1) as u64
+            }
+        }
+    }
+
+    /// Releases the pool entry of a cell, moving the last entry of the
+    /// pool into its slot.
+    fn free(&mut self, value: u64) {
+        let index = (value & INDEX_MASK) as usize;
+        let moved = match value >> KIND_SHIFT {
+            DIRECT => {
+                self.direct.swap_remove(index);
+                self.direct_key.swap_remove(index);
+                self.direct_key.get(index)
+            }
+            INDIRECT4 => {
+                self.indirect4.swap_remove(index);
+                self.indirect4_key.swap_remove(index);
+                self.indirect4_key.get(index)
+            }
+            INDIRECT2 => {
+                self.indirect2.swap_remove(index);
+                self.indirect2_key.swap_remove(index);
+                self.indirect2_key.get(index)
+            }
+            _ => None,
+        };
+        if let Some(&key) = moved
+            && let Some(e) = self.cells.find_mut(mix(key), |e| e.0 == key)
+        {
+            e.1 = (e.1 & !INDEX_MASK) | index as u64;
+        }
+    }
 }
 
-impl<T: Copy> Indirect2<T> {
+impl<T: Copy + Eq> Indirect2<T> {
     unsafe fn get(&self, index: usize) -> T {
         unsafe {
             let b = *self.data.get_unchecked(index >> 2);
@@ -91,9 +389,20 @@ impl<T: Copy> Indirect2<T> {
             *self.palette.get_unchecked(i as usize)
         }
     }
+
+    /// Sets entry `index`, or returns `false` if the palette is full.
+    fn set(&mut self, index: usize, val: T) -> bool {
+        let Some(i) = palette_index(&mut self.palette, &mut self.len, val) else {
+            return false;
+        };
+        let s = (index & 3) << 1;
+        let b = &mut self.data[index >> 2];
+        *b = (*b & !(0x3 << s)) | (i << s);
+        true
+    }
 }
 
-impl<T: Copy> Indirect4<T> {
+impl<T: Copy + Eq> Indirect4<T> {
     unsafe fn get(&self, index: usize) -> T {
         unsafe {
             let b = *self.data.get_unchecked(index >> 1);
@@ -101,6 +410,36 @@ impl<T: Copy> Indirect4<T> {
             *self.palette.get_unchecked(i as usize)
         }
     }
+
+    /// Sets entry `index`, or returns `false` if the palette is full.
+    fn set(&mut self, index: usize, val: T) -> bool {
+        let Some(i) = palette_index(&mut self.palette, &mut self.len, val) else {
+            return false;
+        };
+        let s = (index & 1) << 2;
+        let b = &mut self.data[index >> 1];
+        *b = (*b & !(0xF << s)) | (i << s);
+        true
+    }
+}
+
+/// Finds or appends `val`, returning `None` if the palette is full.
+fn palette_index<T: Copy + Eq>(palette: &mut [T], len: &mut u8, val: T) -> Option<u8> {
+    let used = *len as usize;
+    match palette[..used].iter().position(|&x| x == val) {
+        Some(i) => Some(i as u8),
+        None if used < palette.len() => {
+            palette[used] = val;
+            *len += 1;
+            Some(used as u8)
+        }
+        None => None,
+    }
+}
+
+/// Index of a block in its cell, `y << 4 | z << 2 | x`.
+const fn cell_index(x: i32, y: i32, z: i32) -> usize {
+    ((x & 3) | ((z & 3) << 2) | ((y & 3) << 4)) as usize
 }
 
 const PRIME_MAX_A: u32 = u32::MAX - 4;
@@ -108,7 +447,7 @@ const PRIME_MAX_B: u32 = u32::MAX - 16;
 const PRIME_MAX_C: u32 = u32::MAX - 64;
 
 #[inline]
-fn pack(x: i32, y: i32, z: i32) -> u64 {
+pub(crate) const fn pack(x: i32, y: i32, z: i32) -> u64 {
     let sx = (x & 0x3FF_FFFF) as i64;
     let sy = (y & 0xFFF) as i64;
     let sz = (z & 0x3FF_FFFF) as i64;
@@ -117,7 +456,7 @@ fn pack(x: i32, y: i32, z: i32) -> u64 {
 
 // 32 bits
 #[inline]
-fn mix(v: u64) -> u64 {
+pub(crate) fn mix(v: u64) -> u64 {
     let x = (v >> 38) as i32 as u32;
     let y = ((v << 52) >> 52) as i32 as u32;
     let z = ((v << 26) >> 38) as i32 as u32;
@@ -127,3 +466,118 @@ fn mix(v: u64) -> u64 {
         .wrapping_add(PRIME_MAX_C.wrapping_mul(z));
     m as u64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockContainer, ChunkSection};
+    use alloc::vec;
+
+    #[test]
+    fn test_chunk_cache() {
+        let stone = block::stone.state_default();
+        let mut cache = ChunkCache::new();
+        cache.set_dimension(0, 32);
+
+        let mut mixed = BlockContainer::new(stone.id());
+        for i in 0..4096 {
+            mixed.set(i, stone.id() + (i % 7 * (i / 512)) as u16);
+        }
+        let mut biomes = BiomeContainer::new(2);
+        biomes.set(63, 9);
+        let column = ChunkColumn::new(
+            0,
+            vec![
+                ChunkSection {
+                    block_count: 4096,
+                    block_states: mixed.clone(),
+                    biomes,
+                },
+                ChunkSection {
+                    block_count: 0,
+                    block_states: BlockContainer::new(block::air.state_default().id()),
+                    biomes: BiomeContainer::new(1),
+                },
+            ],
+        );
+        cache.insert_column(0, 0, &column).unwrap();
+        cache.insert_column(-1, 2, &column).unwrap();
+        let mut other = column.clone();
+        other.sections.pop();
+        assert!(cache.insert_column(5, 5, &other).is_err());
+        assert!(
+            cache
+                .insert_column(5, 5, &ChunkColumn::new(-16, column.sections.clone()))
+                .is_err()
+        );
+        assert_eq!(cache.len(), 2 * 128);
+        for i in 0..4096 {
+            let (x, y, z) = ((i & 15) as i32, (i >> 8) as i32, (i >> 4 & 15) as i32);
+            let id = unsafe { *mixed.get_unchecked(i) };
+            assert_eq!(cache.get_block(x, y, z).id(), id);
+            assert_eq!(cache.get_block(x - 16, y, z + 32).id(), id);
+        }
+        assert!(cache.get_block(0, 20, 0) == block::air.state_default());
+        assert!(cache.get_block(16, 0, 0) == block::void_air.state_default());
+        assert_eq!(cache.get_biome(0, 0, 0).map(|x| x.0), Some(2));
+        assert_eq!(cache.get_biome(15, 15, 15).map(|x| x.0), Some(9));
+        assert_eq!(cache.get_biome(0, 16, 0).map(|x| x.0), Some(1));
+        assert!(cache.get_biome(0, 32, 0).is_none());
+
+        // A single cell growing through every layout.
+        let (x, y, z) = (4, 20, 8);
+        for i in 0..20u16 {
+            let state = block_state::new(stone.id() + i).unwrap();
+            let old = cache.set_block(x + (i & 3) as i32, y, z + (i >> 2) as i32, state);
+            assert!(old == Some(block::air.state_default()));
+        }
+        assert_eq!(cache.direct.len(), 1);
+        for i in 0..20u16 {
+            let got = cache.get_block(x + (i & 3) as i32, y, z + (i >> 2) as i32);
+            assert_eq!(got.id(), stone.id() + i);
+        }
+        assert!(cache.set_block(100, 0, 100, stone).is_none());
+        cache.update_section(
+            -1,
+            0,
+            2,
+            [(stone.id() as u64) << 12 | 0x3_2_1, 1 << 40 | 0x3_2_1],
+        );
+        assert!(cache.get_block(-13, 1, 34) == stone);
+
+        let mut biome_data = vec![0, 5];
+        biome_data.extend_from_slice(&[0, 6]);
+        cache.update_biomes(0, 0, &biome_data).unwrap();
+        assert_eq!(cache.get_biome(15, 15, 15).map(|x| x.0), Some(5));
+        assert_eq!(cache.get_biome(0, 31, 0).map(|x| x.0), Some(6));
+        assert!(cache.update_biomes(0, 0, &biome_data[..2]).is_err());
+
+        cache.remove_chunk(0, 0);
+        assert_eq!(cache.len(), 128);
+        for i in 0..4096 {
+            let (x1, y1, z1) = ((i & 15) as i32, (i >> 8) as i32, (i >> 4 & 15) as i32);
+            let id = unsafe { *mixed.get_unchecked(i) };
+            let expected = if (x1, y1, z1) == (3, 1, 2) {
+                stone.id()
+            } else {
+                id
+            };
+            assert_eq!(cache.get_block(x1 - 16, y1, z1 + 32).id(), expected);
+        }
+        cache.remove_chunk(-1, 2);
+        assert!(cache.is_empty());
+        assert!(
+            cache.direct.is_empty() && cache.indirect4.is_empty() && cache.indirect2.is_empty()
+        );
+        cache.shrink_to_fit();
+
+        // Sections without data, as sent for an empty chunk.
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend_from_slice(&[0, 0, 0, stone.id() as u8, 0, 3]);
+        }
+        cache.insert_chunk(5, 5, &data).unwrap();
+        assert!(cache.get_block(80, 31, 80) == stone);
+        assert!(cache.insert_chunk(5, 5, &data[..6]).is_err());
+    }
+}
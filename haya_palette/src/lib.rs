@@ -2,14 +2,18 @@
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
 mod chunk;
+mod light;
+mod section;
 
 extern crate alloc;
 
 pub use self::chunk::{ChunkCache, Direct, Indirect2, Indirect4};
+pub use self::light::{ChunkLightData, LIGHT_LEN, LightEngine};
+pub use self::section::{ChunkColumn, ChunkSection};
 use alloc::boxed::Box;
 use core::array::from_fn;
 use core::slice::from_raw_parts;
-use mser::{Error, Read, Reader, V21, V32, Write, Writer};
+use mser::{Error, ErrorKind, Read, Reader, V21, Write, Writer};
 
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub struct Biome(pub u16);
@@ -30,6 +34,12 @@ impl Write for Biome {
     }
 }
 
+/// Block state IDs of a 16x16x16 section, indexed by `y << 8 | z << 4 | x`.
+pub type BlockContainer = PalettedContainer<u16, 16, 4096, 2048>;
+
+/// Biome IDs of a section at 4x4x4 resolution, indexed by `y << 4 | z << 2 | x`.
+pub type BiomeContainer = PalettedContainer<u16, 4, 64, 32>;
+
 #[derive(Clone)]
 pub struct PalettedContainer<T: Copy, const P: usize, const L: usize, const H: usize> {
     palette: [T; P],
@@ -185,77 +195,29 @@ impl<const L: usize, const H: usize> PalettedContainer<u16, 16, L, H> {
             if self.len == 0 {
                 // Bits per entry
                 w.write_byte(bits);
-
-                // Number of longs in data array
-                V32(data_len(L, bits as usize) as u32).write(w);
                 // Data array
-                let bits_per_u64 = 64 / bits * bits;
-                let mut n = 0_u64;
-                let mut m = 0;
-                for &x in &*self.full {
-                    let y = x as u64;
-                    n |= y << m;
-                    m += bits;
-                    if m == bits_per_u64 {
-                        m = 0;
-                        n.write(w);
-                        n = 0;
-                    }
-                }
-                if m > 0 {
-                    n.write(w);
-                }
+                self.write_full(bits, w);
             } else if self.len == 1 {
                 // Bits per entry
                 w.write_byte(0);
                 // Palette
                 let val = *self.palette().get_unchecked(0);
                 V21(val as u32).write(w);
-                // Number of longs
-                w.write_byte(0);
             } else {
-                let bits_per_entry = u8::BITS - (self.len - 1).leading_zeros();
-                // Bits per entry
-                w.write_byte(bits_per_entry as u8);
+                // Bits per entry, at least 4 for block states
+                w.write_byte(4);
                 // Palette len
                 w.write_byte(self.len);
                 // Palette
-                for val in self.palette() {
-                    val.write(w);
+                for &val in self.palette() {
+                    V21(val as u32).write(w);
                 }
-                // Number of longs in data array
-                V32(data_len(L, bits_per_entry as usize) as u32).write(w);
                 // Data array
                 debug_assert!(H.is_multiple_of(8));
-                if bits_per_entry == 4 {
-                    let ptr = self.half.as_ptr().cast::<[u8; 8]>();
-                    for x in 0..H / 8 {
-                        let y = *ptr.add(x);
-                        w.write(&u64::from_le_bytes(y).to_be_bytes());
-                    }
-                    return;
-                }
-                let bits_per_u64 = 64 / bits_per_entry * bits_per_entry;
-                let mut n = 0_u64;
-                let mut m = 0;
-                for &x in &*self.half {
-                    n |= ((x & 0b1111) as u64) << m;
-                    m += bits_per_entry;
-                    if m == bits_per_u64 {
-                        m = 0;
-                        n.write(w);
-                        n = 0;
-                    }
-                    n |= ((x >> 4) as u64) << m;
-                    m += bits_per_entry;
-                    if m == bits_per_u64 {
-                        m = 0;
-                        n.write(w);
-                        n = 0;
-                    }
-                }
-                if m > 0 {
-                    n.write(w);
+                let ptr = self.half.as_ptr().cast::<[u8; 8]>();
+                for x in 0..H / 8 {
+                    let y = *ptr.add(x);
+                    w.write(&u64::from_le_bytes(y).to_be_bytes());
                 }
             }
         }
@@ -263,28 +225,16 @@ impl<const L: usize, const H: usize> PalettedContainer<u16, 16, L, H> {
 
     pub fn len_s(&self, bits: u8) -> usize {
         if self.len == 0 {
-            let all = data_len(L, bits as usize);
-
-            let mut len = 1;
-            len += V32(all as u32).len_s();
-            len += all * 8;
-            len
+            1 + data_len(L, bits as usize) * 8
         } else if self.len == 1 {
             let val = unsafe { *self.palette().get_unchecked(0) };
-            2 + V21(val as u32).len_s()
+            1 + V21(val as u32).len_s()
         } else {
-            let bits_per_entry = (u8::BITS - (self.len - 1).leading_zeros()) as usize;
-            let mut len = 1;
-
-            let all = data_len(L, bits_per_entry);
-
-            len += 1;
-            for pal in self.palette() {
-                len += pal.len_s();
+            let mut len = 2;
+            for &pal in self.palette() {
+                len += V21(pal as u32).len_s();
             }
-            len += V32(all as u32).len_s();
-            len += all * 8;
-            len
+            len + data_len(L, 4) * 8
         }
     }
 }
@@ -298,34 +248,14 @@ impl<const L: usize, const H: usize> PalettedContainer<u16, 4, L, H> {
             if self.len == 0 {
                 // Bits per entry
                 w.write_byte(bits);
-
-                // Number of longs in data array
-                V32(data_len(L, bits as usize) as u32).write(w);
                 // Data array
-                let bits_per_u64 = 64 / bits * bits;
-                let mut n = 0_u64;
-                let mut m = 0;
-                for &x in &*self.full {
-                    let y = x as u64;
-                    n |= y << m;
-                    m += bits;
-                    if m == bits_per_u64 {
-                        m = 0;
-                        n.write(w);
-                        n = 0;
-                    }
-                }
-                if m > 0 {
-                    n.write(w);
-                }
+                self.write_full(bits, w);
             } else if self.len == 1 {
                 // Bits per entry
                 w.write_byte(0);
                 // Palette
                 let val = *self.palette().get_unchecked(0);
                 V21(val as u32).write(w);
-                // Number of longs
-                w.write_byte(0);
             } else {
                 let bits_per_entry = u8::BITS - (self.len - 1).leading_zeros();
 
@@ -336,11 +266,9 @@ impl<const L: usize, const H: usize> PalettedContainer<u16, 4, L, H> {
                 w.write_byte(self.len);
                 // Palette
                 for &val in self.palette() {
-                    val.write(w);
+                    V21(val as u32).write(w);
                 }
 
-                // Number of longs in data array
-                V32(data_len(L, bits_per_entry as usize) as u32).write(w);
                 // Data array
                 let bits_per_u64 = 64 / bits_per_entry * bits_per_entry;
                 let mut n = 0_u64;
@@ -370,29 +298,130 @@ impl<const L: usize, const H: usize> PalettedContainer<u16, 4, L, H> {
 
     pub fn len_s(&self, bits: u8) -> usize {
         if self.len == 0 {
-            let all = data_len(L, bits as usize);
-
-            let mut len = 1;
-            len += V32(all as u32).len_s();
-            len += all * 8;
-            len
+            1 + data_len(L, bits as usize) * 8
         } else if self.len == 1 {
             let val = unsafe { *self.palette().get_unchecked(0) };
-            2 + V21(val as u32).len_s()
+            1 + V21(val as u32).len_s()
         } else {
             let bits_per_entry = (u8::BITS - (self.len - 1).leading_zeros()) as usize;
-            let mut len = 1;
+            let mut len = 2;
+            for &pal in self.palette() {
+                len += V21(pal as u32).len_s();
+            }
+            len + data_len(L, bits_per_entry) * 8
+        }
+    }
+}
 
-            let all = data_len(L, bits_per_entry);
+impl<'a, const L: usize, const H: usize> Read<'a> for PalettedContainer<u16, 16, L, H> {
+    /// Block states, which use at least 4 bits per entry when indirect.
+    fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
+        Self::read_network(buf, 4, 8)
+    }
+}
 
-            len += 1;
-            for pal in self.palette() {
-                len += pal.len_s();
+impl<'a, const L: usize, const H: usize> Read<'a> for PalettedContainer<u16, 4, L, H> {
+    /// Biomes, which are indirect for up to 3 bits per entry.
+    fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
+        Self::read_network(buf, 1, 3)
+    }
+}
+
+impl<const P: usize, const L: usize, const H: usize> PalettedContainer<u16, P, L, H> {
+    /// # Safety
+    ///
+    /// [write](Writer::write)
+    unsafe fn write_full(&self, bits: u8, w: &mut Writer) {
+        unsafe {
+            let bits_per_u64 = 64 / bits * bits;
+            let mut n = 0_u64;
+            let mut m = 0;
+            for &x in &*self.full {
+                let y = x as u64;
+                n |= y << m;
+                m += bits;
+                if m == bits_per_u64 {
+                    m = 0;
+                    n.write(w);
+                    n = 0;
+                }
+            }
+            if m > 0 {
+                n.write(w);
+            }
+        }
+    }
+
+    /// Reads the network encoding, where an indirect palette uses
+    /// `min_bits..=max_bits` bits per entry and anything wider is direct.
+    ///
+    /// Palettes longer than `P` are expanded to the direct layout.
+    fn read_network(buf: &mut Reader, min_bits: u8, max_bits: u8) -> Result<Self, Error> {
+        let bits = buf.read_byte()?;
+        if bits == 0 {
+            let val = V21::read(buf)?.0;
+            return match u16::try_from(val) {
+                Ok(x) => Ok(Self::new(x)),
+                Err(_) => Err(buf.error(ErrorKind::Invalid)),
+            };
+        }
+        let mut palette = [0u16; 256];
+        let (palette_len, entry_bits) = if bits <= max_bits {
+            let entry_bits = bits.max(min_bits);
+            let len = V21::read(buf)?.0 as usize;
+            if len == 0 || len > 1 << entry_bits {
+                return Err(buf.error(ErrorKind::Invalid));
+            }
+            for slot in &mut palette[..len] {
+                *slot = match u16::try_from(V21::read(buf)?.0) {
+                    Ok(x) => x,
+                    Err(_) => return Err(buf.error(ErrorKind::Invalid)),
+                };
+            }
+            (len, entry_bits)
+        } else if bits <= 16 {
+            (0, bits)
+        } else {
+            return Err(buf.error(ErrorKind::Invalid));
+        };
+        let data = buf.read_slice(data_len(L, entry_bits as usize) * 8)?;
+
+        let mut out = Self::default();
+        let per_u64 = (64 / entry_bits) as usize;
+        let mask = (1u64 << entry_bits) - 1;
+        let mut entries = data
+            .chunks_exact(8)
+            .flat_map(|x| {
+                let n = u64::from_be_bytes(x.try_into().unwrap_or([0; 8]));
+                (0..per_u64).map(move |i| ((n >> (i * entry_bits as usize)) & mask) as usize)
+            })
+            .take(L);
+        if palette_len == 0 {
+            for (slot, x) in out.full.iter_mut().zip(&mut entries) {
+                *slot = x as u16;
+            }
+            out.len = 0;
+        } else if palette_len <= P {
+            for i in 0..H {
+                let lo = entries.next().unwrap_or(0);
+                let hi = entries.next().unwrap_or(0);
+                if lo >= palette_len || hi >= palette_len {
+                    return Err(buf.error(ErrorKind::Invalid));
+                }
+                out.half[i] = (lo | hi << 4) as u8;
+            }
+            out.palette[..palette_len].copy_from_slice(&palette[..palette_len]);
+            out.len = palette_len as u8;
+        } else {
+            for (slot, x) in out.full.iter_mut().zip(&mut entries) {
+                match palette[..palette_len].get(x) {
+                    Some(&y) => *slot = y,
+                    None => return Err(buf.error(ErrorKind::Invalid)),
+                }
             }
-            len += V32(all as u32).len_s();
-            len += all * 8;
-            len
+            out.len = 0;
         }
+        Ok(out)
     }
 }
 
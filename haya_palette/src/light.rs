@@ -0,0 +1,466 @@
+use crate::chunk::{mix, pack};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashTable;
+use minecraft_data::block_state;
+
+/// Length of the nibble array holding the light of a section.
+pub const LIGHT_LEN: usize = 2048;
+
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+    (0, 1, 0),
+    (0, -1, 0),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    Sky,
+    Block,
+}
+
+#[derive(Clone)]
+struct ChunkLight {
+    sky: Box<[[u8; LIGHT_LEN]]>,
+    block: Box<[[u8; LIGHT_LEN]]>,
+}
+
+/// The light of a chunk in the layout of `LightData`.
+///
+/// Bit `i` of a mask is the section `i - 1` counted from the bottom of the
+/// dimension, so the first and last bits are the sections just outside it.
+#[derive(Clone, Default)]
+pub struct ChunkLightData {
+    pub sky_y_mask: Vec<u64>,
+    pub block_y_mask: Vec<u64>,
+    pub empty_sky_y_mask: Vec<u64>,
+    pub empty_block_y_mask: Vec<u64>,
+    pub sky_updates: Vec<[u8; LIGHT_LEN]>,
+    pub block_updates: Vec<[u8; LIGHT_LEN]>,
+}
+
+/// Sky and block light of the loaded chunks of a dimension.
+///
+/// Blocks are read through a `world` callback returning the state at a
+/// position. Light is attenuated by [`block_state::opacity`] in every
+/// direction, so partial blocks such as slabs occlude all of their faces.
+#[derive(Clone)]
+pub struct LightEngine {
+    min_y: i32,
+    sections: usize,
+    has_sky: bool,
+    chunks: HashTable<(u64, ChunkLight)>,
+}
+
+impl LightEngine {
+    /// An engine for a dimension whose `min_y` and `height` come from its
+    /// dimension type, with sky light if it has a skylight.
+    pub fn new(min_y: i32, height: u32, has_sky: bool) -> Self {
+        Self {
+            min_y,
+            sections: (height / 16) as usize,
+            has_sky,
+            chunks: HashTable::new(),
+        }
+    }
+
+    /// Lights a chunk whose blocks are in `world`, spreading light to and
+    /// from its loaded neighbours.
+    pub fn add_chunk(
+        &mut self,
+        world: impl Fn(i32, i32, i32) -> block_state,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) {
+        self.remove_chunk(chunk_x, chunk_z);
+        let layers = self.sections + 2;
+        let mut light = ChunkLight {
+            sky: vec![[0; LIGHT_LEN]; layers].into_boxed_slice(),
+            block: vec![[0; LIGHT_LEN]; layers].into_boxed_slice(),
+        };
+        if self.has_sky {
+            light.sky[layers - 1] = [0xFF; LIGHT_LEN];
+        }
+        let key = chunk_key(chunk_x, chunk_z);
+        self.chunks
+            .insert_unique(mix(key), (key, light), |e| mix(e.0));
+
+        let (bx, bz) = (chunk_x * 16, chunk_z * 16);
+        let bottom = self.min_y - 16;
+        let top = self.min_y + self.sections as i32 * 16;
+        let mut block = VecDeque::new();
+        for y in self.min_y..top {
+            for z in bz..bz + 16 {
+                for x in bx..bx + 16 {
+                    let luminance = world(x, y, z).luminance();
+                    if luminance > 0 {
+                        self.set(Channel::Block, x, y, z, luminance);
+                        block.push_back((x, y, z));
+                    }
+                }
+            }
+        }
+
+        let mut sky = VecDeque::new();
+        if self.has_sky {
+            // Lowest Y still reached by the full sky light of each column.
+            let mut floor = [top; 256];
+            for (i, h) in floor.iter_mut().enumerate() {
+                let (x, z) = (bx + (i & 15) as i32, bz + (i >> 4) as i32);
+                while *h > bottom && opacity(world(x, *h - 1, z)) == 0 {
+                    *h -= 1;
+                    self.set(Channel::Sky, x, *h, z, 15);
+                }
+            }
+            for (i, &h) in floor.iter().enumerate() {
+                let (lx, lz) = (i & 15, i >> 4);
+                let (x, z) = (bx + lx as i32, bz + lz as i32);
+                let border = lx == 0 || lx == 15 || lz == 0 || lz == 15;
+                let mut highest = h;
+                if !border {
+                    for n in [i - 1, i + 1, i - 16, i + 16] {
+                        highest = highest.max(floor[n]);
+                    }
+                }
+                let end = if border { top } else { highest.min(top) };
+                for y in h..end.max(h + 1).min(top) {
+                    sky.push_back((x, y, z));
+                }
+            }
+        }
+
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if self.chunk(chunk_x + dx, chunk_z + dz).is_none() {
+                continue;
+            }
+            for i in 0..16 {
+                let (x, z) = match (dx, dz) {
+                    (1, _) => (bx + 16, bz + i),
+                    (-1, _) => (bx - 1, bz + i),
+                    (_, 1) => (bx + i, bz + 16),
+                    _ => (bx + i, bz - 1),
+                };
+                for y in bottom..top + 16 {
+                    block.push_back((x, y, z));
+                    if self.has_sky {
+                        sky.push_back((x, y, z));
+                    }
+                }
+            }
+        }
+        self.increase(Channel::Block, &world, &mut block);
+        self.increase(Channel::Sky, &world, &mut sky);
+    }
+
+    pub fn remove_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        let key = chunk_key(chunk_x, chunk_z);
+        if let Ok(entry) = self.chunks.find_entry(mix(key), |e| e.0 == key) {
+            entry.remove();
+        }
+    }
+
+    /// Relights around a block after its state in `world` changed.
+    pub fn update_block(
+        &mut self,
+        world: impl Fn(i32, i32, i32) -> block_state,
+        x: i32,
+        y: i32,
+        z: i32,
+    ) {
+        if !self.contains(x, y, z) {
+            return;
+        }
+        let channels: &[Channel] = if self.has_sky {
+            &[Channel::Block, Channel::Sky]
+        } else {
+            &[Channel::Block]
+        };
+        for &channel in channels {
+            let mut increase = VecDeque::new();
+            let mut removal = VecDeque::new();
+            let old = self.get(channel, x, y, z);
+            self.set(channel, x, y, z, 0);
+            removal.push_back((x, y, z, old));
+            self.decrease(channel, &world, &mut removal, &mut increase);
+            if channel == Channel::Block {
+                let luminance = world(x, y, z).luminance();
+                if luminance > 0 {
+                    self.set(channel, x, y, z, luminance);
+                    increase.push_back((x, y, z));
+                }
+            }
+            for (dx, dy, dz) in DIRECTIONS {
+                increase.push_back((x + dx, y + dy, z + dz));
+            }
+            self.increase(channel, &world, &mut increase);
+        }
+    }
+
+    /// Sky light at a position, which is full above the dimension.
+    pub fn sky_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        self.get(Channel::Sky, x, y, z)
+    }
+
+    pub fn block_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        self.get(Channel::Block, x, y, z)
+    }
+
+    /// The light of a loaded chunk as sent to clients. Sections without
+    /// light are only flagged in the empty masks.
+    pub fn light_data(&self, chunk_x: i32, chunk_z: i32) -> Option<ChunkLightData> {
+        let light = self.chunk(chunk_x, chunk_z)?;
+        let mut out = ChunkLightData::default();
+        if self.has_sky {
+            encode(
+                &light.sky,
+                &mut out.sky_y_mask,
+                &mut out.empty_sky_y_mask,
+                &mut out.sky_updates,
+            );
+        }
+        encode(
+            &light.block,
+            &mut out.block_y_mask,
+            &mut out.empty_block_y_mask,
+            &mut out.block_updates,
+        );
+        Some(out)
+    }
+
+    fn chunk(&self, chunk_x: i32, chunk_z: i32) -> Option<&ChunkLight> {
+        let key = chunk_key(chunk_x, chunk_z);
+        self.chunks.find(mix(key), |e| e.0 == key).map(|e| &e.1)
+    }
+
+    /// Light section of `y`, counting the one below the dimension as 0.
+    fn layer(&self, y: i32) -> Option<usize> {
+        let layer = ((y - self.min_y) >> 4) + 1;
+        if layer >= 0 && (layer as usize) < self.sections + 2 {
+            Some(layer as usize)
+        } else {
+            None
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32, z: i32) -> bool {
+        self.layer(y).is_some() && self.chunk(x >> 4, z >> 4).is_some()
+    }
+
+    fn get(&self, channel: Channel, x: i32, y: i32, z: i32) -> u8 {
+        let Some(layer) = self.layer(y) else {
+            let above = y >= self.min_y;
+            return if channel == Channel::Sky && self.has_sky && above {
+                15
+            } else {
+                0
+            };
+        };
+        let Some(light) = self.chunk(x >> 4, z >> 4) else {
+            return 0;
+        };
+        let data = match channel {
+            Channel::Sky => &light.sky[layer],
+            Channel::Block => &light.block[layer],
+        };
+        let index = nibble_index(x, y, z);
+        (data[index >> 1] >> ((index & 1) << 2)) & 15
+    }
+
+    fn set(&mut self, channel: Channel, x: i32, y: i32, z: i32, level: u8) {
+        let Some(layer) = self.layer(y) else {
+            return;
+        };
+        let key = chunk_key(x >> 4, z >> 4);
+        let Some(light) = self.chunks.find_mut(mix(key), |e| e.0 == key) else {
+            return;
+        };
+        let data = match channel {
+            Channel::Sky => &mut light.1.sky[layer],
+            Channel::Block => &mut light.1.block[layer],
+        };
+        let index = nibble_index(x, y, z);
+        let shift = (index & 1) << 2;
+        data[index >> 1] = (data[index >> 1] & !(15 << shift)) | (level << shift);
+    }
+
+    /// Spreads the light of every queued position.
+    fn increase(
+        &mut self,
+        channel: Channel,
+        world: &impl Fn(i32, i32, i32) -> block_state,
+        queue: &mut VecDeque<(i32, i32, i32)>,
+    ) {
+        while let Some((x, y, z)) = queue.pop_front() {
+            if !self.contains(x, y, z) {
+                continue;
+            }
+            let level = self.get(channel, x, y, z);
+            if level <= 1 {
+                continue;
+            }
+            for (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                if !self.contains(nx, ny, nz) {
+                    continue;
+                }
+                let next = step(channel, level, dy < 0, opacity(world(nx, ny, nz)));
+                if next > self.get(channel, nx, ny, nz) {
+                    self.set(channel, nx, ny, nz, next);
+                    queue.push_back((nx, ny, nz));
+                }
+            }
+        }
+    }
+
+    /// Darkens everything lit through the queued positions, collecting the
+    /// brighter edge and any light sources to spread again.
+    fn decrease(
+        &mut self,
+        channel: Channel,
+        world: &impl Fn(i32, i32, i32) -> block_state,
+        removal: &mut VecDeque<(i32, i32, i32, u8)>,
+        increase: &mut VecDeque<(i32, i32, i32)>,
+    ) {
+        while let Some((x, y, z, old)) = removal.pop_front() {
+            for (dx, dy, dz) in DIRECTIONS {
+                let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                if !self.contains(nx, ny, nz) {
+                    continue;
+                }
+                let level = self.get(channel, nx, ny, nz);
+                if level == 0 {
+                    continue;
+                }
+                let state = world(nx, ny, nz);
+                if level > step(channel, old, dy < 0, opacity(state)) {
+                    increase.push_back((nx, ny, nz));
+                    continue;
+                }
+                self.set(channel, nx, ny, nz, 0);
+                removal.push_back((nx, ny, nz, level));
+                if channel == Channel::Block && state.luminance() > 0 {
+                    self.set(channel, nx, ny, nz, state.luminance());
+                    increase.push_back((nx, ny, nz));
+                }
+            }
+        }
+    }
+}
+
+/// Light reaching a neighbour from `level`. Full sky light travels down
+/// through transparent blocks without fading.
+const fn step(channel: Channel, level: u8, down: bool, opacity: u8) -> u8 {
+    if matches!(channel, Channel::Sky) && down && level == 15 && opacity == 0 {
+        15
+    } else {
+        level.saturating_sub(if opacity > 1 { opacity } else { 1 })
+    }
+}
+
+fn opacity(state: block_state) -> u8 {
+    state.opacity().unwrap_or(0)
+}
+
+const fn nibble_index(x: i32, y: i32, z: i32) -> usize {
+    ((y & 15) << 8 | (z & 15) << 4 | (x & 15)) as usize
+}
+
+fn encode(
+    layers: &[[u8; LIGHT_LEN]],
+    mask: &mut Vec<u64>,
+    empty: &mut Vec<u64>,
+    updates: &mut Vec<[u8; LIGHT_LEN]>,
+) {
+    let words = layers.len().div_ceil(64);
+    mask.resize(words, 0);
+    empty.resize(words, 0);
+    for (i, data) in layers.iter().enumerate() {
+        if data.iter().all(|&x| x == 0) {
+            empty[i / 64] |= 1 << (i % 64);
+        } else {
+            mask[i / 64] |= 1 << (i % 64);
+            updates.push(*data);
+        }
+    }
+    for bits in [mask, empty] {
+        while bits.last() == Some(&0) {
+            bits.pop();
+        }
+    }
+}
+
+const fn chunk_key(x: i32, z: i32) -> u64 {
+    pack(x, 0, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minecraft_data::block;
+
+    fn world(blocks: &[(i32, i32, i32, block_state)]) -> impl Fn(i32, i32, i32) -> block_state {
+        move |x, y, z| {
+            if y == 0 {
+                return block::stone.state_default();
+            }
+            match blocks.iter().find(|b| (b.0, b.1, b.2) == (x, y, z)) {
+                Some(b) => b.3,
+                None => block::air.state_default(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_light_engine() {
+        let glowstone = block::glowstone.state_default();
+        let stone = block::stone.state_default();
+        assert_eq!(stone.opacity(), Some(15));
+
+        let mut engine = LightEngine::new(0, 32, true);
+        let mut blocks = alloc::vec![(8, 5, 8, glowstone)];
+        engine.add_chunk(world(&blocks), 0, 0);
+        assert_eq!(engine.block_light(8, 5, 8), 15);
+        assert_eq!(engine.block_light(8, 5, 11), 12);
+        assert_eq!(engine.block_light(8, 1, 8), 11);
+        assert_eq!(engine.block_light(8, -1, 8), 0);
+        assert_eq!(engine.sky_light(5, 1, 5), 15);
+        assert_eq!(engine.sky_light(5, 0, 5), 0);
+        assert_eq!(engine.sky_light(5, 100, 5), 15);
+
+        let data = engine.light_data(0, 0).unwrap();
+        assert_eq!(data.block_y_mask, [0b0110]);
+        assert_eq!(data.empty_block_y_mask, [0b1001]);
+        assert_eq!(data.sky_y_mask, [0b1110]);
+        assert_eq!(data.empty_sky_y_mask, [0b0001]);
+        assert_eq!(data.sky_updates.len(), 3);
+        assert_eq!(data.block_updates[0][nibble_index(8, 5, 8) >> 1], 0xEF);
+
+        blocks.push((3, 10, 3, stone));
+        engine.update_block(world(&blocks), 3, 10, 3);
+        assert_eq!(engine.sky_light(3, 10, 3), 0);
+        assert_eq!(engine.sky_light(3, 9, 3), 14);
+        assert_eq!(engine.sky_light(3, 11, 3), 15);
+
+        blocks.pop();
+        engine.update_block(world(&blocks), 3, 10, 3);
+        assert_eq!(engine.sky_light(3, 10, 3), 15);
+        assert_eq!(engine.sky_light(3, 9, 3), 15);
+
+        blocks.clear();
+        engine.update_block(world(&blocks), 8, 5, 8);
+        assert!(engine.light_data(0, 0).unwrap().block_updates.is_empty());
+
+        // Light crosses into a neighbour loaded later.
+        blocks.push((15, 3, 15, glowstone));
+        engine.update_block(world(&blocks), 15, 3, 15);
+        engine.add_chunk(world(&blocks), 1, 0);
+        assert_eq!(engine.block_light(16, 3, 15), 14);
+        engine.remove_chunk(1, 0);
+        assert!(engine.light_data(1, 0).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{Biome, BiomeContainer, BlockContainer};
+use alloc::vec::Vec;
+use minecraft_data::{block, block_state};
+use mser::{Error, ErrorKind, Read, Reader};
+
+/// A section of the `data` of a `LevelChunkWithLight` packet.
+#[derive(Clone)]
+pub struct ChunkSection {
+    /// Number of blocks that are not air.
+    pub block_count: i16,
+    pub block_states: BlockContainer,
+    pub biomes: BiomeContainer,
+}
+
+impl<'a> Read<'a> for ChunkSection {
+    fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
+        Ok(Self {
+            block_count: i16::read(buf)?,
+            block_states: BlockContainer::read(buf)?,
+            biomes: BiomeContainer::read(buf)?,
+        })
+    }
+}
+
+/// The sections of a chunk from the bottom of the dimension up.
+#[derive(Clone)]
+pub struct ChunkColumn {
+    min_y: i32,
+    pub sections: Vec<ChunkSection>,
+}
+
+impl ChunkColumn {
+    pub const fn new(min_y: i32, sections: Vec<ChunkSection>) -> Self {
+        Self { min_y, sections }
+    }
+
+    /// Decodes the `data` of a chunk in a dimension whose `min_y` and
+    /// `height` come from its dimension type.
+    pub fn read(data: &[u8], min_y: i32, height: u32) -> Result<Self, Error> {
+        let mut buf = Reader::new(data);
+        let count = (height / 16) as usize;
+        let mut sections = Vec::with_capacity(count);
+        for _ in 0..count {
+            sections.push(ChunkSection::read(&mut buf)?);
+        }
+        if !buf.is_empty() {
+            return Err(buf.error(ErrorKind::Invalid));
+        }
+        Ok(Self { min_y, sections })
+    }
+
+    pub const fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    /// The section holding block `y`.
+    pub fn section(&self, y: i32) -> Option<&ChunkSection> {
+        let index = (y - self.min_y) >> 4;
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.sections.get(i))
+    }
+
+    /// The block at `x`, `y`, `z`, where `x` and `z` are taken modulo 16.
+    ///
+    /// Blocks outside of the dimension are void air.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> block_state {
+        let Some(section) = self.section(y) else {
+            return block::void_air.state_default();
+        };
+        let index = ((y & 15) << 8 | (z & 15) << 4 | (x & 15)) as usize;
+        let id = unsafe { *section.block_states.get_unchecked(index) };
+        match block_state::new(id) {
+            Some(x) => x,
+            None => block::void_air.state_default(),
+        }
+    }
+
+    /// The biome at block `x`, `y`, `z`, with `y` clamped to the dimension
+    /// like vanilla.
+    pub fn get_biome(&self, x: i32, y: i32, z: i32) -> Biome {
+        let Some(top) = self.sections.len().checked_sub(1) else {
+            return Biome(0);
+        };
+        let quart = ((y - self.min_y) >> 2).clamp(0, (top as i32) * 4 + 3);
+        let section = &self.sections[(quart >> 2) as usize];
+        let index = ((quart & 3) << 4 | (z >> 2 & 3) << 2 | (x >> 2 & 3)) as usize;
+        Biome(unsafe { *section.biomes.get_unchecked(index) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use mser::Write;
+
+    struct Encode<'a>(&'a ChunkSection);
+
+    impl Write for Encode<'_> {
+        unsafe fn write(&self, w: &mut mser::Writer) {
+            unsafe {
+                self.0.block_count.write(w);
+                self.0.block_states.write(15, w);
+                self.0.biomes.write(7, w);
+            }
+        }
+
+        fn len_s(&self) -> usize {
+            2 + self.0.block_states.len_s(15) + self.0.biomes.len_s(7)
+        }
+    }
+
+    fn encode(section: &ChunkSection, out: &mut Vec<u8>) {
+        let x = Encode(section);
+        out.reserve(x.len_s());
+        unsafe {
+            mser::write_unchecked(out.as_mut_ptr().add(out.len()), &x);
+            out.set_len(out.len() + x.len_s());
+        }
+    }
+
+    #[test]
+    fn test_chunk_column() {
+        let stone = block::stone.state_default().id();
+        let mut states = BlockContainer::new(stone);
+        states.set(0x123, stone + 1);
+        let mut direct = BlockContainer::new(stone);
+        for i in 0..20 {
+            direct.set(i * 100, stone + i as u16);
+        }
+        let mut biomes = BiomeContainer::new(3);
+        biomes.set(5, 40);
+        let mut data = Vec::new();
+        for (block_states, biomes) in [
+            (BlockContainer::new(stone), BiomeContainer::new(1)),
+            (states, biomes),
+            (direct.clone(), BiomeContainer::new(1)),
+        ] {
+            let section = ChunkSection {
+                block_count: 4096,
+                block_states,
+                biomes,
+            };
+            encode(&section, &mut data);
+        }
+        // Block count, then single value stone and biome without data.
+        assert_eq!(data[..6], [16, 0, 0, stone as u8, 0, 1]);
+
+        let column = ChunkColumn::read(&data, -16, 48).unwrap();
+        assert_eq!(column.sections.len(), 3);
+        assert_eq!(column.get_block(5, -16, 7).id(), stone);
+        assert_eq!(column.get_block(3, 1, 2).id(), stone + 1);
+        assert_eq!(column.get_block(3 + 16, 1, 2 - 32).id(), stone + 1);
+        assert_eq!(
+            column.get_block(0, 32, 0).id(),
+            block::void_air.state_default().id()
+        );
+        assert_eq!(
+            column.get_block(0, -17, 0).id(),
+            block::void_air.state_default().id()
+        );
+        for i in 0..4096 {
+            let (x, y, z) = ((i & 15) as i32, (i >> 8) as i32 + 16, (i >> 4 & 15) as i32);
+            assert_eq!(column.get_block(x, y, z).id(), unsafe {
+                *direct.get_unchecked(i)
+            });
+        }
+        assert_eq!(column.get_biome(4, 0, 4).0, 40);
+        assert_eq!(column.get_biome(0, 0, 0).0, 3);
+        assert_eq!(column.get_biome(0, -100, 0).0, 1);
+        assert_eq!(column.get_biome(0, 100, 0).0, 1);
+        assert!(ChunkColumn::read(&data, -16, 64).is_err());
+        assert!(ChunkColumn::read(&data, -16, 32).is_err());
+
+        // A 5 bit palette wider than the container, as vanilla sends it.
+        let mut wide = vec![0, 1, 5, 17];
+        for i in 0..17u8 {
+            wide.push(i + 1);
+        }
+        for i in 0..4096 / 12 + 1 {
+            let mut n = 0u64;
+            for j in 0..12 {
+                n |= (((i * 12 + j) % 17) as u64) << (j * 5);
+            }
+            wide.extend_from_slice(&n.to_be_bytes());
+        }
+        wide.extend_from_slice(&[0, 0]);
+        let section = ChunkSection::read(&mut Reader::new(&wide)).unwrap();
+        assert_eq!(section.block_states.palette_len(), 0);
+        for i in 0..4096 {
+            assert_eq!(
+                unsafe { *section.block_states.get_unchecked(i) },
+                (i % 17) as u16 + 1
+            );
+        }
+        let len = wide.len();
+        wide[len - 2] = 1;
+        assert!(ChunkSection::read(&mut Reader::new(&wide)).is_err());
+    }
+}
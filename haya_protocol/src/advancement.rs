@@ -6,7 +6,7 @@ use haya_nbt::Tag;
 use minecraft_data::{block, data_component_predicate_type, data_component_type};
 use mser::{Either, Read, Utf8, Write};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BlockPredicate<'a> {
     pub blocks: Option<HolderSet<'a, block>>,
     pub properties: Option<StatePropertiesPredicate<'a>>,
@@ -14,36 +14,36 @@ pub struct BlockPredicate<'a> {
     pub components: DataComponentMatchers<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct StatePropertiesPredicate<'a> {
     pub properties: List<'a, PropertyMatcher<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PropertyMatcher<'a> {
     pub name: Utf8<'a>,
     pub value_matcher: ValueMatcher<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ValueMatcher<'a>(pub Either<ExactMatcher<'a>, RangedMatcher<'a>>);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ExactMatcher<'a>(pub Utf8<'a>);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct RangedMatcher<'a> {
     pub min: Utf8<'a>,
     pub max: Utf8<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DataComponentMatchers<'a> {
     pub exact: List<'a, TypedDataComponent<'a>>,
     pub partial: List<'a, SingleDataComponentPredicate, 64>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct SingleDataComponentPredicate(
     pub Either<data_component_predicate_type, data_component_type>,
     pub Tag,
@@ -1,13 +1,13 @@
 use haya_ident::Ident;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct AttributeModifier<'a> {
     pub id: Ident<'a>,
     pub amount: f64,
     pub operation: AttributeOperation,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum AttributeOperation {
@@ -15,3 +15,13 @@ pub enum AttributeOperation {
     AddMultipliedBase,
     AddMultipliedTotal,
 }
+
+impl AttributeOperation {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AddValue => "add_value",
+            Self::AddMultipliedBase => "add_multiplied_base",
+            Self::AddMultipliedTotal => "add_multiplied_total",
+        }
+    }
+}
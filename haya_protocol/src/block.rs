@@ -5,24 +5,24 @@ use haya_collection::List;
 use haya_ident::Ident;
 use mser::Utf8;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BannerPattern<'a> {
     pub asset_id: Ident<'a>,
     pub translation_key: Utf8<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BannerPatternLayers<'a> {
     pub layers: List<'a, BannerPatternLayer<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BannerPatternLayer<'a> {
     pub pattern: Holder<BannerPattern<'a>, BannerPatternRef>,
     pub color: DyeColor,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BeehiveOccupant {
     pub entity_data: TypedEntityDataEntity,
     #[mser(varint)]
@@ -1,3 +1,5 @@
+pub mod session;
+
 use crate::registry::ChatTypeRef;
 use crate::{BitSet, ComponentRaw, Holder, MilliSeconds, StyleRaw};
 use haya_collection::List;
@@ -44,7 +46,7 @@ impl<'a> Read<'a> for MessageSignaturePacked<'a> {
         Ok(if id == 0 {
             Self::FullSignature(MessageSignature::read(buf)?)
         } else {
-            Self::Index(id)
+            Self::Index(id - 1)
         })
     }
 }
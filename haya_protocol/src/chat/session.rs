@@ -0,0 +1,323 @@
+use super::{
+    LastSeenMessagesPacked, LastSeenMessagesUpdate, MessageSignature, MessageSignaturePacked,
+    SignedMessageBodyPacked,
+};
+use crate::MilliSeconds;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use haya_collection::List;
+use mser::{FixedByteArray, Utf8};
+use uuid::Uuid;
+
+pub type SignatureBytes = [u8; 256];
+
+const MESSAGE_SIGNATURE_VERSION: u32 = 1;
+const LAST_SEEN_COUNT: usize = 20;
+const CACHE_SIZE: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatError {
+    MissingSignature,
+    ExpiredProfileKey,
+    /// A previous message failed, so the chain no longer accepts messages.
+    ChainBroken,
+    OutOfOrderChat,
+    InvalidSignature,
+    /// The last seen window moved further than the tracked messages.
+    InvalidOffset,
+    /// An acknowledged bit past the size of the window.
+    TooManyAcknowledged,
+    /// A message acknowledged that was never sent or was ignored.
+    UnknownAcknowledged,
+    /// A message ignored after it was acknowledged.
+    IgnoredAcknowledged,
+    ChecksumMismatch,
+}
+
+/// Checks message signatures against the public key of a chat session.
+pub trait SignatureVerifier {
+    fn verify(&self, payload: &[u8], signature: &SignatureBytes) -> bool;
+}
+
+/// The position of a message in the chain of its sender.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignedMessageLink {
+    pub index: u32,
+    pub sender: Uuid,
+    pub session_id: Uuid,
+}
+
+#[derive(Clone)]
+pub struct SignedMessageBody<'a> {
+    pub content: &'a str,
+    pub timestamp: MilliSeconds,
+    pub salt: u64,
+    pub last_seen: Vec<SignatureBytes>,
+}
+
+impl SignedMessageBody<'_> {
+    /// The bytes signed by the sender for `link`, in the order vanilla
+    /// feeds them to the signature.
+    pub fn signed_payload(&self, link: &SignedMessageLink, out: &mut Vec<u8>) {
+        out.extend_from_slice(&MESSAGE_SIGNATURE_VERSION.to_be_bytes());
+        out.extend_from_slice(link.sender.as_bytes());
+        out.extend_from_slice(link.session_id.as_bytes());
+        out.extend_from_slice(&link.index.to_be_bytes());
+        out.extend_from_slice(&self.salt.to_be_bytes());
+        out.extend_from_slice(&(self.timestamp.0 / 1000).to_be_bytes());
+        out.extend_from_slice(&(self.content.len() as u32).to_be_bytes());
+        out.extend_from_slice(self.content.as_bytes());
+        out.extend_from_slice(&(self.last_seen.len() as u32).to_be_bytes());
+        for signature in &self.last_seen {
+            out.extend_from_slice(signature);
+        }
+    }
+
+    /// The body as sent in `PlayerChat`, with signatures known to the
+    /// receiver replaced by their index in `cache`.
+    pub fn pack<'a>(&'a self, cache: &SignatureCache) -> SignedMessageBodyPacked<'a> {
+        SignedMessageBodyPacked {
+            content: Utf8(self.content),
+            timestamp: self.timestamp,
+            salt: self.salt,
+            last_seen: LastSeenMessagesPacked {
+                entries: List::Owned(self.last_seen.iter().map(|s| cache.pack(s)).collect()),
+            },
+        }
+    }
+}
+
+/// The checksum of a last seen list, as in `LastSeenMessagesUpdate`.
+pub fn checksum(last_seen: &[SignatureBytes]) -> u8 {
+    let mut n = 1i32;
+    for signature in last_seen {
+        let mut hash = 1i32;
+        for &b in signature {
+            hash = hash.wrapping_mul(31).wrapping_add(b as i8 as i32);
+        }
+        n = n.wrapping_mul(31).wrapping_add(hash);
+    }
+    match n as u8 {
+        0 => 1,
+        x => x,
+    }
+}
+
+/// Validates the messages a player receives from others.
+///
+/// Every signed message sent to the player is passed to
+/// [`add_pending`](Self::add_pending), and every update from the player
+/// to [`apply_update`](Self::apply_update).
+#[derive(Clone)]
+pub struct LastSeenValidator {
+    tracked: VecDeque<Option<(Box<SignatureBytes>, bool)>>,
+    last_pending: Option<Box<SignatureBytes>>,
+}
+
+impl Default for LastSeenValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LastSeenValidator {
+    pub fn new() -> Self {
+        let mut tracked = VecDeque::new();
+        tracked.resize(LAST_SEEN_COUNT, None);
+        Self {
+            tracked,
+            last_pending: None,
+        }
+    }
+
+    pub fn add_pending(&mut self, signature: &SignatureBytes) {
+        if self.last_pending.as_deref() != Some(signature) {
+            self.tracked.push_back(Some((Box::new(*signature), true)));
+            self.last_pending = Some(Box::new(*signature));
+        }
+    }
+
+    /// Messages sent but not yet moved out of the window.
+    pub fn tracked_count(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// Moves the window past `offset` messages.
+    pub fn apply_offset(&mut self, offset: u32) -> Result<(), ChatError> {
+        let n = offset as usize;
+        if n > self.tracked.len() - LAST_SEEN_COUNT {
+            return Err(ChatError::InvalidOffset);
+        }
+        self.tracked.drain(..n);
+        Ok(())
+    }
+
+    /// Applies the window of a chat packet, returning the acknowledged
+    /// signatures the message signed.
+    pub fn apply_update(
+        &mut self,
+        update: &LastSeenMessagesUpdate,
+    ) -> Result<Vec<SignatureBytes>, ChatError> {
+        self.apply_offset(update.offset)?;
+        let bits = u32::from_le_bytes([
+            update.acknowledged.0[0],
+            update.acknowledged.0[1],
+            update.acknowledged.0[2],
+            0,
+        ]);
+        if bits >> LAST_SEEN_COUNT != 0 {
+            return Err(ChatError::TooManyAcknowledged);
+        }
+        let mut last_seen = Vec::with_capacity(bits.count_ones() as usize);
+        for i in 0..LAST_SEEN_COUNT {
+            let entry = &mut self.tracked[i];
+            if bits & (1 << i) != 0 {
+                let Some((signature, pending)) = entry else {
+                    return Err(ChatError::UnknownAcknowledged);
+                };
+                *pending = false;
+                last_seen.push(**signature);
+            } else {
+                if matches!(entry, Some((_, false))) {
+                    return Err(ChatError::IgnoredAcknowledged);
+                }
+                *entry = None;
+            }
+        }
+        if update.checksum != 0 && update.checksum != checksum(&last_seen) {
+            return Err(ChatError::ChecksumMismatch);
+        }
+        Ok(last_seen)
+    }
+}
+
+/// The most recent signatures a client knows, which packed signatures
+/// refer to by index.
+///
+/// The server keeps one per receiving player and pushes every message
+/// after sending it, exactly as the client does.
+#[derive(Clone)]
+pub struct SignatureCache {
+    entries: Box<[Option<Box<SignatureBytes>>]>,
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignatureCache {
+    pub fn new() -> Self {
+        Self {
+            entries: (0..CACHE_SIZE).map(|_| None).collect(),
+        }
+    }
+
+    pub fn index_of(&self, signature: &SignatureBytes) -> Option<u32> {
+        self.entries
+            .iter()
+            .position(|e| e.as_deref() == Some(signature))
+            .map(|i| i as u32)
+    }
+
+    pub fn get(&self, index: u32) -> Option<&SignatureBytes> {
+        self.entries.get(index as usize)?.as_deref()
+    }
+
+    pub fn pack<'a>(&self, signature: &'a SignatureBytes) -> MessageSignaturePacked<'a> {
+        match self.index_of(signature) {
+            Some(index) => MessageSignaturePacked::Index(index),
+            None => MessageSignaturePacked::FullSignature(MessageSignature {
+                bytes: FixedByteArray(signature),
+            }),
+        }
+    }
+
+    pub fn unpack<'a>(&'a self, packed: &MessageSignaturePacked<'a>) -> Option<&'a SignatureBytes> {
+        match packed {
+            MessageSignaturePacked::FullSignature(s) => Some(s.bytes.0),
+            MessageSignaturePacked::Index(index) => self.get(*index),
+        }
+    }
+
+    /// Records a message after it was sent, moving its last seen list and
+    /// signature to the front.
+    pub fn push(&mut self, body: &SignedMessageBody, signature: Option<&SignatureBytes>) {
+        let mut queue: VecDeque<Box<SignatureBytes>> =
+            body.last_seen.iter().map(|s| Box::new(*s)).collect();
+        queue.extend(signature.map(|s| Box::new(*s)));
+        let pushed: Vec<SignatureBytes> = queue.iter().map(|s| **s).collect();
+        for entry in self.entries.iter_mut() {
+            let Some(next) = queue.pop_back() else {
+                break;
+            };
+            if let Some(old) = entry.replace(next)
+                && !pushed.contains(&old)
+            {
+                queue.push_front(old);
+            }
+        }
+    }
+}
+
+/// The signed messages of one chat session, checked in order.
+#[derive(Clone)]
+pub struct SignedMessageChain {
+    next: Option<SignedMessageLink>,
+    last_timestamp: MilliSeconds,
+    expires_at: MilliSeconds,
+}
+
+impl SignedMessageChain {
+    /// A chain for the session of `RemoteChatSession`, whose profile key
+    /// expires at `expires_at`.
+    pub const fn new(sender: Uuid, session_id: Uuid, expires_at: MilliSeconds) -> Self {
+        Self {
+            next: Some(SignedMessageLink {
+                index: 0,
+                sender,
+                session_id,
+            }),
+            last_timestamp: MilliSeconds(0),
+            expires_at,
+        }
+    }
+
+    /// Verifies the next message of the sender at time `now`, returning
+    /// its link. An out of order or forged message breaks the chain.
+    pub fn unpack(
+        &mut self,
+        verifier: &impl SignatureVerifier,
+        body: &SignedMessageBody,
+        signature: Option<&SignatureBytes>,
+        now: MilliSeconds,
+    ) -> Result<SignedMessageLink, ChatError> {
+        let Some(sig) = signature else {
+            return Err(ChatError::MissingSignature);
+        };
+        if now.0 >= self.expires_at.0 {
+            return Err(ChatError::ExpiredProfileKey);
+        }
+        let Some(link) = self.next else {
+            return Err(ChatError::ChainBroken);
+        };
+        if body.timestamp.0 < self.last_timestamp.0 {
+            self.next = None;
+            return Err(ChatError::OutOfOrderChat);
+        }
+        self.last_timestamp = body.timestamp;
+        let mut payload = Vec::new();
+        body.signed_payload(&link, &mut payload);
+        if !verifier.verify(&payload, sig) {
+            self.next = None;
+            return Err(ChatError::InvalidSignature);
+        }
+        self.next = link
+            .index
+            .checked_add(1)
+            .map(|index| SignedMessageLink { index, ..link });
+        Ok(link)
+    }
+}
@@ -10,93 +10,180 @@ pub mod login;
 pub mod ping;
 pub mod status;
 
-#[cold]
-fn err() -> Result<(), mser::Error> {
-    Err(mser::Error)
-}
-
 macro_rules! packets {
-    ($m:ty, $handler:ident, $handle:ident, $($variant:ident = $type:ty),+ $(,)*) => {
+    (
+        $m:ty,
+        $packet:ident $(<$lt:lifetime>)?,
+        $handler:ident,
+        $handle:ident,
+        $($variant:ident = $type:ty),+ $(,)*
+    ) => {
         $(
         #[automatically_derived]
-        impl crate::types::Id for $type {
+        impl<'a> crate::types::Id for $type {
             type T = $m;
             const ID: $m = <$m>::$variant;
         }
         )+
 
-        pub trait $handler {
-            fn $handle(&mut self, mut packet: mser::Reader<'_>) -> Result<(), mser::Error> {
-                match <$m as mser::Read>::read(&mut packet)? {
+        #[derive(Clone)]
+        #[allow(non_camel_case_types)]
+        pub enum $packet $(<$lt>)? {
+        $(
+            $variant($type),
+        )+
+        }
+
+        impl $(<$lt>)? $packet $(<$lt>)? {
+            pub const fn id(&self) -> $m {
+                match self {
+                    $(
+                        Self::$variant(_) => <$m>::$variant,
+                    )+
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl<'a> mser::Read<'a> for $packet $(<$lt>)? {
+            fn read(buf: &mut mser::Reader<'a>) -> Result<Self, mser::Error> {
+                Ok(match <$m as mser::Read>::read(buf)? {
                     $(
-                        <$m>::$variant => {
-                            let e = <$type as mser::Read>::read(&mut packet)?;
-                            if !packet.is_empty() {
-                                return err();
+                        <$m>::$variant => Self::$variant(<$type as mser::Read>::read(buf)?),
+                    )+
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl $(<$lt>)? mser::Write for $packet $(<$lt>)? {
+            unsafe fn write(&self, w: &mut mser::Writer) {
+                unsafe {
+                    match self {
+                        $(
+                            Self::$variant(x) => {
+                                <$m>::$variant.write(w);
+                                x.write(w);
                             }
-                            self.$variant(e);
-                        }
+                        )+
+                    }
+                }
+            }
+
+            fn len_s(&self) -> usize {
+                match self {
+                    $(
+                        Self::$variant(x) => <$m>::$variant.len_s() + x.len_s(),
+                    )+
+                }
+            }
+        }
+
+        pub trait $handler {
+            fn $handle(
+                &mut self,
+                mut packet: mser::Reader<'_>,
+            ) -> Result<(), crate::types::HandleError> {
+                let body = packet.peek_slice(packet.len())?;
+                let p = <$packet as mser::Read>::read(&mut packet)?;
+                packet.finish()?;
+                self.dispatch(p, body)
+            }
+
+            /// Calls the method of a packet already decoded from `body`,
+            /// which starts with the packet id.
+            #[allow(clippy::extra_unused_lifetimes)]
+            fn dispatch<'a>(
+                &mut self,
+                p: $packet $(<$lt>)?,
+                body: &[u8],
+            ) -> Result<(), crate::types::HandleError> {
+                let id = p.id();
+                let r = match p {
+                    $(
+                        $packet::$variant(e) => self.$variant(e),
                     )+
+                };
+                match r {
+                    Err(e) if e.is_unhandled() => {
+                        self.unhandled(id, &body[mser::Write::len_s(&id)..])
+                    }
+                    r => r,
                 }
+            }
+
+            /// Called for packets whose method is not overridden, with the
+            /// packet body following the id.
+            fn unhandled(&mut self, id: $m, raw: &[u8]) -> Result<(), crate::types::HandleError> {
+                let _ = (id, raw);
                 Ok(())
             }
         $(
-            fn $variant(&mut self, packet: $type);
+            #[allow(clippy::extra_unused_lifetimes)]
+            fn $variant<'a>(&mut self, packet: $type) -> Result<(), crate::types::HandleError> {
+                let _ = packet;
+                Err(crate::types::HandleError::unhandled())
+            }
         )+
         }
     };
 }
+
 packets! {
     clientbound_status,
+    ClientboundStatusPacket<'a>,
     StatusHandler,
     handle,
-    status_response = status::StatusResponse<'_>,
+    status_response = status::StatusResponse<'a>,
     pong_response = ping::StatusPongResponse,
 }
 packets! {
     clientbound_login,
+    ClientboundLoginPacket<'a>,
     LoginHandler,
     handle,
-    login_disconnect = login::LoginDisconnect<'_>,
-    hello = login::Hello<'_>,
-    login_finished = login::LoginFinished<'_>,
+    login_disconnect = login::LoginDisconnect<'a>,
+    hello = login::Hello<'a>,
+    login_finished = login::LoginFinished<'a>,
     login_compression = login::LoginCompression,
-    custom_query = login::CustomQuery<'_>,
-    cookie_request = cookie::LoginCookieRequest<'_>,
+    custom_query = login::CustomQuery<'a>,
+    cookie_request = cookie::LoginCookieRequest<'a>,
 }
 packets! {
     clientbound_configuration,
+    ClientboundConfigurationPacket<'a>,
     ConfigurationHandler,
     handle,
-    cookie_request = cookie::ConfigurationCookieRequest<'_>,
-    custom_payload = common::ConfigurationCustomPayload<'_>,
+    cookie_request = cookie::ConfigurationCookieRequest<'a>,
+    custom_payload = common::ConfigurationCustomPayload<'a>,
     disconnect = common::ConfigurationDisconnect,
     finish_configuration = configuration::FinishConfiguration,
     keep_alive = common::ConfigurationKeepAlive,
     ping = common::ConfigurationPing,
     reset_chat = common::ResetChat,
-    registry_data = configuration::RegistryData<'_>,
+    registry_data = configuration::RegistryData<'a>,
     resource_pack_pop = common::ResourcePackPop,
-    resource_pack_push = common::ResourcePackPush<'_>,
-    store_cookie = common::ConfigurationStoreCookie<'_>,
-    transfer = common::ConfigurationTransfer<'_>,
-    update_enabled_features = configuration::UpdateEnabledFeatures<'_>,
-    update_tags = common::ConfigurationUpdateTags<'_>,
-    select_known_packs = configuration::SelectKnownPacks<'_>,
-    custom_report_details = common::ConfigurationCustomReportDetails<'_>,
-    server_links = common::ConfigurationServerLinks<'_>,
+    resource_pack_push = common::ResourcePackPush<'a>,
+    store_cookie = common::ConfigurationStoreCookie<'a>,
+    transfer = common::ConfigurationTransfer<'a>,
+    update_enabled_features = configuration::UpdateEnabledFeatures<'a>,
+    update_tags = common::ConfigurationUpdateTags<'a>,
+    select_known_packs = configuration::SelectKnownPacks<'a>,
+    custom_report_details = common::ConfigurationCustomReportDetails<'a>,
+    server_links = common::ConfigurationServerLinks<'a>,
     clear_dialog = common::ConfigurationClearDialog,
     show_dialog = common::ConfigurationShowDialog,
-    code_of_conduct = configuration::CodeOfConduct<'_>,
+    code_of_conduct = configuration::CodeOfConduct<'a>,
 }
 packets! {
     clientbound_play,
+    ClientboundPlayPacket<'a>,
     GameHandler,
     handle,
     bundle_delimiter = game::BundleDelimiter,
     add_entity = game::AddEntity,
     animate = game::Animate,
-    award_stats = game::AwardStats<'_>,
+    award_stats = game::AwardStats<'a>,
     block_changed_ack = game::BlockChangedAck,
     block_destruction = game::BlockDestruction,
     block_entity_data = game::BlockEntityData,
@@ -106,30 +193,30 @@ packets! {
     change_difficulty = game::ChangeDifficulty,
     chunk_batch_finished = game::ChunkBatchFinished,
     chunk_batch_start = game::ChunkBatchStart,
-    chunks_biomes = game::ChunkBiomes<'_>,
+    chunks_biomes = game::ChunkBiomes<'a>,
     clear_titles = game::ClearTitles,
-    command_suggestions = game::CommandSuggestions<'_>,
-    commands = game::Commands<'_>,
+    command_suggestions = game::CommandSuggestions<'a>,
+    commands = game::Commands<'a>,
     container_close = game::ContainerClose,
-    container_set_content = game::ContainerSetContent<'_>,
+    container_set_content = game::ContainerSetContent<'a>,
     container_set_data = game::ContainerSetData,
-    container_set_slot = game::ContainerSetSlot<'_>,
-    cookie_request = cookie::GameCookieRequest<'_>,
-    cooldown = game::Cooldown<'_>,
-    custom_chat_completions = game::CustomChatCompletions<'_>,
-    custom_payload = common::GameCustomPayload<'_>,
+    container_set_slot = game::ContainerSetSlot<'a>,
+    cookie_request = cookie::GameCookieRequest<'a>,
+    cooldown = game::Cooldown<'a>,
+    custom_chat_completions = game::CustomChatCompletions<'a>,
+    custom_payload = common::GameCustomPayload<'a>,
     damage_event = game::DamageEvent,
-    debug_block_value = game::DebugBlockValue<'_>,
-    debug_chunk_value = game::DebugChunkValue<'_>,
-    debug_entity_value = game::DebugEntityValue<'_>,
-    debug_event = game::DebugEvent<'_>,
-    debug_sample = game::DebugSample<'_>,
-    delete_chat = game::DeleteChat<'_>,
+    debug_block_value = game::DebugBlockValue<'a>,
+    debug_chunk_value = game::DebugChunkValue<'a>,
+    debug_entity_value = game::DebugEntityValue<'a>,
+    debug_event = game::DebugEvent<'a>,
+    debug_sample = game::DebugSample<'a>,
+    delete_chat = game::DeleteChat<'a>,
     disconnect = common::GameDisconnect,
-    disguised_chat = game::DisguisedChat<'_>,
+    disguised_chat = game::DisguisedChat<'a>,
     entity_event = game::EntityEvent,
     entity_position_sync = game::EntityPositionSync,
-    explode = game::Explode<'_>,
+    explode = game::Explode<'a>,
     forget_level_chunk = game::ForgetLevelChunk,
     game_event = game::GameEvent,
     game_test_highlight_pos = game::GameTestHighlightPos,
@@ -137,16 +224,16 @@ packets! {
     hurt_animation = game::HurtAnimation,
     initialize_border = game::InitializeBorder,
     keep_alive = common::GameKeepAlive,
-    level_chunk_with_light = game::LevelChunkWithLight<'_>,
+    level_chunk_with_light = game::LevelChunkWithLight<'a>,
     level_event = game::LevelEvent,
-    level_particles = game::LevelParticles<'_>,
-    light_update = game::LightUpdate<'_>,
-    login = game::Login<'_>,
-    map_item_data = game::MapItemData<'_>,
-    merchant_offers = game::MerchantOffers<'_>,
+    level_particles = game::LevelParticles<'a>,
+    light_update = game::LightUpdate<'a>,
+    login = game::Login<'a>,
+    map_item_data = game::MapItemData<'a>,
+    merchant_offers = game::MerchantOffers<'a>,
     move_entity_pos = game::MoveEntityPos,
     move_entity_pos_rot = game::MoveEntityPosRot,
-    move_minecart_along_track = game::MoveMinecartAlongTrack<'_>,
+    move_minecart_along_track = game::MoveMinecartAlongTrack<'a>,
     move_entity_rot = game::MoveEntityRot,
     move_vehicle = game::MoveVehicle,
     open_book = game::OpenBook,
@@ -154,30 +241,30 @@ packets! {
     open_sign_editor = game::OpenSignEditor,
     ping = common::GamePing,
     pong_response = ping::GamePongResponse,
-    place_ghost_recipe = game::PlaceGhostRecipe<'_>,
+    place_ghost_recipe = game::PlaceGhostRecipe<'a>,
     player_abilities = game::PlayerAbilities,
-    player_chat = game::PlayerChat<'_>,
+    player_chat = game::PlayerChat<'a>,
     player_combat_end = game::PlayerCombatEnd,
     player_combat_enter = game::PlayerCombatEnter,
     player_combat_kill = game::PlayerCombatKill,
-    player_info_remove = game::PlayerInfoRemove<'_>,
-    player_info_update = game::PlayerInfoUpdate<'_>,
+    player_info_remove = game::PlayerInfoRemove<'a>,
+    player_info_update = game::PlayerInfoUpdate<'a>,
     player_look_at = game::PlayerLookAt,
     player_position = game::PlayerPosition,
     player_rotation = game::PlayerRotation,
-    recipe_book_add = game::RecipeBookAdd<'_>,
-    recipe_book_remove = game::RecipeBookRemove<'_>,
+    recipe_book_add = game::RecipeBookAdd<'a>,
+    recipe_book_remove = game::RecipeBookRemove<'a>,
     recipe_book_settings = game::RecipeBookSettings,
-    remove_entities = game::RemoveEntities<'_>,
+    remove_entities = game::RemoveEntities<'a>,
     remove_mob_effect = game::RemoveMobEffect,
-    reset_score = game::ResetScore<'_>,
+    reset_score = game::ResetScore<'a>,
     resource_pack_pop = game::ResourcePackPop,
-    resource_pack_push = game::ResourcePackPush<'_>,
-    respawn = game::Respawn<'_>,
+    resource_pack_push = game::ResourcePackPush<'a>,
+    respawn = game::Respawn<'a>,
     rotate_head = game::RotateHead,
-    section_blocks_update = game::SectionBlocksUpdate<'_>,
-    select_advancements_tab = game::SelectAdvancementsTab<'_>,
-    server_data = game::ServerData<'_>,
+    section_blocks_update = game::SectionBlocksUpdate<'a>,
+    select_advancements_tab = game::SelectAdvancementsTab<'a>,
+    server_data = game::ServerData<'a>,
     set_action_bar_text = game::SetActionBarText,
     set_border_center = game::SetBorderCenter,
     set_border_lerp_size = game::SetBorderLerpSize,
@@ -187,31 +274,31 @@ packets! {
     set_camera = game::SetCamera,
     set_chunk_cache_center = game::SetChunkCacheCenter,
     set_chunk_cache_radius = game::SetChunkCacheRadius,
-    set_cursor_item = game::SetCursorItem<'_>,
-    set_default_spawn_position = game::SetDefaultSpawnPosition<'_>,
-    set_display_objective = game::SetDisplayObjective<'_>,
-    set_entity_data = game::SetEntityData<'_>,
+    set_cursor_item = game::SetCursorItem<'a>,
+    set_default_spawn_position = game::SetDefaultSpawnPosition<'a>,
+    set_display_objective = game::SetDisplayObjective<'a>,
+    set_entity_data = game::SetEntityData<'a>,
     set_entity_link = game::SetEntityLink,
     set_entity_motion = game::SetEntityMotion,
-    set_equipment = game::SetEquipment<'_>,
+    set_equipment = game::SetEquipment<'a>,
     set_experience = game::SetExperience,
     set_health = game::SetHealth,
     set_held_slot = game::SetHeldSlot,
-    set_objective = game::SetObjective<'_>,
-    set_passengers = game::SetPassengers<'_>,
-    set_player_inventory = game::SetPlayerInventory<'_>,
-    set_player_team = game::SetPlayerTeam<'_>,
-    set_score = game::SetScore<'_>,
+    set_objective = game::SetObjective<'a>,
+    set_passengers = game::SetPassengers<'a>,
+    set_player_inventory = game::SetPlayerInventory<'a>,
+    set_player_team = game::SetPlayerTeam<'a>,
+    set_score = game::SetScore<'a>,
     set_simulation_distance = game::SetSimulationDistance,
     set_subtitle_text = game::SetSubtitleText,
     set_time = game::SetTime,
     set_title_text = game::SetTitleText,
     set_titles_animation = game::SetTitlesAnimation,
-    sound_entity = game::SoundEntity<'_>,
-    sound = game::Sound<'_>,
+    sound_entity = game::SoundEntity<'a>,
+    sound = game::Sound<'a>,
     start_configuration = game::StartConfiguration,
-    stop_sound = game::StopSound<'_>,
-    store_cookie = common::GameStoreCookie<'_>,
+    stop_sound = game::StopSound<'a>,
+    store_cookie = common::GameStoreCookie<'a>,
     system_chat = game::SystemChat,
     tab_list = game::TabList,
     tag_query = game::TagQuery,
@@ -220,16 +307,16 @@ packets! {
     test_instance_block_status = game::TestInstanceBlockStatus,
     ticking_state = game::TickingState,
     ticking_step = game::TickingStep,
-    transfer = common::GameTransfer<'_>,
-    update_advancements = game::UpdateAdvancements<'_>,
-    update_attributes = game::UpdateAttributes<'_>,
+    transfer = common::GameTransfer<'a>,
+    update_advancements = game::UpdateAdvancements<'a>,
+    update_attributes = game::UpdateAttributes<'a>,
     update_mob_effect = game::UpdateMobEffect,
-    update_recipes = game::UpdateRecipes<'_>,
-    update_tags = common::GameUpdateTags<'_>,
+    update_recipes = game::UpdateRecipes<'a>,
+    update_tags = common::GameUpdateTags<'a>,
     projectile_power = game::ProjectilePower,
-    custom_report_details = common::GameCustomReportDetails<'_>,
-    server_links = common::GameServerLinks<'_>,
-    waypoint = game::Waypoint<'_>,
+    custom_report_details = common::GameCustomReportDetails<'a>,
+    server_links = common::GameServerLinks<'a>,
+    waypoint = game::Waypoint<'a>,
     clear_dialog = common::GameClearDialog,
     show_dialog = common::GameShowDialog,
 }
@@ -1,7 +1,8 @@
 use crate::registry::DialogRef;
 use crate::{ComponentRaw, DialogRaw, Holder, ServerLinkUntrustedEntry, V32List};
 use haya_collection::{List, Map};
-use haya_ident::{Ident, ResourceKey};
+use haya_ident::{Ident, ResourceKey, TagKey};
+use minecraft_data::{TAG_REGISTRIES, Tag};
 use mser::{ByteArray, Rest, Utf8};
 use uuid::Uuid;
 
@@ -103,6 +104,43 @@ pub struct UpdateTags<'a> {
     pub tags: Map<'a, ResourceKey<'a>, Map<'a, Ident<'a>, V32List<'a>>>,
 }
 
+impl UpdateTags<'static> {
+    /// Builds the payload from the tags generated in `minecraft_data`.
+    pub fn vanilla() -> Self {
+        let tags = TAG_REGISTRIES
+            .iter()
+            .map(|registry| {
+                let entries = registry
+                    .names
+                    .iter()
+                    .zip(registry.ids)
+                    .map(|(&name, &ids)| {
+                        (
+                            unsafe { Ident::new_unchecked(None, name) },
+                            V32List(List::Borrowed(ids)),
+                        )
+                    })
+                    .collect();
+                (
+                    ResourceKey {
+                        identifier: unsafe { Ident::new_unchecked(None, registry.registry) },
+                    },
+                    Map(List::Owned(entries)),
+                )
+            })
+            .collect();
+        Self {
+            tags: Map(List::Owned(tags)),
+        }
+    }
+}
+
+pub fn tag_key<T: Tag>(tag: T) -> TagKey<'static> {
+    TagKey {
+        location: unsafe { Ident::new_unchecked(None, tag.name()) },
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ConfigurationCustomReportDetails<'a>(pub CustomReportDetails<'a>);
 
@@ -1,8 +1,17 @@
 use crate::KnownPack;
+use alloc::vec::Vec;
 use haya_collection::List;
-use haya_ident::{Ident, ResourceKey};
+use haya_ident::{Ident, ResourceKey, parse_ident};
 use haya_nbt::Tag;
-use mser::Utf8;
+use minecraft_data::{NAME_VERSION, REGISTRY_DATA};
+use mser::{Error, ErrorKind, Read, Reader, Utf8};
+
+/// The built-in pack that vanilla registry entries belong to.
+pub const CORE_PACK: KnownPack<'static> = KnownPack {
+    namespace: Utf8("minecraft"),
+    id: Utf8("core"),
+    version: Utf8(NAME_VERSION),
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FinishConfiguration {}
@@ -19,6 +28,53 @@ pub struct RegistryEntry<'a> {
     pub data: Option<Tag>,
 }
 
+impl RegistryData<'static> {
+    /// Builds the packets for every registry synchronised during configuration.
+    ///
+    /// `known_packs` is the client's reply to [`SelectKnownPacks`]. Entry data
+    /// is omitted when it contains [`CORE_PACK`], since the client already
+    /// has the vanilla entries.
+    pub fn vanilla(known_packs: &[KnownPack<'_>]) -> Result<Vec<Self>, Error> {
+        let known = known_packs.iter().any(|pack| {
+            pack.namespace.0 == CORE_PACK.namespace.0
+                && pack.id.0 == CORE_PACK.id.0
+                && pack.version.0 == CORE_PACK.version.0
+        });
+        REGISTRY_DATA
+            .iter()
+            .map(|registry| {
+                let entries = registry
+                    .entries
+                    .iter()
+                    .map(|&(id, data)| {
+                        Ok(RegistryEntry {
+                            id: entry_id(id)?,
+                            data: if known {
+                                None
+                            } else {
+                                Some(Tag::read(&mut Reader::new(data))?)
+                            },
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(Self {
+                    registry: ResourceKey {
+                        identifier: unsafe { Ident::new_unchecked(None, registry.registry) },
+                    },
+                    entries: List::Owned(entries),
+                })
+            })
+            .collect()
+    }
+}
+
+fn entry_id(id: &str) -> Result<Ident<'_>, Error> {
+    match parse_ident(id.as_bytes()) {
+        Some(x) => Ok(x),
+        None => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UpdateEnabledFeatures<'a> {
     pub features: List<'a, Ident<'a>>,
@@ -29,6 +85,15 @@ pub struct SelectKnownPacks<'a> {
     pub known_packs: List<'a, KnownPack<'a>>,
 }
 
+impl SelectKnownPacks<'static> {
+    /// Offers [`CORE_PACK`] to the client.
+    pub fn core() -> Self {
+        Self {
+            known_packs: List::Borrowed(&[CORE_PACK]),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CodeOfConduct<'a> {
     pub code_of_conduct: Utf8<'a>,
@@ -30,7 +30,7 @@ use alloc::vec::Vec;
 use haya_collection::{List, Map, capacity_fix};
 use haya_ident::{Ident, ResourceKey};
 use haya_math::{BlockPosPacked, ByteAngle, ChunkPos, ChunkSectionPosPacked, IVec3, LpVec3, Vec3};
-use haya_nbt::Tag;
+use haya_nbt::{Tag, TagRef};
 use minecraft_data::{
     attribute, block, block_entity_type, block_state, entity_type, menu, mob_effect,
     number_format_type, sound_event,
@@ -253,7 +253,7 @@ pub struct ContainerClose {
     pub container_id: ContainerId,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Skip)]
 pub struct ContainerSetContent<'a> {
     pub container_id: ContainerId,
     #[mser(varint)]
@@ -468,29 +468,29 @@ pub struct InitializeBorder {
     pub warning_time: u32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Skip)]
 pub struct LevelChunkWithLight<'a> {
     pub pos: ChunkPos,
     pub chunk_data: ChunkData<'a>,
     pub light_data: LightData<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Skip)]
 pub struct ChunkData<'a> {
     pub heightmaps: Map<'a, HeightmapType, List<'a, u64>>,
     pub data: ByteArray<'a>,
-    pub block_entities_data: List<'a, BlockEntityInfo>,
+    pub block_entities_data: List<'a, BlockEntityInfo<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct BlockEntityInfo {
+#[derive(Clone, Serialize, Deserialize, Skip)]
+pub struct BlockEntityInfo<'a> {
     pub packed_xz: u8,
     pub y: i16,
     pub ty: block_entity_type,
-    pub tag: Tag,
+    pub tag: TagRef<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Skip)]
 pub struct LightData<'a> {
     pub sky_y_mask: BitSet<'a>,
     pub block_y_mask: BitSet<'a>,
@@ -700,6 +700,7 @@ pub struct PlayerInfoRemove<'a> {
     pub profile_ids: List<'a, Uuid>,
 }
 
+#[derive(Clone)]
 pub struct PlayerInfoUpdate<'a> {
     pub actions: PlayerInfoUpdateActions,
     pub entries: List<'a, PlayerInfoUpdateEntry<'a>>,
@@ -1,6 +1,221 @@
-use mser::Utf8;
+use alloc::string::String;
+use alloc::vec::Vec;
+use haya_nbt::{CompoundTag, ListTag, StringTag, Tag};
+use haya_text::chat::{Content, Style, TextComponent};
+use haya_text::json::{component_boolean, json_to_tag, tag_to_json};
+use mser::{Error, ErrorKind, Utf8};
+use uuid::Uuid;
+
+const DESCRIPTION: &str = "description";
+const PLAYERS: &str = "players";
+const PLAYERS_MAX: &str = "max";
+const PLAYERS_ONLINE: &str = "online";
+const PLAYERS_SAMPLE: &str = "sample";
+const SAMPLE_NAME: &str = "name";
+const SAMPLE_ID: &str = "id";
+const VERSION: &str = "version";
+const VERSION_NAME: &str = "name";
+const VERSION_PROTOCOL: &str = "protocol";
+const FAVICON: &str = "favicon";
+const ENFORCES_SECURE_CHAT: &str = "enforcesSecureChat";
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StatusResponse<'a> {
     pub status: Utf8<'a>,
 }
+
+impl StatusResponse<'_> {
+    /// Decodes the status JSON.
+    pub fn parse(&self) -> Result<ServerStatus, Error> {
+        ServerStatus::from_json(self.status.0)
+    }
+}
+
+/// The JSON document carried by [`StatusResponse`].
+#[derive(Clone)]
+pub struct ServerStatus {
+    pub description: TextComponent,
+    pub players: Option<StatusPlayers>,
+    pub version: Option<StatusVersion>,
+    /// `data:image/png;base64,` URI of a 64x64 image.
+    pub favicon: Option<StringTag>,
+    pub enforces_secure_chat: bool,
+}
+
+#[derive(Clone)]
+pub struct StatusPlayers {
+    pub max: i32,
+    pub online: i32,
+    pub sample: Vec<PlayerSample>,
+}
+
+#[derive(Clone)]
+pub struct PlayerSample {
+    pub name: StringTag,
+    pub id: Uuid,
+}
+
+#[derive(Clone)]
+pub struct StatusVersion {
+    pub name: StringTag,
+    pub protocol: i32,
+}
+
+impl ServerStatus {
+    pub fn to_json(&self) -> String {
+        let mut w = String::new();
+        tag_to_json(
+            &Tag::Compound(self.to_tag()),
+            &|k| k == ENFORCES_SECURE_CHAT || component_boolean(k),
+            &mut w,
+        );
+        w
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        match json_to_tag(s)? {
+            Tag::Compound(x) => Self::from_tag(x),
+            _ => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+
+    fn to_tag(&self) -> CompoundTag {
+        let mut out = CompoundTag::new();
+        out.push(
+            StringTag::from_utf8(DESCRIPTION),
+            self.description.to_json_tag(),
+        );
+        if let Some(players) = &self.players {
+            let mut x = CompoundTag::new();
+            x.push(StringTag::from_utf8(PLAYERS_MAX), Tag::Int(players.max));
+            x.push(
+                StringTag::from_utf8(PLAYERS_ONLINE),
+                Tag::Int(players.online),
+            );
+            if !players.sample.is_empty() {
+                let sample = players
+                    .sample
+                    .iter()
+                    .map(|player| {
+                        let mut buf = [0; 36];
+                        let id = player.id.hyphenated().encode_lower(&mut buf);
+                        let mut y = CompoundTag::new();
+                        y.push(
+                            StringTag::from_utf8(SAMPLE_NAME),
+                            Tag::String(player.name.clone()),
+                        );
+                        y.push(
+                            StringTag::from_utf8(SAMPLE_ID),
+                            Tag::String(StringTag::from_utf8(id)),
+                        );
+                        y
+                    })
+                    .collect();
+                x.push(
+                    StringTag::from_utf8(PLAYERS_SAMPLE),
+                    Tag::List(ListTag::Compound(sample)),
+                );
+            }
+            out.push(StringTag::from_utf8(PLAYERS), Tag::Compound(x));
+        }
+        if let Some(version) = &self.version {
+            let mut x = CompoundTag::new();
+            x.push(
+                StringTag::from_utf8(VERSION_NAME),
+                Tag::String(version.name.clone()),
+            );
+            x.push(
+                StringTag::from_utf8(VERSION_PROTOCOL),
+                Tag::Int(version.protocol),
+            );
+            out.push(StringTag::from_utf8(VERSION), Tag::Compound(x));
+        }
+        if let Some(favicon) = &self.favicon {
+            out.push(StringTag::from_utf8(FAVICON), Tag::String(favicon.clone()));
+        }
+        out.push(
+            StringTag::from_utf8(ENFORCES_SECURE_CHAT),
+            Tag::Byte(self.enforces_secure_chat as i8),
+        );
+        out
+    }
+
+    fn from_tag(mut tag: CompoundTag) -> Result<Self, Error> {
+        let description = match tag.find_remove(DESCRIPTION) {
+            Some(x) => TextComponent::from_json_tag(x)?,
+            None => TextComponent {
+                content: Content::Literal {
+                    content: StringTag::new(),
+                },
+                style: Style::new(),
+                siblings: Vec::new(),
+            },
+        };
+        let players = match tag.find_remove(PLAYERS) {
+            Some(Tag::Compound(mut x)) => {
+                let max = int(x.find_remove(PLAYERS_MAX))?;
+                let online = int(x.find_remove(PLAYERS_ONLINE))?;
+                let sample = match x.find_remove(PLAYERS_SAMPLE) {
+                    None | Some(Tag::List(ListTag::None)) => Vec::new(),
+                    Some(Tag::List(ListTag::Compound(y))) => y
+                        .into_iter()
+                        .map(|mut player| {
+                            let name = string(player.find_remove(SAMPLE_NAME))?;
+                            let raw = string(player.find_remove(SAMPLE_ID))?;
+                            match Uuid::try_parse(&raw) {
+                                Ok(id) => Ok(PlayerSample { name, id }),
+                                Err(_) => Err(Error::new(ErrorKind::Invalid)),
+                            }
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?,
+                    Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+                };
+                Some(StatusPlayers {
+                    max,
+                    online,
+                    sample,
+                })
+            }
+            None => None,
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let version = match tag.find_remove(VERSION) {
+            Some(Tag::Compound(mut x)) => Some(StatusVersion {
+                name: string(x.find_remove(VERSION_NAME))?,
+                protocol: int(x.find_remove(VERSION_PROTOCOL))?,
+            }),
+            None => None,
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        let favicon = match tag.find_remove(FAVICON) {
+            Some(x) => Some(string(Some(x))?),
+            None => None,
+        };
+        let enforces_secure_chat = match tag.find_remove(ENFORCES_SECURE_CHAT) {
+            Some(Tag::Byte(x)) => x != 0,
+            None => false,
+            Some(_) => return Err(Error::new(ErrorKind::Invalid)),
+        };
+        Ok(Self {
+            description,
+            players,
+            version,
+            favicon,
+            enforces_secure_chat,
+        })
+    }
+}
+
+fn int(tag: Option<Tag>) -> Result<i32, Error> {
+    match tag {
+        Some(Tag::Int(x)) => Ok(x),
+        _ => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
+
+fn string(tag: Option<Tag>) -> Result<StringTag, Error> {
+    match tag {
+        Some(Tag::String(x)) => Ok(x),
+        _ => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
@@ -0,0 +1,430 @@
+use crate::types::{Id, encode_packet};
+use aes::Aes128;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use miniz_oxide::deflate::core::{
+    CompressorOxide, TDEFLFlush, TDEFLStatus, compress_to_output, create_comp_flags_from_zip_params,
+};
+use miniz_oxide::inflate::TINFLStatus;
+use miniz_oxide::inflate::core::inflate_flags::{
+    TINFL_FLAG_PARSE_ZLIB_HEADER, TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+};
+use miniz_oxide::inflate::core::{DecompressorOxide, decompress};
+use mser::{Error, ErrorKind, Read, Reader, V21, V21MAX, V32, Write};
+
+/// Maximum length of a frame, the largest value of a 3 byte VarInt.
+pub const MAX_FRAME_LEN: usize = V21MAX;
+/// Maximum length of a decompressed packet body.
+pub const MAX_DATA_LEN: usize = 8388608;
+
+const COMPRESSION_LEVEL: i32 = 6;
+
+/// AES-128 in CFB8 mode, keyed and initialised with the shared secret.
+#[derive(Clone)]
+pub struct Cipher {
+    aes: Aes128,
+    iv: [u8; 16],
+}
+
+impl Cipher {
+    pub fn new(key: &[u8; 16]) -> Self {
+        Self {
+            aes: Aes128::new(GenericArray::from_slice(key)),
+            iv: *key,
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> u8 {
+        let mut block = GenericArray::clone_from_slice(&self.iv);
+        self.aes.encrypt_block(&mut block);
+        block[0]
+    }
+
+    #[inline]
+    fn shift(&mut self, byte: u8) {
+        self.iv.copy_within(1.., 0);
+        self.iv[15] = byte;
+    }
+
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        for x in data {
+            *x ^= self.next();
+            self.shift(*x);
+        }
+    }
+
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        for x in data {
+            let c = *x;
+            *x ^= self.next();
+            self.shift(c);
+        }
+    }
+}
+
+impl crate::clientbound::login::LoginCompression {
+    /// Negative thresholds disable compression.
+    pub const fn threshold(&self) -> Option<u32> {
+        if (self.compression_threshold as i32) < 0 {
+            None
+        } else {
+            Some(self.compression_threshold)
+        }
+    }
+}
+
+/// Splits a byte stream into packet bodies.
+///
+/// Bytes are pushed with [`feed`] and complete packets are taken with
+/// [`next_frame`], which yields the packet id followed by its fields.
+///
+/// [`feed`]: FrameDecoder::feed
+/// [`next_frame`]: FrameDecoder::next_frame
+pub struct FrameDecoder {
+    buf: Vec<u8>,
+    pos: usize,
+    threshold: Option<u32>,
+    cipher: Option<Cipher>,
+    inflate: Option<Box<DecompressorOxide>>,
+    data: Vec<u8>,
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            pos: 0,
+            threshold: None,
+            cipher: None,
+            inflate: None,
+            data: Vec::new(),
+        }
+    }
+
+    /// Switches to the compressed format after `LoginCompression`.
+    pub fn set_compression(&mut self, threshold: Option<u32>) {
+        self.threshold = threshold;
+    }
+
+    /// Enables decryption. Bytes already fed but not yet consumed are
+    /// decrypted in place.
+    pub fn set_encryption(&mut self, key: &[u8; 16]) {
+        let mut cipher = Cipher::new(key);
+        cipher.decrypt(&mut self.buf[self.pos..]);
+        self.cipher = Some(cipher);
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.pos != 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        let start = self.buf.len();
+        self.buf.extend_from_slice(bytes);
+        if let Some(cipher) = &mut self.cipher {
+            cipher.decrypt(&mut self.buf[start..]);
+        }
+    }
+
+    /// Number of buffered bytes that have not been consumed.
+    pub fn buffered(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns the next complete packet body, or [`None`] if more input is
+    /// needed.
+    pub fn next_frame(&mut self) -> Result<Option<Reader<'_>>, Error> {
+        let input = &self.buf[self.pos..];
+        let (len, n) = match frame_len(input)? {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if input.len() - n < len {
+            return Ok(None);
+        }
+        let start = self.pos + n;
+        self.pos = start + len;
+        let frame = &self.buf[start..self.pos];
+        let threshold = match self.threshold {
+            Some(x) => x as usize,
+            None => return Ok(Some(Reader::new(frame))),
+        };
+        let mut r = Reader::new(frame);
+        let data_len = V32::read(&mut r)?.0 as usize;
+        let rest = r.peek_slice(r.len())?;
+        if data_len == 0 {
+            // Bodies at or above the threshold must have been compressed.
+            if rest.len() >= threshold {
+                return Err(r.error(ErrorKind::Invalid));
+            }
+            return Ok(Some(Reader::new(rest)));
+        }
+        if data_len < threshold {
+            return Err(r.error(ErrorKind::Invalid));
+        }
+        if data_len > MAX_DATA_LEN {
+            return Err(r.error(ErrorKind::TooLong));
+        }
+        let inflate = self
+            .inflate
+            .get_or_insert_with(|| Box::new(DecompressorOxide::new()));
+        inflate.init();
+        self.data.clear();
+        self.data.resize(data_len, 0);
+        let (status, _, written) = decompress(
+            inflate,
+            rest,
+            &mut self.data,
+            0,
+            TINFL_FLAG_PARSE_ZLIB_HEADER | TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+        if status != TINFLStatus::Done || written != data_len {
+            return Err(r.error(ErrorKind::Invalid));
+        }
+        Ok(Some(Reader::new(&self.data)))
+    }
+}
+
+fn frame_len(input: &[u8]) -> Result<Option<(usize, usize)>, Error> {
+    let mut len = 0;
+    for (i, &b) in input.iter().take(3).enumerate() {
+        len |= ((b & 0x7F) as usize) << (7 * i);
+        if b & 0x80 == 0 {
+            return Ok(Some((len, i + 1)));
+        }
+    }
+    if input.len() >= 3 {
+        Err(Error::with_offset(ErrorKind::VarInt, 3))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Frames packet bodies into bytes ready to be written to a socket.
+pub struct FrameEncoder {
+    buf: Vec<u8>,
+    pos: usize,
+    threshold: Option<u32>,
+    cipher: Option<Cipher>,
+    deflate: Option<Box<CompressorOxide>>,
+    body: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Default for FrameEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameEncoder {
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            pos: 0,
+            threshold: None,
+            cipher: None,
+            deflate: None,
+            body: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    pub fn set_compression(&mut self, threshold: Option<u32>) {
+        self.threshold = threshold;
+    }
+
+    /// Enables encryption for every frame encoded afterwards.
+    pub fn set_encryption(&mut self, key: &[u8; 16]) {
+        self.cipher = Some(Cipher::new(key));
+    }
+
+    /// Encodes the packet id of `P` followed by the packet.
+    pub fn encode<P: Id + Write>(&mut self, packet: &P) -> Result<(), Error> {
+        let mut body = core::mem::take(&mut self.body);
+        body.clear();
+        encode_packet(packet, &mut body);
+        let r = self.encode_raw(&body);
+        self.body = body;
+        r
+    }
+
+    /// Like [`encode`](Self::encode), but only frames the body if `check`
+    /// accepts it.
+    pub fn encode_checked<P: Id + Write>(
+        &mut self,
+        packet: &P,
+        check: impl FnOnce(&[u8]) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let mut body = core::mem::take(&mut self.body);
+        body.clear();
+        encode_packet(packet, &mut body);
+        let r = check(&body).and_then(|()| self.encode_raw(&body));
+        self.body = body;
+        r
+    }
+
+    /// Encodes a packet body that already starts with its packet id.
+    pub fn encode_raw(&mut self, body: &[u8]) -> Result<(), Error> {
+        let start = self.buf.len();
+        match self.threshold {
+            None => {
+                if body.len() > MAX_FRAME_LEN {
+                    return Err(Error::new(ErrorKind::TooLong));
+                }
+                extend(&mut self.buf, &V21(body.len() as u32));
+                self.buf.extend_from_slice(body);
+            }
+            Some(threshold) if body.len() < threshold as usize => {
+                if body.len() + 1 > MAX_FRAME_LEN {
+                    return Err(Error::new(ErrorKind::TooLong));
+                }
+                extend(&mut self.buf, &V21(body.len() as u32 + 1));
+                self.buf.push(0);
+                self.buf.extend_from_slice(body);
+            }
+            Some(_) => {
+                if body.len() > MAX_DATA_LEN {
+                    return Err(Error::new(ErrorKind::TooLong));
+                }
+                let deflate = self.deflate.get_or_insert_with(|| {
+                    Box::new(CompressorOxide::new(create_comp_flags_from_zip_params(
+                        COMPRESSION_LEVEL,
+                        15,
+                        0,
+                    )))
+                });
+                deflate.reset();
+                let data = &mut self.data;
+                data.clear();
+                let (status, _) = compress_to_output(deflate, body, TDEFLFlush::Finish, |x| {
+                    data.extend_from_slice(x);
+                    true
+                });
+                if status != TDEFLStatus::Done {
+                    return Err(Error::new(ErrorKind::Invalid));
+                }
+                let data_len = V32(body.len() as u32);
+                let len = data_len.len_s() + data.len();
+                if len > MAX_FRAME_LEN {
+                    return Err(Error::new(ErrorKind::TooLong));
+                }
+                extend(&mut self.buf, &V21(len as u32));
+                extend(&mut self.buf, &data_len);
+                self.buf.extend_from_slice(data);
+            }
+        }
+        if let Some(cipher) = &mut self.cipher {
+            cipher.encrypt(&mut self.buf[start..]);
+        }
+        Ok(())
+    }
+
+    /// Bytes waiting to be written.
+    pub fn pending(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Marks `n` bytes of [`pending`] as written.
+    ///
+    /// [`pending`]: FrameEncoder::pending
+    pub fn consume(&mut self, n: usize) {
+        self.pos += n;
+        debug_assert!(self.pos <= self.buf.len());
+        if self.pos >= self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+    }
+}
+
+fn extend(buf: &mut Vec<u8>, x: &impl Write) {
+    let len = x.len_s();
+    buf.reserve(len);
+    unsafe {
+        mser::write_unchecked(buf.as_mut_ptr().add(buf.len()), x);
+        buf.set_len(buf.len() + len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clientbound::common::{GameKeepAlive, KeepAlive};
+    use minecraft_data::clientbound_play;
+
+    #[test]
+    fn test_cfb8() {
+        let key = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ];
+        let mut cipher = Cipher::new(&key);
+        cipher.iv = core::array::from_fn(|i| i as u8);
+        let mut data = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a, 0xae, 0x2d,
+        ];
+        let plain = data;
+        let mut decrypt = cipher.clone();
+        cipher.encrypt(&mut data);
+        assert_eq!(
+            data,
+            [
+                0x3b, 0x79, 0x42, 0x4c, 0x9c, 0x0d, 0xd4, 0x36, 0xba, 0xce, 0x9e, 0x0e, 0xd4, 0x58,
+                0x6a, 0x4f, 0x32, 0xb9,
+            ]
+        );
+        decrypt.decrypt(&mut data);
+        assert_eq!(data, plain);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = [7u8; 16];
+        let mut enc = FrameEncoder::new();
+        let mut dec = FrameDecoder::new();
+        enc.encode(&GameKeepAlive(KeepAlive { id: 42 })).unwrap();
+        enc.set_compression(Some(64));
+        enc.set_encryption(&key);
+        enc.encode_raw(&[0xAB; 300]).unwrap();
+        enc.encode_raw(&[0xCD; 3]).unwrap();
+
+        let bytes = enc.pending().to_vec();
+        enc.consume(bytes.len());
+        assert!(enc.pending().is_empty());
+
+        dec.feed(&bytes[..10]);
+        let mut r = dec.next_frame().unwrap().unwrap();
+        assert_eq!(clientbound_play::read(&mut r).unwrap(), GameKeepAlive::ID);
+        assert_eq!(u64::read(&mut r).unwrap(), 42);
+        assert!(r.is_empty());
+        assert!(dec.next_frame().unwrap().is_none());
+
+        dec.set_compression(Some(64));
+        dec.set_encryption(&key);
+        dec.feed(&bytes[10..20]);
+        assert!(dec.next_frame().unwrap().is_none());
+        dec.feed(&bytes[20..]);
+        let r = dec.next_frame().unwrap().unwrap();
+        assert_eq!(r.peek_slice(r.len()).unwrap(), &[0xAB; 300]);
+        let r = dec.next_frame().unwrap().unwrap();
+        assert_eq!(r.peek_slice(r.len()).unwrap(), &[0xCD; 3]);
+        assert!(dec.next_frame().unwrap().is_none());
+        assert_eq!(dec.buffered(), 0);
+
+        let mut dec = FrameDecoder::new();
+        dec.set_compression(Some(4));
+        dec.feed(&[5, 0, 1, 2, 3, 4]);
+        assert!(dec.next_frame().is_err());
+    }
+}
@@ -1,3 +1,6 @@
+pub mod reader;
+pub mod tree;
+
 use crate::chat::MessageSignature;
 use haya_collection::List;
 use haya_ident::{Ident, ResourceKey};
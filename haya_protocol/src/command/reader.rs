@@ -0,0 +1,460 @@
+use super::{CommandArgument, StringType};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use haya_ident::{Ident, parse_ident};
+use haya_text::selector::{EntitySelector, Variable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// No command starts with the first word.
+    UnknownCommand,
+    /// No child of the current node accepts the next word.
+    UnknownArgument,
+    /// The input ended on a node that does not execute.
+    IncompleteCommand,
+    ExpectedSeparator,
+    ExpectedBool,
+    ExpectedInteger,
+    ExpectedLong,
+    ExpectedFloat,
+    ExpectedDouble,
+    ExpectedString,
+    /// A number was below the minimum of its argument.
+    TooLow,
+    /// A number was above the maximum of its argument.
+    TooHigh,
+    UnclosedQuote,
+    InvalidEscape,
+    /// Fewer coordinates than the argument needs.
+    IncompleteCoordinates,
+    /// Local `^` coordinates mixed with world coordinates.
+    MixedCoordinates,
+    InvalidId,
+    InvalidEntity,
+    /// A selector for many entities where only one is allowed.
+    TooManyEntities,
+    /// A selector that can match entities other than players.
+    PlayersOnly,
+    InvalidUnit,
+}
+
+/// A parse failure at a byte offset of the command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandError {
+    pub kind: CommandErrorKind,
+    pub cursor: usize,
+}
+
+/// A value of a [`CommandArgument`] parsed from a command.
+#[derive(Clone, Debug)]
+pub enum ArgumentValue<'s> {
+    Bool(bool),
+    Float(f32),
+    Double(f64),
+    Integer(i32),
+    Long(i64),
+    String(Cow<'s, str>),
+    BlockPos(Coordinates<3>),
+    ColumnPos(Coordinates<2>),
+    Vec3(Coordinates<3>),
+    Vec2(Coordinates<2>),
+    ResourceLocation(Ident<'s>),
+    Entity(EntitySelector<'s>),
+    /// A duration in ticks.
+    Time(i32),
+    /// The text of an argument without a typed value.
+    Raw(&'s str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Coordinates<const N: usize> {
+    World([WorldCoordinate; N]),
+    /// Left, up and forwards relative to the rotation of the source.
+    Local([f64; N]),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldCoordinate {
+    pub value: f64,
+    /// Whether `value` is an offset from the source written as `~`.
+    pub relative: bool,
+}
+
+impl<const N: usize> Coordinates<N> {
+    /// The position relative to `origin` for world coordinates.
+    ///
+    /// Local coordinates are returned unresolved.
+    pub fn resolve(&self, origin: [f64; N]) -> [f64; N] {
+        match self {
+            Self::World(c) => core::array::from_fn(|i| {
+                if c[i].relative {
+                    origin[i] + c[i].value
+                } else {
+                    c[i].value
+                }
+            }),
+            Self::Local(c) => *c,
+        }
+    }
+}
+
+/// A cursor over the text of a command.
+#[derive(Clone, Copy, Debug)]
+pub struct StringReader<'s> {
+    input: &'s str,
+    cursor: usize,
+}
+
+impl<'s> StringReader<'s> {
+    pub const fn new(input: &'s str) -> Self {
+        Self { input, cursor: 0 }
+    }
+
+    pub const fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub const fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    pub fn remaining(&self) -> &'s str {
+        &self.input[self.cursor..]
+    }
+
+    pub const fn can_read(&self) -> bool {
+        self.cursor < self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.cursor).copied()
+    }
+
+    pub const fn error(&self, kind: CommandErrorKind) -> CommandError {
+        CommandError {
+            kind,
+            cursor: self.cursor,
+        }
+    }
+
+    /// Skips `c` or fails with [`CommandErrorKind::ExpectedSeparator`]
+    /// when the next byte is something else.
+    pub fn expect(&mut self, c: u8) -> Result<(), CommandError> {
+        if self.peek() == Some(c) {
+            self.cursor += 1;
+            Ok(())
+        } else {
+            Err(self.error(CommandErrorKind::ExpectedSeparator))
+        }
+    }
+
+    pub fn read_while(&mut self, f: impl Fn(u8) -> bool) -> &'s str {
+        let start = self.cursor;
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.cursor += 1;
+        }
+        &self.input[start..self.cursor]
+    }
+
+    /// Reads to the end of the command.
+    pub fn read_rest(&mut self) -> &'s str {
+        let rest = self.remaining();
+        self.cursor = self.input.len();
+        rest
+    }
+
+    pub fn read_unquoted(&mut self) -> &'s str {
+        self.read_while(|c| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'+'))
+    }
+
+    /// Reads a string in `"` or `'` with `\` escapes.
+    pub fn read_quoted(&mut self) -> Result<Cow<'s, str>, CommandError> {
+        let Some(quote @ (b'"' | b'\'')) = self.peek() else {
+            return Err(self.error(CommandErrorKind::ExpectedString));
+        };
+        self.cursor += 1;
+        let start = self.cursor;
+        let mut owned: Option<String> = None;
+        let mut run = start;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error(CommandErrorKind::UnclosedQuote));
+            };
+            if c == quote {
+                let tail = &self.input[run..self.cursor];
+                self.cursor += 1;
+                return Ok(match owned {
+                    Some(mut s) => {
+                        s.push_str(tail);
+                        Cow::Owned(s)
+                    }
+                    None => Cow::Borrowed(tail),
+                });
+            }
+            if c == b'\\' {
+                let escaped = self.input.as_bytes().get(self.cursor + 1).copied();
+                if escaped != Some(quote) && escaped != Some(b'\\') {
+                    self.cursor += 1;
+                    return Err(self.error(CommandErrorKind::InvalidEscape));
+                }
+                let s = owned.get_or_insert_with(String::new);
+                s.push_str(&self.input[run..self.cursor]);
+                s.push(escaped.unwrap_or_default() as char);
+                self.cursor += 2;
+                run = self.cursor;
+                continue;
+            }
+            self.cursor += 1;
+        }
+    }
+
+    pub fn read_string(&mut self) -> Result<Cow<'s, str>, CommandError> {
+        match self.peek() {
+            Some(b'"' | b'\'') => self.read_quoted(),
+            _ => Ok(Cow::Borrowed(self.read_unquoted())),
+        }
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, CommandError> {
+        let start = self.cursor;
+        match self.read_unquoted() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => {
+                self.cursor = start;
+                Err(self.error(CommandErrorKind::ExpectedBool))
+            }
+        }
+    }
+
+    fn read_number<T: core::str::FromStr>(
+        &mut self,
+        kind: CommandErrorKind,
+    ) -> Result<T, CommandError> {
+        let start = self.cursor;
+        let s = self.read_while(|c| c.is_ascii_digit() || c == b'.' || c == b'-');
+        match s.parse() {
+            Ok(x) => Ok(x),
+            Err(_) => {
+                self.cursor = start;
+                Err(self.error(kind))
+            }
+        }
+    }
+
+    pub fn read_int(&mut self) -> Result<i32, CommandError> {
+        self.read_number(CommandErrorKind::ExpectedInteger)
+    }
+
+    pub fn read_long(&mut self) -> Result<i64, CommandError> {
+        self.read_number(CommandErrorKind::ExpectedLong)
+    }
+
+    pub fn read_float(&mut self) -> Result<f32, CommandError> {
+        self.read_number(CommandErrorKind::ExpectedFloat)
+    }
+
+    pub fn read_double(&mut self) -> Result<f64, CommandError> {
+        self.read_number(CommandErrorKind::ExpectedDouble)
+    }
+
+    /// Reads up to the next space outside of quotes and brackets, as used
+    /// by arguments such as NBT and components.
+    pub fn read_token(&mut self) -> Result<&'s str, CommandError> {
+        let start = self.cursor;
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                b' ' if depth == 0 => break,
+                b'"' | b'\'' => {
+                    self.read_quoted()?;
+                    continue;
+                }
+                b'{' | b'[' | b'(' => depth += 1,
+                b'}' | b']' | b')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.cursor += 1;
+        }
+        Ok(&self.input[start..self.cursor])
+    }
+
+    /// Parses the value of `argument`, leaving the cursor after it.
+    pub fn read_argument(
+        &mut self,
+        argument: &CommandArgument,
+    ) -> Result<ArgumentValue<'s>, CommandError> {
+        let start = self.cursor;
+        let value = match *argument {
+            CommandArgument::Bool => ArgumentValue::Bool(self.read_bool()?),
+            CommandArgument::Float { min, max } => {
+                let v = self.read_float()?;
+                self.check_range(start, v, min, max)?;
+                ArgumentValue::Float(v)
+            }
+            CommandArgument::Double { min, max } => {
+                let v = self.read_double()?;
+                self.check_range(start, v, min, max)?;
+                ArgumentValue::Double(v)
+            }
+            CommandArgument::Integer { min, max } => {
+                let v = self.read_int()?;
+                self.check_range(start, v, min, max)?;
+                ArgumentValue::Integer(v)
+            }
+            CommandArgument::Long { min, max } => {
+                let v = self.read_long()?;
+                self.check_range(start, v, min, max)?;
+                ArgumentValue::Long(v)
+            }
+            CommandArgument::String { ty } => ArgumentValue::String(match ty {
+                StringType::SingleWord => Cow::Borrowed(self.read_unquoted()),
+                StringType::QuotablePhrase => self.read_string()?,
+                StringType::GreedyPhrase => Cow::Borrowed(self.read_rest()),
+            }),
+            CommandArgument::Entity {
+                single,
+                players_only,
+            } => ArgumentValue::Entity(self.read_entity(single, players_only)?),
+            CommandArgument::GameProfile => ArgumentValue::Entity(self.read_entity(false, true)?),
+            CommandArgument::BlockPos => ArgumentValue::BlockPos(self.read_coordinates(true)?),
+            CommandArgument::ColumnPos => ArgumentValue::ColumnPos(self.read_coordinates(true)?),
+            CommandArgument::Vec3 => ArgumentValue::Vec3(self.read_coordinates(false)?),
+            CommandArgument::Vec2 => ArgumentValue::Vec2(self.read_coordinates(false)?),
+            CommandArgument::ResourceLocation | CommandArgument::Dimension => {
+                let s = self.read_while(|c| {
+                    c.is_ascii_digit()
+                        || c.is_ascii_lowercase()
+                        || matches!(c, b'_' | b'-' | b'.' | b'/' | b':')
+                });
+                match parse_ident(s.as_bytes()) {
+                    Some(id) => ArgumentValue::ResourceLocation(id),
+                    None => {
+                        self.cursor = start;
+                        return Err(self.error(CommandErrorKind::InvalidId));
+                    }
+                }
+            }
+            CommandArgument::Time { min } => {
+                let v = self.read_float()?;
+                let scale = match self.peek() {
+                    Some(b'd') => 24000.0,
+                    Some(b's') => 20.0,
+                    Some(b't') => 1.0,
+                    Some(b' ') | None => 0.0,
+                    Some(_) => return Err(self.error(CommandErrorKind::InvalidUnit)),
+                };
+                if scale != 0.0 {
+                    self.cursor += 1;
+                }
+                let t = v * if scale == 0.0 { 1.0 } else { scale };
+                let ticks = if t < 0.0 { t - 0.5 } else { t + 0.5 } as i32;
+                if ticks < min {
+                    self.cursor = start;
+                    return Err(self.error(CommandErrorKind::TooLow));
+                }
+                ArgumentValue::Time(ticks)
+            }
+            CommandArgument::Message => ArgumentValue::Raw(self.read_rest()),
+            _ => ArgumentValue::Raw(self.read_token()?),
+        };
+        Ok(value)
+    }
+
+    fn check_range<T: PartialOrd>(
+        &mut self,
+        start: usize,
+        v: T,
+        min: T,
+        max: T,
+    ) -> Result<(), CommandError> {
+        let kind = if v < min {
+            CommandErrorKind::TooLow
+        } else if v > max {
+            CommandErrorKind::TooHigh
+        } else {
+            return Ok(());
+        };
+        self.cursor = start;
+        Err(self.error(kind))
+    }
+
+    fn read_entity(
+        &mut self,
+        single: bool,
+        players_only: bool,
+    ) -> Result<EntitySelector<'s>, CommandError> {
+        let start = self.cursor;
+        let (selector, len) =
+            EntitySelector::parse_prefix(self.remaining()).map_err(|e| CommandError {
+                kind: CommandErrorKind::InvalidEntity,
+                cursor: start + e.cursor,
+            })?;
+        let this = matches!(&selector, EntitySelector::Select(s) if s.variable == Variable::This);
+        let kind = if single && selector.max_results() > 1 {
+            CommandErrorKind::TooManyEntities
+        } else if players_only && !this && !selector.players_only() {
+            CommandErrorKind::PlayersOnly
+        } else {
+            self.cursor += len;
+            return Ok(selector);
+        };
+        Err(self.error(kind))
+    }
+
+    /// Reads `N` coordinates separated by single spaces. Integer
+    /// coordinates are block positions, otherwise absolute horizontal
+    /// values without a fraction are moved to the block centre.
+    fn read_coordinates<const N: usize>(
+        &mut self,
+        integer: bool,
+    ) -> Result<Coordinates<N>, CommandError> {
+        let start = self.cursor;
+        let local = self.peek() == Some(b'^');
+        if local && N != 3 {
+            return Err(self.error(CommandErrorKind::MixedCoordinates));
+        }
+        let mut world = [WorldCoordinate {
+            value: 0.0,
+            relative: false,
+        }; N];
+        for (i, c) in world.iter_mut().enumerate() {
+            if i != 0 {
+                if !self.can_read() {
+                    self.cursor = start;
+                    return Err(self.error(CommandErrorKind::IncompleteCoordinates));
+                }
+                self.expect(b' ')?;
+            }
+            let prefix = self.peek();
+            if local != (prefix == Some(b'^')) {
+                return Err(self.error(CommandErrorKind::MixedCoordinates));
+            }
+            if matches!(prefix, Some(b'^' | b'~')) {
+                self.cursor += 1;
+                c.relative = true;
+                if self.can_read() && self.peek() != Some(b' ') {
+                    c.value = self.read_double()?;
+                }
+            } else if integer {
+                c.value = f64::from(self.read_int()?);
+            } else {
+                let s = self.remaining();
+                c.value = self.read_double()?;
+                let horizontal = i != 1 || N == 2;
+                let text = &s[..s.len() - self.remaining().len()];
+                if horizontal && !text.contains('.') {
+                    c.value += 0.5;
+                }
+            }
+        }
+        Ok(if local {
+            Coordinates::Local(world.map(|c| c.value))
+        } else {
+            Coordinates::World(world)
+        })
+    }
+}
@@ -0,0 +1,312 @@
+use super::reader::{ArgumentValue, CommandError, CommandErrorKind, StringReader};
+use super::{CommandArgument, CommandNode, Suggestions};
+use crate::clientbound::game::{CommandSuggestions, Commands, SuggestionEntry};
+use crate::serverbound::game::CommandSuggestion;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use haya_collection::List;
+use mser::{Utf8, V21};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug)]
+enum NodeKind<'a> {
+    Root,
+    Literal(Box<str>),
+    Argument(Box<str>, CommandArgument<'a>),
+}
+
+#[derive(Clone, Debug)]
+struct Node<'a, C> {
+    kind: NodeKind<'a>,
+    children: Vec<NodeId>,
+    redirect: Option<NodeId>,
+    command: Option<C>,
+    permission: u8,
+    suggestions: Option<Suggestions>,
+}
+
+/// A Brigadier command tree whose executable nodes carry a `C`.
+#[derive(Clone, Debug)]
+pub struct CommandTree<'a, C> {
+    nodes: Vec<Node<'a, C>>,
+}
+
+/// A command matched by [`CommandTree::parse`].
+#[derive(Clone, Debug)]
+pub struct ParsedCommand<'t, 's, C> {
+    pub command: &'t C,
+    pub arguments: Vec<(&'t str, ArgumentValue<'s>)>,
+}
+
+impl<'s, C> ParsedCommand<'_, 's, C> {
+    pub fn get(&self, name: &str) -> Option<&ArgumentValue<'s>> {
+        self.arguments.iter().find(|x| x.0 == name).map(|x| &x.1)
+    }
+}
+
+impl<C> Default for CommandTree<'_, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C> CommandTree<'a, C> {
+    pub const ROOT: NodeId = NodeId(0);
+
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                kind: NodeKind::Root,
+                children: Vec::new(),
+                redirect: None,
+                command: None,
+                permission: 0,
+                suggestions: None,
+            }],
+        }
+    }
+
+    fn push(&mut self, parent: NodeId, kind: NodeKind<'a>) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            kind,
+            children: Vec::new(),
+            redirect: None,
+            command: None,
+            permission: 0,
+            suggestions: None,
+        });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    /// Adds a literal under `parent`, or returns the existing one of the
+    /// same name.
+    pub fn literal(&mut self, parent: NodeId, name: &str) -> NodeId {
+        let existing = self.nodes[parent.0]
+            .children
+            .iter()
+            .find(|c| matches!(&self.nodes[c.0].kind, NodeKind::Literal(n) if &**n == name));
+        match existing {
+            Some(&id) => id,
+            None => self.push(parent, NodeKind::Literal(name.into())),
+        }
+    }
+
+    pub fn argument(&mut self, parent: NodeId, name: &str, ty: CommandArgument<'a>) -> NodeId {
+        self.push(parent, NodeKind::Argument(name.into(), ty))
+    }
+
+    /// Makes the command end at `node` run `command`.
+    pub fn executes(&mut self, node: NodeId, command: C) -> &mut Self {
+        self.nodes[node.0].command = Some(command);
+        self
+    }
+
+    /// Continues parsing after `node` with the children of `target`.
+    pub fn redirect(&mut self, node: NodeId, target: NodeId) -> &mut Self {
+        self.nodes[node.0].redirect = Some(target);
+        self
+    }
+
+    /// Hides `node` and its children from sources below `permission`.
+    pub fn requires(&mut self, node: NodeId, permission: u8) -> &mut Self {
+        self.nodes[node.0].permission = permission;
+        self
+    }
+
+    /// Sets the client side suggestions of an argument node.
+    pub fn suggests(&mut self, node: NodeId, suggestions: Suggestions) -> &mut Self {
+        self.nodes[node.0].suggestions = Some(suggestions);
+        self
+    }
+
+    fn children(&self, node: NodeId, permission: u8) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes[node.0]
+            .children
+            .iter()
+            .copied()
+            .filter(move |c| self.nodes[c.0].permission <= permission)
+    }
+
+    /// The nodes visible at `permission`, flattened for the `Commands`
+    /// packet.
+    pub fn commands(&self, permission: u8) -> Commands<'_> {
+        let mut index = vec![None; self.nodes.len()];
+        let mut order = vec![Self::ROOT];
+        index[0] = Some(0);
+        let mut i = 0;
+        while let Some(&node) = order.get(i) {
+            for c in self.children(node, permission) {
+                if index[c.0].is_none() {
+                    index[c.0] = Some(order.len() as u32);
+                    order.push(c);
+                }
+            }
+            i += 1;
+        }
+        let entries = order
+            .iter()
+            .map(|&id| {
+                let node = &self.nodes[id.0];
+                let children = List::Owned(
+                    self.children(id, permission)
+                        .filter_map(|c| index[c.0].map(V21))
+                        .collect(),
+                );
+                let redirect = node.redirect.and_then(|r| index[r.0]).map(V21);
+                let executable = node.command.is_some();
+                let restricted = node.permission > 0;
+                match &node.kind {
+                    NodeKind::Root => CommandNode::Root { children, redirect },
+                    NodeKind::Literal(name) => CommandNode::Literal {
+                        children,
+                        redirect,
+                        name: Utf8(name),
+                        executable,
+                        restricted,
+                    },
+                    NodeKind::Argument(name, ty) => CommandNode::Argument {
+                        children,
+                        redirect,
+                        name: Utf8(name),
+                        executable,
+                        restricted,
+                        arg_type: ty.clone(),
+                        suggestions: node.suggestions,
+                    },
+                }
+            })
+            .collect();
+        Commands {
+            entries: List::Owned(entries),
+            root_index: V21(0),
+        }
+    }
+
+    /// Tries the children of `node` on the next word, literals first.
+    fn step<'s>(
+        &self,
+        node: NodeId,
+        permission: u8,
+        reader: &mut StringReader<'s>,
+    ) -> Result<(NodeId, Option<ArgumentValue<'s>>), CommandError> {
+        let start = *reader;
+        let word = start.remaining().split(' ').next().unwrap_or_default();
+        let mut error = None;
+        for c in self.children(node, permission) {
+            if let NodeKind::Literal(name) = &self.nodes[c.0].kind
+                && &**name == word
+            {
+                reader.set_cursor(start.cursor() + word.len());
+                return Ok((c, None));
+            }
+        }
+        for c in self.children(node, permission) {
+            let NodeKind::Argument(_, ty) = &self.nodes[c.0].kind else {
+                continue;
+            };
+            *reader = start;
+            match reader.read_argument(ty) {
+                Ok(value) if matches!(reader.peek(), None | Some(b' ')) => {
+                    return Ok((c, Some(value)));
+                }
+                Ok(_) => {
+                    error.get_or_insert(reader.error(CommandErrorKind::ExpectedSeparator));
+                }
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        *reader = start;
+        Err(error.unwrap_or_else(|| {
+            reader.error(if node == Self::ROOT {
+                CommandErrorKind::UnknownCommand
+            } else {
+                CommandErrorKind::UnknownArgument
+            })
+        }))
+    }
+
+    /// Parses a command without its leading `/`, as in `ChatCommand`.
+    pub fn parse<'s>(
+        &self,
+        input: &'s str,
+        permission: u8,
+    ) -> Result<ParsedCommand<'_, 's, C>, CommandError> {
+        let mut reader = StringReader::new(input);
+        let mut arguments = Vec::new();
+        let mut last = Self::ROOT;
+        let mut node = Self::ROOT;
+        while reader.can_read() {
+            if last != Self::ROOT {
+                reader.expect(b' ')?;
+            }
+            let (child, parsed) = self.step(node, permission, &mut reader)?;
+            if let (Some(value), NodeKind::Argument(name, _)) = (parsed, &self.nodes[child.0].kind)
+            {
+                arguments.push((&**name, value));
+            }
+            last = child;
+            node = self.nodes[child.0].redirect.unwrap_or(child);
+        }
+        match &self.nodes[last.0].command {
+            Some(command) => Ok(ParsedCommand { command, arguments }),
+            None => Err(reader.error(CommandErrorKind::IncompleteCommand)),
+        }
+    }
+
+    /// Completes the last word of a `CommandSuggestion` request with
+    /// literals and booleans. Arguments asking the server get nothing.
+    /// `start` and `length` count UTF-16 code units, like the client.
+    pub fn suggest<'t>(
+        &'t self,
+        request: &CommandSuggestion,
+        permission: u8,
+    ) -> CommandSuggestions<'t> {
+        let text = request.command.0;
+        let offset = usize::from(text.starts_with('/'));
+        let mut reader = StringReader::new(&text[offset..]);
+        let mut node = Self::ROOT;
+        loop {
+            let mut next = reader;
+            match self.step(node, permission, &mut next) {
+                Ok((child, _)) if next.peek() == Some(b' ') => {
+                    next.set_cursor(next.cursor() + 1);
+                    reader = next;
+                    node = self.nodes[child.0].redirect.unwrap_or(child);
+                }
+                _ => break,
+            }
+        }
+        let partial = reader.remaining();
+        let mut suggestions = Vec::new();
+        for c in self.children(node, permission) {
+            let names: [&str; 2] = match &self.nodes[c.0].kind {
+                NodeKind::Literal(name) => [name, ""],
+                NodeKind::Argument(_, CommandArgument::Bool) => ["true", "false"],
+                _ => ["", ""],
+            };
+            for name in names {
+                if !name.is_empty() && name.starts_with(partial) {
+                    suggestions.push(SuggestionEntry {
+                        text: Utf8(name),
+                        tooltip: None,
+                    });
+                }
+            }
+        }
+        suggestions.sort_by(|a, b| a.text.0.cmp(b.text.0));
+        let start = &text[..offset + reader.cursor()];
+        CommandSuggestions {
+            id: request.id,
+            start: start.encode_utf16().count() as u32,
+            length: partial.encode_utf16().count() as u32,
+            suggestions: List::Owned(suggestions),
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use crate::codec::FrameEncoder;
+use crate::serverbound::handshake::{ClientIntent, Intention};
+use crate::types::{HandleError, Id};
+use crate::{clientbound, serverbound};
+use core::marker::PhantomData;
+use minecraft_data::{
+    clientbound_configuration, clientbound_login, clientbound_play, clientbound_status,
+    serverbound_configuration, serverbound_handshake, serverbound_login, serverbound_play,
+    serverbound_status,
+};
+use mser::{Error, ErrorKind, Read, Reader, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum State {
+    Handshake,
+    Status,
+    Login,
+    Configuration,
+    Play,
+}
+
+pub trait Side {
+    /// Whether packets sent by this side are clientbound.
+    const CLIENTBOUND: bool;
+}
+
+/// The server end, receiving serverbound packets.
+#[derive(Clone, Copy, Debug)]
+pub struct Server;
+
+/// The client end, receiving clientbound packets.
+#[derive(Clone, Copy, Debug)]
+pub struct Client;
+
+impl Side for Server {
+    const CLIENTBOUND: bool = true;
+}
+
+impl Side for Client {
+    const CLIENTBOUND: bool = false;
+}
+
+pub trait ServerHandler:
+    serverbound::HandshakeHandler
+    + serverbound::StatusHandler
+    + serverbound::LoginHandler
+    + serverbound::ConfigurationHandler
+    + serverbound::GameHandler
+{
+}
+
+impl<T> ServerHandler for T where
+    T: serverbound::HandshakeHandler
+        + serverbound::StatusHandler
+        + serverbound::LoginHandler
+        + serverbound::ConfigurationHandler
+        + serverbound::GameHandler
+{
+}
+
+pub trait ClientHandler:
+    clientbound::StatusHandler
+    + clientbound::LoginHandler
+    + clientbound::ConfigurationHandler
+    + clientbound::GameHandler
+{
+}
+
+impl<T> ClientHandler for T where
+    T: clientbound::StatusHandler
+        + clientbound::LoginHandler
+        + clientbound::ConfigurationHandler
+        + clientbound::GameHandler
+{
+}
+
+/// Tracks the protocol phase of both directions of a connection.
+///
+/// Each direction switches on the packet that ends its phase. As in vanilla,
+/// the server keeps sending in the old phase until the client acknowledges
+/// the switch, so its clientbound side only enters configuration after
+/// `LoginAcknowledged`, while the client's already did after `LoginFinished`.
+#[derive(Clone, Copy, Debug)]
+pub struct Connection<S> {
+    serverbound: State,
+    clientbound: State,
+    side: PhantomData<S>,
+}
+
+impl<S: Side> Default for Connection<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Side> Connection<S> {
+    pub const fn new() -> Self {
+        Self {
+            serverbound: State::Handshake,
+            clientbound: State::Handshake,
+            side: PhantomData,
+        }
+    }
+
+    /// Phase of packets received by this side.
+    pub const fn inbound(&self) -> State {
+        if S::CLIENTBOUND {
+            self.serverbound
+        } else {
+            self.clientbound
+        }
+    }
+
+    /// Phase of packets sent by this side.
+    pub const fn outbound(&self) -> State {
+        if S::CLIENTBOUND {
+            self.clientbound
+        } else {
+            self.serverbound
+        }
+    }
+
+    /// Checks a received packet body against the inbound phase and advances it.
+    pub fn received(&mut self, body: &[u8]) -> Result<(), Error> {
+        if S::CLIENTBOUND {
+            self.serverbound(body)
+        } else {
+            self.clientbound(body)
+        }
+    }
+
+    /// Checks a packet body about to be sent against the outbound phase and
+    /// advances it.
+    pub fn sent(&mut self, body: &[u8]) -> Result<(), Error> {
+        if S::CLIENTBOUND {
+            self.clientbound(body)
+        } else {
+            self.serverbound(body)
+        }
+    }
+
+    /// Encodes `packet` into `encoder` if it is valid in the outbound phase.
+    pub fn send<P: Id + Write>(
+        &mut self,
+        packet: &P,
+        encoder: &mut FrameEncoder,
+    ) -> Result<(), Error> {
+        encoder.encode_checked(packet, |body| self.sent(body))
+    }
+
+    fn serverbound(&mut self, body: &[u8]) -> Result<(), Error> {
+        let mut packet = Reader::new(body);
+        match self.serverbound {
+            State::Handshake => {
+                let _ = serverbound_handshake::read(&mut packet)?;
+                let next = intent_state(Intention::read(&mut packet)?.intention);
+                self.serverbound = next;
+                self.clientbound = next;
+            }
+            State::Status => {
+                let _ = serverbound_status::read(&mut packet)?;
+            }
+            State::Login => {
+                if serverbound_login::read(&mut packet)? == serverbound_login::login_acknowledged {
+                    self.acknowledged(State::Configuration);
+                }
+            }
+            State::Configuration => {
+                if serverbound_configuration::read(&mut packet)?
+                    == serverbound_configuration::finish_configuration
+                {
+                    self.acknowledged(State::Play);
+                }
+            }
+            State::Play => {
+                if serverbound_play::read(&mut packet)?
+                    == serverbound_play::configuration_acknowledged
+                {
+                    self.acknowledged(State::Configuration);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn clientbound(&mut self, body: &[u8]) -> Result<(), Error> {
+        let mut packet = Reader::new(body);
+        match self.clientbound {
+            State::Handshake => return Err(Error::new(ErrorKind::Invalid)),
+            State::Status => {
+                let _ = clientbound_status::read(&mut packet)?;
+            }
+            State::Login => {
+                if clientbound_login::read(&mut packet)? == clientbound_login::login_finished
+                    && !S::CLIENTBOUND
+                {
+                    self.clientbound = State::Configuration;
+                }
+            }
+            State::Configuration => {
+                if clientbound_configuration::read(&mut packet)?
+                    == clientbound_configuration::finish_configuration
+                    && !S::CLIENTBOUND
+                {
+                    self.clientbound = State::Play;
+                }
+            }
+            State::Play => {
+                if clientbound_play::read(&mut packet)? == clientbound_play::start_configuration
+                    && !S::CLIENTBOUND
+                {
+                    self.clientbound = State::Configuration;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Switches the serverbound phase on an acknowledgement from the client,
+    /// which is also when the server starts sending in the new phase.
+    fn acknowledged(&mut self, next: State) {
+        self.serverbound = next;
+        if S::CLIENTBOUND {
+            self.clientbound = next;
+        }
+    }
+}
+
+impl Connection<Server> {
+    /// Dispatches a serverbound packet to the handler of the inbound phase.
+    pub fn receive<H: ServerHandler + ?Sized>(
+        &mut self,
+        mut packet: Reader<'_>,
+        handler: &mut H,
+    ) -> Result<(), HandleError> {
+        let body = packet.peek_slice(packet.len())?;
+        match self.serverbound {
+            State::Handshake => {
+                let p = decode::<serverbound::ServerboundHandshakePacket>(&mut packet)?;
+                let serverbound::ServerboundHandshakePacket::intention(intention) = &p;
+                let next = intent_state(intention.intention);
+                serverbound::HandshakeHandler::dispatch(handler, p, body)?;
+                self.serverbound = next;
+                self.clientbound = next;
+            }
+            State::Status => {
+                let p = decode(&mut packet)?;
+                serverbound::StatusHandler::dispatch(handler, p, body)?;
+            }
+            State::Login => {
+                let p = decode::<serverbound::ServerboundLoginPacket>(&mut packet)?;
+                let done = p.id() == serverbound_login::login_acknowledged;
+                serverbound::LoginHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.acknowledged(State::Configuration);
+                }
+            }
+            State::Configuration => {
+                let p = decode::<serverbound::ServerboundConfigurationPacket>(&mut packet)?;
+                let done = p.id() == serverbound_configuration::finish_configuration;
+                serverbound::ConfigurationHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.acknowledged(State::Play);
+                }
+            }
+            State::Play => {
+                let p = decode::<serverbound::ServerboundPlayPacket>(&mut packet)?;
+                let done = p.id() == serverbound_play::configuration_acknowledged;
+                serverbound::GameHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.acknowledged(State::Configuration);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Connection<Client> {
+    /// Dispatches a clientbound packet to the handler of the inbound phase.
+    pub fn receive<H: ClientHandler + ?Sized>(
+        &mut self,
+        mut packet: Reader<'_>,
+        handler: &mut H,
+    ) -> Result<(), HandleError> {
+        let body = packet.peek_slice(packet.len())?;
+        match self.clientbound {
+            State::Handshake => return Err(Error::new(ErrorKind::Invalid).into()),
+            State::Status => {
+                let p = decode(&mut packet)?;
+                clientbound::StatusHandler::dispatch(handler, p, body)?;
+            }
+            State::Login => {
+                let p = decode::<clientbound::ClientboundLoginPacket>(&mut packet)?;
+                let done = p.id() == clientbound_login::login_finished;
+                clientbound::LoginHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.clientbound = State::Configuration;
+                }
+            }
+            State::Configuration => {
+                let p = decode::<clientbound::ClientboundConfigurationPacket>(&mut packet)?;
+                let done = p.id() == clientbound_configuration::finish_configuration;
+                clientbound::ConfigurationHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.clientbound = State::Play;
+                }
+            }
+            State::Play => {
+                let p = decode::<clientbound::ClientboundPlayPacket>(&mut packet)?;
+                let done = p.id() == clientbound_play::start_configuration;
+                clientbound::GameHandler::dispatch(handler, p, body)?;
+                if done {
+                    self.clientbound = State::Configuration;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a whole packet body.
+fn decode<'a, T: Read<'a>>(packet: &mut Reader<'a>) -> Result<T, Error> {
+    let p = T::read(packet)?;
+    packet.finish()?;
+    Ok(p)
+}
+
+const fn intent_state(intent: ClientIntent) -> State {
+    match intent {
+        ClientIntent::Status => State::Status,
+        ClientIntent::Login | ClientIntent::Transfer => State::Login,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clientbound::common::{GameKeepAlive, KeepAlive};
+    use crate::serverbound::login::LoginAcknowledged;
+    use crate::types::encode_packet;
+    use alloc::vec::Vec;
+    use mser::{Utf8, V32};
+
+    #[test]
+    fn test_phases() {
+        let mut server = Connection::<Server>::new();
+        let mut client = Connection::<Client>::new();
+        let mut encoder = FrameEncoder::new();
+
+        client
+            .send(
+                &Intention {
+                    protocol_version: V32(773),
+                    host_name: Utf8("localhost"),
+                    port: 25565,
+                    intention: ClientIntent::Login,
+                },
+                &mut encoder,
+            )
+            .unwrap();
+        assert_eq!(client.outbound(), State::Login);
+        assert_eq!(client.inbound(), State::Login);
+
+        let intention = [0, 0x85, 0x06, 1, b'a', 0x63, 0xdd, 2];
+        server.received(&intention).unwrap();
+        assert_eq!(server.inbound(), State::Login);
+        assert_eq!(server.outbound(), State::Login);
+
+        // login_finished with an empty profile.
+        let finished = [2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        server.sent(&finished).unwrap();
+        assert_eq!(server.outbound(), State::Login);
+        assert_eq!(server.inbound(), State::Login);
+        client.received(&finished).unwrap();
+        assert_eq!(client.inbound(), State::Configuration);
+
+        client.send(&LoginAcknowledged {}, &mut encoder).unwrap();
+        assert_eq!(client.outbound(), State::Configuration);
+        server
+            .received(&[serverbound_login::login_acknowledged as u8])
+            .unwrap();
+        assert_eq!(server.inbound(), State::Configuration);
+        assert_eq!(server.outbound(), State::Configuration);
+        assert!(server.received(&[0x7f]).is_err());
+
+        let finish = [clientbound_configuration::finish_configuration as u8];
+        server.sent(&finish).unwrap();
+        assert_eq!(server.outbound(), State::Configuration);
+        client.received(&finish).unwrap();
+        let finish = [serverbound_configuration::finish_configuration as u8];
+        client.sent(&finish).unwrap();
+        server.received(&finish).unwrap();
+        assert_eq!(server.inbound(), State::Play);
+        assert_eq!(server.outbound(), State::Play);
+        assert_eq!(client.inbound(), State::Play);
+
+        // start_configuration, then configuration_acknowledged.
+        let start = [clientbound_play::start_configuration as u8];
+        let ack = [serverbound_play::configuration_acknowledged as u8];
+        server.sent(&start).unwrap();
+        assert_eq!(server.outbound(), State::Play);
+        client.received(&start).unwrap();
+        assert_eq!(client.inbound(), State::Configuration);
+        client.sent(&ack).unwrap();
+        server.received(&ack).unwrap();
+        assert_eq!(server.inbound(), State::Configuration);
+        assert_eq!(server.outbound(), State::Configuration);
+
+        let mut status = Connection::<Server>::new();
+        assert!(status.sent(&[0]).is_err());
+        status.received(&[0, 0, 0, 0, 0, 1]).unwrap();
+        assert_eq!(status.inbound(), State::Status);
+        assert!(status.received(&[0x7f]).is_err());
+    }
+
+    #[derive(Default)]
+    struct Bot {
+        keep_alive: u64,
+        unhandled: Vec<clientbound_play>,
+    }
+
+    impl clientbound::StatusHandler for Bot {}
+    impl clientbound::LoginHandler for Bot {}
+    impl clientbound::ConfigurationHandler for Bot {}
+    impl clientbound::GameHandler for Bot {
+        fn keep_alive(&mut self, packet: GameKeepAlive) -> Result<(), HandleError> {
+            if packet.0.id == 0 {
+                return Err(HandleError::disconnect("zero keep alive"));
+            }
+            self.keep_alive = packet.0.id;
+            Ok(())
+        }
+
+        fn unhandled(&mut self, id: clientbound_play, raw: &[u8]) -> Result<(), HandleError> {
+            assert!(raw.is_empty());
+            self.unhandled.push(id);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_handler() {
+        let mut client = Connection::<Client>::new();
+        let mut bot = Bot::default();
+        let mut keep_alive = Vec::new();
+        encode_packet(&GameKeepAlive(KeepAlive { id: 7 }), &mut keep_alive);
+        assert!(client.receive(Reader::new(&keep_alive), &mut bot).is_err());
+        client.clientbound = State::Play;
+        client.receive(Reader::new(&keep_alive), &mut bot).unwrap();
+        assert_eq!(bot.keep_alive, 7);
+
+        keep_alive.clear();
+        encode_packet(&GameKeepAlive(KeepAlive { id: 0 }), &mut keep_alive);
+        let e = client
+            .receive(Reader::new(&keep_alive), &mut bot)
+            .unwrap_err();
+        assert_eq!(e.reason(), Some("zero keep alive"));
+
+        let start = [clientbound_play::start_configuration as u8];
+        client.receive(Reader::new(&start), &mut bot).unwrap();
+        assert_eq!(bot.unhandled, [clientbound_play::start_configuration]);
+        assert_eq!(client.inbound(), State::Configuration);
+    }
+}
@@ -1,13 +1,13 @@
 use haya_collection::Cow;
 use minecraft_data::mob_effect;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MobEffect<'a> {
     pub effect: mob_effect,
     pub details: MobEffectDetails<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MobEffectDetails<'a> {
     #[mser(varint)]
     pub amplifier: u32,
@@ -4,7 +4,7 @@ use minecraft_data::{villager_profession, villager_type};
 use mser::{Read, V21, Write};
 use uuid::Uuid;
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum FoxVariant {
@@ -21,7 +21,7 @@ impl FoxVariant {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum SalmonVariant {
@@ -40,7 +40,7 @@ impl SalmonVariant {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum ParrotVariant {
@@ -63,7 +63,7 @@ impl ParrotVariant {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum TropicalFishPattern {
@@ -100,7 +100,7 @@ impl TropicalFishPattern {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum MushroomCowVariant {
@@ -117,7 +117,7 @@ impl MushroomCowVariant {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Skip)]
 #[repr(u8)]
 pub enum RabbitVariant {
     Brown,
@@ -167,7 +167,7 @@ impl RabbitVariant {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum EquineVariant {
@@ -194,7 +194,7 @@ impl EquineVariant {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PaintingVariant<'a> {
     #[mser(varint)]
     pub width: u32,
@@ -205,7 +205,7 @@ pub struct PaintingVariant<'a> {
     pub author: Option<ComponentRaw>,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum LlamaVariant {
@@ -226,7 +226,7 @@ impl LlamaVariant {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum AxolotlVariant {
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct FoodProperties {
     #[mser(varint)]
     pub nutrition: u32,
@@ -1,3 +1,5 @@
+pub mod container;
+
 use crate::Translatable;
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -21,10 +23,10 @@ impl HumanoidArm {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, Serialize, Deserialize, Skip)]
 pub struct ContainerId(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum EquipmentSlotGroup {
@@ -59,7 +61,7 @@ impl EquipmentSlotGroup {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum EquipmentSlot {
@@ -0,0 +1,549 @@
+use super::ContainerId;
+use crate::clientbound::game::{ContainerSetContent, ContainerSetSlot, SetCursorItem};
+use crate::item_stack::hash::RegistryLookup;
+use crate::item_stack::{DataComponentPatch, ItemStack, OptionalItemStack, TypedDataComponent};
+use crate::serverbound::game::ContainerClick;
+use crate::{ClickType, HashedStack};
+use alloc::vec::Vec;
+use haya_collection::List;
+use minecraft_data::{data_component_type, item};
+
+/// The slot of clicks outside the window.
+const SLOT_OUTSIDE: i16 = -999;
+const STATE_ID_MASK: u32 = 32767;
+const INVENTORY_SIZE: usize = 36;
+const HOTBAR_SIZE: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickError {
+    /// The click targets a container that is not open.
+    WrongContainer,
+    InvalidSlot,
+}
+
+/// A packet correcting the client after [`Container::changes`].
+#[derive(Clone)]
+pub enum ContainerSync<'a> {
+    Content(ContainerSetContent<'a>),
+    Slot(ContainerSetSlot<'a>),
+    Cursor(SetCursorItem<'a>),
+}
+
+/// What the client is believed to hold in a slot.
+#[derive(Clone)]
+enum RemoteSlot<'a> {
+    Unknown,
+    Known(Option<ItemStack<'a>>),
+}
+
+#[derive(Clone, Default)]
+struct QuickCraft {
+    status: u8,
+    ty: u8,
+    slots: Vec<usize>,
+}
+
+/// The slots of an open window and the stack on the cursor, simulating
+/// vanilla clicks.
+///
+/// Slots from `inventory_start` hold the 27 slots of the player inventory
+/// followed by the hotbar. Every slot accepts any item, so only plain
+/// chest-style menus are covered: the inventory menu, with its armor and
+/// crafting slots, is not. The offhand is outside the menu, so a swap with
+/// it is refused and the next [`changes`](Self::changes) resends the whole
+/// content.
+#[derive(Clone)]
+pub struct Container<'a> {
+    pub container_id: ContainerId,
+    state_id: u32,
+    slots: Vec<Option<ItemStack<'a>>>,
+    carried: Option<ItemStack<'a>>,
+    inventory_start: usize,
+    remote_slots: Vec<RemoteSlot<'a>>,
+    remote_carried: RemoteSlot<'a>,
+    resync: bool,
+    quick_craft: QuickCraft,
+}
+
+impl<'a> Container<'a> {
+    /// An empty container of `inventory_start` slots followed by the
+    /// player inventory. The first [`changes`](Self::changes) sends the
+    /// whole content.
+    pub fn new(container_id: ContainerId, inventory_start: usize) -> Self {
+        let len = inventory_start + INVENTORY_SIZE;
+        Self {
+            container_id,
+            state_id: 0,
+            slots: (0..len).map(|_| None).collect(),
+            carried: None,
+            inventory_start,
+            remote_slots: (0..len).map(|_| RemoteSlot::Unknown).collect(),
+            remote_carried: RemoteSlot::Unknown,
+            resync: true,
+            quick_craft: QuickCraft::default(),
+        }
+    }
+
+    pub const fn state_id(&self) -> u32 {
+        self.state_id
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack<'a>>] {
+        &self.slots
+    }
+
+    pub fn slot(&self, index: usize) -> Option<&ItemStack<'a>> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    pub fn set_slot(&mut self, index: usize, stack: Option<ItemStack<'a>>) {
+        self.slots[index] = stack;
+    }
+
+    pub fn carried(&self) -> Option<&ItemStack<'a>> {
+        self.carried.as_ref()
+    }
+
+    pub fn set_carried(&mut self, stack: Option<ItemStack<'a>>) {
+        self.carried = stack;
+    }
+
+    /// Sends the whole content on the next [`changes`](Self::changes).
+    pub fn resync(&mut self) {
+        self.resync = true;
+    }
+
+    /// Applies a click of a player, returning the stacks it dropped.
+    ///
+    /// The slots the client predicted in `changed_slots` are compared with
+    /// the result, so that [`changes`](Self::changes) corrects them. A
+    /// stale `state_id` resends everything.
+    pub fn click(
+        &mut self,
+        packet: &ContainerClick,
+        creative: bool,
+        registries: &dyn RegistryLookup,
+    ) -> Result<Vec<ItemStack<'a>>, ClickError> {
+        if packet.container_id.0 != self.container_id.0 {
+            return Err(ClickError::WrongContainer);
+        }
+        let slot = packet.slot_num as i16;
+        let index = usize::try_from(slot).ok();
+        if index.is_some_and(|i| i >= self.slots.len()) || (slot < -1 && slot != SLOT_OUTSIDE) {
+            return Err(ClickError::InvalidSlot);
+        }
+        if packet.state_id != self.state_id {
+            self.resync = true;
+        }
+        let mut dropped = Vec::new();
+        match packet.click_type {
+            ClickType::QuickCraft => {
+                self.quick_craft(index, packet.button_num, creative);
+            }
+            _ if self.quick_craft.status != 0 => self.quick_craft = QuickCraft::default(),
+            ClickType::Pickup | ClickType::QuickMove if packet.button_num > 1 => {}
+            ClickType::Pickup if slot == SLOT_OUTSIDE => {
+                if packet.button_num == 0 {
+                    dropped.extend(self.carried.take());
+                } else {
+                    dropped.extend(split(&mut self.carried, 1));
+                }
+            }
+            ClickType::Pickup => {
+                if let Some(i) = index {
+                    self.pickup(i, packet.button_num);
+                }
+            }
+            ClickType::QuickMove => {
+                if let Some(i) = index {
+                    self.quick_move(i);
+                }
+            }
+            ClickType::Swap => {
+                let target = match packet.button_num {
+                    b @ 0..9 => {
+                        Some(self.inventory_start + INVENTORY_SIZE - HOTBAR_SIZE + b as usize)
+                    }
+                    _ => None,
+                };
+                match (index, target) {
+                    (Some(i), Some(j)) => self.slots.swap(i, j),
+                    // The client moved the stack into or out of its offhand.
+                    (Some(_), None) if packet.button_num == 40 => self.resync = true,
+                    _ => {}
+                }
+            }
+            ClickType::Clone => {
+                if let Some(i) = index
+                    && creative
+                    && self.carried.is_none()
+                    && let Some(stack) = &self.slots[i]
+                {
+                    let mut copy = stack.clone();
+                    copy.count = max_stack_size(stack);
+                    self.carried = Some(copy);
+                }
+            }
+            ClickType::Throw => {
+                if let Some(i) = index
+                    && self.carried.is_none()
+                {
+                    let n = match &self.slots[i] {
+                        Some(stack) if packet.button_num != 0 => stack.count,
+                        _ => 1,
+                    };
+                    dropped.extend(split(&mut self.slots[i], n));
+                }
+            }
+            ClickType::PickupAll => {
+                if let Some(i) = index
+                    && self.slots[i].is_none()
+                {
+                    self.pickup_all(packet.button_num == 0);
+                }
+            }
+        }
+        for (n, reported) in packet.changed_slots.0.iter() {
+            if let Some(remote) = self.remote_slots.get_mut(*n as usize) {
+                *remote = remote_slot(reported, &self.slots[*n as usize], registries);
+            }
+        }
+        self.remote_carried = remote_slot(&packet.carried_item, &self.carried, registries);
+        Ok(dropped)
+    }
+
+    fn pickup(&mut self, i: usize, button: u8) {
+        let slot = &mut self.slots[i];
+        match (slot.as_mut(), self.carried.as_mut()) {
+            (None, Some(carried)) => {
+                let wanted = if button == 0 { carried.count } else { 1 };
+                let n = wanted.min(max_stack_size(carried));
+                *slot = split(&mut self.carried, n);
+            }
+            (Some(stack), None) => {
+                let n = if button == 0 {
+                    stack.count
+                } else {
+                    (stack.count + 1) / 2
+                };
+                self.carried = split(slot, n);
+            }
+            (Some(stack), Some(carried)) if same_item_same_components(stack, carried) => {
+                let wanted = if button == 0 { carried.count } else { 1 };
+                let n = wanted.min(max_stack_size(stack) - stack.count).max(0);
+                stack.count += n;
+                shrink(&mut self.carried, n);
+            }
+            (Some(_), Some(carried)) => {
+                if carried.count <= max_stack_size(carried) {
+                    core::mem::swap(slot, &mut self.carried);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn quick_move(&mut self, i: usize) {
+        let hotbar = self.inventory_start + INVENTORY_SIZE - HOTBAR_SIZE;
+        let inventory = self.inventory_start..self.inventory_start + INVENTORY_SIZE;
+        let (range, reverse) = if !inventory.contains(&i) {
+            (inventory, true)
+        } else if self.inventory_start != 0 {
+            (0..self.inventory_start, false)
+        } else if i < hotbar {
+            (hotbar..inventory.end, false)
+        } else {
+            (inventory.start..hotbar, false)
+        };
+        let mut stack = self.slots[i].take();
+        self.move_to(&mut stack, range, reverse);
+        self.slots[i] = stack;
+    }
+
+    /// Merges `stack` into the stacks of `range`, then into its first
+    /// empty slot.
+    fn move_to(
+        &mut self,
+        stack: &mut Option<ItemStack<'a>>,
+        range: core::ops::Range<usize>,
+        reverse: bool,
+    ) {
+        let order = |r: core::ops::Range<usize>| -> Vec<usize> {
+            if reverse {
+                r.rev().collect()
+            } else {
+                r.collect()
+            }
+        };
+        for j in order(range.clone()) {
+            let (Some(s), Some(target)) = (stack.as_mut(), self.slots[j].as_mut()) else {
+                continue;
+            };
+            if same_item_same_components(s, target) {
+                let n = s.count.min(max_stack_size(target) - target.count).max(0);
+                target.count += n;
+                shrink(stack, n);
+            }
+        }
+        if let Some(s) = stack
+            && let Some(j) = order(range).into_iter().find(|&j| self.slots[j].is_none())
+        {
+            let n = s.count.min(max_stack_size(s));
+            self.slots[j] = split(stack, n);
+        }
+    }
+
+    fn pickup_all(&mut self, forward: bool) {
+        let Some(carried) = &mut self.carried else {
+            return;
+        };
+        let max = max_stack_size(carried);
+        let order: Vec<usize> = if forward {
+            (0..self.slots.len()).collect()
+        } else {
+            (0..self.slots.len()).rev().collect()
+        };
+        // Full stacks are only taken once the partial ones ran out.
+        for pass in 0..2 {
+            for &j in &order {
+                if carried.count >= max {
+                    return;
+                }
+                let Some(stack) = &self.slots[j] else {
+                    continue;
+                };
+                if same_item_same_components(stack, carried)
+                    && (pass != 0 || stack.count != max_stack_size(stack))
+                {
+                    let n = stack.count.min(max - carried.count);
+                    carried.count += n;
+                    shrink(&mut self.slots[j], n);
+                }
+            }
+        }
+    }
+
+    /// The drag of a stack over slots, started, continued and ended by the
+    /// header in the low bits of `button`.
+    fn quick_craft(&mut self, index: Option<usize>, button: u8, creative: bool) {
+        let previous = self.quick_craft.status;
+        let status = button & 3;
+        if (previous != 1 || status != 2) && previous != status {
+            self.quick_craft = QuickCraft::default();
+            return;
+        }
+        let Some(carried) = &self.carried else {
+            self.quick_craft = QuickCraft::default();
+            return;
+        };
+        match status {
+            0 => {
+                let ty = (button >> 2) & 3;
+                self.quick_craft = if ty < 2 || ty == 2 && creative {
+                    QuickCraft {
+                        status: 1,
+                        ty,
+                        slots: Vec::new(),
+                    }
+                } else {
+                    QuickCraft::default()
+                };
+            }
+            1 => {
+                let qc = &mut self.quick_craft;
+                if let Some(i) = index
+                    && can_quick_replace(&self.slots[i], carried)
+                    && (qc.ty == 2 || carried.count as usize > qc.slots.len())
+                    && !qc.slots.contains(&i)
+                {
+                    qc.slots.push(i);
+                }
+            }
+            _ => {
+                let QuickCraft { ty, slots, .. } = core::mem::take(&mut self.quick_craft);
+                match slots[..] {
+                    [] => return,
+                    [i] => {
+                        if ty < 2 {
+                            self.pickup(i, ty);
+                        }
+                        return;
+                    }
+                    _ => {}
+                }
+                let count = slots.len();
+                let mut remaining = carried.clone();
+                let max = max_stack_size(carried);
+                let each = match ty {
+                    0 => carried.count / count as i32,
+                    1 => 1,
+                    _ => max,
+                };
+                for i in slots {
+                    if can_quick_replace(&self.slots[i], carried)
+                        && (ty == 2 || carried.count as usize >= count)
+                    {
+                        let existing = self.slots[i].as_ref().map_or(0, |s| s.count);
+                        let n = (each + existing).min(max);
+                        remaining.count -= n - existing;
+                        let mut placed = carried.clone();
+                        placed.count = n;
+                        self.slots[i] = Some(placed);
+                    }
+                }
+                self.carried = (remaining.count > 0).then_some(remaining);
+            }
+        }
+    }
+
+    /// The packets bringing the client in line with the container.
+    pub fn changes(&mut self) -> Vec<ContainerSync<'_>> {
+        if self.resync {
+            self.resync = false;
+            self.state_id = (self.state_id + 1) & STATE_ID_MASK;
+            for (remote, slot) in self.remote_slots.iter_mut().zip(&self.slots) {
+                *remote = RemoteSlot::Known(slot.clone());
+            }
+            self.remote_carried = RemoteSlot::Known(self.carried.clone());
+            return alloc::vec![ContainerSync::Content(ContainerSetContent {
+                container_id: self.container_id,
+                state_id: self.state_id,
+                items: List::Owned(self.slots.iter().map(optional).collect()),
+                carried_item: optional(&self.carried),
+            })];
+        }
+        let mut out = Vec::new();
+        for (i, slot) in self.slots.iter().enumerate() {
+            if !is_known(&self.remote_slots[i], slot) {
+                self.remote_slots[i] = RemoteSlot::Known(slot.clone());
+                self.state_id = (self.state_id + 1) & STATE_ID_MASK;
+                out.push(ContainerSync::Slot(ContainerSetSlot {
+                    container_id: self.container_id,
+                    state_id: self.state_id,
+                    slot: i as u16,
+                    item_stack: optional(slot),
+                }));
+            }
+        }
+        if !is_known(&self.remote_carried, &self.carried) {
+            self.remote_carried = RemoteSlot::Known(self.carried.clone());
+            out.push(ContainerSync::Cursor(SetCursorItem {
+                contents: optional(&self.carried),
+            }));
+        }
+        out
+    }
+}
+
+/// The stack size limit, which the `max_stack_size` component overrides.
+pub fn max_stack_size(stack: &ItemStack) -> i32 {
+    let patch = &stack.components;
+    for c in patch.patch_add.iter() {
+        if let TypedDataComponent::MaxStackSize(n) = c {
+            return *n as i32;
+        }
+    }
+    if patch
+        .patch_remove
+        .contains(&data_component_type::max_stack_size)
+    {
+        1
+    } else {
+        stack.id.max_count() as i32
+    }
+}
+
+/// Whether `a` and `b` stack, in any component order.
+pub fn same_item_same_components(a: &ItemStack, b: &ItemStack) -> bool {
+    let (x, y) = (&a.components, &b.components);
+    a.id == b.id
+        && x.patch_add.len() == y.patch_add.len()
+        && x.patch_remove.len() == y.patch_remove.len()
+        && x.patch_remove.iter().all(|ty| y.patch_remove.contains(ty))
+        && x.patch_add.iter().all(|c| y.patch_add.contains(c))
+}
+
+fn same_stack(a: &Option<ItemStack>, b: &Option<ItemStack>) -> bool {
+    match (a, b) {
+        (Some(x), Some(y)) => x.count == y.count && same_item_same_components(x, y),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_known(remote: &RemoteSlot, slot: &Option<ItemStack>) -> bool {
+    match remote {
+        RemoteSlot::Known(x) => same_stack(x, slot),
+        RemoteSlot::Unknown => false,
+    }
+}
+
+/// The remote state of a slot the client predicted as `reported`.
+fn remote_slot<'a>(
+    reported: &Option<HashedStack>,
+    slot: &Option<ItemStack<'a>>,
+    registries: &dyn RegistryLookup,
+) -> RemoteSlot<'a> {
+    let matches = match (reported, slot) {
+        (Some(hashed), Some(stack)) => hashed.matches(stack, registries),
+        (None, None) => true,
+        _ => false,
+    };
+    if matches {
+        RemoteSlot::Known(slot.clone())
+    } else {
+        RemoteSlot::Unknown
+    }
+}
+
+fn can_quick_replace(slot: &Option<ItemStack>, stack: &ItemStack) -> bool {
+    match slot {
+        Some(s) => same_item_same_components(s, stack) && s.count <= max_stack_size(stack),
+        None => true,
+    }
+}
+
+/// Removes `n` items from `slot`, returning them.
+fn split<'a>(slot: &mut Option<ItemStack<'a>>, n: i32) -> Option<ItemStack<'a>> {
+    let stack = slot.as_mut()?;
+    if n <= 0 {
+        return None;
+    }
+    if n >= stack.count {
+        return slot.take();
+    }
+    let mut taken = stack.clone();
+    taken.count = n;
+    stack.count -= n;
+    Some(taken)
+}
+
+fn shrink(slot: &mut Option<ItemStack>, n: i32) {
+    if let Some(stack) = slot {
+        stack.count -= n;
+        if stack.count <= 0 {
+            *slot = None;
+        }
+    }
+}
+
+fn optional<'b>(stack: &'b Option<ItemStack>) -> OptionalItemStack<'b> {
+    match stack {
+        Some(s) => OptionalItemStack {
+            id: s.id,
+            count: s.count,
+            components: DataComponentPatch {
+                patch_add: List::Borrowed(&s.components.patch_add),
+                patch_remove: List::Borrowed(&s.components.patch_remove),
+            },
+        },
+        None => OptionalItemStack {
+            id: item::air,
+            count: 0,
+            components: DataComponentPatch {
+                patch_add: List::Borrowed(&[]),
+                patch_remove: List::Borrowed(&[]),
+            },
+        },
+    }
+}
@@ -1,6 +1,7 @@
 pub mod consumable;
 pub mod consume_effect;
 pub mod firework_explosion;
+pub mod hash;
 pub mod item_attribute_modifiers;
 pub mod item_enchantments;
 pub mod kinetic_weapon;
@@ -33,25 +34,24 @@ use crate::registry::{
 };
 use crate::sound::SoundEvent;
 use crate::trim::{TrimMaterial, TrimPattern};
-use crate::{ComponentRaw, DyeColor, Filterable, Holder, HolderSet, LockCode, Rarity};
+use crate::{ComponentRaw, DyeColor, Filterable, GlobalPos, Holder, HolderSet, LockCode, Rarity};
 use alloc::vec::Vec;
 use haya_collection::{List, Map, capacity_fix};
 use haya_ident::{Ident, ResourceKey, TagKey};
-use haya_math::BlockPosPacked;
 use haya_nbt::Tag;
 use minecraft_data::{
     block_entity_type, data_component_type, entity_type, item, potion, sound_event,
 };
-use mser::{Either, Error, Read, Reader, Utf8, V21, V32, Write, Writer};
+use mser::{Either, Error, ErrorKind, Read, Reader, Skip, Utf8, V21, V32, Write, Writer, skip_n};
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct ItemStack<'a> {
     pub id: item,
     pub count: i32,
     pub components: DataComponentPatch<'a>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct DataComponentPatch<'a> {
     pub patch_add: List<'a, TypedDataComponent<'a>>,
     pub patch_remove: List<'a, data_component_type>,
@@ -65,7 +65,7 @@ impl<'a> Read<'a> for ItemStack<'a> {
             components,
         } = Read::read(buf)?;
         if count <= 0 || id == item::air {
-            Err(Error)
+            Err(buf.error(ErrorKind::Invalid))
         } else {
             Ok(Self {
                 id,
@@ -99,7 +99,7 @@ impl<'a> Write for ItemStack<'a> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct OptionalItemStack<'a> {
     pub id: item,
     pub count: i32,
@@ -133,12 +133,24 @@ impl<'a> Read<'a> for OptionalItemStack<'a> {
                 })
             } else {
                 let mut patch_add = Vec::with_capacity(capacity_fix(positive));
-                for _ in 0..positive {
-                    patch_add.push(TypedDataComponent::read(buf)?);
+                for i in 0..positive {
+                    match TypedDataComponent::read(buf) {
+                        Ok(x) => patch_add.push(x),
+                        Err(e) => {
+                            return Err(e.with_index(i).with_field("ItemStack", "components"));
+                        }
+                    }
                 }
                 let mut patch_remove = Vec::with_capacity(capacity_fix(negative));
-                for _ in 0..negative {
-                    patch_remove.push(data_component_type::read(buf)?);
+                for i in 0..negative {
+                    match data_component_type::read(buf) {
+                        Ok(x) => patch_remove.push(x),
+                        Err(e) => {
+                            return Err(e
+                                .with_index(i)
+                                .with_field("ItemStack", "removed_components"));
+                        }
+                    }
                 }
                 Ok(Self {
                     id,
@@ -153,6 +165,46 @@ impl<'a> Read<'a> for OptionalItemStack<'a> {
     }
 }
 
+impl Skip for ItemStack<'_> {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let count = V32::read(buf)?.0 as i32;
+        if count <= 0 {
+            return Err(buf.error(ErrorKind::Invalid));
+        }
+        if item::read(buf)? == item::air {
+            return Err(buf.error(ErrorKind::Invalid));
+        }
+        skip_components(buf)
+    }
+}
+
+impl Skip for OptionalItemStack<'_> {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let count = V32::read(buf)?.0 as i32;
+        if count <= 0 {
+            return Ok(());
+        }
+        let _ = item::read(buf)?;
+        skip_components(buf)
+    }
+}
+
+/// Components are skipped without being decoded, the patch itself is never
+/// collected.
+fn skip_components(buf: &mut Reader<'_>) -> Result<(), Error> {
+    let positive = V32::read(buf)?.0 as usize;
+    let negative = V32::read(buf)?.0 as usize;
+    for i in 0..positive {
+        if let Err(e) = TypedDataComponent::skip(buf) {
+            return Err(e.with_index(i).with_field("ItemStack", "components"));
+        }
+    }
+    match skip_n::<data_component_type>(buf, negative) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(e.with_field("ItemStack", "removed_components")),
+    }
+}
+
 unsafe fn write_item_stack(
     id: item,
     count: i32,
@@ -228,25 +280,25 @@ impl<'a> Write for OptionalItemStack<'a> {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct UseEffects {
     pub can_sprint: bool,
     pub interact_vibrations: bool,
     pub speed_multiplier: f32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct CustomData(pub Tag);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ItemLore<'a>(pub List<'a, ComponentRaw, 256>);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct AdventureModePredicate<'a> {
     pub predicates: List<'a, BlockPredicate<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct CustomModelData<'a> {
     pub floats: List<'a, f32>,
     pub flags: List<'a, bool>,
@@ -254,36 +306,36 @@ pub struct CustomModelData<'a> {
     pub colors: List<'a, u32>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct TooltipDisplay<'a> {
     pub hide_tooltip: bool,
     pub hidden_components: List<'a, data_component_type>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct UseRemainder<'a> {
     pub convert_into: OptionalItemStack<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct UseCooldown<'a> {
     pub seconds: f32,
     pub cooldown_group: Option<Ident<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DamageResistant<'a> {
     pub types: TagKey<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Weapon {
     #[mser(varint)]
     pub item_damage_per_attack: u32,
     pub disable_blocking_for_seconds: f32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct AttackRange {
     pub min_range: f32,
     pub max_range: f32,
@@ -293,7 +345,7 @@ pub struct AttackRange {
     pub mob_factor: f32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Enchantable {
     #[mser(varint, filter = validate_enchantable)]
     pub value: i32,
@@ -303,7 +355,7 @@ fn validate_enchantable(value: &i32) -> bool {
     *value > 0
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Equippable<'a> {
     pub slot: EquipmentSlot,
     pub equip_sound: Holder<SoundEvent<'a>, sound_event>,
@@ -318,17 +370,17 @@ pub struct Equippable<'a> {
     pub shearing_sound: Holder<SoundEvent<'a>, sound_event>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Repairable<'a> {
     pub items: HolderSet<'a, item>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DeathProtection<'a> {
     pub death_effects: List<'a, ConsumeEffect<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BlocksAttacks<'a> {
     pub block_delay_seconds: f32,
     pub disable_cooldown_scale: f32,
@@ -339,7 +391,7 @@ pub struct BlocksAttacks<'a> {
     pub disable_sound: Option<Holder<SoundEvent<'a>, sound_event>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DamageReduction<'a> {
     pub horizontal_blocking_angle: f32,
     pub ty: Option<HolderSet<'a, DamageTypeRef>>,
@@ -347,14 +399,14 @@ pub struct DamageReduction<'a> {
     pub factor: f32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ItemDamageFunction {
     pub threshold: f32,
     pub base: f32,
     pub factor: f32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PiercingWeapon<'a> {
     pub deals_knockback: bool,
     pub dismounts: bool,
@@ -362,14 +414,14 @@ pub struct PiercingWeapon<'a> {
     pub hit_sound: Option<Holder<SoundEvent<'a>, sound_event>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct SwingAnimation {
     pub ty: SwingAnimationType,
     #[mser(varint)]
     pub duration: u32,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum SwingAnimationType {
@@ -388,18 +440,18 @@ impl SwingAnimationType {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DyedItemColor(pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MapItemColor(pub u32);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MapDecorations {
     pub tag: Tag,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum MapPostProcessing {
@@ -407,17 +459,17 @@ pub enum MapPostProcessing {
     Scale,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ChargedProjectiles<'a> {
     pub items: List<'a, ItemStack<'a>, 64>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BundleContents<'a> {
     pub items: List<'a, ItemStack<'a>, 256>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PotionContents<'a> {
     pub potion: Option<potion>,
     pub custom_color: Option<u32>,
@@ -425,12 +477,12 @@ pub struct PotionContents<'a> {
     pub custom_name: Option<Utf8<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct WritableBookContent<'a> {
     pub pages: List<'a, Filterable<Utf8<'a, 1024>>, 100>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct WrittenBookContent<'a> {
     pub title: Filterable<Utf8<'a, 32>>,
     pub author: Utf8<'a>,
@@ -444,30 +496,30 @@ fn validate_written_book_content(generation: &u32) -> bool {
     (0..=3).contains(generation)
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ArmorTrim<'a> {
     pub material: Holder<TrimMaterial<'a>, TrimMaterialRef>,
     pub pattern: Holder<TrimPattern<'a>, TrimPatternRef>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DebugStickState {
     pub tag: Tag,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct TypedEntityDataEntity {
     pub ty: entity_type,
     pub tag: Tag,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct TypedEntityDataBlockEntity {
     pub ty: block_entity_type,
     pub tag: Tag,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Instrument<'a> {
     pub sound_event: Holder<SoundEvent<'a>, sound_event>,
     pub use_duration: f32,
@@ -475,23 +527,23 @@ pub struct Instrument<'a> {
     pub description: ComponentRaw,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ProvidesTrimMaterial<'a> {
     pub material: Either<TrimMaterial<'a>, ResourceKey<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct OminousBottleAmplifier {
     #[mser(varint)]
     pub value: u32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct JukeboxPlayable<'a> {
     pub song: Either<Holder<JukeboxSong<'a>, JukeboxSongRef>, ResourceKey<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct JukeboxSong<'a> {
     pub sound_event: Holder<SoundEvent<'a>, sound_event>,
     pub description: ComponentRaw,
@@ -500,41 +552,41 @@ pub struct JukeboxSong<'a> {
     pub comparator_output: u32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Recipes(pub Tag);
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct LodestoneTracker {
-    pub target: Option<BlockPosPacked>,
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
+pub struct LodestoneTracker<'a> {
+    pub target: Option<GlobalPos<'a>>,
     pub tracked: bool,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Fireworks<'a> {
     #[mser(varint)]
     pub flight_duration: u32,
     pub explosions: List<'a, FireworkExplosion<'a>, 256>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ItemContainerContents<'a> {
     pub items: List<'a, OptionalItemStack<'a>, 256>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct BlockItemStateProperties<'a> {
     pub properties: Map<'a, Utf8<'a>, Utf8<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Bees<'a> {
     pub bees: List<'a, BeehiveOccupant>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct SeededContainerLoot(pub Tag);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 #[mser(header = data_component_type)]
 pub enum TypedDataComponent<'a> {
     CustomData(CustomData),
@@ -602,7 +654,7 @@ pub enum TypedDataComponent<'a> {
     JukeboxPlayable(JukeboxPlayable<'a>),
     ProvidesBannerPatterns(TagKey<'a>),
     Recipes(Recipes),
-    LodestoneTracker(LodestoneTracker),
+    LodestoneTracker(LodestoneTracker<'a>),
     FireworkExplosion(FireworkExplosion<'a>),
     Fireworks(Fireworks<'a>),
     Profile(ResolvableProfileRef<'a>),
@@ -643,6 +695,117 @@ pub enum TypedDataComponent<'a> {
     ShulkerColor(DyeColor),
 }
 
+impl TypedDataComponent<'_> {
+    pub const fn ty(&self) -> data_component_type {
+        match self {
+            Self::CustomData(..) => data_component_type::custom_data,
+            Self::MaxStackSize(..) => data_component_type::max_stack_size,
+            Self::MaxDamage(..) => data_component_type::max_damage,
+            Self::Damage(..) => data_component_type::damage,
+            Self::Unbreakable => data_component_type::unbreakable,
+            Self::UseEffects(..) => data_component_type::use_effects,
+            Self::CustomName(..) => data_component_type::custom_name,
+            Self::MinimumAttackCharge(..) => data_component_type::minimum_attack_charge,
+            Self::DamageType(..) => data_component_type::damage_type,
+            Self::ItemName(..) => data_component_type::item_name,
+            Self::ItemModel(..) => data_component_type::item_model,
+            Self::Lore(..) => data_component_type::lore,
+            Self::Rarity(..) => data_component_type::rarity,
+            Self::Enchantments(..) => data_component_type::enchantments,
+            Self::CanPlaceOn(..) => data_component_type::can_place_on,
+            Self::CanBreak(..) => data_component_type::can_break,
+            Self::AttributeModifiers(..) => data_component_type::attribute_modifiers,
+            Self::CustomModelData(..) => data_component_type::custom_model_data,
+            Self::TooltipDisplay(..) => data_component_type::tooltip_display,
+            Self::RepairCost(..) => data_component_type::repair_cost,
+            Self::CreativeSlotLock => data_component_type::creative_slot_lock,
+            Self::EnchantmentGlintOverride(..) => data_component_type::enchantment_glint_override,
+            Self::IntangibleProjectile => data_component_type::intangible_projectile,
+            Self::Food(..) => data_component_type::food,
+            Self::Consumable(..) => data_component_type::consumable,
+            Self::UseRemainder(..) => data_component_type::use_remainder,
+            Self::UseCooldown(..) => data_component_type::use_cooldown,
+            Self::DamageResistant(..) => data_component_type::damage_resistant,
+            Self::Tool(..) => data_component_type::tool,
+            Self::Weapon(..) => data_component_type::weapon,
+            Self::AttackRange(..) => data_component_type::attack_range,
+            Self::Enchantable(..) => data_component_type::enchantable,
+            Self::Equippable(..) => data_component_type::equippable,
+            Self::Repairable(..) => data_component_type::repairable,
+            Self::Glider => data_component_type::glider,
+            Self::TooltipStyle(..) => data_component_type::tooltip_style,
+            Self::DeathProtection(..) => data_component_type::death_protection,
+            Self::BlocksAttacks(..) => data_component_type::blocks_attacks,
+            Self::PiercingWeapon(..) => data_component_type::piercing_weapon,
+            Self::KineticWeapon(..) => data_component_type::kinetic_weapon,
+            Self::SwingAnimation(..) => data_component_type::swing_animation,
+            Self::StoredEnchantments(..) => data_component_type::stored_enchantments,
+            Self::DyedColor(..) => data_component_type::dyed_color,
+            Self::MapColor(..) => data_component_type::map_color,
+            Self::MapId(..) => data_component_type::map_id,
+            Self::MapDecorations(..) => data_component_type::map_decorations,
+            Self::MapPostProcessing(..) => data_component_type::map_post_processing,
+            Self::ChargedProjectiles(..) => data_component_type::charged_projectiles,
+            Self::BundleContents(..) => data_component_type::bundle_contents,
+            Self::PotionContents(..) => data_component_type::potion_contents,
+            Self::PotionDurationScale(..) => data_component_type::potion_duration_scale,
+            Self::SuspiciousStewEffects(..) => data_component_type::suspicious_stew_effects,
+            Self::WritableBookContent(..) => data_component_type::writable_book_content,
+            Self::WrittenBookContent(..) => data_component_type::written_book_content,
+            Self::Trim(..) => data_component_type::trim,
+            Self::DebugStickState(..) => data_component_type::debug_stick_state,
+            Self::EntityData(..) => data_component_type::entity_data,
+            Self::BucketEntityData(..) => data_component_type::bucket_entity_data,
+            Self::BlockEntityData(..) => data_component_type::block_entity_data,
+            Self::Instrument(..) => data_component_type::instrument,
+            Self::ProvidesTrimMaterial(..) => data_component_type::provides_trim_material,
+            Self::OminousBottleAmplifier(..) => data_component_type::ominous_bottle_amplifier,
+            Self::JukeboxPlayable(..) => data_component_type::jukebox_playable,
+            Self::ProvidesBannerPatterns(..) => data_component_type::provides_banner_patterns,
+            Self::Recipes(..) => data_component_type::recipes,
+            Self::LodestoneTracker(..) => data_component_type::lodestone_tracker,
+            Self::FireworkExplosion(..) => data_component_type::firework_explosion,
+            Self::Fireworks(..) => data_component_type::fireworks,
+            Self::Profile(..) => data_component_type::profile,
+            Self::NoteBlockSound(..) => data_component_type::note_block_sound,
+            Self::BannerPatterns(..) => data_component_type::banner_patterns,
+            Self::BaseColor(..) => data_component_type::base_color,
+            Self::PotDecorations(..) => data_component_type::pot_decorations,
+            Self::Container(..) => data_component_type::container,
+            Self::BlockState(..) => data_component_type::block_state,
+            Self::Bees(..) => data_component_type::bees,
+            Self::Lock(..) => data_component_type::lock,
+            Self::ContainerLoot(..) => data_component_type::container_loot,
+            Self::BreakSound(..) => data_component_type::break_sound,
+            Self::VillagerVariant(..) => data_component_type::villager_variant,
+            Self::WolfVariant(..) => data_component_type::wolf_variant,
+            Self::WolfSoundVariant(..) => data_component_type::wolf_sound_variant,
+            Self::WolfCollar(..) => data_component_type::wolf_collar,
+            Self::FoxVariant(..) => data_component_type::fox_variant,
+            Self::SalmonSize(..) => data_component_type::salmon_size,
+            Self::ParrotVariant(..) => data_component_type::parrot_variant,
+            Self::TropicalFishPattern(..) => data_component_type::tropical_fish_pattern,
+            Self::TropicalFishBaseColor(..) => data_component_type::tropical_fish_base_color,
+            Self::TropicalFishPatternColor(..) => data_component_type::tropical_fish_pattern_color,
+            Self::MooshroomVariant(..) => data_component_type::mooshroom_variant,
+            Self::RabbitVariant(..) => data_component_type::rabbit_variant,
+            Self::PigVariant(..) => data_component_type::pig_variant,
+            Self::CowVariant(..) => data_component_type::cow_variant,
+            Self::ChickenVariant(..) => data_component_type::chicken_variant,
+            Self::ZombieNautilusVariant(..) => data_component_type::zombie_nautilus_variant,
+            Self::FrogVariant(..) => data_component_type::frog_variant,
+            Self::HorseVariant(..) => data_component_type::horse_variant,
+            Self::PaintingVariant(..) => data_component_type::painting_variant,
+            Self::LlamaVariant(..) => data_component_type::llama_variant,
+            Self::AxolotlVariant(..) => data_component_type::axolotl_variant,
+            Self::CatVariant(..) => data_component_type::cat_variant,
+            Self::CatCollar(..) => data_component_type::cat_collar,
+            Self::SheepColor(..) => data_component_type::sheep_color,
+            Self::ShulkerColor(..) => data_component_type::shulker_color,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct DataComponentExactPredicate<'a> {
     pub expected_components: List<'a, TypedDataComponent<'a>>,
@@ -2,14 +2,14 @@ use crate::Holder;
 use crate::sound::SoundEvent;
 use minecraft_data::sound_event;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Consumable<'a> {
     pub consume_seconds: f32,
     pub animation: ItemUseAnimation,
     pub sound: Holder<SoundEvent<'a>, sound_event>,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[mser(varint)]
 #[repr(u8)]
 pub enum ItemUseAnimation {
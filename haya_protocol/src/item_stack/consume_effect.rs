@@ -4,7 +4,7 @@ use crate::{Holder, HolderSet};
 use haya_collection::List;
 use minecraft_data::{consume_effect_type, mob_effect, sound_event};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 #[mser(header = consume_effect_type)]
 pub enum ConsumeEffect<'a> {
     ApplyEffects {
@@ -1,7 +1,7 @@
 use crate::Translatable;
 use haya_collection::List;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct FireworkExplosion<'a> {
     pub shape: Shape,
     pub colors: List<'a, u32>,
@@ -10,7 +10,7 @@ pub struct FireworkExplosion<'a> {
     pub has_twinkle: bool,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[mser(varint)]
 #[repr(u8)]
 pub enum Shape {
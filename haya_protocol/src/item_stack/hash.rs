@@ -0,0 +1,1527 @@
+use super::consumable::ItemUseAnimation;
+use super::consume_effect::ConsumeEffect;
+use super::firework_explosion::FireworkExplosion;
+use super::item_attribute_modifiers::Display;
+use super::kinetic_weapon::Condition;
+use super::{
+    AdventureModePredicate, DataComponentPatch, Instrument, ItemStack, JukeboxSong,
+    SwingAnimationType, TypedDataComponent,
+};
+use crate::advancement::BlockPredicate;
+use crate::block::BannerPattern;
+use crate::effect::{MobEffect, MobEffectDetails};
+use crate::entity::PaintingVariant;
+use crate::inventory::EquipmentSlotGroup;
+use crate::profile::{PropertyRef, ResolvableProfileRef};
+use crate::sound::SoundEvent;
+use crate::trim::{TrimMaterial, TrimPattern};
+use crate::{ComponentRaw, Filterable, HashedPatchMap, HashedStack, Holder, HolderSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+use haya_collection::{List, Map};
+use haya_ident::{Ident, MINECRAFT, ResourceKey, TagKey};
+use haya_nbt::{CompoundTag, ListTag, Tag};
+use minecraft_data::{item, sound_event};
+use mser::Either;
+use uuid::Uuid;
+
+const TAG_EMPTY: u8 = 1;
+const TAG_MAP_START: u8 = 2;
+const TAG_MAP_END: u8 = 3;
+const TAG_LIST_START: u8 = 4;
+const TAG_LIST_END: u8 = 5;
+const TAG_BYTE: u8 = 6;
+const TAG_SHORT: u8 = 7;
+const TAG_INT: u8 = 8;
+const TAG_LONG: u8 = 9;
+const TAG_FLOAT: u8 = 10;
+const TAG_DOUBLE: u8 = 11;
+const TAG_STRING: u8 = 12;
+const TAG_BOOLEAN: u8 = 13;
+const TAG_BYTE_ARRAY_START: u8 = 14;
+const TAG_BYTE_ARRAY_END: u8 = 15;
+const TAG_INT_ARRAY_START: u8 = 16;
+const TAG_INT_ARRAY_END: u8 = 17;
+const TAG_LONG_ARRAY_START: u8 = 18;
+const TAG_LONG_ARRAY_END: u8 = 19;
+
+/// Keys of text components whose byte tags are booleans in the codec.
+const TEXT_BOOLEANS: [&str; 6] = [
+    "bold",
+    "italic",
+    "underlined",
+    "strikethrough",
+    "obfuscated",
+    "interpret",
+];
+
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0x82f6_3b78 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// Names of the entries of synchronised registries, which codecs encode in
+/// place of network IDs.
+pub trait RegistryLookup {
+    /// The name of entry `id` of `registry`, such as `enchantment`.
+    fn name(&self, registry: &str, id: u32) -> Option<Ident<'_>>;
+}
+
+#[derive(Clone, Copy)]
+struct Hasher(u32);
+
+impl Hasher {
+    const fn new() -> Self {
+        Self(!0)
+    }
+
+    fn bytes(self, data: &[u8]) -> Self {
+        let mut crc = self.0;
+        for &b in data {
+            crc = CRC32C_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+        }
+        Self(crc)
+    }
+
+    fn byte(self, b: u8) -> Self {
+        self.bytes(&[b])
+    }
+
+    fn chars(self, s: &str) -> Self {
+        s.encode_utf16()
+            .fold(self, |h, c| h.bytes(&c.to_le_bytes()))
+    }
+
+    const fn finish(self) -> u32 {
+        !self.0
+    }
+}
+
+pub fn crc32c(data: &[u8]) -> u32 {
+    Hasher::new().bytes(data).finish()
+}
+
+pub fn empty() -> u32 {
+    Hasher::new().byte(TAG_EMPTY).finish()
+}
+
+pub fn empty_map() -> u32 {
+    MapHasher::new().finish()
+}
+
+pub fn boolean(value: bool) -> u32 {
+    Hasher::new().byte(TAG_BOOLEAN).byte(value as u8).finish()
+}
+
+pub fn byte(value: i8) -> u32 {
+    Hasher::new().byte(TAG_BYTE).byte(value as u8).finish()
+}
+
+pub fn short(value: i16) -> u32 {
+    Hasher::new()
+        .byte(TAG_SHORT)
+        .bytes(&value.to_le_bytes())
+        .finish()
+}
+
+pub fn int(value: i32) -> u32 {
+    Hasher::new()
+        .byte(TAG_INT)
+        .bytes(&value.to_le_bytes())
+        .finish()
+}
+
+pub fn long(value: i64) -> u32 {
+    Hasher::new()
+        .byte(TAG_LONG)
+        .bytes(&value.to_le_bytes())
+        .finish()
+}
+
+/// Hashed as `Float.floatToRawIntBits`, so NaN payloads are kept.
+pub fn float(value: f32) -> u32 {
+    Hasher::new()
+        .byte(TAG_FLOAT)
+        .bytes(&value.to_bits().to_le_bytes())
+        .finish()
+}
+
+/// Hashed as `Double.doubleToRawLongBits`, so NaN payloads are kept.
+pub fn double(value: f64) -> u32 {
+    Hasher::new()
+        .byte(TAG_DOUBLE)
+        .bytes(&value.to_bits().to_le_bytes())
+        .finish()
+}
+
+pub fn string(value: &str) -> u32 {
+    concat(&[value])
+}
+
+/// A string of `parts`, hashed as UTF-16 like Java strings.
+fn concat(parts: &[&str]) -> u32 {
+    let len: usize = parts.iter().map(|s| s.encode_utf16().count()).sum();
+    let start = Hasher::new()
+        .byte(TAG_STRING)
+        .bytes(&(len as u32).to_le_bytes());
+    parts.iter().fold(start, |h, s| h.chars(s)).finish()
+}
+
+pub fn list(values: impl IntoIterator<Item = u32>) -> u32 {
+    values
+        .into_iter()
+        .fold(Hasher::new().byte(TAG_LIST_START), |h, x| {
+            h.bytes(&x.to_le_bytes())
+        })
+        .byte(TAG_LIST_END)
+        .finish()
+}
+
+pub fn byte_array(values: &[i8]) -> u32 {
+    values
+        .iter()
+        .fold(Hasher::new().byte(TAG_BYTE_ARRAY_START), |h, &x| {
+            h.byte(x as u8)
+        })
+        .byte(TAG_BYTE_ARRAY_END)
+        .finish()
+}
+
+pub fn int_array(values: &[i32]) -> u32 {
+    values
+        .iter()
+        .fold(Hasher::new().byte(TAG_INT_ARRAY_START), |h, x| {
+            h.bytes(&x.to_le_bytes())
+        })
+        .byte(TAG_INT_ARRAY_END)
+        .finish()
+}
+
+pub fn long_array(values: &[i64]) -> u32 {
+    values
+        .iter()
+        .fold(Hasher::new().byte(TAG_LONG_ARRAY_START), |h, x| {
+            h.bytes(&x.to_le_bytes())
+        })
+        .byte(TAG_LONG_ARRAY_END)
+        .finish()
+}
+
+/// Entries of a map, hashed sorted by key and value hash.
+#[derive(Clone, Default)]
+pub struct MapHasher(Vec<(u32, u32)>);
+
+impl MapHasher {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn insert(&mut self, key: &str, value: u32) -> &mut Self {
+        self.insert_hashed(string(key), value)
+    }
+
+    pub fn insert_hashed(&mut self, key: u32, value: u32) -> &mut Self {
+        self.0.push((key, value));
+        self
+    }
+
+    pub fn finish(&mut self) -> u32 {
+        self.0.sort_unstable();
+        self.0
+            .iter()
+            .fold(Hasher::new().byte(TAG_MAP_START), |h, (k, v)| {
+                h.bytes(&k.to_le_bytes()).bytes(&v.to_le_bytes())
+            })
+            .byte(TAG_MAP_END)
+            .finish()
+    }
+}
+
+/// The hash of an NBT tag converted to the codec encoding.
+pub fn tag(value: &Tag) -> u32 {
+    nbt(value, false)
+}
+
+/// The hash of a text component, whose style flags are booleans rather than
+/// the bytes of its network NBT.
+pub fn text(value: &ComponentRaw) -> u32 {
+    nbt(&value.0, true)
+}
+
+fn nbt(value: &Tag, text: bool) -> u32 {
+    match value {
+        Tag::Byte(x) => byte(*x),
+        Tag::Short(x) => short(*x),
+        Tag::Int(x) => int(*x),
+        Tag::Long(x) => long(*x),
+        Tag::Float(x) => float(*x),
+        Tag::Double(x) => double(*x),
+        Tag::String(x) => string(x),
+        Tag::ByteArray(x) => byte_array(x),
+        Tag::IntArray(x) => int_array(x),
+        Tag::LongArray(x) => long_array(x),
+        Tag::List(x) => nbt_list(x, text),
+        Tag::Compound(x) => compound(x, text),
+        Tag::End => empty(),
+    }
+}
+
+fn compound(value: &CompoundTag, text: bool) -> u32 {
+    let mut m = MapHasher::new();
+    for (k, v) in value.iter() {
+        let hash = match v {
+            Tag::Byte(b) if text && TEXT_BOOLEANS.contains(&&**k) => boolean(*b != 0),
+            _ => nbt(v, text),
+        };
+        m.insert(k, hash);
+    }
+    m.finish()
+}
+
+fn nbt_list(value: &ListTag, text: bool) -> u32 {
+    match value {
+        ListTag::None => list([]),
+        ListTag::Byte(x) => list(x.iter().map(|&v| byte(v))),
+        ListTag::Short(x) => list(x.iter().map(|&v| short(v))),
+        ListTag::Int(x) => list(x.iter().map(|&v| int(v))),
+        ListTag::Long(x) => list(x.iter().map(|&v| long(v))),
+        ListTag::Float(x) => list(x.iter().map(|&v| float(v))),
+        ListTag::Double(x) => list(x.iter().map(|&v| double(v))),
+        ListTag::String(x) => list(x.iter().map(|v| string(v))),
+        ListTag::ByteArray(x) => list(x.iter().map(|v| byte_array(v))),
+        ListTag::IntArray(x) => list(x.iter().map(|v| int_array(v))),
+        ListTag::LongArray(x) => list(x.iter().map(|v| long_array(v))),
+        ListTag::List(x) => list(x.iter().map(|v| nbt_list(v, text))),
+        // Elements of mixed lists are wrapped in compounds with an empty key.
+        ListTag::Compound(x) => list(x.iter().map(|v| match v.get(0) {
+            Some(("", inner)) if v.len() == 1 => nbt(inner, text),
+            _ => compound(v, text),
+        })),
+    }
+}
+
+/// The text of `CompoundTag::toString`, which `NbtPredicate` encodes in
+/// place of the tag.
+fn snbt(value: &Tag, out: &mut String) {
+    match value {
+        Tag::Byte(x) => _ = write!(out, "{x}b"),
+        Tag::Short(x) => _ = write!(out, "{x}s"),
+        Tag::Int(x) => _ = write!(out, "{x}"),
+        Tag::Long(x) => _ = write!(out, "{x}L"),
+        Tag::Float(x) => {
+            java_decimal(&alloc::format!("{x:e}"), out);
+            out.push('f');
+        }
+        Tag::Double(x) => {
+            java_decimal(&alloc::format!("{x:e}"), out);
+            out.push('d');
+        }
+        Tag::String(x) => snbt_string(x, out),
+        Tag::ByteArray(x) => snbt_seq(out, "[B;", x.iter().map(|v| alloc::format!("{v}B"))),
+        Tag::IntArray(x) => snbt_seq(out, "[I;", x.iter().map(|v| alloc::format!("{v}"))),
+        Tag::LongArray(x) => snbt_seq(out, "[L;", x.iter().map(|v| alloc::format!("{v}L"))),
+        Tag::List(x) => snbt_list(x, out),
+        Tag::Compound(x) => snbt_compound(x, out),
+        Tag::End => out.push_str("END"),
+    }
+}
+
+fn snbt_seq(out: &mut String, open: &str, items: impl Iterator<Item = String>) {
+    out.push_str(open);
+    for (i, item) in items.enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        out.push_str(&item);
+    }
+    out.push(']');
+}
+
+fn snbt_list(value: &ListTag, out: &mut String) {
+    let items: Vec<Tag> = match value {
+        ListTag::None => Vec::new(),
+        ListTag::Byte(x) => x.iter().map(|&v| Tag::Byte(v)).collect(),
+        ListTag::Short(x) => x.iter().map(|&v| Tag::Short(v)).collect(),
+        ListTag::Int(x) => x.iter().map(|&v| Tag::Int(v)).collect(),
+        ListTag::Long(x) => x.iter().map(|&v| Tag::Long(v)).collect(),
+        ListTag::Float(x) => x.iter().map(|&v| Tag::Float(v)).collect(),
+        ListTag::Double(x) => x.iter().map(|&v| Tag::Double(v)).collect(),
+        ListTag::String(x) => x.iter().cloned().map(Tag::String).collect(),
+        ListTag::ByteArray(x) => x.iter().cloned().map(Tag::ByteArray).collect(),
+        ListTag::IntArray(x) => x.iter().cloned().map(Tag::IntArray).collect(),
+        ListTag::LongArray(x) => x.iter().cloned().map(Tag::LongArray).collect(),
+        ListTag::List(x) => x.iter().cloned().map(Tag::List).collect(),
+        // Elements of mixed lists are wrapped in compounds with an empty key.
+        ListTag::Compound(x) => x
+            .iter()
+            .map(|v| match v.get(0) {
+                Some(("", inner)) if v.len() == 1 => inner.clone(),
+                _ => Tag::Compound(v.clone()),
+            })
+            .collect(),
+    };
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        snbt(item, out);
+    }
+    out.push(']');
+}
+
+/// Keys are sorted as Java strings and quoted unless they are plain words.
+fn snbt_compound(value: &CompoundTag, out: &mut String) {
+    let mut entries = value.iter().collect::<Vec<_>>();
+    entries.sort_unstable_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+    out.push('{');
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        let bytes = k.as_bytes();
+        let plain = bytes
+            .first()
+            .is_some_and(|&c| c.is_ascii_alphabetic() || c == b'.' || c == b'_')
+            && bytes
+                .iter()
+                .all(|&c| c.is_ascii_alphanumeric() || matches!(c, b'.' | b'_' | b'+' | b'-'));
+        if plain {
+            out.push_str(k);
+        } else {
+            snbt_string(k, out);
+        }
+        out.push(':');
+        snbt(v, out);
+    }
+    out.push('}');
+}
+
+/// `StringTag.quoteAndEscape`, quoting with whichever quote the text
+/// contains first does not need.
+fn snbt_string(value: &str, out: &mut String) {
+    let quote = match value.chars().find(|&c| c == '"' || c == '\'') {
+        Some('"') => '\'',
+        _ => '"',
+    };
+    out.push(quote);
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => _ = write!(out, "\\x{:02x}", c as u32),
+            c => {
+                if c == quote {
+                    out.push('\\');
+                }
+                out.push(c);
+            }
+        }
+    }
+    out.push(quote);
+}
+
+/// `Float.toString` and `Double.toString` from `exp`, the `{:e}` form of
+/// the value, which has the same shortest digits.
+fn java_decimal(exp: &str, out: &mut String) {
+    match exp {
+        "NaN" => return out.push_str("NaN"),
+        "inf" => return out.push_str("Infinity"),
+        "-inf" => return out.push_str("-Infinity"),
+        _ => {}
+    }
+    let (negative, unsigned) = match exp.strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, exp),
+    };
+    let (mantissa, exponent) = unsigned.split_once('e').unwrap_or((unsigned, "0"));
+    let e = exponent.parse::<i32>().unwrap_or(0);
+    let digits = mantissa.replace('.', "");
+    if negative {
+        out.push('-');
+    }
+    if digits == "0" {
+        out.push_str("0.0");
+    } else if (-3..7).contains(&e) {
+        if e < 0 {
+            out.push_str("0.");
+            (1..-e).for_each(|_| out.push('0'));
+            out.push_str(&digits);
+        } else {
+            let int_len = e as usize + 1;
+            if digits.len() <= int_len {
+                out.push_str(&digits);
+                (digits.len()..int_len).for_each(|_| out.push('0'));
+                out.push_str(".0");
+            } else {
+                out.push_str(&digits[..int_len]);
+                out.push('.');
+                out.push_str(&digits[int_len..]);
+            }
+        }
+    } else {
+        out.push_str(&digits[..1]);
+        out.push('.');
+        out.push_str(if digits.len() > 1 { &digits[1..] } else { "0" });
+        _ = write!(out, "E{e}");
+    }
+}
+
+fn ident(id: Ident) -> u32 {
+    concat(&[id.namespace().unwrap_or(MINECRAFT), ":", id.path()])
+}
+
+fn builtin(name: &str) -> u32 {
+    concat(&[MINECRAFT, ":", name])
+}
+
+fn tag_key(key: &TagKey) -> u32 {
+    let id = key.location;
+    concat(&["#", id.namespace().unwrap_or(MINECRAFT), ":", id.path()])
+}
+
+fn same(a: f32, b: f32) -> bool {
+    a.to_bits() == b.to_bits()
+}
+
+fn reference(registries: &dyn RegistryLookup, registry: &str, id: u32) -> Option<u32> {
+    registries.name(registry, id).map(ident)
+}
+
+/// A registry entry by name, or inline through the codec of the registry.
+fn holder<T, R: Copy>(
+    value: &Holder<T, R>,
+    f: impl FnOnce(R) -> Option<u32>,
+    direct: impl FnOnce(&T) -> u32,
+) -> Option<u32> {
+    match value {
+        Holder::Reference(id) => f(*id),
+        Holder::Direct(x) => Some(direct(x)),
+    }
+}
+
+fn uuid(id: Uuid) -> u32 {
+    let v = id.as_u128();
+    int_array(&[
+        (v >> 96) as i32,
+        (v >> 64) as i32,
+        (v >> 32) as i32,
+        v as i32,
+    ])
+}
+
+fn trim_material(value: &TrimMaterial) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("asset_name", string(value.assets.base.suffix.0));
+    if !value.assets.overrides.0.is_empty() {
+        let mut overrides = MapHasher::new();
+        for (key, asset) in value.assets.overrides.0.iter() {
+            overrides.insert_hashed(ident(key.identifier), string(asset.suffix.0));
+        }
+        m.insert("override_armor_assets", overrides.finish());
+    }
+    m.insert("description", text(&value.description));
+    m.finish()
+}
+
+fn trim_pattern(value: &TrimPattern) -> u32 {
+    MapHasher::new()
+        .insert("asset_id", ident(value.asset_id))
+        .insert("description", text(&value.description))
+        .insert("decal", boolean(value.decal))
+        .finish()
+}
+
+fn instrument(value: &Instrument) -> u32 {
+    MapHasher::new()
+        .insert("sound_event", sound(&value.sound_event))
+        .insert("use_duration", float(value.use_duration))
+        .insert("range", float(value.range))
+        .insert("description", text(&value.description))
+        .finish()
+}
+
+fn jukebox_song(value: &JukeboxSong) -> u32 {
+    MapHasher::new()
+        .insert("sound_event", sound(&value.sound_event))
+        .insert("description", text(&value.description))
+        .insert("length_in_seconds", float(value.length_in_seconds))
+        .insert("comparator_output", int(value.comparator_output as i32))
+        .finish()
+}
+
+fn painting_variant(value: &PaintingVariant) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("width", int(value.width as i32))
+        .insert("height", int(value.height as i32))
+        .insert("asset_id", ident(value.asset_id));
+    if let Some(title) = &value.title {
+        m.insert("title", text(title));
+    }
+    if let Some(author) = &value.author {
+        m.insert("author", text(author));
+    }
+    m.finish()
+}
+
+fn banner_pattern(value: &BannerPattern) -> u32 {
+    MapHasher::new()
+        .insert("asset_id", ident(value.asset_id))
+        .insert("translation_key", string(value.translation_key.0))
+        .finish()
+}
+
+fn properties(values: &[PropertyRef]) -> u32 {
+    list(values.iter().map(|p| {
+        let mut m = MapHasher::new();
+        m.insert("name", string(p.name.0))
+            .insert("value", string(p.value.0));
+        if let Some(signature) = p.signature {
+            m.insert("signature", string(signature.0));
+        }
+        m.finish()
+    }))
+}
+
+fn profile(value: &ResolvableProfileRef) -> u32 {
+    let mut m = MapHasher::new();
+    match &value.profile {
+        Either::Left(p) => {
+            m.insert("id", uuid(p.id)).insert("name", string(p.name.0));
+            if !p.properties.0.is_empty() {
+                m.insert("properties", properties(&p.properties.0));
+            }
+        }
+        Either::Right(p) => {
+            if let Some(name) = p.name {
+                m.insert("name", string(name.0));
+            }
+            if let Some(id) = p.id {
+                m.insert("id", uuid(id));
+            }
+            if !p.properties.is_empty() {
+                m.insert("properties", properties(&p.properties));
+            }
+        }
+    }
+    let skin = &value.skin_patch;
+    if let Some(id) = skin.body {
+        m.insert("texture", ident(id));
+    }
+    if let Some(id) = skin.cape {
+        m.insert("cape", ident(id));
+    }
+    if let Some(id) = skin.elytra {
+        m.insert("elytra", ident(id));
+    }
+    if let Some(model) = skin.model {
+        m.insert("model", string(model.name()));
+    }
+    m.finish()
+}
+
+/// `can_place_on` and `can_break`, with a single predicate unwrapped.
+fn adventure_predicate(
+    value: &AdventureModePredicate,
+    registries: &dyn RegistryLookup,
+) -> Option<u32> {
+    let predicates = value
+        .predicates
+        .iter()
+        .map(|p| block_predicate(p, registries))
+        .collect::<Option<Vec<_>>>()?;
+    Some(match &*predicates {
+        [single] => *single,
+        _ => list(predicates),
+    })
+}
+
+fn block_predicate(value: &BlockPredicate, registries: &dyn RegistryLookup) -> Option<u32> {
+    let mut m = MapHasher::new();
+    if let Some(set) = &value.blocks {
+        m.insert("blocks", holder_set(set, |b| Some(builtin(b.name())))?);
+    }
+    if let Some(state) = &value.properties {
+        let mut s = MapHasher::new();
+        for p in state.properties.iter() {
+            let matcher = match &p.value_matcher.0 {
+                Either::Left(exact) => string(exact.0.0),
+                Either::Right(range) => MapHasher::new()
+                    .insert("min", string(range.min.0))
+                    .insert("max", string(range.max.0))
+                    .finish(),
+            };
+            s.insert(p.name.0, matcher);
+        }
+        m.insert("state", s.finish());
+    }
+    if let Some(nbt) = &value.nbt {
+        let mut out = String::new();
+        snbt(nbt, &mut out);
+        m.insert("nbt", string(&out));
+    }
+    let components = &value.components;
+    if !components.exact.is_empty() {
+        let mut c = MapHasher::new();
+        for x in components.exact.iter() {
+            c.insert_hashed(builtin(x.ty().name()), x.hash(registries)?);
+        }
+        m.insert("components", c.finish());
+    }
+    if !components.partial.is_empty() {
+        let mut c = MapHasher::new();
+        for x in components.partial.iter() {
+            match x.0 {
+                Either::Left(ty) => c.insert_hashed(builtin(ty.name()), tag(&x.1)),
+                Either::Right(ty) => c.insert_hashed(builtin(ty.name()), empty_map()),
+            };
+        }
+        m.insert("predicates", c.finish());
+    }
+    Some(m.finish())
+}
+
+fn kinetic_condition(value: &Condition) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("max_duration_ticks", int(value.max_duration_ticks as i32));
+    if !same(value.min_speed, 0.0) {
+        m.insert("min_speed", float(value.min_speed));
+    }
+    if !same(value.min_relative_speed, 0.0) {
+        m.insert("min_relative_speed", float(value.min_relative_speed));
+    }
+    m.finish()
+}
+
+fn either_key<T>(value: &Either<T, ResourceKey>, f: impl FnOnce(&T) -> Option<u32>) -> Option<u32> {
+    match value {
+        Either::Left(x) => f(x),
+        Either::Right(key) => Some(ident(key.identifier)),
+    }
+}
+
+fn holder_set<T>(set: &HolderSet<T>, f: impl FnMut(&T) -> Option<u32>) -> Option<u32> {
+    match set {
+        HolderSet::Named(id) => Some(concat(&[
+            "#",
+            id.namespace().unwrap_or(MINECRAFT),
+            ":",
+            id.path(),
+        ])),
+        HolderSet::Direct(x) if x.len() == 1 => x.iter().map(f).next().flatten(),
+        HolderSet::Direct(x) => x.iter().map(f).collect::<Option<Vec<_>>>().map(list),
+    }
+}
+
+fn sound(holder: &Holder<SoundEvent, sound_event>) -> u32 {
+    match holder {
+        Holder::Reference(id) => builtin(id.name()),
+        Holder::Direct(s) => {
+            let mut m = MapHasher::new();
+            m.insert("sound_id", ident(s.location));
+            if let Some(range) = s.fixed_range {
+                m.insert("range", float(range));
+            }
+            m.finish()
+        }
+    }
+}
+
+fn is_sound(holder: &Holder<SoundEvent, sound_event>, name: &str) -> bool {
+    matches!(holder, Holder::Reference(id) if id.name() == name)
+}
+
+fn filterable<T>(value: &Filterable<T>, f: impl Fn(&T) -> u32) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("raw", f(&value.raw));
+    if let Some(x) = &value.filtered {
+        m.insert("filtered", f(x));
+    }
+    m.finish()
+}
+
+fn stack(
+    id: item,
+    count: i32,
+    patch: &DataComponentPatch,
+    registries: &dyn RegistryLookup,
+) -> Option<u32> {
+    if count <= 0 || id == item::air {
+        return None;
+    }
+    let mut m = MapHasher::new();
+    m.insert("id", builtin(id.name()))
+        .insert("count", int(count));
+    if !patch.patch_add.is_empty() || !patch.patch_remove.is_empty() {
+        let mut components = MapHasher::new();
+        for c in patch.patch_add.iter() {
+            components.insert_hashed(builtin(c.ty().name()), c.hash(registries)?);
+        }
+        for ty in patch.patch_remove.iter() {
+            components.insert_hashed(concat(&["!", MINECRAFT, ":", ty.name()]), empty_map());
+        }
+        m.insert("components", components.finish());
+    }
+    Some(m.finish())
+}
+
+fn stacks(values: &[ItemStack], registries: &dyn RegistryLookup) -> Option<u32> {
+    values
+        .iter()
+        .map(|x| stack(x.id, x.count, &x.components, registries))
+        .collect::<Option<Vec<_>>>()
+        .map(list)
+}
+
+fn mob_effect(value: &MobEffect) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("id", builtin(value.effect.name()));
+    effect_details(&mut m, &value.details);
+    m.finish()
+}
+
+fn effect_details(m: &mut MapHasher, value: &MobEffectDetails) {
+    if value.amplifier != 0 {
+        m.insert("amplifier", byte(value.amplifier as u8 as i8));
+    }
+    if value.duration != 0 {
+        m.insert("duration", int(value.duration as i32));
+    }
+    if value.ambient {
+        m.insert("ambient", boolean(true));
+    }
+    if !value.show_particles {
+        m.insert("show_particles", boolean(false));
+    }
+    m.insert("show_icon", boolean(value.show_icon));
+    if let Some(hidden) = &value.hidden_effect {
+        let mut h = MapHasher::new();
+        effect_details(&mut h, hidden.as_ref());
+        m.insert("hidden_effect", h.finish());
+    }
+}
+
+fn consume_effect(value: &ConsumeEffect) -> Option<u32> {
+    let mut m = MapHasher::new();
+    match value {
+        ConsumeEffect::ApplyEffects {
+            effects,
+            probability,
+        } => {
+            m.insert("type", builtin("apply_effects"))
+                .insert("effects", list(effects.iter().map(mob_effect)));
+            if !same(*probability, 1.0) {
+                m.insert("probability", float(*probability));
+            }
+        }
+        ConsumeEffect::RemoveEffects { effects } => {
+            m.insert("type", builtin("remove_effects"))
+                .insert("effects", holder_set(effects, |x| Some(builtin(x.name())))?);
+        }
+        ConsumeEffect::ClearAllEffects => {
+            m.insert("type", builtin("clear_all_effects"));
+        }
+        ConsumeEffect::TeleportRandomly { diameter } => {
+            m.insert("type", builtin("teleport_randomly"));
+            if !same(*diameter, 16.0) {
+                m.insert("diameter", float(*diameter));
+            }
+        }
+        ConsumeEffect::PlaySound { sound: s } => {
+            m.insert("type", builtin("play_sound"))
+                .insert("sound", sound(s));
+        }
+    }
+    Some(m.finish())
+}
+
+fn explosion(value: &FireworkExplosion) -> u32 {
+    let mut m = MapHasher::new();
+    m.insert("shape", string(value.shape.name()));
+    if !value.colors.is_empty() {
+        m.insert("colors", list(value.colors.iter().map(|&c| int(c as i32))));
+    }
+    if !value.fade_colors.is_empty() {
+        m.insert(
+            "fade_colors",
+            list(value.fade_colors.iter().map(|&c| int(c as i32))),
+        );
+    }
+    if value.has_trail {
+        m.insert("has_trail", boolean(true));
+    }
+    if value.has_twinkle {
+        m.insert("has_twinkle", boolean(true));
+    }
+    m.finish()
+}
+
+/// Entity data encodes its type as an `id` entry of the tag.
+fn entity_data(ty: &str, value: &Tag) -> Option<u32> {
+    let Tag::Compound(c) = value else {
+        return None;
+    };
+    let mut m = MapHasher::new();
+    for (k, v) in c.iter() {
+        m.insert(k, tag(v));
+    }
+    m.insert("id", builtin(ty));
+    Some(m.finish())
+}
+
+impl TypedDataComponent<'_> {
+    /// The CRC32C of the value in the encoding of vanilla `HashOps`, as sent
+    /// in `HashedPatchMap`.
+    ///
+    /// `None` if the value refers to an entry missing from `registries`.
+    pub fn hash(&self, registries: &dyn RegistryLookup) -> Option<u32> {
+        let r = registries;
+        Some(match self {
+            Self::CustomData(x) | Self::BucketEntityData(x) => tag(&x.0),
+            Self::MaxStackSize(x) | Self::MaxDamage(x) | Self::Damage(x) | Self::RepairCost(x) => {
+                int(*x as i32)
+            }
+            Self::Unbreakable
+            | Self::CreativeSlotLock
+            | Self::IntangibleProjectile
+            | Self::Glider => empty_map(),
+            Self::CustomName(x) | Self::ItemName(x) => text(x),
+            Self::MinimumAttackCharge(x) | Self::PotionDurationScale(x) => float(*x),
+            Self::DamageType(x) => either_key(x, |id| reference(r, "damage_type", id.0))?,
+            Self::ItemModel(x) | Self::TooltipStyle(x) | Self::NoteBlockSound(x) => ident(*x),
+            Self::Lore(x) => list(x.0.iter().map(text)),
+            Self::Rarity(x) => string(x.name()),
+            Self::Enchantments(x) | Self::StoredEnchantments(x) => {
+                let mut m = MapHasher::new();
+                for (id, level) in x.0.0.iter() {
+                    m.insert_hashed(reference(r, "enchantment", id.0)?, int(level.0));
+                }
+                m.finish()
+            }
+            Self::AttributeModifiers(x) => list(
+                x.modifiers
+                    .iter()
+                    .map(|e| {
+                        let mut m = MapHasher::new();
+                        m.insert("type", builtin(e.attribute.name()))
+                            .insert("id", ident(e.modifier.id))
+                            .insert("amount", double(e.modifier.amount))
+                            .insert("operation", string(e.modifier.operation.name()));
+                        if !matches!(e.slot, EquipmentSlotGroup::Any) {
+                            m.insert("slot", string(e.slot.name()));
+                        }
+                        match &e.display {
+                            Display::Default => {}
+                            Display::Hidden => {
+                                m.insert(
+                                    "display",
+                                    MapHasher::new().insert("type", string("hidden")).finish(),
+                                );
+                            }
+                            Display::Override(c) => {
+                                m.insert(
+                                    "display",
+                                    MapHasher::new()
+                                        .insert("type", string("override"))
+                                        .insert("value", text(c))
+                                        .finish(),
+                                );
+                            }
+                        }
+                        m.finish()
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Self::CustomModelData(x) => {
+                let mut m = MapHasher::new();
+                if !x.floats.is_empty() {
+                    m.insert("floats", list(x.floats.iter().map(|&v| float(v))));
+                }
+                if !x.flags.is_empty() {
+                    m.insert("flags", list(x.flags.iter().map(|&v| boolean(v))));
+                }
+                if !x.strings.is_empty() {
+                    m.insert("strings", list(x.strings.iter().map(|v| string(v.0))));
+                }
+                if !x.colors.is_empty() {
+                    m.insert("colors", list(x.colors.iter().map(|&v| int(v as i32))));
+                }
+                m.finish()
+            }
+            Self::TooltipDisplay(x) => {
+                let mut m = MapHasher::new();
+                if x.hide_tooltip {
+                    m.insert("hide_tooltip", boolean(true));
+                }
+                if !x.hidden_components.is_empty() {
+                    m.insert(
+                        "hidden_components",
+                        list(x.hidden_components.iter().map(|ty| builtin(ty.name()))),
+                    );
+                }
+                m.finish()
+            }
+            Self::EnchantmentGlintOverride(x) => boolean(*x),
+            Self::Food(x) => {
+                let mut m = MapHasher::new();
+                m.insert("nutrition", int(x.nutrition as i32))
+                    .insert("saturation", float(x.saturation));
+                if x.can_always_eat {
+                    m.insert("can_always_eat", boolean(true));
+                }
+                m.finish()
+            }
+            Self::Consumable(x) => {
+                let mut m = MapHasher::new();
+                if !same(x.consume_seconds, 1.6) {
+                    m.insert("consume_seconds", float(x.consume_seconds));
+                }
+                if !matches!(x.animation, ItemUseAnimation::Eat) {
+                    m.insert("animation", string(x.animation.name()));
+                }
+                if !is_sound(&x.sound, "entity.generic.eat") {
+                    m.insert("sound", sound(&x.sound));
+                }
+                m.finish()
+            }
+            Self::UseRemainder(x) => {
+                let s = &x.convert_into;
+                MapHasher::new()
+                    .insert("convert_into", stack(s.id, s.count, &s.components, r)?)
+                    .finish()
+            }
+            Self::UseCooldown(x) => {
+                let mut m = MapHasher::new();
+                m.insert("seconds", float(x.seconds));
+                if let Some(group) = x.cooldown_group {
+                    m.insert("cooldown_group", ident(group));
+                }
+                m.finish()
+            }
+            Self::DamageResistant(x) => {
+                MapHasher::new().insert("types", tag_key(&x.types)).finish()
+            }
+            Self::Tool(x) => {
+                let mut rules = Vec::with_capacity(x.rules.len());
+                for rule in x.rules.iter() {
+                    let mut m = MapHasher::new();
+                    m.insert(
+                        "blocks",
+                        holder_set(&rule.blocks, |b| Some(builtin(b.name())))?,
+                    );
+                    if let Some(speed) = rule.speed {
+                        m.insert("speed", float(speed));
+                    }
+                    if let Some(correct) = rule.correct_for_drops {
+                        m.insert("correct_for_drops", boolean(correct));
+                    }
+                    rules.push(m.finish());
+                }
+                let mut m = MapHasher::new();
+                m.insert("rules", list(rules));
+                if !same(x.default_mining_speed, 1.0) {
+                    m.insert("default_mining_speed", float(x.default_mining_speed));
+                }
+                if x.damage_per_block != 1 {
+                    m.insert("damage_per_block", int(x.damage_per_block as i32));
+                }
+                if !x.can_destroy_blocks_in_creative {
+                    m.insert("can_destroy_blocks_in_creative", boolean(false));
+                }
+                m.finish()
+            }
+            Self::Weapon(x) => {
+                let mut m = MapHasher::new();
+                if x.item_damage_per_attack != 1 {
+                    m.insert(
+                        "item_damage_per_attack",
+                        int(x.item_damage_per_attack as i32),
+                    );
+                }
+                if !same(x.disable_blocking_for_seconds, 0.0) {
+                    m.insert(
+                        "disable_blocking_for_seconds",
+                        float(x.disable_blocking_for_seconds),
+                    );
+                }
+                m.finish()
+            }
+            Self::Enchantable(x) => MapHasher::new().insert("value", int(x.value)).finish(),
+            Self::Equippable(x) => {
+                let mut m = MapHasher::new();
+                m.insert("slot", string(x.slot.name()));
+                if !is_sound(&x.equip_sound, "item.armor.equip_generic") {
+                    m.insert("equip_sound", sound(&x.equip_sound));
+                }
+                if let Some(id) = x.asset_id {
+                    m.insert("asset_id", ident(id));
+                }
+                if let Some(id) = x.camera_overlay {
+                    m.insert("camera_overlay", ident(id));
+                }
+                if let Some(set) = &x.allowed_entities {
+                    m.insert(
+                        "allowed_entities",
+                        holder_set(set, |e| Some(builtin(e.name())))?,
+                    );
+                }
+                if !x.dispensable {
+                    m.insert("dispensable", boolean(false));
+                }
+                if !x.swappable {
+                    m.insert("swappable", boolean(false));
+                }
+                if !x.damage_on_hurt {
+                    m.insert("damage_on_hurt", boolean(false));
+                }
+                if x.equip_on_interact {
+                    m.insert("equip_on_interact", boolean(true));
+                }
+                if x.can_be_sheared {
+                    m.insert("can_be_sheared", boolean(true));
+                }
+                if !is_sound(&x.shearing_sound, "item.shears.snip") {
+                    m.insert("shearing_sound", sound(&x.shearing_sound));
+                }
+                m.finish()
+            }
+            Self::Repairable(x) => MapHasher::new()
+                .insert("items", holder_set(&x.items, |i| Some(builtin(i.name())))?)
+                .finish(),
+            Self::DeathProtection(x) => {
+                let mut m = MapHasher::new();
+                if !x.death_effects.is_empty() {
+                    let effects = x
+                        .death_effects
+                        .iter()
+                        .map(consume_effect)
+                        .collect::<Option<Vec<_>>>()?;
+                    m.insert("death_effects", list(effects));
+                }
+                m.finish()
+            }
+            Self::BlocksAttacks(x) => {
+                let mut m = MapHasher::new();
+                if !same(x.block_delay_seconds, 0.0) {
+                    m.insert("block_delay_seconds", float(x.block_delay_seconds));
+                }
+                if !same(x.disable_cooldown_scale, 1.0) {
+                    m.insert("disable_cooldown_scale", float(x.disable_cooldown_scale));
+                }
+                let default_reductions = match &*x.damage_reductions {
+                    [d] => {
+                        same(d.horizontal_blocking_angle, 90.0)
+                            && d.ty.is_none()
+                            && same(d.base, 0.0)
+                            && same(d.factor, 1.0)
+                    }
+                    _ => false,
+                };
+                if !default_reductions {
+                    let mut reductions = Vec::with_capacity(x.damage_reductions.len());
+                    for d in x.damage_reductions.iter() {
+                        let mut e = MapHasher::new();
+                        if !same(d.horizontal_blocking_angle, 90.0) {
+                            e.insert(
+                                "horizontal_blocking_angle",
+                                float(d.horizontal_blocking_angle),
+                            );
+                        }
+                        if let Some(set) = &d.ty {
+                            e.insert(
+                                "type",
+                                holder_set(set, |id| reference(r, "damage_type", id.0))?,
+                            );
+                        }
+                        e.insert("base", float(d.base))
+                            .insert("factor", float(d.factor));
+                        reductions.push(e.finish());
+                    }
+                    m.insert("damage_reductions", list(reductions));
+                }
+                let f = &x.item_damage;
+                if !(same(f.threshold, 1.0) && same(f.base, 0.0) && same(f.factor, 1.0)) {
+                    m.insert(
+                        "item_damage",
+                        MapHasher::new()
+                            .insert("threshold", float(f.threshold))
+                            .insert("base", float(f.base))
+                            .insert("factor", float(f.factor))
+                            .finish(),
+                    );
+                }
+                if let Some(key) = &x.bypassed_by {
+                    m.insert("bypassed_by", tag_key(key));
+                }
+                if let Some(s) = &x.block_sound {
+                    m.insert("block_sound", sound(s));
+                }
+                if let Some(s) = &x.disable_sound {
+                    m.insert("disabled_sound", sound(s));
+                }
+                m.finish()
+            }
+            Self::DyedColor(x) => int(x.0 as i32),
+            Self::MapColor(x) => int(x.0 as i32),
+            Self::MapId(x) => int(x.0 as i32),
+            Self::MapDecorations(x) => tag(&x.tag),
+            Self::ChargedProjectiles(x) => stacks(&x.items, r)?,
+            Self::BundleContents(x) => stacks(&x.items, r)?,
+            Self::PotionContents(x) => {
+                let mut m = MapHasher::new();
+                if let Some(potion) = x.potion {
+                    m.insert("potion", builtin(potion.name()));
+                }
+                if let Some(color) = x.custom_color {
+                    m.insert("custom_color", int(color as i32));
+                }
+                if !x.custom_effects.is_empty() {
+                    m.insert(
+                        "custom_effects",
+                        list(x.custom_effects.iter().map(mob_effect)),
+                    );
+                }
+                if let Some(name) = x.custom_name {
+                    m.insert("custom_name", string(name.0));
+                }
+                m.finish()
+            }
+            Self::SuspiciousStewEffects(x) => list(x.effects.iter().map(|e| {
+                let mut m = MapHasher::new();
+                m.insert("id", builtin(e.effect.name()));
+                if e.duration != 160 {
+                    m.insert("duration", int(e.duration as i32));
+                }
+                m.finish()
+            })),
+            Self::WritableBookContent(x) => {
+                let mut m = MapHasher::new();
+                if !x.pages.is_empty() {
+                    m.insert(
+                        "pages",
+                        list(x.pages.iter().map(|p| filterable(p, |s| string(s.0)))),
+                    );
+                }
+                m.finish()
+            }
+            Self::WrittenBookContent(x) => {
+                let mut m = MapHasher::new();
+                m.insert("title", filterable(&x.title, |s| string(s.0)))
+                    .insert("author", string(x.author.0));
+                if x.generation != 0 {
+                    m.insert("generation", int(x.generation as i32));
+                }
+                if !x.pages.is_empty() {
+                    m.insert("pages", list(x.pages.iter().map(|p| filterable(p, text))));
+                }
+                if x.resolved {
+                    m.insert("resolved", boolean(true));
+                }
+                m.finish()
+            }
+            Self::Trim(x) => MapHasher::new()
+                .insert(
+                    "material",
+                    holder(
+                        &x.material,
+                        |id| reference(r, "trim_material", id.0),
+                        trim_material,
+                    )?,
+                )
+                .insert(
+                    "pattern",
+                    holder(
+                        &x.pattern,
+                        |id| reference(r, "trim_pattern", id.0),
+                        trim_pattern,
+                    )?,
+                )
+                .finish(),
+            Self::DebugStickState(x) => tag(&x.tag),
+            Self::EntityData(x) => entity_data(x.ty.name(), &x.tag)?,
+            Self::BlockEntityData(x) => entity_data(x.ty.name(), &x.tag)?,
+            Self::Instrument(x) => either_key(x, |h| {
+                holder(h, |id| reference(r, "instrument", id.0), instrument)
+            })?,
+            Self::ProvidesTrimMaterial(x) => either_key(&x.material, |m| Some(trim_material(m)))?,
+            Self::OminousBottleAmplifier(x) => int(x.value as i32),
+            Self::JukeboxPlayable(x) => either_key(&x.song, |h| {
+                holder(h, |id| reference(r, "jukebox_song", id.0), jukebox_song)
+            })?,
+            Self::ProvidesBannerPatterns(x) => tag_key(x),
+            Self::Recipes(x) => tag(&x.0),
+            Self::LodestoneTracker(x) => {
+                let mut m = MapHasher::new();
+                if let Some(target) = &x.target {
+                    let pos = target.pos.to_pos();
+                    m.insert(
+                        "target",
+                        MapHasher::new()
+                            .insert("dimension", ident(target.dimension.identifier))
+                            .insert("pos", int_array(&[pos.x, pos.y, pos.z]))
+                            .finish(),
+                    );
+                }
+                if !x.tracked {
+                    m.insert("tracked", boolean(false));
+                }
+                m.finish()
+            }
+            Self::FireworkExplosion(x) => explosion(x),
+            Self::Fireworks(x) => {
+                let mut m = MapHasher::new();
+                if x.flight_duration != 0 {
+                    m.insert("flight_duration", byte(x.flight_duration as u8 as i8));
+                }
+                if !x.explosions.is_empty() {
+                    m.insert("explosions", list(x.explosions.iter().map(explosion)));
+                }
+                m.finish()
+            }
+            Self::BannerPatterns(x) => {
+                let mut layers = Vec::with_capacity(x.layers.len());
+                for layer in x.layers.iter() {
+                    layers.push(
+                        MapHasher::new()
+                            .insert(
+                                "pattern",
+                                holder(
+                                    &layer.pattern,
+                                    |id| reference(r, "banner_pattern", id.0),
+                                    banner_pattern,
+                                )?,
+                            )
+                            .insert("color", string(layer.color.name()))
+                            .finish(),
+                    );
+                }
+                list(layers)
+            }
+            Self::BaseColor(x)
+            | Self::WolfCollar(x)
+            | Self::TropicalFishBaseColor(x)
+            | Self::TropicalFishPatternColor(x)
+            | Self::CatCollar(x)
+            | Self::SheepColor(x)
+            | Self::ShulkerColor(x) => string(x.name()),
+            Self::PotDecorations(x) => list(x.iter().map(|i| builtin(i.name()))),
+            Self::Container(x) => {
+                let mut slots = Vec::new();
+                for (i, s) in x.items.iter().enumerate() {
+                    if s.count <= 0 || s.id == item::air {
+                        continue;
+                    }
+                    slots.push(
+                        MapHasher::new()
+                            .insert("slot", int(i as i32))
+                            .insert("item", stack(s.id, s.count, &s.components, r)?)
+                            .finish(),
+                    );
+                }
+                list(slots)
+            }
+            Self::BlockState(x) => {
+                let mut m = MapHasher::new();
+                for (k, v) in x.properties.0.iter() {
+                    m.insert(k.0, string(v.0));
+                }
+                m.finish()
+            }
+            Self::Bees(x) => {
+                let mut bees = Vec::with_capacity(x.bees.len());
+                for bee in x.bees.iter() {
+                    let data = &bee.entity_data;
+                    bees.push(
+                        MapHasher::new()
+                            .insert("entity_data", entity_data(data.ty.name(), &data.tag)?)
+                            .insert("ticks_in_hive", int(bee.ticks_in_hive as i32))
+                            .insert("min_ticks_in_hive", int(bee.min_ticks_in_hive as i32))
+                            .finish(),
+                    );
+                }
+                list(bees)
+            }
+            Self::Lock(x) => tag(&x.0),
+            Self::ContainerLoot(x) => tag(&x.0),
+            Self::BreakSound(x) => sound(x),
+            Self::VillagerVariant(x) => reference(r, "villager_type", x.0)?,
+            Self::WolfVariant(x) => reference(r, "wolf_variant", x.0)?,
+            Self::WolfSoundVariant(x) => reference(r, "wolf_sound_variant", x.0)?,
+            Self::PigVariant(x) => reference(r, "pig_variant", x.0)?,
+            Self::CowVariant(x) => reference(r, "cow_variant", x.0)?,
+            Self::FrogVariant(x) => reference(r, "frog_variant", x.0)?,
+            Self::CatVariant(x) => reference(r, "cat_variant", x.0)?,
+            Self::ChickenVariant(x) => either_key(x, |id| reference(r, "chicken_variant", id.0))?,
+            Self::ZombieNautilusVariant(x) => {
+                either_key(x, |id| reference(r, "zombie_nautilus_variant", id.0))?
+            }
+            Self::PaintingVariant(x) => holder(
+                x,
+                |id| reference(r, "painting_variant", id.0),
+                painting_variant,
+            )?,
+            Self::FoxVariant(x) => string(x.name()),
+            Self::SalmonSize(x) => string(x.name()),
+            Self::ParrotVariant(x) => string(x.name()),
+            Self::TropicalFishPattern(x) => string(x.name()),
+            Self::MooshroomVariant(x) => string(x.name()),
+            Self::RabbitVariant(x) => string(x.name()),
+            Self::HorseVariant(x) => string(x.name()),
+            Self::LlamaVariant(x) => string(x.name()),
+            Self::AxolotlVariant(x) => string(x.name()),
+            Self::UseEffects(x) => {
+                let mut m = MapHasher::new();
+                if x.can_sprint {
+                    m.insert("can_sprint", boolean(true));
+                }
+                if !x.interact_vibrations {
+                    m.insert("interact_vibrations", boolean(false));
+                }
+                if !same(x.speed_multiplier, 0.2) {
+                    m.insert("speed_multiplier", float(x.speed_multiplier));
+                }
+                m.finish()
+            }
+            Self::CanPlaceOn(x) | Self::CanBreak(x) => adventure_predicate(x, r)?,
+            Self::AttackRange(x) => {
+                let mut m = MapHasher::new();
+                for (key, value, default) in [
+                    ("min_reach", x.min_range, 0.0),
+                    ("max_reach", x.max_range, 3.0),
+                    ("min_creative_reach", x.min_creative_range, 0.0),
+                    ("max_creative_reach", x.max_creative_range, 5.0),
+                    ("hitbox_margin", x.hitbox_margin, 0.3),
+                    ("mob_factor", x.mob_factor, 1.0),
+                ] {
+                    if !same(value, default) {
+                        m.insert(key, float(value));
+                    }
+                }
+                m.finish()
+            }
+            Self::PiercingWeapon(x) => {
+                let mut m = MapHasher::new();
+                if !x.deals_knockback {
+                    m.insert("deals_knockback", boolean(false));
+                }
+                if x.dismounts {
+                    m.insert("dismounts", boolean(true));
+                }
+                if let Some(s) = &x.sound {
+                    m.insert("sound", sound(s));
+                }
+                if let Some(s) = &x.hit_sound {
+                    m.insert("hit_sound", sound(s));
+                }
+                m.finish()
+            }
+            Self::KineticWeapon(x) => {
+                let mut m = MapHasher::new();
+                if x.contact_cooldown_ticks != 10 {
+                    m.insert(
+                        "contact_cooldown_ticks",
+                        int(x.contact_cooldown_ticks as i32),
+                    );
+                }
+                if x.delay_ticks != 0 {
+                    m.insert("delay_ticks", int(x.delay_ticks as i32));
+                }
+                if let Some(c) = &x.dismount_conditions {
+                    m.insert("dismount_conditions", kinetic_condition(c));
+                }
+                if let Some(c) = &x.knockback_conditions {
+                    m.insert("knockback_conditions", kinetic_condition(c));
+                }
+                if let Some(c) = &x.damage_conditions {
+                    m.insert("damage_conditions", kinetic_condition(c));
+                }
+                if !same(x.forward_movement, 0.0) {
+                    m.insert("forward_movement", float(x.forward_movement));
+                }
+                if !same(x.damage_multiplier, 1.0) {
+                    m.insert("damage_multiplier", float(x.damage_multiplier));
+                }
+                if let Some(s) = &x.sound {
+                    m.insert("sound", sound(s));
+                }
+                if let Some(s) = &x.hit_sound {
+                    m.insert("hit_sound", sound(s));
+                }
+                m.finish()
+            }
+            Self::SwingAnimation(x) => {
+                let mut m = MapHasher::new();
+                if !matches!(x.ty, SwingAnimationType::Whack) {
+                    m.insert("type", string(x.ty.name()));
+                }
+                if x.duration != 6 {
+                    m.insert("duration", int(x.duration as i32));
+                }
+                m.finish()
+            }
+            // Not persisted, so hashed by the ID it is synchronised with.
+            Self::MapPostProcessing(x) => int(*x as i32),
+            Self::Profile(x) => profile(x),
+        })
+    }
+}
+
+impl ItemStack<'_> {
+    /// The stack as sent in `ContainerClick`, with each added component
+    /// replaced by its [`hash`](TypedDataComponent::hash).
+    pub fn hashed(&self, registries: &dyn RegistryLookup) -> Option<HashedStack<'static>> {
+        let added = self
+            .components
+            .patch_add
+            .iter()
+            .map(|c| Some((c.ty(), c.hash(registries)?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(HashedStack {
+            item: self.id,
+            count: self.count as u32,
+            components: HashedPatchMap {
+                added_components: Map(List::Owned(added)),
+                removed_components: List::Owned(self.components.patch_remove.to_vec()),
+            },
+        })
+    }
+}
+
+impl HashedStack<'_> {
+    /// Whether the client view of a slot agrees with `stack`, in any
+    /// component order.
+    pub fn matches(&self, stack: &ItemStack, registries: &dyn RegistryLookup) -> bool {
+        let patch = &stack.components;
+        let added = &self.components.added_components.0;
+        let removed = &self.components.removed_components;
+        self.item == stack.id
+            && self.count == stack.count as u32
+            && added.len() == patch.patch_add.len()
+            && removed.len() == patch.patch_remove.len()
+            && patch.patch_remove.iter().all(|ty| removed.contains(ty))
+            && patch.patch_add.iter().all(|c| match c.hash(registries) {
+                Some(hash) => added.contains(&(c.ty(), hash)),
+                None => false,
+            })
+    }
+}
@@ -3,14 +3,14 @@ use crate::attribute::AttributeModifier;
 use crate::inventory::EquipmentSlotGroup;
 use haya_collection::List;
 use minecraft_data::attribute;
-use mser::{Error, Read, Reader, V21, Write, Writer};
+use mser::{Error, Read, Reader, Skip, V21, Write, Writer};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ItemAttributeModifiers<'a> {
     pub modifiers: List<'a, Entry<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Entry<'a> {
     pub attribute: attribute,
     pub modifier: AttributeModifier<'a>,
@@ -18,7 +18,7 @@ pub struct Entry<'a> {
     pub display: Display,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum Display {
     Default,
     Hidden,
@@ -35,6 +35,16 @@ impl<'a> Read<'a> for Display {
     }
 }
 
+impl Skip for Display {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        if V21::read(buf)?.0 == 2 {
+            ComponentRaw::skip(buf)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 impl Write for Display {
     unsafe fn write(&self, w: &mut Writer) {
         unsafe {
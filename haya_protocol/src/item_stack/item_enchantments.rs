@@ -1,10 +1,10 @@
 use crate::registry::EnchntmentRef;
 use haya_collection::Map;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ItemEnchantments<'a>(pub Map<'a, EnchntmentRef, Level>);
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Level(#[mser(varint, filter = validate_enchantment_level)] pub i32);
 
 fn validate_enchantment_level(level: &i32) -> bool {
@@ -2,7 +2,7 @@ use crate::Holder;
 use crate::sound::SoundEvent;
 use minecraft_data::sound_event;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct KineticWeapon<'a> {
     #[mser(varint)]
     pub contact_cooldown_ticks: u32,
@@ -17,7 +17,7 @@ pub struct KineticWeapon<'a> {
     pub hit_sound: Option<Holder<SoundEvent<'a>, sound_event>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Condition {
     #[mser(varint)]
     pub max_duration_ticks: u32,
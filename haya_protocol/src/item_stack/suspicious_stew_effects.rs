@@ -1,12 +1,12 @@
 use haya_collection::List;
 use minecraft_data::mob_effect;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct SuspiciousStewEffects<'a> {
     pub effects: List<'a, Entry>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Entry {
     pub effect: mob_effect,
     #[mser(varint)]
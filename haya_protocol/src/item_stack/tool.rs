@@ -2,14 +2,14 @@ use crate::HolderSet;
 use haya_collection::List;
 use minecraft_data::block;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Rule<'a> {
     pub blocks: HolderSet<'a, block>,
     pub speed: Option<f32>,
     pub correct_for_drops: Option<bool>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct Tool<'a> {
     pub rules: List<'a, Rule<'a>>,
     pub default_mining_speed: f32,
@@ -9,14 +9,16 @@ use haya_ident::{Ident, ResourceKey};
 use haya_math::{BlockPosPacked, Direction, FVec3, IVec3};
 use haya_nbt::Tag;
 use minecraft_data::data_component_type;
-use mser::{Either, Error, Read, Reader, Utf8, V21, V32, Write, Writer};
+use mser::{Either, Error, ErrorKind, Read, Reader, Skip, Utf8, V21, V32, Write, Writer, skip_n};
 
 pub mod advancement;
 pub mod attribute;
 pub mod block;
 pub mod chat;
 pub mod clientbound;
+pub mod codec;
 pub mod command;
+pub mod connection;
 pub mod crafting;
 pub mod debug;
 pub mod effect;
@@ -54,7 +56,13 @@ pub struct Translatable<'a>(pub &'a str, pub &'a str);
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ComponentJson<'a>(pub Utf8<'a, 262144>);
 
-#[derive(Clone, Serialize, Deserialize)]
+impl ComponentJson<'_> {
+    pub fn parse(&self) -> Result<haya_text::chat::TextComponent, Error> {
+        haya_text::chat::TextComponent::from_json(self.0.0)
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(transparent)]
 pub struct ComponentRaw(pub Tag);
 
@@ -208,12 +216,12 @@ impl FromStr for Difficulty {
             "easy" => Self::Easy,
             "normal" => Self::Normal,
             "hard" => Self::Hard,
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         })
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum Rarity {
@@ -234,7 +242,7 @@ impl Rarity {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum HolderSet<'a, T> {
     Named(Ident<'a>),
     Direct(List<'a, T>),
@@ -249,14 +257,26 @@ impl<'a, T: Read<'a>> Read<'a> for HolderSet<'a, T> {
         } else {
             let len2 = (len - 1) as usize;
             let mut vec = Vec::with_capacity(capacity_fix(len2));
-            for _ in 0..len2 {
-                vec.push(T::read(buf)?);
+            for i in 0..len2 {
+                match T::read(buf) {
+                    Ok(x) => vec.push(x),
+                    Err(e) => return Err(e.with_index(i)),
+                }
             }
             Ok(Self::Direct(List::Owned(vec)))
         }
     }
 }
 
+impl<T: Skip> Skip for HolderSet<'_, T> {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        match V32::read(buf)?.0 {
+            0 => Ident::skip(buf),
+            len => skip_n::<T>(buf, (len - 1) as usize),
+        }
+    }
+}
+
 impl<T: Write> Write for HolderSet<'_, T> {
     unsafe fn write(&self, w: &mut Writer) {
         match self {
@@ -287,13 +307,24 @@ impl<T: Write> Write for HolderSet<'_, T> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum Holder<T, R> {
     Reference(R),
     Direct(T),
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+/// Reference ids are not checked against their registry.
+impl<T: Skip, R> Skip for Holder<T, R> {
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        if V32::read(buf)?.0 == 0 {
+            T::skip(buf)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum DyeColor {
@@ -338,7 +369,7 @@ impl DyeColor {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Skip)]
 pub struct Filterable<T> {
     pub raw: T,
     pub filtered: Option<T>,
@@ -366,7 +397,7 @@ impl<'a, T: Read<'a>> Read<'a> for Filterable<T> {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct LockCode(pub Tag);
 
 #[derive(Clone)]
@@ -415,7 +446,7 @@ impl<'a, T: Read<'a>> Read<'a> for Weighted<T> {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, Serialize, Deserialize, Skip)]
 #[repr(u8)]
 #[mser(varint)]
 pub enum HeightmapType {
@@ -440,7 +471,7 @@ impl HeightmapType {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Skip)]
 pub struct BitSet<'a>(pub List<'a, u64>);
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -496,7 +527,7 @@ impl Write for OptionalGameType {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct GlobalPos<'a> {
     pub dimension: ResourceKey<'a>,
     pub pos: BlockPosPacked,
@@ -982,4 +1013,931 @@ mod tests {
         assert_eq!(V32::read(&mut reader).unwrap().0, 0);
         assert!(reader.is_empty());
     }
+
+    #[test]
+    #[cfg(feature = "detail")]
+    fn test_error_path() {
+        let mut data = alloc::vec![LoginFinished::ID as u8];
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&[3, b'a']);
+        let mut reader = Reader::new(&data);
+        let id = clientbound_login::read(&mut reader).unwrap();
+        assert_eq!(id, LoginFinished::ID);
+        let e = match LoginFinished::read(&mut reader) {
+            Ok(_) => panic!(),
+            Err(e) => e,
+        };
+        assert_eq!(e.kind(), Some(mser::ErrorKind::UnexpectedEof));
+        assert_eq!(e.offset(), Some(18));
+        assert_eq!(
+            alloc::format!("{}", e.path()),
+            "LoginFinished.game_profile.name"
+        );
+    }
+
+    #[test]
+    fn test_packet_enum() {
+        let packet = LoginFinished {
+            game_profile: GameProfileRef {
+                id: Uuid::nil(),
+                name: Utf8("abc"),
+                properties: PropertyMapRef(List::Borrowed(&[])),
+            },
+        };
+        let mut data = alloc::vec::Vec::new();
+        crate::types::encode_packet(&packet, &mut data);
+        let mut reader = Reader::new(&data);
+        let p = clientbound::ClientboundLoginPacket::read(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(p.id(), LoginFinished::ID);
+        match &p {
+            clientbound::ClientboundLoginPacket::login_finished(x) => {
+                assert_eq!(x.game_profile.name.0, "abc");
+            }
+            _ => panic!(),
+        }
+        let mut out = alloc::vec::Vec::with_capacity(p.len_s());
+        unsafe {
+            mser::write_unchecked(out.as_mut_ptr(), &p);
+            out.set_len(p.len_s());
+        }
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_skip() {
+        use crate::clientbound::game::{
+            BlockEntityInfo, ChunkData, LevelChunkWithLight, LightData,
+        };
+        use haya_nbt::{CompoundTag, ListTag, StringTag, TagRef};
+        use minecraft_data::block_entity_type;
+        use mser::{ByteArray, Skip};
+
+        let mut inner = CompoundTag::new();
+        inner.push(
+            StringTag::from_utf8("id"),
+            Tag::String(StringTag::from_utf8("x")),
+        );
+        inner.push(StringTag::from_utf8("n"), Tag::IntArray(alloc::vec![1, 2]));
+        let mut tag = CompoundTag::new();
+        tag.push(
+            StringTag::from_utf8("items"),
+            Tag::List(ListTag::Compound(alloc::vec![inner.clone(), inner])),
+        );
+        tag.push(
+            StringTag::from_utf8("f"),
+            Tag::List(ListTag::Float(alloc::vec![1.0; 3])),
+        );
+        let heightmaps = [(
+            HeightmapType::MotionBlocking,
+            List::Borrowed(&[1u64, 2][..]),
+        )];
+        let owned = Tag::Compound(tag);
+        let mut nbt = alloc::vec::Vec::with_capacity(owned.len_s());
+        unsafe {
+            mser::write_unchecked(nbt.as_mut_ptr(), &owned);
+            nbt.set_len(owned.len_s());
+        }
+        let block_entities = [BlockEntityInfo {
+            packed_xz: 0,
+            y: 64,
+            ty: block_entity_type::chest,
+            tag: TagRef::read(&mut Reader::new(&nbt)).unwrap(),
+        }];
+        let light = [ByteArray(&[0u8; 2048][..])];
+        let packet = LevelChunkWithLight {
+            pos: haya_math::ChunkPos { x: 1, z: -1 },
+            chunk_data: ChunkData {
+                heightmaps: Map(List::Borrowed(&heightmaps)),
+                data: ByteArray(&[7; 100]),
+                block_entities_data: List::Borrowed(&block_entities),
+            },
+            light_data: LightData {
+                sky_y_mask: BitSet(List::Borrowed(&[3])),
+                block_y_mask: BitSet(List::Borrowed(&[])),
+                empty_sky_y_mask: BitSet(List::Borrowed(&[])),
+                empty_block_y_mask: BitSet(List::Borrowed(&[])),
+                sky_updates: List::Borrowed(&light),
+                block_updates: List::Borrowed(&[]),
+            },
+        };
+        let mut data = alloc::vec::Vec::with_capacity(packet.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), &packet);
+            data.set_len(packet.len_s());
+        }
+        let mut reader = Reader::new(&data);
+        LevelChunkWithLight::skip(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        let mut reader = Reader::new(&data[..data.len() - 1]);
+        assert!(LevelChunkWithLight::skip(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_item_stack_skip() {
+        use crate::item_stack::item_enchantments::{ItemEnchantments, Level};
+        use crate::item_stack::{DataComponentPatch, ItemStack, Repairable, TypedDataComponent};
+        use crate::registry::EnchntmentRef;
+        use crate::sound::SoundEvent;
+        use haya_nbt::StringTag;
+        use minecraft_data::{data_component_type, item};
+        use mser::Skip;
+
+        let components = [
+            TypedDataComponent::Damage(300),
+            TypedDataComponent::CustomName(ComponentRaw(Tag::String(StringTag::from_utf8("a")))),
+            TypedDataComponent::Enchantments(ItemEnchantments(Map(List::Owned(alloc::vec![(
+                EnchntmentRef(2),
+                Level(5)
+            )])))),
+            TypedDataComponent::BreakSound(Holder::Direct(SoundEvent {
+                location: Ident::new("block.stone.break").unwrap(),
+                fixed_range: Some(16.0),
+            })),
+            TypedDataComponent::Repairable(Repairable {
+                items: HolderSet::Direct(List::Owned(alloc::vec![item::stick, item::diamond])),
+            }),
+            TypedDataComponent::Unbreakable,
+        ];
+        let stack = ItemStack {
+            id: item::diamond_sword,
+            count: 1,
+            components: DataComponentPatch {
+                patch_add: List::Borrowed(&components),
+                patch_remove: List::Borrowed(&[data_component_type::lore]),
+            },
+        };
+        let mut data = alloc::vec::Vec::with_capacity(stack.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), &stack);
+            data.set_len(stack.len_s());
+        }
+        let mut reader = Reader::new(&data);
+        ItemStack::skip(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        let mut reader = Reader::new(&data[..data.len() - 1]);
+        assert!(ItemStack::skip(&mut reader).is_err());
+    }
+
+    #[test]
+    #[ignore = "needs haya_data/generated/registry_data.txt from generator/datagen.sh"]
+    fn test_vanilla_registry_data() {
+        use crate::clientbound::configuration::{CORE_PACK, RegistryData};
+        use minecraft_data::REGISTRY_DATA;
+
+        let full = RegistryData::vanilla(&[]).unwrap();
+        let known = RegistryData::vanilla(&[CORE_PACK]).unwrap();
+        assert_eq!(full.len(), REGISTRY_DATA.len());
+        for ((a, b), registry) in full.iter().zip(&known).zip(REGISTRY_DATA) {
+            assert_eq!(a.registry.identifier.path(), registry.registry);
+            assert_eq!(a.entries.len(), registry.entries.len());
+            assert!(a.entries.iter().all(|x| x.data.is_some()));
+            assert!(b.entries.iter().all(|x| x.data.is_none()));
+        }
+
+        let entry = |registry: &str, name: &str| {
+            full.iter()
+                .find(|x| x.registry.identifier.path() == registry)
+                .and_then(|x| x.entries.iter().find(|e| e.id.path() == name))
+                .is_some()
+        };
+        assert!(entry("dimension_type", "overworld"));
+        assert!(entry("dimension_type", "the_nether"));
+        assert!(entry("worldgen/biome", "plains"));
+        assert!(entry("damage_type", "fall"));
+        assert!(!entry("dimension_type", "plains"));
+    }
+
+    #[test]
+    fn test_registry_data() {
+        use crate::clientbound::configuration::{RegistryData, RegistryEntry};
+
+        let plains = RegistryData {
+            registry: ResourceKey {
+                identifier: Ident::new("worldgen/biome").unwrap(),
+            },
+            entries: List::Owned(alloc::vec![RegistryEntry {
+                id: haya_ident::parse_ident(b"minecraft:plains").unwrap(),
+                data: None,
+            }]),
+        };
+        let mut data = alloc::vec::Vec::with_capacity(plains.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), &plains);
+            data.set_len(plains.len_s());
+        }
+        assert!(data.windows(16).any(|x| x == b"minecraft:plains"));
+        let read = RegistryData::read(&mut Reader::new(&data)).unwrap();
+        assert_eq!(read.entries[0].id, Ident::new("plains").unwrap());
+    }
+
+    #[test]
+    fn test_server_status() {
+        use crate::clientbound::status::ServerStatus;
+        use haya_nbt::Tag;
+        use haya_text::json::json_to_tag;
+
+        let json = r#"{"description":{"text":"A \"Minecraft\" Server\n","color":"gold","extra":[{"text":"é😀","bold":true}]},"players":{"max":20,"online":1,"sample":[{"name":"Notch","id":"069a79f4-44e9-4726-a5be-fca90e38aaf5"}]},"version":{"name":"1.21.11","protocol":774},"favicon":"data:image/png;base64,AA==","enforcesSecureChat":true}"#;
+        let status = ServerStatus::from_json(json).unwrap();
+        let players = status.players.as_ref().unwrap();
+        assert_eq!((players.max, players.online), (20, 1));
+        assert_eq!(&*players.sample[0].name, "Notch");
+        assert_eq!(status.version.as_ref().unwrap().protocol, 774);
+        assert_eq!(status.description.siblings.len(), 1);
+        assert!(status.enforces_secure_chat);
+        let encoded = status.to_json();
+        let again = ServerStatus::from_json(&encoded).unwrap();
+        assert_eq!(again.to_json(), encoded);
+        assert!(encoded.contains(r#""bold":true"#));
+        assert!(encoded.contains(r#""enforcesSecureChat":true"#));
+        assert!(encoded.contains(r#""max":20,"online":1"#));
+        assert_eq!(again.players.unwrap().sample[0].id, players.sample[0].id);
+        assert!(ServerStatus::from_json("{\"players\":1}").is_err());
+        assert!(ServerStatus::from_json(r#"{"players":{"max":+20,"online":1}}"#).is_err());
+        assert!(matches!(json_to_tag("2e+1"), Ok(Tag::Double(20.0))));
+        assert!(json_to_tag("+1").is_err());
+        assert!(json_to_tag("1+1").is_err());
+        let exp = ServerStatus::from_json(r#"{"version":{"name":"","protocol":-7}}"#).unwrap();
+        assert_eq!(exp.version.unwrap().protocol, -7);
+        assert!(ServerStatus::from_json("{} {}").is_err());
+    }
+
+    #[test]
+    #[ignore = "needs haya_data/generated/tags.txt from generator/datagen.sh"]
+    fn test_update_tags() {
+        use crate::clientbound::common::UpdateTags;
+        use minecraft_data::TAG_REGISTRIES;
+
+        let tags = UpdateTags::vanilla();
+        let mut data = alloc::vec::Vec::with_capacity(tags.len_s());
+        unsafe {
+            mser::write_unchecked(data.as_mut_ptr(), &tags);
+            data.set_len(tags.len_s());
+        }
+        let mut reader = Reader::new(&data);
+        let read = UpdateTags::read(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(read.tags.0.len(), TAG_REGISTRIES.len());
+        for ((key, entries), registry) in read.tags.0.iter().zip(TAG_REGISTRIES) {
+            assert_eq!(key.identifier.path(), registry.registry);
+            for ((name, ids), (&n, &i)) in entries
+                .0
+                .iter()
+                .zip(registry.names.iter().zip(registry.ids))
+            {
+                assert_eq!(name.path(), n);
+                assert_eq!(&*ids.0, i);
+            }
+        }
+
+        let blocks = TAG_REGISTRIES
+            .iter()
+            .find(|x| x.registry == "block")
+            .unwrap();
+        let logs = blocks.names.iter().position(|&x| x == "logs").unwrap();
+        assert!(blocks.ids[logs].contains(&(minecraft_data::block::oak_log as u32)));
+        assert!(!blocks.ids[logs].contains(&(minecraft_data::block::stone as u32)));
+    }
+
+    #[test]
+    fn test_command_tree() {
+        use crate::command::reader::{ArgumentValue, CommandErrorKind, Coordinates};
+        use crate::command::tree::CommandTree;
+        use crate::command::{CommandArgument, CommandNode};
+        use crate::serverbound::game::CommandSuggestion;
+        use haya_text::selector::{EntitySelector, Variable};
+
+        let mut tree = CommandTree::new();
+        let tp = tree.literal(CommandTree::<u8>::ROOT, "tp");
+        let pos = tree.argument(tp, "pos", CommandArgument::Vec3);
+        tree.executes(pos, 1);
+        let time = tree.literal(CommandTree::<u8>::ROOT, "time");
+        let add = tree.literal(time, "add");
+        let ticks = tree.argument(add, "ticks", CommandArgument::Time { min: 0 });
+        tree.executes(ticks, 2);
+        let give = tree.literal(CommandTree::<u8>::ROOT, "give");
+        tree.requires(give, 2);
+        let target = tree.argument(
+            give,
+            "target",
+            CommandArgument::Entity {
+                single: false,
+                players_only: true,
+            },
+        );
+        let item = tree.argument(target, "item", CommandArgument::ResourceLocation);
+        let count = tree.argument(item, "count", CommandArgument::Integer { min: 1, max: 64 });
+        tree.executes(item, 3).executes(count, 3);
+        let run = tree.literal(CommandTree::<u8>::ROOT, "run");
+        tree.redirect(run, CommandTree::<u8>::ROOT);
+        assert_eq!(tree.literal(CommandTree::<u8>::ROOT, "tp"), tp);
+
+        let parsed = tree.parse("tp ~ 64 10", 0).unwrap();
+        assert_eq!(*parsed.command, 1);
+        let Some(ArgumentValue::Vec3(Coordinates::World(c))) = parsed.get("pos") else {
+            panic!()
+        };
+        assert!(c[0].relative && c[1].value == 64.0 && c[2].value == 10.5);
+        assert!(matches!(
+            tree.parse("run time add 1.5s", 0).unwrap().get("ticks"),
+            Some(ArgumentValue::Time(30))
+        ));
+
+        let parsed = tree
+            .parse("give @a[tag=x,name=\"a b\"] minecraft:stone 5", 2)
+            .unwrap();
+        let Some(ArgumentValue::Entity(EntitySelector::Select(target))) = parsed.get("target")
+        else {
+            panic!()
+        };
+        assert_eq!(target.variable, Variable::AllPlayers);
+        assert_eq!(target.options.len(), 2);
+        assert!(
+            matches!(parsed.get("item"), Some(ArgumentValue::ResourceLocation(id)) if id.path() == "stone")
+        );
+        assert!(matches!(
+            parsed.get("count"),
+            Some(ArgumentValue::Integer(5))
+        ));
+
+        let err = |s| tree.parse(s, 2).unwrap_err();
+        assert_eq!(err("give @a stone 65").kind, CommandErrorKind::TooHigh);
+        assert_eq!(err("give @a stone 65").cursor, 14);
+        assert_eq!(err("tp 1 2").kind, CommandErrorKind::IncompleteCoordinates);
+        assert_eq!(err("tp ^1 ~ 2").kind, CommandErrorKind::MixedCoordinates);
+        assert_eq!(err("time add").kind, CommandErrorKind::IncompleteCommand);
+        assert_eq!(err("nope").kind, CommandErrorKind::UnknownCommand);
+        assert_eq!(
+            tree.parse("give @a stone", 0).unwrap_err().kind,
+            CommandErrorKind::UnknownCommand
+        );
+
+        let commands = tree.commands(0);
+        assert_eq!(commands.entries.len(), 7);
+        let CommandNode::Root { children, .. } = &commands.entries[0] else {
+            panic!()
+        };
+        assert_eq!(children.len(), 3);
+        assert!(matches!(
+            &commands.entries[3],
+            CommandNode::Literal {
+                redirect: Some(V21(0)),
+                ..
+            }
+        ));
+        let mut buf = alloc::vec![0; commands.len_s()];
+        unsafe { mser::write_unchecked(buf.as_mut_ptr(), &commands) };
+        let decoded = crate::clientbound::game::Commands::read(&mut Reader::new(&buf)).unwrap();
+        assert_eq!(decoded.entries.len(), 7);
+        assert_eq!(tree.commands(2).entries.len(), 11);
+
+        let request = CommandSuggestion {
+            id: 9,
+            command: Utf8("/run t"),
+        };
+        let response = tree.suggest(&request, 0);
+        assert_eq!((response.id, response.start, response.length), (9, 5, 1));
+        assert_eq!(response.suggestions.len(), 2);
+        assert_eq!(response.suggestions[0].text.0, "time");
+        assert_eq!(response.suggestions[1].text.0, "tp");
+        let request = CommandSuggestion {
+            id: 1,
+            command: Utf8("time a"),
+        };
+        let response = tree.suggest(&request, 0);
+        assert_eq!((response.start, response.suggestions[0].text.0), (5, "add"));
+        let request = CommandSuggestion {
+            id: 2,
+            command: Utf8("/run t\u{e9}"),
+        };
+        let response = tree.suggest(&request, 0);
+        assert_eq!((response.start, response.length), (5, 2));
+        assert!(response.suggestions.is_empty());
+        let request = CommandSuggestion {
+            id: 3,
+            command: Utf8("give @a[name=\"\u{1f600}\"] st"),
+        };
+        let response = tree.suggest(&request, 2);
+        assert_eq!((response.start, response.length), (19, 2));
+    }
+
+    #[test]
+    fn test_secure_chat() {
+        use crate::chat::session::*;
+        use crate::chat::{LastSeenMessagesUpdate, MessageSignaturePacked};
+        use alloc::vec::Vec;
+        use mser::FixedByteArray;
+
+        struct Signer;
+        impl Signer {
+            fn sign(&self, payload: &[u8]) -> SignatureBytes {
+                let mut s = [0u8; 256];
+                for (i, &b) in payload.iter().enumerate() {
+                    s[i % 256] = s[i % 256].wrapping_mul(31).wrapping_add(b);
+                }
+                s
+            }
+        }
+        impl SignatureVerifier for Signer {
+            fn verify(&self, payload: &[u8], signature: &SignatureBytes) -> bool {
+                self.sign(payload) == *signature
+            }
+        }
+
+        let sender = Uuid::from_u128(1);
+        let session = Uuid::from_u128(2);
+        let body = |content, timestamp, last_seen| SignedMessageBody {
+            content,
+            timestamp: MilliSeconds(timestamp),
+            salt: 7,
+            last_seen,
+        };
+        let sign = |b: &SignedMessageBody, index| {
+            let mut payload = Vec::new();
+            b.signed_payload(
+                &SignedMessageLink {
+                    index,
+                    sender,
+                    session_id: session,
+                },
+                &mut payload,
+            );
+            Signer.sign(&payload)
+        };
+
+        let first = body("hi", 5_999, Vec::new());
+        let mut payload = Vec::new();
+        first.signed_payload(
+            &SignedMessageLink {
+                index: 3,
+                sender,
+                session_id: session,
+            },
+            &mut payload,
+        );
+        assert_eq!(payload.len(), 4 + 16 + 16 + 4 + 8 + 8 + 4 + 2 + 4);
+        assert_eq!(payload[..4], [0, 0, 0, 1]);
+        assert_eq!(payload[36..40], [0, 0, 0, 3]);
+        assert_eq!(payload[48..56], 5u64.to_be_bytes());
+        assert_eq!(&payload[60..62], b"hi");
+
+        let mut chain = SignedMessageChain::new(sender, session, MilliSeconds(100_000));
+        let s0 = sign(&first, 0);
+        assert_eq!(
+            chain
+                .unpack(&Signer, &first, Some(&s0), MilliSeconds(6_000))
+                .unwrap()
+                .index,
+            0
+        );
+        let second = body("again", 7_000, alloc::vec![s0]);
+        let s1 = sign(&second, 1);
+        assert_eq!(
+            chain.unpack(&Signer, &second, None, MilliSeconds(7_000)),
+            Err(ChatError::MissingSignature)
+        );
+        assert_eq!(
+            chain.unpack(&Signer, &second, Some(&s0), MilliSeconds(7_000)),
+            Err(ChatError::InvalidSignature)
+        );
+        assert_eq!(
+            chain.unpack(&Signer, &second, Some(&s1), MilliSeconds(7_000)),
+            Err(ChatError::ChainBroken)
+        );
+        let mut chain = SignedMessageChain::new(sender, session, MilliSeconds(100_000));
+        chain
+            .unpack(&Signer, &first, Some(&s0), MilliSeconds(6_000))
+            .unwrap();
+        assert_eq!(
+            chain
+                .unpack(&Signer, &second, Some(&s1), MilliSeconds(7_000))
+                .unwrap()
+                .index,
+            1
+        );
+        assert_eq!(
+            chain.unpack(&Signer, &first, Some(&s0), MilliSeconds(7_000)),
+            Err(ChatError::OutOfOrderChat)
+        );
+        assert_eq!(
+            SignedMessageChain::new(sender, session, MilliSeconds(0)).unpack(
+                &Signer,
+                &first,
+                Some(&s0),
+                MilliSeconds(6_000)
+            ),
+            Err(ChatError::ExpiredProfileKey)
+        );
+
+        let mut cache = SignatureCache::new();
+        assert!(matches!(
+            cache.pack(&s0),
+            MessageSignaturePacked::FullSignature(_)
+        ));
+        cache.push(&first, Some(&s0));
+        cache.push(&second, Some(&s1));
+        assert_eq!(cache.index_of(&s1), Some(0));
+        assert_eq!(cache.index_of(&s0), Some(1));
+        let packed = second.pack(&cache);
+        assert!(matches!(
+            packed.last_seen.entries[0],
+            MessageSignaturePacked::Index(1)
+        ));
+        assert_eq!(cache.unpack(&packed.last_seen.entries[0]), Some(&s0));
+
+        let entry = &packed.last_seen.entries[0];
+        let buf = unsafe {
+            let mut buf = Vec::with_capacity(entry.len_s());
+            mser::write_unchecked(buf.as_mut_ptr(), entry);
+            buf.set_len(entry.len_s());
+            buf
+        };
+        let read = MessageSignaturePacked::read(&mut Reader::new(&buf)).unwrap();
+        assert!(matches!(read, MessageSignaturePacked::Index(1)));
+
+        let mut validator = LastSeenValidator::new();
+        validator.add_pending(&s0);
+        validator.add_pending(&s0);
+        validator.add_pending(&s1);
+        assert_eq!(validator.tracked_count(), 22);
+        let apply = |v: &mut LastSeenValidator, offset, bits: u32, checksum| {
+            let acknowledged = bits.to_le_bytes();
+            v.apply_update(&LastSeenMessagesUpdate {
+                offset,
+                acknowledged: FixedByteArray(acknowledged[..3].try_into().unwrap()),
+                checksum,
+            })
+        };
+        assert_eq!(
+            apply(&mut validator.clone(), 3, 0, 0),
+            Err(ChatError::InvalidOffset)
+        );
+        assert_eq!(
+            apply(&mut validator.clone(), 2, 1 << 20, 0),
+            Err(ChatError::TooManyAcknowledged)
+        );
+        assert_eq!(
+            apply(&mut validator.clone(), 2, 1 << 19 | 1, 0),
+            Err(ChatError::UnknownAcknowledged)
+        );
+        assert_eq!(
+            apply(&mut validator.clone(), 2, 1 << 18, 0xAB ^ checksum(&[s0])),
+            Err(ChatError::ChecksumMismatch)
+        );
+        let seen = apply(&mut validator, 2, 1 << 18 | 1 << 19, checksum(&[s0, s1])).unwrap();
+        assert_eq!(seen, [s0, s1]);
+        assert_eq!(validator.tracked_count(), 20);
+        assert_eq!(
+            apply(&mut validator, 0, 1 << 19, 0),
+            Err(ChatError::IgnoredAcknowledged)
+        );
+    }
+
+    #[test]
+    fn test_component_hash() {
+        use crate::advancement::{BlockPredicate, DataComponentMatchers};
+        use crate::entity::PaintingVariant;
+        use crate::item_stack::hash::*;
+        use crate::item_stack::item_enchantments::{ItemEnchantments, Level};
+        use crate::item_stack::{
+            AdventureModePredicate, DataComponentPatch, ItemStack, LodestoneTracker,
+            TypedDataComponent,
+        };
+        use crate::registry::EnchntmentRef;
+        use haya_collection::Map;
+        use haya_ident::{Ident, ResourceKey};
+        use haya_math::BlockPos;
+        use haya_nbt::{CompoundTag, ListTag, StringTag, Tag};
+        use minecraft_data::item;
+
+        struct Registries;
+        impl RegistryLookup for Registries {
+            fn name(&self, registry: &str, id: u32) -> Option<Ident<'_>> {
+                match (registry, id) {
+                    ("enchantment", 0) => Ident::new("sharpness"),
+                    _ => None,
+                }
+            }
+        }
+
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+        assert_eq!(int(1), crc32c(&[8, 1, 0, 0, 0]));
+        assert_eq!(string("a"), crc32c(&[12, 1, 0, 0, 0, b'a', 0]));
+        assert_eq!(empty_map(), crc32c(&[2, 3]));
+        assert_eq!(
+            MapHasher::new()
+                .insert("a", int(1))
+                .insert("b", int(2))
+                .finish(),
+            MapHasher::new()
+                .insert("b", int(2))
+                .insert("a", int(1))
+                .finish()
+        );
+
+        let enchantments = TypedDataComponent::Enchantments(ItemEnchantments(Map(List::Owned(
+            alloc::vec![(EnchntmentRef(0), Level(5))],
+        ))));
+        let stack = |components: Vec<TypedDataComponent<'static>>| ItemStack {
+            id: item::diamond_sword,
+            count: 1,
+            components: DataComponentPatch {
+                patch_add: List::Owned(components),
+                patch_remove: List::Borrowed(&[]),
+            },
+        };
+        let a = stack(alloc::vec![
+            TypedDataComponent::Damage(3),
+            enchantments.clone()
+        ]);
+        let b = stack(alloc::vec![enchantments, TypedDataComponent::Damage(3)]);
+        let hashed = a.hashed(&Registries).unwrap();
+        assert_eq!(
+            TypedDataComponent::Unbreakable.hash(&Registries),
+            Some(empty_map())
+        );
+        assert!(hashed.matches(&b, &Registries));
+        assert!(!hashed.matches(
+            &stack(alloc::vec![TypedDataComponent::Damage(3)]),
+            &Registries
+        ));
+        let unknown = TypedDataComponent::Enchantments(ItemEnchantments(Map(List::Owned(
+            alloc::vec![(EnchntmentRef(1), Level(1))],
+        ))));
+        assert!(stack(alloc::vec![unknown]).hashed(&Registries).is_none());
+
+        let mut nbt = CompoundTag::new();
+        nbt.push(
+            StringTag::from_utf8("q"),
+            Tag::String(StringTag::from_utf8("it's")),
+        );
+        nbt.push(
+            StringTag::from_utf8("b c"),
+            Tag::List(ListTag::Float(alloc::vec![1e7, 0.001, -2.5])),
+        );
+        nbt.push(StringTag::from_utf8("a"), Tag::Byte(1));
+        let predicate = BlockPredicate {
+            blocks: None,
+            properties: None,
+            nbt: Some(Tag::Compound(nbt)),
+            components: DataComponentMatchers {
+                exact: List::Borrowed(&[]),
+                partial: List::Borrowed(&[]),
+            },
+        };
+        assert_eq!(
+            TypedDataComponent::CanBreak(AdventureModePredicate {
+                predicates: List::Owned(alloc::vec![predicate]),
+            })
+            .hash(&Registries),
+            Some(
+                MapHasher::new()
+                    .insert(
+                        "nbt",
+                        string("{a:1b,\"b c\":[1.0E7f,0.001f,-2.5f],q:\"it's\"}")
+                    )
+                    .finish()
+            )
+        );
+
+        let lodestone = TypedDataComponent::LodestoneTracker(LodestoneTracker {
+            target: Some(GlobalPos {
+                dimension: ResourceKey {
+                    identifier: Ident::new("overworld").unwrap(),
+                },
+                pos: BlockPos { x: 1, y: -2, z: 3 }.pack(),
+            }),
+            tracked: true,
+        });
+        let target = MapHasher::new()
+            .insert("dimension", string("minecraft:overworld"))
+            .insert("pos", int_array(&[1, -2, 3]))
+            .finish();
+        assert_eq!(
+            lodestone.hash(&Registries),
+            Some(MapHasher::new().insert("target", target).finish())
+        );
+        let painting = TypedDataComponent::PaintingVariant(Holder::Direct(PaintingVariant {
+            width: 2,
+            height: 1,
+            asset_id: Ident::new("kebab").unwrap(),
+            title: None,
+            author: None,
+        }));
+        assert_eq!(
+            painting.hash(&Registries),
+            Some(
+                MapHasher::new()
+                    .insert("width", int(2))
+                    .insert("height", int(1))
+                    .insert("asset_id", string("minecraft:kebab"))
+                    .finish()
+            )
+        );
+    }
+
+    #[test]
+    fn test_container_click() {
+        use crate::inventory::ContainerId;
+        use crate::inventory::container::{Container, ContainerSync};
+        use crate::item_stack::hash::RegistryLookup;
+        use crate::item_stack::{DataComponentPatch, ItemStack};
+        use crate::serverbound::game::ContainerClick;
+        use haya_ident::Ident;
+        use minecraft_data::item;
+
+        struct Registries;
+        impl RegistryLookup for Registries {
+            fn name(&self, _: &str, _: u32) -> Option<Ident<'_>> {
+                None
+            }
+        }
+
+        let stack = |id, count| ItemStack {
+            id,
+            count,
+            components: DataComponentPatch {
+                patch_add: List::Borrowed(&[]),
+                patch_remove: List::Borrowed(&[]),
+            },
+        };
+        let count = |c: &Container, i| c.slot(i).map_or(0, |s| s.count);
+        let mut container = Container::new(ContainerId(1), 27);
+        container.set_slot(0, Some(stack(item::ender_pearl, 10)));
+        container.set_slot(1, Some(stack(item::ender_pearl, 12)));
+        assert!(matches!(&container.changes()[..], [ContainerSync::Content(c)] if c.state_id == 1));
+
+        let click = |container: &mut Container<'static>,
+                     slot: i16,
+                     button,
+                     click_type,
+                     changed,
+                     carried| {
+            let packet = ContainerClick {
+                container_id: ContainerId(1),
+                state_id: container.state_id(),
+                slot_num: slot as u16,
+                button_num: button,
+                click_type,
+                changed_slots: Map(List::Owned(changed)),
+                carried_item: carried,
+            };
+            container.click(&packet, false, &Registries).unwrap()
+        };
+
+        // Left click picks up the whole stack, as the client predicted.
+        let pearls = stack(item::ender_pearl, 10).hashed(&Registries);
+        click(
+            &mut container,
+            0,
+            0,
+            ClickType::Pickup,
+            alloc::vec![(0, None)],
+            pearls.clone(),
+        );
+        assert_eq!(count(&container, 0), 0);
+        assert_eq!(container.carried().unwrap().count, 10);
+        assert!(container.changes().is_empty());
+        // Placing onto 12 pearls fills the stack to 16, while the client
+        // predicted slot 1 empty and the cursor unchanged.
+        click(
+            &mut container,
+            1,
+            0,
+            ClickType::Pickup,
+            alloc::vec![(1, None)],
+            pearls,
+        );
+        assert_eq!(count(&container, 1), 16);
+        assert_eq!(container.carried().unwrap().count, 6);
+        let changes = container.changes();
+        assert_eq!(changes.len(), 2);
+        assert!(
+            matches!(&changes[0], ContainerSync::Slot(s) if s.slot == 1 && s.item_stack.count == 16)
+        );
+        assert!(matches!(&changes[1], ContainerSync::Cursor(s) if s.contents.count == 6));
+
+        // A drag ended without slots changes nothing.
+        click(
+            &mut container,
+            -999,
+            0,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        click(
+            &mut container,
+            -999,
+            2,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        assert_eq!(container.carried().unwrap().count, 6);
+        assert_eq!((count(&container, 2), count(&container, 3)), (0, 0));
+
+        // Dragging the 6 pearls over two slots splits them evenly.
+        click(
+            &mut container,
+            -999,
+            0,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        click(
+            &mut container,
+            2,
+            1,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        click(
+            &mut container,
+            3,
+            1,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        click(
+            &mut container,
+            -999,
+            2,
+            ClickType::QuickCraft,
+            Vec::new(),
+            None,
+        );
+        assert_eq!((count(&container, 2), count(&container, 3)), (3, 3));
+        assert!(container.carried().is_none());
+
+        // Shift clicking moves into the last hotbar slot first.
+        click(&mut container, 1, 0, ClickType::QuickMove, Vec::new(), None);
+        assert_eq!(count(&container, 27 + 35), 16);
+        let dropped = click(&mut container, 2, 0, ClickType::Throw, Vec::new(), None);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(count(&container, 2), 2);
+
+        // Number keys swap with the hotbar, and the offhand is not a slot.
+        click(&mut container, 3, 0, ClickType::Swap, Vec::new(), None);
+        assert_eq!((count(&container, 3), count(&container, 27 + 27)), (0, 3));
+        click(&mut container, 2, 40, ClickType::Swap, Vec::new(), None);
+        assert_eq!(count(&container, 2), 2);
+        let state_id = container.state_id();
+        assert!(matches!(
+            &container.changes()[..],
+            [ContainerSync::Content(c)] if c.state_id == state_id + 1 && c.items[2].count == 2
+        ));
+
+        // Double clicking takes partial stacks before full ones.
+        click(&mut container, 2, 0, ClickType::Pickup, Vec::new(), None);
+        click(&mut container, 2, 0, ClickType::PickupAll, Vec::new(), None);
+        assert_eq!(container.carried().unwrap().count, 16);
+        assert_eq!(
+            (count(&container, 27 + 27), count(&container, 27 + 35)),
+            (0, 5)
+        );
+
+        // Clicking outside drops one item with the right button, then the
+        // rest with the left.
+        let dropped = click(&mut container, -999, 1, ClickType::Pickup, Vec::new(), None);
+        assert_eq!(dropped[0].count, 1);
+        let dropped = click(&mut container, -999, 0, ClickType::Pickup, Vec::new(), None);
+        assert_eq!(dropped[0].count, 15);
+        assert!(container.carried().is_none());
+
+        // Middle click copies a full stack, in creative only.
+        let clone = |state_id| ContainerClick {
+            container_id: ContainerId(1),
+            state_id,
+            slot_num: 27 + 35,
+            button_num: 2,
+            click_type: ClickType::Clone,
+            changed_slots: Map(List::Owned(Vec::new())),
+            carried_item: None,
+        };
+        container
+            .click(&clone(container.state_id()), false, &Registries)
+            .unwrap();
+        assert!(container.carried().is_none());
+        container
+            .click(&clone(container.state_id()), true, &Registries)
+            .unwrap();
+        assert_eq!(container.carried().unwrap().count, 16);
+        assert_eq!(count(&container, 27 + 35), 5);
+
+        // A click from a stale state resends the whole content.
+        container.changes();
+        let state_id = container.state_id();
+        container
+            .click(&clone(state_id.wrapping_sub(1)), true, &Registries)
+            .unwrap();
+        assert!(matches!(
+            &container.changes()[..],
+            [ContainerSync::Content(c)] if c.state_id == state_id + 1 && c.items.len() == 63
+        ));
+        assert!(container.changes().is_empty());
+    }
 }
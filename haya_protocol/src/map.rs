@@ -2,7 +2,7 @@ use crate::ComponentRaw;
 use minecraft_data::map_decoration_type;
 use mser::{ByteArray, Read, Write};
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MapId(#[mser(varint)] pub u32);
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -3,37 +3,37 @@ use haya_ident::Ident;
 use mser::{Either, Read, Utf8, Write};
 use uuid::Uuid;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct GameProfileRef<'a> {
     pub id: Uuid,
     pub name: Utf8<'a, 16>,
     pub properties: PropertyMapRef<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PropertyMapRef<'a>(pub List<'a, PropertyRef<'a>, 16>);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PropertyRef<'a> {
     pub name: Utf8<'a, 64>,
     pub value: Utf8<'a, 32767>,
     pub signature: Option<Utf8<'a, 1024>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ResolvableProfileRef<'a> {
     pub profile: Either<GameProfileRef<'a>, PartialRef<'a>>,
     pub skin_patch: PlayerSkinPatchRef<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PartialRef<'a> {
     pub name: Option<Utf8<'a, 16>>,
     pub id: Option<Uuid>,
     pub properties: List<'a, PropertyRef<'a>, 16>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PlayerSkinPatchRef<'a> {
     pub body: Option<Ident<'a>>,
     pub cape: Option<Ident<'a>>,
@@ -41,12 +41,21 @@ pub struct PlayerSkinPatchRef<'a> {
     pub model: Option<PlayerModelType>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Skip)]
 pub enum PlayerModelType {
     Slim,
     Wide,
 }
 
+impl PlayerModelType {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Slim => "slim",
+            Self::Wide => "wide",
+        }
+    }
+}
+
 impl<'a> Read<'a> for PlayerModelType {
     fn read(buf: &mut mser::Reader<'a>) -> Result<Self, mser::Error> {
         match bool::read(buf) {
@@ -6,57 +6,57 @@ use crate::sound::SoundEvent;
 use crate::trim::{TrimMaterial, TrimPattern};
 use crate::{DialogRaw, Holder};
 use minecraft_data::sound_event;
-use mser::{Error, Read, Reader, V32, Write, Writer};
+use mser::{Error, ErrorKind, Read, Reader, V32, Write, Writer};
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct DamageTypeRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TrimMaterialRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct EnchntmentRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TrimPatternRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct InstrumentRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct JukeboxSongRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct BannerPatternRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct VillagerTypeRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct WolfVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct WolfSoundVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct PigVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct CowVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ChickenVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct ZombieNautilusVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct FrogVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct PaintingVariantRef(#[mser(varint)] pub u32);
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Skip)]
 pub struct CatVariantRef(#[mser(varint)] pub u32);
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -79,7 +79,7 @@ impl<'a> Read<'a> for Holder<SoundEvent<'a>, sound_event> {
                 Err(_) => None,
             } {
                 Some(x) => Ok(Self::Reference(x)),
-                None => Err(Error),
+                None => Err(buf.error(ErrorKind::Discriminant)),
             }
         }
     }
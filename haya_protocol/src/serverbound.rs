@@ -12,50 +12,135 @@ pub mod login;
 pub mod ping;
 pub mod status;
 
-#[cold]
-fn err() -> Result<(), mser::Error> {
-    Err(mser::Error)
-}
-
 macro_rules! packets {
-    ($m:ty, $handler:ident, $handle:ident, $($variant:ident = $type:ty),+ $(,)*) => {
+    (
+        $m:ty,
+        $packet:ident $(<$lt:lifetime>)?,
+        $handler:ident,
+        $handle:ident,
+        $($variant:ident = $type:ty),+ $(,)*
+    ) => {
         $(
         #[automatically_derived]
-        impl crate::types::Id for $type {
+        impl<'a> crate::types::Id for $type {
             type T = $m;
             const ID: $m = <$m>::$variant;
         }
         )+
 
-        pub trait $handler {
-            fn $handle(&mut self, mut packet: mser::Reader<'_>) -> Result<(), mser::Error> {
-                match <$m as mser::Read>::read(&mut packet)? {
+        #[derive(Clone)]
+        #[allow(non_camel_case_types)]
+        pub enum $packet $(<$lt>)? {
+        $(
+            $variant($type),
+        )+
+        }
+
+        impl $(<$lt>)? $packet $(<$lt>)? {
+            pub const fn id(&self) -> $m {
+                match self {
+                    $(
+                        Self::$variant(_) => <$m>::$variant,
+                    )+
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl<'a> mser::Read<'a> for $packet $(<$lt>)? {
+            fn read(buf: &mut mser::Reader<'a>) -> Result<Self, mser::Error> {
+                Ok(match <$m as mser::Read>::read(buf)? {
                     $(
-                        <$m>::$variant => {
-                            let e = <$type as mser::Read>::read(&mut packet)?;
-                            if !packet.is_empty() {
-                                return err();
+                        <$m>::$variant => Self::$variant(<$type as mser::Read>::read(buf)?),
+                    )+
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl $(<$lt>)? mser::Write for $packet $(<$lt>)? {
+            unsafe fn write(&self, w: &mut mser::Writer) {
+                unsafe {
+                    match self {
+                        $(
+                            Self::$variant(x) => {
+                                <$m>::$variant.write(w);
+                                x.write(w);
                             }
-                            self.$variant(e);
-                        }
+                        )+
+                    }
+                }
+            }
+
+            fn len_s(&self) -> usize {
+                match self {
+                    $(
+                        Self::$variant(x) => <$m>::$variant.len_s() + x.len_s(),
+                    )+
+                }
+            }
+        }
+
+        pub trait $handler {
+            fn $handle(
+                &mut self,
+                mut packet: mser::Reader<'_>,
+            ) -> Result<(), crate::types::HandleError> {
+                let body = packet.peek_slice(packet.len())?;
+                let p = <$packet as mser::Read>::read(&mut packet)?;
+                packet.finish()?;
+                self.dispatch(p, body)
+            }
+
+            /// Calls the method of a packet already decoded from `body`,
+            /// which starts with the packet id.
+            #[allow(clippy::extra_unused_lifetimes)]
+            fn dispatch<'a>(
+                &mut self,
+                p: $packet $(<$lt>)?,
+                body: &[u8],
+            ) -> Result<(), crate::types::HandleError> {
+                let id = p.id();
+                let r = match p {
+                    $(
+                        $packet::$variant(e) => self.$variant(e),
                     )+
+                };
+                match r {
+                    Err(e) if e.is_unhandled() => {
+                        self.unhandled(id, &body[mser::Write::len_s(&id)..])
+                    }
+                    r => r,
                 }
+            }
+
+            /// Called for packets whose method is not overridden, with the
+            /// packet body following the id.
+            fn unhandled(&mut self, id: $m, raw: &[u8]) -> Result<(), crate::types::HandleError> {
+                let _ = (id, raw);
                 Ok(())
             }
         $(
-            fn $variant(&mut self, packet: $type);
+            #[allow(clippy::extra_unused_lifetimes)]
+            fn $variant<'a>(&mut self, packet: $type) -> Result<(), crate::types::HandleError> {
+                let _ = packet;
+                Err(crate::types::HandleError::unhandled())
+            }
         )+
         }
     };
 }
+
 packets! {
     serverbound_handshake,
+    ServerboundHandshakePacket<'a>,
     HandshakeHandler,
     handle,
-    intention = handshake::Intention<'_>,
+    intention = handshake::Intention<'a>,
 }
 packets! {
     serverbound_status,
+    ServerboundStatusPacket,
     StatusHandler,
     handle,
     status_request = status::StatusRequest,
@@ -63,31 +148,34 @@ packets! {
 }
 packets! {
     serverbound_login,
+    ServerboundLoginPacket<'a>,
     LoginHandler,
     handle,
-    hello = login::Hello<'_>,
-    key = login::Key<'_>,
-    custom_query_answer = login::CustomQueryAnswer<'_>,
+    hello = login::Hello<'a>,
+    key = login::Key<'a>,
+    custom_query_answer = login::CustomQueryAnswer<'a>,
     login_acknowledged = login::LoginAcknowledged,
-    cookie_response = cookie::LoginCookieResponse<'_>,
+    cookie_response = cookie::LoginCookieResponse<'a>,
 }
 packets! {
     serverbound_configuration,
+    ServerboundConfigurationPacket<'a>,
     ConfigurationHandler,
     handle,
-    client_information = common::ConfigurationClientInformation<'_>,
-    cookie_response = cookie::ConfigurationCookieResponse<'_>,
-    custom_payload = common::ConfigurationCustomPayload<'_>,
+    client_information = common::ConfigurationClientInformation<'a>,
+    cookie_response = cookie::ConfigurationCookieResponse<'a>,
+    custom_payload = common::ConfigurationCustomPayload<'a>,
     finish_configuration = configuration::FinishConfiguration,
     keep_alive = common::ConfigurationKeepAlive,
     pong = common::ConfigurationPong,
     resource_pack = common::ConfigurationResourcePack,
-    select_known_packs = configuration::SelectKnownPacks<'_>,
-    custom_click_action = common::CustomClickAction<'_>,
+    select_known_packs = configuration::SelectKnownPacks<'a>,
+    custom_click_action = common::CustomClickAction<'a>,
     accept_code_of_conduct = configuration::AcceptCodeOfConduct,
 }
 packets! {
     serverbound_play,
+    ServerboundPlayPacket<'a>,
     GameHandler,
     handle,
     accept_teleportation = game::AcceptTeleportation,
@@ -96,24 +184,24 @@ packets! {
     change_difficulty = game::ChangeDifficulty,
     change_game_mode = game::ChangeGameMode,
     chat_ack = game::ChatAck,
-    chat_command = game::ChatCommand<'_>,
-    chat_command_signed = game::ChatCommandSigned<'_>,
-    chat = game::Chat<'_>,
-    chat_session_update = game::ChatSessionUpdate<'_>,
+    chat_command = game::ChatCommand<'a>,
+    chat_command_signed = game::ChatCommandSigned<'a>,
+    chat = game::Chat<'a>,
+    chat_session_update = game::ChatSessionUpdate<'a>,
     chunk_batch_received = game::ChunkBatchReceived,
     client_command = game::ClientCommand,
     client_tick_end = game::ClientTickEnd,
-    client_information = game::ClientInformation<'_>,
-    command_suggestion = game::CommandSuggestion<'_>,
+    client_information = game::ClientInformation<'a>,
+    command_suggestion = game::CommandSuggestion<'a>,
     configuration_acknowledged = game::ConfigurationAcknowledged,
     container_button_click = game::ContainerButtonClick,
-    container_click = game::ContainerClick<'_>,
+    container_click = game::ContainerClick<'a>,
     container_close = game::ContainerClose,
     container_slot_state_changed = game::ContainerSlotStateChanged,
-    cookie_response = cookie::GameCookieResponse<'_>,
-    custom_payload = common::GameCustomPayload<'_>,
-    debug_subscription_request = game::DebugSubscriptionRequest<'_>,
-    edit_book = game::EditBook<'_>,
+    cookie_response = cookie::GameCookieResponse<'a>,
+    custom_payload = common::GameCustomPayload<'a>,
+    debug_subscription_request = game::DebugSubscriptionRequest<'a>,
+    edit_book = game::EditBook<'a>,
     entity_tag_query = game::EntityTagQuery,
     interact = game::Interact,
     jigsaw_generate = game::JigsawGenerate,
@@ -137,23 +225,23 @@ packets! {
     pong = common::GamePong,
     recipe_book_change_settings = game::RecipeBookChangeSettings,
     recipe_book_seen_recipe = game::RecipeBookSeenRecipe,
-    rename_item = game::RenameItem<'_>,
+    rename_item = game::RenameItem<'a>,
     resource_pack = common::GameResourcePack,
-    seen_advancements = game::SeenAdvancements<'_>,
+    seen_advancements = game::SeenAdvancements<'a>,
     select_trade = game::SelectTrade,
     set_beacon = game::SetBeacon,
     set_carried_item = game::SetCarriedItem,
-    set_command_block = game::SetCommandBlock<'_>,
-    set_command_minecart = game::SetCommandMinecart<'_>,
-    set_creative_mode_slot = game::SetCreativeModeSlot<'_>,
-    set_jigsaw_block = game::SetJigsawBlock<'_>,
-    set_structure_block = game::SetStructureBlock<'_>,
-    set_test_block = game::SetTestBlock<'_>,
-    sign_update = game::SignUpdate<'_>,
+    set_command_block = game::SetCommandBlock<'a>,
+    set_command_minecart = game::SetCommandMinecart<'a>,
+    set_creative_mode_slot = game::SetCreativeModeSlot<'a>,
+    set_jigsaw_block = game::SetJigsawBlock<'a>,
+    set_structure_block = game::SetStructureBlock<'a>,
+    set_test_block = game::SetTestBlock<'a>,
+    sign_update = game::SignUpdate<'a>,
     swing = game::Swing,
     teleport_to_entity = game::TeleportToEntity,
-    test_instance_block_action = game::TestInstanceBlockAction<'_>,
+    test_instance_block_action = game::TestInstanceBlockAction<'a>,
     use_item_on = game::UseItemOn,
     use_item = game::UseItem,
-    custom_click_action = game::CustomClickAction<'_>,
+    custom_click_action = game::CustomClickAction<'a>,
 }
@@ -35,7 +35,7 @@ impl<'a> mser::Read<'a> for ClientIntent {
             1 => Ok(Self::Status),
             2 => Ok(Self::Login),
             3 => Ok(Self::Transfer),
-            _ => Err(mser::Error),
+            _ => Err(buf.error(mser::ErrorKind::Discriminant)),
         }
     }
 }
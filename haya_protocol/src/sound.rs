@@ -1,6 +1,6 @@
 use haya_ident::Ident;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct SoundEvent<'a> {
     pub location: Ident<'a>,
     pub fixed_range: Option<f32>,
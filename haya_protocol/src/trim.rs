@@ -3,24 +3,24 @@ use haya_collection::Map;
 use haya_ident::{Ident, ResourceKey};
 use mser::Utf8;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct TrimMaterial<'a> {
     pub assets: MaterialAssetGroup<'a>,
     pub description: ComponentRaw,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct MaterialAssetGroup<'a> {
     pub base: AssetInfo<'a>,
     pub overrides: Map<'a, ResourceKey<'a>, AssetInfo<'a>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct AssetInfo<'a> {
     pub suffix: Utf8<'a>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Skip)]
 pub struct TrimPattern<'a> {
     pub asset_id: Ident<'a>,
     pub description: ComponentRaw,
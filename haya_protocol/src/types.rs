@@ -20,3 +20,77 @@ impl PacketType for minecraft_data::serverbound_play {}
 pub fn packet_id<R: PacketType, T: Id<T = R> + ?Sized>(_: &T) -> R {
     T::ID
 }
+
+/// Appends the packet id of `P` followed by the packet.
+pub fn encode_packet<P: Id + mser::Write>(packet: &P, buf: &mut alloc::vec::Vec<u8>) {
+    let id = mser::Write::len_s(&P::ID);
+    let len = id + mser::Write::len_s(packet);
+    buf.reserve(len);
+    unsafe {
+        let ptr = buf.as_mut_ptr().add(buf.len());
+        mser::write_unchecked(ptr, &P::ID);
+        mser::write_unchecked(ptr.add(id), packet);
+        buf.set_len(buf.len() + len);
+    }
+}
+
+/// Returned by handler methods to stop processing a connection.
+#[derive(Clone, Debug)]
+pub struct HandleError(Repr);
+
+#[derive(Clone, Debug)]
+enum Repr {
+    Decode(mser::Error),
+    Disconnect(alloc::borrow::Cow<'static, str>),
+    Unhandled,
+}
+
+impl HandleError {
+    pub fn disconnect(reason: impl Into<alloc::borrow::Cow<'static, str>>) -> Self {
+        Self(Repr::Disconnect(reason.into()))
+    }
+
+    /// The reason passed to [`disconnect`].
+    ///
+    /// [`disconnect`]: HandleError::disconnect
+    pub fn reason(&self) -> Option<&str> {
+        match &self.0 {
+            Repr::Disconnect(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub const fn decode_error(&self) -> Option<&mser::Error> {
+        match &self.0 {
+            Repr::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub(crate) const fn unhandled() -> Self {
+        Self(Repr::Unhandled)
+    }
+
+    pub(crate) const fn is_unhandled(&self) -> bool {
+        matches!(self.0, Repr::Unhandled)
+    }
+}
+
+impl From<mser::Error> for HandleError {
+    #[inline]
+    fn from(e: mser::Error) -> Self {
+        Self(Repr::Decode(e))
+    }
+}
+
+impl core::fmt::Display for HandleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.0 {
+            Repr::Decode(e) => e.fmt(f),
+            Repr::Disconnect(x) => write!(f, "disconnected: {x}"),
+            Repr::Unhandled => f.write_str("unhandled packet"),
+        }
+    }
+}
+
+impl core::error::Error for HandleError {}
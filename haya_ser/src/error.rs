@@ -0,0 +1,299 @@
+use crate::{Error, ErrorKind, Reader};
+#[cfg(feature = "detail")]
+use alloc::boxed::Box;
+use core::fmt;
+
+#[cfg(feature = "detail")]
+const PATH_DEPTH: usize = 6;
+#[cfg(feature = "detail")]
+const NO_OFFSET: u32 = u32::MAX;
+
+#[cfg(feature = "detail")]
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Field(&'static str),
+    Index(u32),
+}
+
+#[cfg(feature = "detail")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Detail {
+    kind: ErrorKind,
+    offset: u32,
+    root: &'static str,
+    len: u8,
+    truncated: bool,
+    path: [Segment; PATH_DEPTH],
+}
+
+#[cfg(feature = "detail")]
+impl Default for Detail {
+    fn default() -> Self {
+        Self::new(ErrorKind::Invalid, NO_OFFSET)
+    }
+}
+
+#[cfg(feature = "detail")]
+impl Detail {
+    const fn new(kind: ErrorKind, offset: u32) -> Self {
+        Self {
+            kind,
+            offset,
+            root: "",
+            len: 0,
+            truncated: false,
+            path: [Segment::Index(0); PATH_DEPTH],
+        }
+    }
+
+    /// Segments are pushed innermost first, so once the path is full the
+    /// outer ones are dropped and printed as `..` after the root.
+    #[inline]
+    fn push(&mut self, segment: Segment) {
+        if (self.len as usize) < PATH_DEPTH {
+            self.path[self.len as usize] = segment;
+            self.len += 1;
+        } else {
+            self.truncated = true;
+        }
+    }
+}
+
+impl ErrorKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::UnexpectedEof => "unexpected end of input",
+            Self::VarInt => "malformed varint",
+            Self::TooLong => "length exceeds maximum",
+            Self::Utf8 => "invalid utf-8",
+            Self::Discriminant => "unknown discriminant",
+            Self::TrailingBytes => "trailing bytes",
+            Self::Invalid => "invalid value",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Error {
+    #[inline]
+    #[allow(unused_variables)]
+    pub fn new(kind: ErrorKind) -> Self {
+        Self {
+            #[cfg(feature = "detail")]
+            detail: Box::new(Detail::new(kind, NO_OFFSET)),
+        }
+    }
+
+    #[inline]
+    #[allow(unused_variables)]
+    pub fn with_offset(kind: ErrorKind, offset: usize) -> Self {
+        Self {
+            #[cfg(feature = "detail")]
+            detail: Box::new(Detail::new(
+                kind,
+                if offset < NO_OFFSET as usize {
+                    offset as u32
+                } else {
+                    NO_OFFSET
+                },
+            )),
+        }
+    }
+
+    /// Returns [`None`] without the `detail` feature.
+    #[inline]
+    pub const fn kind(&self) -> Option<ErrorKind> {
+        #[cfg(feature = "detail")]
+        {
+            Some(self.detail.kind)
+        }
+        #[cfg(not(feature = "detail"))]
+        {
+            None
+        }
+    }
+
+    /// Byte offset from the start of the [`Reader`] where the error was
+    /// detected.
+    #[inline]
+    pub const fn offset(&self) -> Option<usize> {
+        #[cfg(feature = "detail")]
+        {
+            if self.detail.offset == NO_OFFSET {
+                None
+            } else {
+                Some(self.detail.offset as usize)
+            }
+        }
+        #[cfg(not(feature = "detail"))]
+        {
+            None
+        }
+    }
+
+    /// Field path such as `AddEntity.movement` or `ItemStack.components[3]`.
+    #[inline]
+    pub const fn path(&self) -> ErrorPath<'_> {
+        ErrorPath(self)
+    }
+
+    /// Prepends `ty.field` to the path.
+    #[inline]
+    #[allow(unused_mut, unused_variables)]
+    pub fn with_field(mut self, ty: &'static str, field: &'static str) -> Self {
+        #[cfg(feature = "detail")]
+        {
+            self.detail.push(Segment::Field(field));
+            self.detail.root = ty;
+        }
+        self
+    }
+
+    /// Prepends `[index]` to the path.
+    #[inline]
+    #[allow(unused_mut, unused_variables)]
+    pub fn with_index(mut self, index: usize) -> Self {
+        #[cfg(feature = "detail")]
+        {
+            self.detail.push(Segment::Index(index as u32));
+            self.detail.root = "";
+        }
+        self
+    }
+}
+
+impl From<ErrorKind> for Error {
+    #[inline]
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl fmt::Display for Error {
+    #[cfg(feature = "detail")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.detail.kind.as_str())?;
+        if let Some(offset) = self.offset() {
+            write!(f, " at offset {offset}")?;
+        }
+        if self.detail.len != 0 {
+            write!(f, " in {}", self.path())?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "detail"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("decode error")
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "detail"), allow(dead_code))]
+pub struct ErrorPath<'a>(&'a Error);
+
+impl fmt::Display for ErrorPath<'_> {
+    #[cfg(feature = "detail")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = &self.0.detail;
+        f.write_str(d.root)?;
+        if d.truncated {
+            f.write_str("..")?;
+        }
+        for (i, segment) in d.path[..d.len as usize].iter().rev().enumerate() {
+            match *segment {
+                Segment::Field(x) => {
+                    if i != 0 || !d.root.is_empty() {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(x)?;
+                }
+                Segment::Index(x) => write!(f, "[{x}]")?,
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "detail"))]
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ErrorPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Creates an error at the current position.
+    #[inline]
+    pub fn error(&self, kind: ErrorKind) -> Error {
+        #[cfg(feature = "detail")]
+        {
+            Error::with_offset(kind, unsafe { self.ptr.offset_from_unsigned(self.start) })
+        }
+        #[cfg(not(feature = "detail"))]
+        {
+            Error::new(kind)
+        }
+    }
+
+    /// Returns `Err` with [`ErrorKind::TrailingBytes`] if any input is left.
+    #[inline]
+    pub fn finish(&self) -> Result<(), Error> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            crate::cold_path();
+            Err(self.error(ErrorKind::TrailingBytes))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "detail"))]
+mod tests {
+    extern crate std;
+
+    use crate::{Error, ErrorKind, Read, Reader, Utf8, V21};
+    use std::string::ToString;
+
+    #[test]
+    fn test_detail() {
+        let mut r = Reader::new(&[0x80, 0x80]);
+        let e = V21::read(&mut r).unwrap_err();
+        assert_eq!(e.kind(), Some(ErrorKind::UnexpectedEof));
+        assert_eq!(e.offset(), Some(2));
+
+        let mut r = Reader::new(&[3, b'a', 0xFF, b'c']);
+        let e = Utf8::<'_, 8>::read(&mut r).unwrap_err();
+        assert_eq!(e.kind(), Some(ErrorKind::Utf8));
+        assert_eq!(e.offset(), Some(1));
+
+        let path = e
+            .with_field("Tool", "rules")
+            .with_index(3)
+            .with_field("ItemStack", "components");
+        assert_eq!(path.path().to_string(), "ItemStack.components[3].rules");
+        assert_eq!(
+            path.to_string(),
+            "invalid utf-8 at offset 1 in ItemStack.components[3].rules"
+        );
+
+        let mut deep = Error::new(ErrorKind::Invalid).with_field("Inner", "x");
+        for i in 0..8 {
+            deep = deep.with_index(i);
+        }
+        let outer = deep.with_field("Packet", "items");
+        assert_eq!(outer.path().to_string(), "Packet..[4][3][2][1][0].x");
+        assert!(size_of::<Error>() <= size_of::<usize>());
+    }
+}
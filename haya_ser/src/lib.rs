@@ -1,19 +1,29 @@
 #![no_std]
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
+#[cfg(feature = "detail")]
+extern crate alloc;
+
+mod error;
 mod memchr;
 mod read;
 mod reader;
+mod skip;
 mod varint;
 mod write;
 mod writer;
 
+pub use self::error::ErrorPath;
+pub use self::skip::{size_add, skip_n};
+
 use core::marker::PhantomData;
 
 #[derive(Debug)]
 pub struct Reader<'a> {
     pub(crate) ptr: *const u8,
     pub(crate) end: *const u8,
+    #[cfg(feature = "detail")]
+    pub(crate) start: *const u8,
     pub(crate) marker: PhantomData<&'a [u8]>,
 }
 
@@ -31,13 +41,49 @@ pub trait Write {
     fn len_s(&self) -> usize;
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct Error;
+/// Decoding error.
+///
+/// Without the `detail` feature this is zero-sized and every accessor
+/// returns [`None`]. With it, the error records its [`ErrorKind`], the
+/// offset inside the [`Reader`] and the field path filled in by the derives.
+#[derive(Clone, Debug, Default)]
+pub struct Error {
+    #[cfg(feature = "detail")]
+    pub(crate) detail: alloc::boxed::Box<error::Detail>,
+}
+
+#[non_exhaustive]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ErrorKind {
+    /// The input ended before the value was complete.
+    UnexpectedEof,
+    /// A VarInt or VarLong was longer than its maximum encoded size.
+    VarInt,
+    /// A length prefix exceeded its maximum.
+    TooLong,
+    /// A string was not valid UTF-8 or modified UTF-8.
+    Utf8,
+    /// An enum discriminant or registry id was not recognised.
+    Discriminant,
+    /// Bytes were left over after the value was read.
+    TrailingBytes,
+    /// A value was rejected by a filter or failed validation.
+    #[default]
+    Invalid,
+}
 
 pub trait Read<'a>: Sized {
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error>;
 }
 
+/// Advances past a value without building it.
+pub trait Skip {
+    /// Encoded length when it is the same for every value.
+    const SIZE: Option<usize> = None;
+
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error>;
+}
+
 pub const V21MAX: usize = 0x1FFFFF;
 pub const V7MAX: usize = 0x7F;
 
@@ -118,15 +164,16 @@ impl<'a, const MAX: usize> Read<'a> for Utf8<'a, MAX> {
     #[inline]
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
         let len = read_v21_len(buf, MAX.saturating_mul(3))?;
-        let bytes = buf.read_slice(len)?;
+        let bytes = buf.peek_slice(len)?;
         let s = match core::str::from_utf8(bytes) {
             Ok(x) => x,
-            Err(_) => return Err(Error),
+            Err(_) => return Err(buf.error(ErrorKind::Utf8)),
         };
         if s.chars().map(char::len_utf16).sum::<usize>() <= MAX {
+            unsafe { buf.advance(len) };
             Ok(Self(s))
         } else {
-            Err(Error)
+            Err(buf.error(ErrorKind::TooLong))
         }
     }
 }
@@ -178,7 +225,7 @@ impl<'a, const MAX: usize> Read<'a> for Rest<'a, MAX> {
     fn read(buf: &mut Reader<'a>) -> Result<Self, Error> {
         let len = buf.len();
         if len > MAX {
-            return Err(Error);
+            return Err(buf.error(ErrorKind::TooLong));
         }
         Ok(Self(buf.read_slice(len)?))
     }
@@ -199,7 +246,7 @@ pub fn read_v21_len(buf: &mut Reader<'_>, max: usize) -> Result<usize, Error> {
     let len = V21::read(buf)?.0 as usize;
     if len > max {
         cold_path();
-        return Err(Error);
+        return Err(buf.error(ErrorKind::TooLong));
     }
     Ok(len)
 }
@@ -1,4 +1,4 @@
-use crate::{Error, Reader, cold_path};
+use crate::{Error, ErrorKind, Reader, cold_path};
 use core::marker::PhantomData;
 
 impl<'a> Reader<'a> {
@@ -7,6 +7,8 @@ impl<'a> Reader<'a> {
         Self {
             ptr: buf.as_ptr(),
             end: unsafe { buf.as_ptr().add(buf.len()) },
+            #[cfg(feature = "detail")]
+            start: buf.as_ptr(),
             marker: PhantomData,
         }
     }
@@ -15,7 +17,7 @@ impl<'a> Reader<'a> {
     pub fn read_byte(&mut self) -> Result<u8, Error> {
         if core::ptr::eq(self.ptr, self.end) {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             let b = unsafe { *self.ptr };
             self.ptr = unsafe { self.ptr.add(1) };
@@ -27,7 +29,7 @@ impl<'a> Reader<'a> {
     pub fn read_array<const L: usize>(&mut self) -> Result<&'a [u8; L], Error> {
         if unsafe { self.ptr.add(L) > self.end } {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             unsafe {
                 let a = &*(self.ptr as *const [u8; L]);
@@ -41,7 +43,7 @@ impl<'a> Reader<'a> {
     pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
         if unsafe { self.ptr.add(len) > self.end } {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             unsafe {
                 let a = core::slice::from_raw_parts(self.ptr, len);
@@ -55,7 +57,7 @@ impl<'a> Reader<'a> {
     pub fn peek_byte(&self) -> Result<u8, Error> {
         if core::ptr::eq(self.ptr, self.end) {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             unsafe { Ok(*self.ptr) }
         }
@@ -73,7 +75,7 @@ impl<'a> Reader<'a> {
     pub fn peek_array<const L: usize>(&self) -> Result<&'a [u8; L], Error> {
         if unsafe { self.ptr.add(L) > self.end } {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             unsafe { Ok(&*(self.ptr as *const [u8; L])) }
         }
@@ -83,7 +85,7 @@ impl<'a> Reader<'a> {
     pub fn peek_slice(&self, len: usize) -> Result<&'a [u8], Error> {
         if unsafe { self.ptr.add(len) > self.end } {
             cold_path();
-            Err(Error)
+            Err(self.error(ErrorKind::UnexpectedEof))
         } else {
             unsafe { Ok(core::slice::from_raw_parts(self.ptr, len)) }
         }
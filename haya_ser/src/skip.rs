@@ -0,0 +1,173 @@
+use crate::{
+    ByteArray, Either, Error, ErrorKind, FixedByteArray, Read, Reader, Rest, Skip, Utf8, V21, V32,
+    V64, read_v21_len,
+};
+
+macro_rules! fixed {
+    ($($t:ty = $n:literal),+ $(,)?) => {
+        $(
+        impl Skip for $t {
+            const SIZE: Option<usize> = Some($n);
+
+            #[inline]
+            fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+                buf.read_array::<$n>()?;
+                Ok(())
+            }
+        }
+        )+
+    };
+}
+
+fixed! {
+    u8 = 1,
+    i8 = 1,
+    bool = 1,
+    u16 = 2,
+    i16 = 2,
+    u32 = 4,
+    i32 = 4,
+    u64 = 8,
+    i64 = 8,
+    u128 = 16,
+    i128 = 16,
+    f32 = 4,
+    f64 = 8,
+    uuid::Uuid = 16,
+}
+
+impl<const N: usize> Skip for [u8; N] {
+    const SIZE: Option<usize> = Some(N);
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        buf.read_array::<N>()?;
+        Ok(())
+    }
+}
+
+impl<const L: usize> Skip for FixedByteArray<'_, L> {
+    const SIZE: Option<usize> = Some(L);
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        buf.read_array::<L>()?;
+        Ok(())
+    }
+}
+
+impl Skip for V21 {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let _ = Self::read(buf)?;
+        Ok(())
+    }
+}
+
+impl Skip for V32 {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let _ = Self::read(buf)?;
+        Ok(())
+    }
+}
+
+impl Skip for V64 {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let _ = Self::read(buf)?;
+        Ok(())
+    }
+}
+
+impl<T: Skip> Skip for Option<T> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        if bool::read(buf)? {
+            T::skip(buf)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<L: Skip, R: Skip> Skip for Either<L, R> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        if bool::read(buf)? {
+            L::skip(buf)
+        } else {
+            R::skip(buf)
+        }
+    }
+}
+
+impl<A: Skip, B: Skip> Skip for (A, B) {
+    const SIZE: Option<usize> = size_add(A::SIZE, B::SIZE);
+
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        A::skip(buf)?;
+        B::skip(buf)
+    }
+}
+
+impl<const MAX: usize> Skip for ByteArray<'_, MAX> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let len = read_v21_len(buf, MAX)?;
+        buf.read_slice(len)?;
+        Ok(())
+    }
+}
+
+impl<const MAX: usize> Skip for Utf8<'_, MAX> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        Utf8::<'_, MAX>::read(buf)?;
+        Ok(())
+    }
+}
+
+impl<const MAX: usize> Skip for Rest<'_, MAX> {
+    #[inline]
+    fn skip(buf: &mut Reader<'_>) -> Result<(), Error> {
+        let len = buf.len();
+        if len > MAX {
+            return Err(buf.error(ErrorKind::TooLong));
+        }
+        unsafe { buf.advance(len) };
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+pub const fn size_add(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(x), Some(y)) => x.checked_add(y),
+        _ => None,
+    }
+}
+
+/// Skips `len` values of `T`, with a single bounds check when `T` has a fixed
+/// size.
+#[inline]
+pub fn skip_n<T: Skip>(buf: &mut Reader<'_>, len: usize) -> Result<(), Error> {
+    match T::SIZE {
+        Some(size) => match size.checked_mul(len) {
+            Some(n) => {
+                buf.read_slice(n)?;
+                Ok(())
+            }
+            None => Err(buf.error(ErrorKind::TooLong)),
+        },
+        None => {
+            for i in 0..len {
+                if let Err(e) = T::skip(buf) {
+                    return Err(e.with_index(i));
+                }
+            }
+            Ok(())
+        }
+    }
+}
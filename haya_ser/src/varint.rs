@@ -1,4 +1,4 @@
-use crate::{Error, Read, Reader, V21, V32, V64, Write, Writer, cold_path};
+use crate::{Error, ErrorKind, Read, Reader, V21, V32, V64, Write, Writer, cold_path};
 
 impl V21 {
     #[inline]
@@ -36,7 +36,7 @@ impl<'a> Read<'a> for V21 {
                 break 'd;
             }
             cold_path();
-            return Err(Error);
+            return Err(buf.error(ErrorKind::VarInt));
         };
         Ok(Self(p))
     }
@@ -154,7 +154,7 @@ impl<'a> Read<'a> for V32 {
                 break 'd;
             }
             cold_path();
-            return Err(Error);
+            return Err(buf.error(ErrorKind::VarInt));
         };
         Ok(Self(p))
     }
@@ -309,7 +309,7 @@ impl<'a> Read<'a> for V64 {
                     break 'd;
                 }
             }
-            return Err(Error);
+            return Err(buf.error(ErrorKind::VarInt));
         }
         Ok(Self(p))
     }
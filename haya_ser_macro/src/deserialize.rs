@@ -1,7 +1,10 @@
 use crate::{Attrs, Ty, V21MAX, ident_case, parse_fields, ty};
+use alloc::format;
+use alloc::string::ToString;
 use alloc::vec::Vec;
 use proc_macro2::TokenStream;
 use quote::{ToTokens, TokenStreamExt, quote};
+use syn::ext::IdentExt;
 
 pub fn deserialize_struct(
     input: syn::DeriveInput,
@@ -24,9 +27,10 @@ pub fn deserialize_struct(
         syn::Data::Struct(data) => parse_fields(&data.fields)?,
         _ => unreachable!(),
     };
+    let ty_name = name.to_string();
     let read1 = fields
         .iter()
-        .map(|(field, attrs, m)| read_field(&cratename, field, attrs, m));
+        .map(|(field, attrs, m)| read_field(&cratename, &ty_name, field, attrs, m));
     let read2 = quote!(Self {
         #(#read1,)*
     });
@@ -36,7 +40,7 @@ pub fn deserialize_struct(
             if #filter(&__v) {
                 ::core::result::Result::Ok(__v)
             } else {
-                ::core::result::Result::Err(::#cratename::Error)
+                ::core::result::Result::Err(__r.error(::#cratename::ErrorKind::Invalid))
             }
         }
     } else {
@@ -103,9 +107,10 @@ pub fn deserialize_enum(
                 proc_macro2::Span::call_site(),
             );
             let fields2 = parse_fields(&variant.fields)?;
+            let ty_name = format!("{name}::{variant_name}");
             let fields3 = fields2
                 .iter()
-                .map(|(field, attrs, m)| read_field(&cratename, field, attrs, m));
+                .map(|(field, attrs, m)| read_field(&cratename, &ty_name, field, attrs, m));
             match_arms.push(quote! {
                 #header::#header_variant => Self::#variant_name { #(#fields3,)* }
             });
@@ -133,7 +138,7 @@ pub fn deserialize_enum(
             if #filter(&__v) {
                 ::core::result::Result::Ok(__v)
             } else {
-                ::core::result::Result::Err(::#cratename::Error)
+                ::core::result::Result::Err(__r.error(::#cratename::ErrorKind::Invalid))
             }
         }
     } else {
@@ -163,19 +168,32 @@ pub fn deserialize_enum(
 
 fn read_field(
     cratename: &syn::Path,
+    ty_name: &str,
     field: &&syn::Field,
     attrs: &crate::FieldAttrs,
     m: &syn::Member,
 ) -> TokenStream {
-    let v = if attrs.varint {
+    let (read, map) = if attrs.varint {
         match ty(&field.ty) {
-            Ty::I32 => quote!(::#cratename::V32::read(__r)?.0 as i32),
-            Ty::U32 => quote!(::#cratename::V32::read(__r)?.0),
-            Ty::I64 => quote!(::#cratename::V64::read(__r)?.0 as i64),
-            _ => quote!(::#cratename::V64::read(__r)?.0),
+            Ty::I32 => (quote!(::#cratename::V32::read(__r)), quote!(__v.0 as i32)),
+            Ty::U32 => (quote!(::#cratename::V32::read(__r)), quote!(__v.0)),
+            Ty::I64 => (quote!(::#cratename::V64::read(__r)), quote!(__v.0 as i64)),
+            _ => (quote!(::#cratename::V64::read(__r)), quote!(__v.0)),
         }
     } else {
-        quote!(::#cratename::Read::read(__r)?)
+        (quote!(::#cratename::Read::read(__r)), quote!(__v))
+    };
+    let field_name = match m {
+        syn::Member::Named(x) => x.unraw().to_string(),
+        syn::Member::Unnamed(x) => x.index.to_string(),
+    };
+    let v = quote! {
+        match #read {
+            ::core::result::Result::Ok(__v) => #map,
+            ::core::result::Result::Err(__e) => {
+                return ::core::result::Result::Err(__e.with_field(#ty_name, #field_name));
+            }
+        }
     };
     match &attrs.filter {
         Some(x) => quote! {
@@ -184,7 +202,9 @@ fn read_field(
                 if #x(&__v) {
                     __v
                 } else {
-                    return ::core::result::Result::Err(::#cratename::Error);
+                    return ::core::result::Result::Err(
+                        __r.error(::#cratename::ErrorKind::Invalid).with_field(#ty_name, #field_name),
+                    );
                 }
             }
         },
@@ -321,7 +341,7 @@ where
         }
     }
 }
-pub struct TypeGenerics<'a>(&'a syn::Generics);
+pub struct TypeGenerics<'a>(pub(crate) &'a syn::Generics);
 impl<'a> ToTokens for TypeGenerics<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         if self.0.params.is_empty() {
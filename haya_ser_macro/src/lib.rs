@@ -5,7 +5,9 @@
 extern crate alloc;
 
 mod deserialize;
+mod nbt;
 mod serialize;
+mod skip;
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
@@ -124,6 +126,70 @@ pub fn deserialize(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(Skip, attributes(mser))]
+pub fn skip(input: TokenStream) -> TokenStream {
+    let i = parse_macro_input!(input as syn::DeriveInput);
+    let (attrs, cratename) = match crate_name(&i) {
+        Ok(cratename) => cratename,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let x = match &i.data {
+        syn::Data::Struct(_) => skip::skip_struct(i, cratename, attrs),
+        syn::Data::Enum(_) => skip::skip_enum(i, cratename, attrs),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(i, "unions are not supported")),
+    };
+    match x {
+        Ok(token) => token.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implements `haya_nbt::Serialize` as a compound, or as a string for enums
+/// of unit variants.
+///
+/// `#[nbt(tag = "type")]` stores the variant name of an enum under `type`,
+/// next to its fields. Fields accept `rename = ".."`, `default`, `flatten`
+/// and `with = path`, where `path::serialize` and `path::deserialize` replace
+/// the trait methods. `Option` fields are omitted when `None`. A `flatten`
+/// field, or the single field of a tagged enum variant, has to implement
+/// `haya_nbt::SerializeCompound`, which is also derived for structs and
+/// tagged enums.
+#[proc_macro_derive(NbtSerialize, attributes(nbt))]
+pub fn nbt_serialize(input: TokenStream) -> TokenStream {
+    let i = parse_macro_input!(input as syn::DeriveInput);
+    let x = match &i.data {
+        syn::Data::Struct(_) => nbt::serialize_struct(i),
+        syn::Data::Enum(_) => nbt::serialize_enum(i),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(i, "unions are not supported")),
+    };
+    match x {
+        Ok(token) => token.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Implements `haya_nbt::Deserialize`, the inverse of [`NbtSerialize`].
+///
+/// Unknown keys are ignored, or collected by the `flatten` field. Missing
+/// fields are an error unless they are `Option` or `default`.
+/// `#[nbt(filter = path)]` rejects values for which `path(&value)` is false.
+#[proc_macro_derive(NbtDeserialize, attributes(nbt))]
+pub fn nbt_deserialize(input: TokenStream) -> TokenStream {
+    let i = parse_macro_input!(input as syn::DeriveInput);
+    let x = match &i.data {
+        syn::Data::Struct(_) => nbt::deserialize_struct(i),
+        syn::Data::Enum(_) => nbt::deserialize_enum(i),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(i, "unions are not supported")),
+    };
+    match x {
+        Ok(token) => token.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 struct FieldAttrs {
     filter: Option<syn::Path>,
     varint: bool,
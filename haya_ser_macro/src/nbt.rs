@@ -0,0 +1,547 @@
+use crate::{Attrs, ident_case};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::ParseStream;
+
+mod kw {
+    syn::custom_keyword!(tag);
+    syn::custom_keyword!(filter);
+    syn::custom_keyword!(rename);
+    syn::custom_keyword!(default);
+    syn::custom_keyword!(flatten);
+    syn::custom_keyword!(with);
+}
+
+#[derive(Default)]
+struct NbtAttrs {
+    tag: Option<syn::LitStr>,
+    filter: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for NbtAttrs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::tag) {
+                let _: kw::tag = input.parse()?;
+                let _: syn::Token![=] = input.parse()?;
+                attr.tag = Some(input.parse()?);
+            } else if lookahead.peek(kw::filter) {
+                let _: kw::filter = input.parse()?;
+                let _: syn::Token![=] = input.parse()?;
+                attr.filter = Some(input.parse()?);
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                let _: syn::Token![,] = input.parse()?;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+#[derive(Default)]
+struct NbtFieldAttrs {
+    rename: Option<syn::LitStr>,
+    default: bool,
+    flatten: bool,
+    with: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for NbtFieldAttrs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attr = Self::default();
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::rename) {
+                let _: kw::rename = input.parse()?;
+                let _: syn::Token![=] = input.parse()?;
+                attr.rename = Some(input.parse()?);
+            } else if lookahead.peek(kw::default) {
+                let _: kw::default = input.parse()?;
+                attr.default = true;
+            } else if lookahead.peek(kw::flatten) {
+                let _: kw::flatten = input.parse()?;
+                attr.flatten = true;
+            } else if lookahead.peek(kw::with) {
+                let _: kw::with = input.parse()?;
+                let _: syn::Token![=] = input.parse()?;
+                attr.with = Some(input.parse()?);
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                let _: syn::Token![,] = input.parse()?;
+            }
+        }
+        Ok(attr)
+    }
+}
+
+fn parse_attrs<T: syn::parse::Parse + Default>(attrs: &[syn::Attribute]) -> syn::Result<T> {
+    let mut find = None;
+    for attr in attrs {
+        if attr.path().is_ident("nbt") {
+            if find.is_some() {
+                return Err(syn::Error::new_spanned(attr, "multiple `nbt` attributes"));
+            }
+            find = Some(attr.parse_args()?);
+        }
+    }
+    Ok(find.unwrap_or_default())
+}
+
+struct Field<'a> {
+    ident: &'a syn::Ident,
+    var: syn::Ident,
+    ty: &'a syn::Type,
+    key: String,
+    /// `T` of an `Option<T>` field, which is omitted when `None`.
+    option: Option<&'a syn::Type>,
+    attrs: NbtFieldAttrs,
+}
+
+fn parse_fields(fields: &syn::FieldsNamed) -> syn::Result<Vec<Field<'_>>> {
+    let mut vec = Vec::with_capacity(fields.named.len());
+    let mut flatten = false;
+    for (idx, field) in fields.named.iter().enumerate() {
+        let attrs: NbtFieldAttrs = parse_attrs(&field.attrs)?;
+        let ident = match &field.ident {
+            Some(x) => x,
+            None => unreachable!(),
+        };
+        if attrs.flatten {
+            if flatten {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "expected at most one `flatten` field",
+                ));
+            }
+            if attrs.with.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "expected no `with` on a `flatten` field",
+                ));
+            }
+            flatten = true;
+        }
+        let key = match &attrs.rename {
+            Some(x) => x.value(),
+            None => ident.unraw().to_string(),
+        };
+        vec.push(Field {
+            ident,
+            var: format_ident!("__f{}", idx),
+            ty: &field.ty,
+            key,
+            option: option(&field.ty),
+            attrs,
+        });
+    }
+    Ok(vec)
+}
+
+fn option(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(x)) if args.args.len() == 1 => Some(x),
+        _ => None,
+    }
+}
+
+fn variant_name(variant: &syn::Variant) -> syn::Result<String> {
+    let attrs: NbtFieldAttrs = parse_attrs(&variant.attrs)?;
+    if attrs.default || attrs.flatten || attrs.with.is_some() {
+        return Err(syn::Error::new_spanned(
+            variant,
+            "expected only `rename` on enum variants",
+        ));
+    }
+    Ok(match attrs.rename {
+        Some(x) => x.value(),
+        None => ident_case(&Attrs::default(), &variant.ident),
+    })
+}
+
+fn invalid() -> TokenStream {
+    quote!(::mser::Error::new(::mser::ErrorKind::Invalid))
+}
+
+/// A `StringTag` built at compile time when `value` fits inline.
+fn string_tag(value: &str) -> TokenStream {
+    quote! {{
+        const __K: ::core::option::Option<::haya_nbt::StringTag> =
+            ::haya_nbt::StringTag::from_ascii_nonzero(#value.as_bytes());
+        match __K {
+            ::core::option::Option::Some(__k) => __k,
+            ::core::option::Option::None => ::haya_nbt::StringTag::from_utf8(#value),
+        }
+    }}
+}
+
+/// Pushes the entries of `value` into `__c`. Its type has to implement
+/// `SerializeCompound`, so anything else fails to compile.
+fn ser_flatten(value: TokenStream) -> TokenStream {
+    quote! {
+        for (__k, __v) in ::haya_nbt::SerializeCompound::serialize_compound(#value) {
+            __c.push(__k, __v);
+        }
+    }
+}
+
+fn ser_value(with: Option<&syn::Path>, value: TokenStream) -> TokenStream {
+    match with {
+        Some(path) => quote!(#path::serialize(#value)),
+        None => quote!(::haya_nbt::Serialize::serialize(#value)),
+    }
+}
+
+fn de_value(with: Option<&syn::Path>, ty: &syn::Type, tag: TokenStream) -> TokenStream {
+    match with {
+        Some(path) => quote!(#path::deserialize(#tag)?),
+        None => quote!(<#ty as ::haya_nbt::Deserialize>::deserialize(#tag)?),
+    }
+}
+
+/// Pushes every field into `__c`, with `access` yielding a reference to it.
+fn ser_fields(fields: &[Field], access: impl Fn(&Field) -> TokenStream) -> TokenStream {
+    let push = fields.iter().map(|field| {
+        let value = access(field);
+        let key = string_tag(&field.key);
+        let with = field.attrs.with.as_ref();
+        if field.attrs.flatten {
+            ser_flatten(value)
+        } else if field.option.is_some() {
+            let x = ser_value(with, quote!(__x));
+            quote! {
+                if let ::core::option::Option::Some(__x) = #value {
+                    __c.push(#key, #x);
+                }
+            }
+        } else {
+            let x = ser_value(with, value);
+            quote!(__c.push(#key, #x);)
+        }
+    });
+    quote!(#(#push)*)
+}
+
+/// Consumes the compound `__c` into `path { .. }`.
+fn de_fields(fields: &[Field], path: TokenStream) -> TokenStream {
+    let invalid = invalid();
+    let mut vars = Vec::new();
+    let mut arms = Vec::new();
+    let mut values = Vec::new();
+    let mut rest = None;
+    for field in fields {
+        let var = &field.var;
+        let ident = field.ident;
+        let with = field.attrs.with.as_ref();
+        if field.attrs.flatten {
+            let ty = field.ty;
+            let x = de_value(with, ty, quote!(::haya_nbt::Tag::Compound(__rest)));
+            rest = Some(quote!(__rest.push(__k, __v)));
+            vars.push(quote!(let mut __rest = ::haya_nbt::CompoundTag::new();));
+            values.push(quote!(#ident: #x));
+            continue;
+        }
+        let ty = field.option.unwrap_or(field.ty);
+        let key = &field.key;
+        let x = de_value(with, ty, quote!(__v));
+        vars.push(
+            quote!(let mut #var: ::core::option::Option<#ty> = ::core::option::Option::None;),
+        );
+        arms.push(quote!(#key => #var = ::core::option::Option::Some(#x),));
+        values.push(if field.option.is_some() {
+            quote!(#ident: #var)
+        } else if field.attrs.default {
+            quote!(#ident: ::core::option::Option::unwrap_or_default(#var))
+        } else {
+            quote! {
+                #ident: match #var {
+                    ::core::option::Option::Some(__x) => __x,
+                    ::core::option::Option::None => return ::core::result::Result::Err(#invalid),
+                }
+            }
+        });
+    }
+    let other = rest.unwrap_or(quote!({}));
+    quote! {
+        #(#vars)*
+        for (__k, __v) in __c {
+            match &*__k {
+                #(#arms)*
+                _ => #other,
+            }
+        }
+        #path { #(#values,)* }
+    }
+}
+
+fn filter(attrs: &NbtAttrs, value: TokenStream) -> TokenStream {
+    let invalid = invalid();
+    match &attrs.filter {
+        Some(filter) => quote! {
+            let __v = #value;
+            if #filter(&__v) {
+                ::core::result::Result::Ok(__v)
+            } else {
+                ::core::result::Result::Err(#invalid)
+            }
+        },
+        None => quote!(::core::result::Result::Ok(#value)),
+    }
+}
+
+fn named(input: &syn::DeriveInput) -> syn::Result<&syn::FieldsNamed> {
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => Ok(fields),
+        _ => Err(syn::Error::new_spanned(input, "expected named fields")),
+    }
+}
+
+pub fn serialize_struct(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let fields = parse_fields(named(&input)?)?;
+    let push = ser_fields(&fields, |field| {
+        let ident = field.ident;
+        quote!(&self.#ident)
+    });
+    Ok(serialize_compound(
+        &input,
+        quote! {
+            let mut __c = ::haya_nbt::CompoundTag::new();
+            #push
+            __c
+        },
+    ))
+}
+
+/// Implements `SerializeCompound` with `body`, and `Serialize` through it.
+fn serialize_compound(input: &syn::DeriveInput, body: TokenStream) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::haya_nbt::SerializeCompound for #name #ty_generics #where_clause {
+            fn serialize_compound(&self) -> ::haya_nbt::CompoundTag {
+                #body
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::haya_nbt::Serialize for #name #ty_generics #where_clause {
+            fn serialize(&self) -> ::haya_nbt::Tag {
+                ::haya_nbt::Tag::Compound(::haya_nbt::SerializeCompound::serialize_compound(self))
+            }
+        }
+    }
+}
+
+pub fn deserialize_struct(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs: NbtAttrs = parse_attrs(&input.attrs)?;
+    if attrs.tag.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "expected `tag` only on enums",
+        ));
+    }
+    let fields = parse_fields(named(&input)?)?;
+    let invalid = invalid();
+    let body = de_fields(&fields, quote!(Self));
+    let ret = filter(&attrs, quote!({ #body }));
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::haya_nbt::Deserialize for #name #ty_generics #where_clause {
+            fn deserialize(__nbt: ::haya_nbt::Tag) -> ::core::result::Result<Self, ::mser::Error> {
+                let __c = match __nbt {
+                    ::haya_nbt::Tag::Compound(__c) => __c,
+                    _ => return ::core::result::Result::Err(#invalid),
+                };
+                #ret
+            }
+        }
+    })
+}
+
+fn variants(
+    input: &syn::DeriveInput,
+) -> &syn::punctuated::Punctuated<syn::Variant, syn::Token![,]> {
+    match &input.data {
+        syn::Data::Enum(data) => &data.variants,
+        _ => unreachable!(),
+    }
+}
+
+pub fn serialize_enum(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs: NbtAttrs = parse_attrs(&input.attrs)?;
+    let mut arms = Vec::new();
+    for variant in variants(&input) {
+        let ident = &variant.ident;
+        let value = variant_name(variant)?;
+        let Some(tag) = &attrs.tag else {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected `#[nbt(tag = \"..\")]` for enums with fields",
+                ));
+            }
+            let value_tag = string_tag(&value);
+            arms.push(quote! {
+                Self::#ident => ::haya_nbt::Tag::String(#value_tag),
+            });
+            continue;
+        };
+        let tag_key = string_tag(&tag.value());
+        let value_tag = string_tag(&value);
+        let push_tag = quote! {
+            __c.push(#tag_key, ::haya_nbt::Tag::String(#value_tag));
+        };
+        let arm = match &variant.fields {
+            syn::Fields::Unit => quote!(Self::#ident => { #push_tag }),
+            syn::Fields::Named(named) => {
+                let fields = parse_fields(named)?;
+                let idents = fields.iter().map(|field| field.ident);
+                let push = ser_fields(&fields, |field| {
+                    let x = field.ident;
+                    quote!(#x)
+                });
+                quote!(Self::#ident { #(#idents,)* } => { #push_tag #push })
+            }
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let push = ser_flatten(quote!(__x));
+                quote! {
+                    Self::#ident(__x) => {
+                        #push_tag
+                        #push
+                    }
+                }
+            }
+            syn::Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected named fields or a single unnamed field",
+                ));
+            }
+        };
+        arms.push(arm);
+    }
+    if attrs.tag.is_some() {
+        return Ok(serialize_compound(
+            &input,
+            quote! {
+                let mut __c = ::haya_nbt::CompoundTag::new();
+                match self {
+                    #(#arms)*
+                }
+                __c
+            },
+        ));
+    }
+    let body = quote! {
+        match self {
+            #(#arms)*
+        }
+    };
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::haya_nbt::Serialize for #name #ty_generics #where_clause {
+            fn serialize(&self) -> ::haya_nbt::Tag {
+                #body
+            }
+        }
+    })
+}
+
+pub fn deserialize_enum(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs: NbtAttrs = parse_attrs(&input.attrs)?;
+    let invalid = invalid();
+    let mut arms = Vec::new();
+    for variant in variants(&input) {
+        let ident = &variant.ident;
+        let value = variant_name(variant)?;
+        let arm = match &variant.fields {
+            syn::Fields::Unit => quote!(#value => Self::#ident,),
+            _ if attrs.tag.is_none() => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected `#[nbt(tag = \"..\")]` for enums with fields",
+                ));
+            }
+            syn::Fields::Named(named) => {
+                let fields = parse_fields(named)?;
+                let body = de_fields(&fields, quote!(Self::#ident));
+                quote!(#value => { #body })
+            }
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let ty = &unnamed.unnamed[0].ty;
+                let x = de_value(None, ty, quote!(::haya_nbt::Tag::Compound(__c)));
+                quote!(#value => Self::#ident(#x),)
+            }
+            syn::Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected named fields or a single unnamed field",
+                ));
+            }
+        };
+        arms.push(arm);
+    }
+    let value = quote! {
+        match &*__t {
+            #(#arms)*
+            _ => return ::core::result::Result::Err(#invalid),
+        }
+    };
+    let ret = filter(&attrs, value);
+    let body = match &attrs.tag {
+        Some(tag) => quote! {
+            let mut __c = match __nbt {
+                ::haya_nbt::Tag::Compound(__c) => __c,
+                _ => return ::core::result::Result::Err(#invalid),
+            };
+            let __t = match __c.find_remove(#tag) {
+                ::core::option::Option::Some(::haya_nbt::Tag::String(__t)) => __t,
+                _ => return ::core::result::Result::Err(#invalid),
+            };
+            #ret
+        },
+        None => quote! {
+            let __t = match __nbt {
+                ::haya_nbt::Tag::String(__t) => __t,
+                _ => return ::core::result::Result::Err(#invalid),
+            };
+            #ret
+        },
+    };
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::haya_nbt::Deserialize for #name #ty_generics #where_clause {
+            fn deserialize(__nbt: ::haya_nbt::Tag) -> ::core::result::Result<Self, ::mser::Error> {
+                #body
+            }
+        }
+    })
+}
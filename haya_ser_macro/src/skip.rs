@@ -0,0 +1,182 @@
+use crate::deserialize::TypeGenerics;
+use crate::{Attrs, FieldAttrs, Ty, ident_case, parse_fields, ty};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+
+pub fn skip_struct(
+    input: syn::DeriveInput,
+    cratename: syn::Path,
+    attrs: Attrs,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(data) => parse_fields(&data.fields)?,
+        _ => unreachable!(),
+    };
+    if attrs.filter.is_some() {
+        let body = read_self(&input, &cratename);
+        return Ok(skip_impl(&input, &cratename, None, body));
+    }
+    let ty_name = name.to_string();
+    let skip = fields
+        .iter()
+        .map(|(field, attrs, m)| skip_field(&cratename, &ty_name, field, attrs, m));
+    let mut size = quote!(::core::option::Option::Some(0));
+    for (field, attrs, _) in fields.iter() {
+        let field_ty = &field.ty;
+        size = if attrs.varint || attrs.filter.is_some() {
+            quote!(::core::option::Option::None)
+        } else {
+            quote!(::#cratename::size_add(#size, <#field_ty as ::#cratename::Skip>::SIZE))
+        };
+    }
+    Ok(skip_impl(&input, &cratename, Some(size), quote!(#(#skip)*)))
+}
+
+pub fn skip_enum(
+    input: syn::DeriveInput,
+    cratename: syn::Path,
+    attrs: Attrs,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        syn::Data::Enum(data) => &data.variants,
+        _ => unreachable!(),
+    };
+    let is_repr_enum = variants
+        .iter()
+        .all(|x| matches!(x.fields, syn::Fields::Unit));
+    let body = if is_repr_enum || attrs.filter.is_some() {
+        read_self(&input, &cratename)
+    } else {
+        let header = match &attrs.header {
+            Some(x) => x,
+            None => return Err(syn::Error::new_spanned(&input, "expected header")),
+        };
+        let mut match_arms = Vec::with_capacity(variants.len());
+        for variant in variants.iter() {
+            let variant_name = &variant.ident;
+            let header_variant = syn::Ident::new(
+                &ident_case(&attrs, variant_name),
+                proc_macro2::Span::call_site(),
+            );
+            let fields = parse_fields(&variant.fields)?;
+            let ty_name = format!("{name}::{variant_name}");
+            let skip = fields
+                .iter()
+                .map(|(field, attrs, m)| skip_field(&cratename, &ty_name, field, attrs, m));
+            match_arms.push(quote! {
+                #header::#header_variant => { #(#skip)* }
+            });
+        }
+        quote! {
+            match <#header as ::#cratename::Read>::read(__r)? {
+                #(#match_arms,)*
+            }
+        }
+    };
+    Ok(skip_impl(&input, &cratename, None, body))
+}
+
+fn skip_impl(
+    input: &syn::DeriveInput,
+    cratename: &syn::Path,
+    size: Option<TokenStream>,
+    body: TokenStream,
+) -> TokenStream {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::#cratename::Skip));
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let size_const = size.map(|x| quote!(const SIZE: ::core::option::Option<usize> = #x;));
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::#cratename::Skip for #name #type_generics #where_clause {
+            #size_const
+
+            #[inline]
+            fn skip(__r: &mut ::#cratename::Reader<'_>) -> ::core::result::Result<(), ::#cratename::Error> {
+                #body
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+/// Values checked by a filter have to be decoded.
+fn read_self(input: &syn::DeriveInput, cratename: &syn::Path) -> TokenStream {
+    let name = &input.ident;
+    let generics = &input.generics;
+    let tok = if generics.lifetimes().next().is_some() {
+        let type_generics = TypeGenerics(generics);
+        quote!(<'_ #type_generics)
+    } else {
+        let (_, type_generics, _) = generics.split_for_impl();
+        quote!(#type_generics)
+    };
+    quote! {
+        let _: #name #tok = ::#cratename::Read::read(__r)?;
+    }
+}
+
+fn skip_field(
+    cratename: &syn::Path,
+    ty_name: &str,
+    field: &syn::Field,
+    attrs: &FieldAttrs,
+    m: &syn::Member,
+) -> TokenStream {
+    let field_name = match m {
+        syn::Member::Named(x) => x.unraw().to_string(),
+        syn::Member::Unnamed(x) => x.index.to_string(),
+    };
+    let field_ty = &field.ty;
+    let skip = if let Some(filter) = &attrs.filter {
+        let read = if attrs.varint {
+            match ty(field_ty) {
+                Ty::I32 => {
+                    quote!(<::#cratename::V32 as ::#cratename::Read>::read(__r).map(|__v| __v.0 as i32))
+                }
+                Ty::U32 => {
+                    quote!(<::#cratename::V32 as ::#cratename::Read>::read(__r).map(|__v| __v.0))
+                }
+                Ty::I64 => {
+                    quote!(<::#cratename::V64 as ::#cratename::Read>::read(__r).map(|__v| __v.0 as i64))
+                }
+                _ => quote!(<::#cratename::V64 as ::#cratename::Read>::read(__r).map(|__v| __v.0)),
+            }
+        } else {
+            quote!(::#cratename::Read::read(__r))
+        };
+        quote! {
+            match #read {
+                ::core::result::Result::Ok(__v) => {
+                    if #filter(&__v) {
+                        ::core::result::Result::Ok(())
+                    } else {
+                        ::core::result::Result::Err(__r.error(::#cratename::ErrorKind::Invalid))
+                    }
+                }
+                ::core::result::Result::Err(__e) => ::core::result::Result::Err(__e),
+            }
+        }
+    } else if attrs.varint {
+        match ty(field_ty) {
+            Ty::I32 | Ty::U32 => quote!(<::#cratename::V32 as ::#cratename::Skip>::skip(__r)),
+            _ => quote!(<::#cratename::V64 as ::#cratename::Skip>::skip(__r)),
+        }
+    } else {
+        quote!(<#field_ty as ::#cratename::Skip>::skip(__r))
+    };
+    quote! {
+        if let ::core::result::Result::Err(__e) = #skip {
+            return ::core::result::Result::Err(__e.with_field(#ty_name, #field_name));
+        }
+    }
+}
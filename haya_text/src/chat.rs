@@ -3,14 +3,15 @@ use crate::color::{TextColor, TextColorNamed, TextColorRgb};
 use crate::decoration::DecorationMap;
 use crate::hover_event::HoverEvent;
 use crate::profile::{PlayerSkinPatch, PropertyMap, ResolvableProfile};
-use crate::{capacity_fix, key};
+use crate::selector::{EntitySelector, SelectorError};
+use crate::{capacity_fix, key, list_items};
 use alloc::boxed::Box;
 use alloc::vec;
 use alloc::vec::Vec;
 use haya_ident::Identifier;
 use haya_math::{f32_to_u8, f64_to_u8};
 use haya_nbt::{CompoundTag, Deserialize, ListTag, Serialize, StringTag, Tag};
-use mser::Error;
+use mser::{Error, ErrorKind};
 
 const TYPE: &str = "type";
 const EXTRA: &str = "extra";
@@ -99,7 +100,7 @@ pub enum Content {
     Translatable {
         key: StringTag,
         fallback: Option<StringTag>,
-        args: Vec<TextComponent>,
+        args: Vec<TranslationArg>,
     },
     Score {
         name: StringTag,
@@ -123,6 +124,54 @@ pub enum Content {
     },
 }
 
+/// An argument of a translatable component. Numbers stay numbers instead of
+/// becoming text, and booleans are stored as bytes as elsewhere in NBT.
+#[derive(Clone)]
+pub enum TranslationArg {
+    Component(Box<TextComponent>),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+}
+
+impl Serialize for TranslationArg {
+    fn serialize(&self) -> Tag {
+        match self {
+            Self::Component(x) => x.serialize(),
+            Self::Byte(x) => Tag::Byte(*x),
+            Self::Short(x) => Tag::Short(*x),
+            Self::Int(x) => Tag::Int(*x),
+            Self::Long(x) => Tag::Long(*x),
+            Self::Float(x) => Tag::Float(*x),
+            Self::Double(x) => Tag::Double(*x),
+        }
+    }
+}
+
+impl Deserialize for TranslationArg {
+    fn deserialize(nbt: Tag) -> Result<Self, Error> {
+        Ok(match nbt {
+            Tag::Byte(x) => Self::Byte(x),
+            Tag::Short(x) => Self::Short(x),
+            Tag::Int(x) => Self::Int(x),
+            Tag::Long(x) => Self::Long(x),
+            Tag::Float(x) => Self::Float(x),
+            Tag::Double(x) => Self::Double(x),
+            // An element of a list whose elements differ in type.
+            Tag::Compound(c) if matches!(c.as_ref(), [(k, _)] if k.is_empty()) => {
+                match c.into_inner().pop() {
+                    Some((_, v)) => return Self::deserialize(v),
+                    None => unreachable!(),
+                }
+            }
+            x => Self::Component(Box::new(TextComponent::deserialize(x)?)),
+        })
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum Color {
     NS(TextColorNamed, ShadowColor),
@@ -360,7 +409,7 @@ impl Deserialize for ShadowColor {
                         | (f32_to_u8(b) as u32) << 16
                         | (f32_to_u8(a) as u32) << 24,
                 }),
-                _ => Err(Error),
+                _ => Err(Error::new(ErrorKind::Invalid)),
             },
             Tag::List(ListTag::Double(value)) => match value[..] {
                 [r, g, b, a] => Ok(Self {
@@ -369,9 +418,9 @@ impl Deserialize for ShadowColor {
                         | (f64_to_u8(b) as u32) << 16
                         | (f64_to_u8(a) as u32) << 24,
                 }),
-                _ => Err(Error),
+                _ => Err(Error::new(ErrorKind::Invalid)),
             },
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -397,6 +446,21 @@ pub enum NbtContent {
     Storage { storage: Identifier },
 }
 
+impl Content {
+    /// Parses the selector of a selector component or of the entity an NBT
+    /// component reads from.
+    pub fn selector(&self) -> Option<Result<EntitySelector<'_>, SelectorError>> {
+        match self {
+            Self::Selector { pattern, .. } => Some(EntitySelector::parse(pattern)),
+            Self::Nbt {
+                content: NbtContent::Entity { selector },
+                ..
+            } => Some(EntitySelector::parse(selector)),
+            _ => None,
+        }
+    }
+}
+
 impl Serialize for TextComponent {
     fn serialize(&self) -> Tag {
         let mut nbt = CompoundTag::new();
@@ -428,7 +492,7 @@ impl Serialize for TextComponent {
                     for arg in args {
                         match arg.serialize() {
                             Tag::Compound(x) => vec.push(x),
-                            _ => unreachable!(),
+                            x => vec.push(CompoundTag::from(vec![(StringTag::new(), x)])),
                         }
                     }
                     nbt.push(TRANSLATE_WITH_K, Tag::List(ListTag::Compound(vec)));
@@ -606,7 +670,7 @@ impl Deserialize for TextComponent {
                     siblings: Vec::new(),
                 });
             }
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         };
         let mut content = Content::Literal {
             content: StringTag::new(),
@@ -655,28 +719,13 @@ impl Deserialize for TextComponent {
                     }
                 }
                 TRANSLATE_WITH => {
-                    let w = match v {
-                        Tag::List(ListTag::Compound(l)) => {
-                            let mut vec = Vec::with_capacity(capacity_fix(l.len()));
-                            for nbt in l {
-                                vec.push(Self::deserialize(Tag::Compound(nbt))?);
-                            }
-                            vec
-                        }
-                        Tag::List(ListTag::String(l)) => {
-                            let mut vec = Vec::with_capacity(capacity_fix(l.len()));
-                            for nbt in l {
-                                vec.push(Self {
-                                    content: Content::Literal { content: nbt },
-                                    style: Style::new(),
-                                    siblings: Vec::new(),
-                                });
-                            }
-                            vec
-                        }
-                        Tag::List(ListTag::None) => Vec::new(),
-                        _ => return Err(Error),
+                    let Tag::List(l) = v else {
+                        return Err(Error::new(ErrorKind::Invalid));
                     };
+                    let w = list_items(l)
+                        .into_iter()
+                        .map(TranslationArg::deserialize)
+                        .collect::<Result<Vec<_>, Error>>()?;
                     match &mut content {
                         Content::Translatable { args, .. } => *args = w,
                         _ => {
@@ -691,7 +740,7 @@ impl Deserialize for TextComponent {
                 SCORE => {
                     let score = match v {
                         Tag::Compound(c) => c,
-                        _ => return Err(Error),
+                        _ => return Err(Error::new(ErrorKind::Invalid)),
                     };
                     let mut name = StringTag::new();
                     let mut objective = StringTag::new();
@@ -703,7 +752,7 @@ impl Deserialize for TextComponent {
                             SCORE_OBJECTIVE => {
                                 objective = StringTag::deserialize(sv)?;
                             }
-                            _ => return Err(Error),
+                            _ => return Err(Error::new(ErrorKind::Invalid)),
                         }
                     }
                     content = Content::Score { name, objective };
@@ -875,7 +924,7 @@ impl Deserialize for TextComponent {
                         }
                     }
                     Tag::List(ListTag::None) => {}
-                    _ => return Err(Error),
+                    _ => return Err(Error::new(ErrorKind::Invalid)),
                 },
                 COLOR => {
                     let color = TextColor::deserialize(v)?;
@@ -922,7 +971,7 @@ impl Deserialize for TextComponent {
                     let font = StringTag::deserialize(v)?;
                     style.font = Some(font);
                 }
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             }
         }
         if let Some(sep) = separator {
@@ -933,11 +982,11 @@ impl Deserialize for TextComponent {
                 Content::Nbt { separator: s, .. } => {
                     *s = Some(sep);
                 }
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             }
         }
         if atlas.is_some() && sprite.is_none() {
-            return Err(Error);
+            return Err(Error::new(ErrorKind::Invalid));
         }
         if let Some(s) = sprite {
             if matches!(
@@ -946,7 +995,7 @@ impl Deserialize for TextComponent {
                     content: ObjectInfo::Player { .. }
                 }
             ) {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             }
             content = Content::Object {
                 content: ObjectInfo::Atlas {
@@ -969,7 +1018,7 @@ impl Deserialize for TextComponent {
                 Content::Object { .. } => OBJECT_TYPE,
             };
             if ty2 != ty1 {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             }
         }
         if let Some(source1) = source.as_deref() {
@@ -986,10 +1035,10 @@ impl Deserialize for TextComponent {
                     content: NbtContent::Storage { .. },
                     ..
                 } => NBT_STORAGE,
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             };
             if source2 != source1 {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             }
         }
         if let Some(object1) = object.as_deref() {
@@ -1000,10 +1049,10 @@ impl Deserialize for TextComponent {
                 Content::Object {
                     content: ObjectInfo::Player { .. },
                 } => OBJECT_PLAYER,
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             };
             if object2 != object1 {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             }
         }
 
@@ -3,7 +3,7 @@ use alloc::boxed::Box;
 use alloc::vec;
 use haya_ident::Identifier;
 use haya_nbt::{CompoundTag, Deserialize, Serialize, StringTag, Tag};
-use mser::Error;
+use mser::{Error, ErrorKind};
 
 const ACTION: &str = "action";
 const VALUE: &str = "value";
@@ -96,7 +96,7 @@ impl Deserialize for ClickEvent {
     fn deserialize(nbt: Tag) -> Result<Self, Error> {
         let c = match nbt {
             Tag::Compound(c) => c,
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         };
         let mut action = None;
         let mut value = None;
@@ -104,14 +104,14 @@ impl Deserialize for ClickEvent {
             match &*k {
                 ACTION => action = Some(v),
                 VALUE => value = Some(v),
-                _ => return Err(Error),
+                _ => return Err(Error::new(ErrorKind::Invalid)),
             }
         }
         let Some(Tag::String(action1)) = action else {
-            return Err(Error);
+            return Err(Error::new(ErrorKind::Invalid));
         };
         let Some(Tag::Compound(value1)) = value else {
-            return Err(Error);
+            return Err(Error::new(ErrorKind::Invalid));
         };
         let iter = value1.into_iter();
         match &*action1 {
@@ -120,7 +120,7 @@ impl Deserialize for ClickEvent {
                 if &*k == URL {
                     Ok(Self::OpenUrl(StringTag::deserialize(v)?))
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             OPEN_FILE => {
@@ -128,7 +128,7 @@ impl Deserialize for ClickEvent {
                 if &*k == PATH {
                     Ok(Self::OpenFile(StringTag::deserialize(v)?))
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             RUN_COMMAND => {
@@ -138,10 +138,10 @@ impl Deserialize for ClickEvent {
                     if command.chars().all(|x| x != '\u{a7}' && !x.is_control()) {
                         Ok(Self::RunCommand(command))
                     } else {
-                        Err(Error)
+                        Err(Error::new(ErrorKind::Invalid))
                     }
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             SUGGEST_COMMAND => {
@@ -151,10 +151,10 @@ impl Deserialize for ClickEvent {
                     if command.chars().all(|x| x != '\u{a7}' && !x.is_control()) {
                         Ok(Self::SuggestCommand(command))
                     } else {
-                        Err(Error)
+                        Err(Error::new(ErrorKind::Invalid))
                     }
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             SHOW_DIALOG => {
@@ -162,7 +162,7 @@ impl Deserialize for ClickEvent {
                 if &*k == SHOW_DIALOG {
                     Ok(Self::ShowDialog(v))
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             CHANGE_PAGE => {
@@ -172,10 +172,10 @@ impl Deserialize for ClickEvent {
                     if page > 0 {
                         Ok(Self::ChangePage(page))
                     } else {
-                        Err(Error)
+                        Err(Error::new(ErrorKind::Invalid))
                     }
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             COPY_TO_CLIPBOARD => {
@@ -183,7 +183,7 @@ impl Deserialize for ClickEvent {
                 if &*k == VALUE {
                     Ok(Self::CopyToClipboard(StringTag::deserialize(v)?))
                 } else {
-                    Err(Error)
+                    Err(Error::new(ErrorKind::Invalid))
                 }
             }
             CUSTOM => {
@@ -193,16 +193,16 @@ impl Deserialize for ClickEvent {
                     match &*k {
                         ID => id = Some(Identifier::deserialize(v)?),
                         PAYLOAD => payload = Some(v),
-                        _ => return Err(Error),
+                        _ => return Err(Error::new(ErrorKind::Invalid)),
                     }
                 }
                 let id1 = match id {
                     Some(x) => x,
-                    None => return Err(Error),
+                    None => return Err(Error::new(ErrorKind::Invalid)),
                 };
                 Ok(Self::Custom(Box::new((id1, payload))))
             }
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
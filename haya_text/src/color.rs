@@ -2,7 +2,7 @@ use crate::key;
 use core::str::FromStr;
 use haya_nbt::{Deserialize, Serialize, StringTag, Tag};
 use haya_str::{hex_to_u8, u8_to_hex};
-use mser::Error;
+use mser::{Error, ErrorKind};
 
 #[derive(Clone, Copy)]
 pub enum TextColor {
@@ -34,11 +34,11 @@ impl FromStr for TextColor {
         }
         let hex = match s.strip_prefix('#') {
             Some(rest) => rest,
-            None => return Err(Error),
+            None => return Err(Error::new(ErrorKind::Invalid)),
         };
         let a = match u32::from_str_radix(hex, 16) {
             Ok(x) => x,
-            Err(_) => return Err(Error),
+            Err(_) => return Err(Error::new(ErrorKind::Invalid)),
         };
         if a <= 0xffffff {
             Ok(Self::Rgb(TextColorRgb {
@@ -47,7 +47,7 @@ impl FromStr for TextColor {
                 blue: (a & 0xff) as u8,
             }))
         } else {
-            Err(Error)
+            Err(Error::new(ErrorKind::Invalid))
         }
     }
 }
@@ -269,7 +269,7 @@ impl FromStr for TextColorNamed {
             LIGHT_PURPLE => Self::LightPurple,
             YELLOW => Self::Yellow,
             WHITE => Self::White,
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         })
     }
 }
@@ -321,11 +321,11 @@ impl Deserialize for TextColor {
     fn deserialize(nbt: Tag) -> Result<Self, Error> {
         let s = match nbt {
             Tag::String(s) => s,
-            _ => return Err(Error),
+            _ => return Err(Error::new(ErrorKind::Invalid)),
         };
         if let Some(bytes) = s.strip_prefix('#').map(|x| x.as_bytes()) {
             let [c0, c1, c2, c3, c4, c5] = bytes[..] else {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             };
             let r1 = hex_to_u8(c0);
             let r2 = hex_to_u8(c1);
@@ -335,7 +335,7 @@ impl Deserialize for TextColor {
             let b2 = hex_to_u8(c5);
             let (Some(a), Some(b), Some(c), Some(d), Some(e), Some(f)) = (r1, r2, g1, g2, b1, b2)
             else {
-                return Err(Error);
+                return Err(Error::new(ErrorKind::Invalid));
             };
             Ok(Self::Rgb(TextColorRgb {
                 red: (a << 4) | b,
@@ -1,6 +1,14 @@
+use crate::chat::TextComponent;
+use crate::{key, list_items};
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
 use core::str::from_utf8_unchecked;
-use haya_str::u8_to_hex;
+use haya_nbt::{CompoundStringify, CompoundTag, Deserialize, ListTag, Serialize, StringTag, Tag};
+use haya_str::{hex_to_u8, u8_to_hex};
+use mser::{Error, ErrorKind};
+
+const MAX_DEPTH: usize = 512;
 
 pub fn json_escaped_string(s: &str, w: &mut String) {
     let mut start = 0;
@@ -60,3 +68,797 @@ const ESCAPE: &[u8; 256] = &[
 const fn json_char_width_escaped(ch: u8) -> u8 {
     ESCAPE[ch as usize]
 }
+
+/// Whether `key` holds a boolean in a text component. Translation arguments
+/// that are not components are written under an empty key, and are booleans
+/// when they hold a byte.
+pub fn component_boolean(key: &str) -> bool {
+    matches!(
+        key,
+        "bold"
+            | "italic"
+            | "underlined"
+            | "strikethrough"
+            | "obfuscated"
+            | "interpret"
+            | "hat"
+            | ""
+    )
+}
+
+/// Writes `tag` as JSON, unwrapping the `{"": value}` entries that NBT uses
+/// for heterogeneous lists.
+///
+/// NBT has no booleans, so bytes are written as numbers unless they hold 0 or
+/// 1 under a compound key for which `boolean` returns true.
+pub fn tag_to_json(tag: &Tag, boolean: &dyn Fn(&str) -> bool, w: &mut String) {
+    match tag {
+        Tag::Byte(x) => write_num(w, x),
+        Tag::Short(x) => write_num(w, x),
+        Tag::Int(x) => write_num(w, x),
+        Tag::Long(x) => write_num(w, x),
+        Tag::Float(x) => write_float(w, *x as f64),
+        Tag::Double(x) => write_float(w, *x),
+        Tag::String(x) => write_str(w, x),
+        Tag::ByteArray(x) => write_nums(w, x),
+        Tag::IntArray(x) => write_nums(w, x),
+        Tag::LongArray(x) => write_nums(w, x),
+        Tag::List(x) => list_to_json(x, boolean, w),
+        Tag::Compound(x) => compound_to_json(x, boolean, w),
+        Tag::End => w.push_str("null"),
+    }
+}
+
+fn list_to_json(list: &ListTag, boolean: &dyn Fn(&str) -> bool, w: &mut String) {
+    w.push('[');
+    match list {
+        ListTag::None => {}
+        ListTag::Byte(x) => write_seq(w, x, write_num),
+        ListTag::Short(x) => write_seq(w, x, write_num),
+        ListTag::Int(x) => write_seq(w, x, write_num),
+        ListTag::Long(x) => write_seq(w, x, write_num),
+        ListTag::Float(x) => write_seq(w, x, |s, &n| write_float(s, n as f64)),
+        ListTag::Double(x) => write_seq(w, x, |s, &n| write_float(s, n)),
+        ListTag::String(x) => write_seq(w, x, |s, n| write_str(s, n)),
+        ListTag::ByteArray(x) => write_seq(w, x, |s, n| write_nums(s, n)),
+        ListTag::IntArray(x) => write_seq(w, x, |s, n| write_nums(s, n)),
+        ListTag::LongArray(x) => write_seq(w, x, |s, n| write_nums(s, n)),
+        ListTag::List(x) => write_seq(w, x, |s, n| list_to_json(n, boolean, s)),
+        ListTag::Compound(x) => write_seq(w, x, |s, n| match n.as_ref() {
+            [(k, v)] if k.is_empty() => value_to_json(k, v, boolean, s),
+            _ => compound_to_json(n, boolean, s),
+        }),
+    }
+    w.push(']');
+}
+
+fn compound_to_json(compound: &CompoundTag, boolean: &dyn Fn(&str) -> bool, w: &mut String) {
+    w.push('{');
+    for (index, (k, v)) in compound.iter().enumerate() {
+        if index != 0 {
+            w.push(',');
+        }
+        write_str(w, k);
+        w.push(':');
+        value_to_json(k, v, boolean, w);
+    }
+    w.push('}');
+}
+
+fn value_to_json(key: &str, v: &Tag, boolean: &dyn Fn(&str) -> bool, w: &mut String) {
+    match v {
+        Tag::Byte(0) if boolean(key) => w.push_str("false"),
+        Tag::Byte(1) if boolean(key) => w.push_str("true"),
+        _ => tag_to_json(v, boolean, w),
+    }
+}
+
+fn write_seq<T>(w: &mut String, seq: &[T], f: impl Fn(&mut String, &T)) {
+    for (index, x) in seq.iter().enumerate() {
+        if index != 0 {
+            w.push(',');
+        }
+        f(w, x);
+    }
+}
+
+fn write_nums<T: core::fmt::Display>(w: &mut String, seq: &[T]) {
+    w.push('[');
+    write_seq(w, seq, write_num);
+    w.push(']');
+}
+
+fn write_num(w: &mut String, n: &impl core::fmt::Display) {
+    let _ = write!(w, "{n}");
+}
+
+fn write_float(w: &mut String, n: f64) {
+    if n.is_finite() {
+        let _ = write!(w, "{n}");
+    } else {
+        w.push('0');
+    }
+}
+
+fn write_str(w: &mut String, s: &str) {
+    w.push('"');
+    json_escaped_string(s, w);
+    w.push('"');
+}
+
+/// Parses JSON into the NBT model used by the component codecs.
+///
+/// Booleans become bytes, integers become ints or longs, other numbers become
+/// doubles and `null` becomes [`Tag::End`]. Arrays whose elements differ in
+/// type are stored as compounds with a single empty key.
+pub fn json_to_tag(s: &str) -> Result<Tag, Error> {
+    let mut p = Parser {
+        n: s.as_bytes(),
+        pos: 0,
+    };
+    let tag = p.value(MAX_DEPTH)?;
+    p.ws();
+    if p.pos != p.n.len() {
+        return Err(Error::new(ErrorKind::TrailingBytes));
+    }
+    Ok(tag)
+}
+
+struct Parser<'a> {
+    n: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.n.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Result<u8, Error> {
+        self.ws();
+        match self.n.get(self.pos) {
+            Some(&x) => Ok(x),
+            None => Err(Error::new(ErrorKind::UnexpectedEof)),
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), Error> {
+        if self.peek()? == c {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::Invalid))
+        }
+    }
+
+    fn literal(&mut self, s: &[u8]) -> Result<(), Error> {
+        if self.n[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::Invalid))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Tag, Error> {
+        if depth == 0 {
+            return Err(Error::new(ErrorKind::TooLong));
+        }
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                let mut compound = CompoundTag::new();
+                if self.peek()? == b'}' {
+                    self.pos += 1;
+                    return Ok(Tag::Compound(compound));
+                }
+                loop {
+                    if self.peek()? != b'"' {
+                        return Err(Error::new(ErrorKind::Invalid));
+                    }
+                    let k = self.string()?;
+                    self.expect(b':')?;
+                    let v = self.value(depth - 1)?;
+                    if !matches!(v, Tag::End) {
+                        compound.push(StringTag::from_owned(k.into_boxed_str()), v);
+                    }
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        b'}' => {
+                            self.pos += 1;
+                            return Ok(Tag::Compound(compound));
+                        }
+                        _ => return Err(Error::new(ErrorKind::Invalid)),
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut vec = Vec::new();
+                if self.peek()? == b']' {
+                    self.pos += 1;
+                    return Ok(Tag::List(ListTag::None));
+                }
+                loop {
+                    vec.push(self.value(depth - 1)?);
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        b']' => {
+                            self.pos += 1;
+                            return Ok(Tag::List(list_of(vec)));
+                        }
+                        _ => return Err(Error::new(ErrorKind::Invalid)),
+                    }
+                }
+            }
+            b'"' => Ok(Tag::String(StringTag::from_owned(
+                self.string()?.into_boxed_str(),
+            ))),
+            b't' => self.literal(b"true").map(|()| Tag::Byte(1)),
+            b'f' => self.literal(b"false").map(|()| Tag::Byte(0)),
+            b'n' => self.literal(b"null").map(|()| Tag::End),
+            _ => self.number(),
+        }
+    }
+
+    fn number(&mut self) -> Result<Tag, Error> {
+        let start = self.pos;
+        if self.n.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        if !matches!(self.n.get(self.pos), Some(b'0'..=b'9')) {
+            return Err(Error::new(ErrorKind::Invalid));
+        }
+        let mut float = false;
+        while let Some(&c) = self.n.get(self.pos) {
+            match c {
+                b'0'..=b'9' => {}
+                b'.' | b'e' | b'E' => float = true,
+                // Signs are only allowed at the start of the exponent.
+                b'-' | b'+' if matches!(self.n[self.pos - 1], b'e' | b'E') => {}
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let s = unsafe { from_utf8_unchecked(&self.n[start..self.pos]) };
+        if !float && let Ok(x) = s.parse::<i64>() {
+            return Ok(match i32::try_from(x) {
+                Ok(y) => Tag::Int(y),
+                Err(_) => Tag::Long(x),
+            });
+        }
+        match s.parse::<f64>() {
+            Ok(x) => Ok(Tag::Double(x)),
+            Err(_) => Err(Error::new(ErrorKind::Invalid)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.pos += 1;
+        let mut out = String::new();
+        let mut start = self.pos;
+        loop {
+            let Some(&c) = self.n.get(self.pos) else {
+                return Err(Error::new(ErrorKind::UnexpectedEof));
+            };
+            match c {
+                b'"' => {
+                    out.push_str(unsafe { from_utf8_unchecked(&self.n[start..self.pos]) });
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                b'\\' => {
+                    out.push_str(unsafe { from_utf8_unchecked(&self.n[start..self.pos]) });
+                    self.pos += 1;
+                    let Some(&e) = self.n.get(self.pos) else {
+                        return Err(Error::new(ErrorKind::UnexpectedEof));
+                    };
+                    self.pos += 1;
+                    match e {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\x08'),
+                        b'f' => out.push('\x0c'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => {
+                            let hi = self.hex4()?;
+                            let ch = if (0xD800..0xDC00).contains(&hi) {
+                                self.literal(b"\\u")?;
+                                let lo = self.hex4()?;
+                                if !(0xDC00..0xE000).contains(&lo) {
+                                    return Err(Error::new(ErrorKind::Utf8));
+                                }
+                                0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+                            } else {
+                                hi
+                            };
+                            match char::from_u32(ch) {
+                                Some(x) => out.push(x),
+                                None => return Err(Error::new(ErrorKind::Utf8)),
+                            }
+                        }
+                        _ => return Err(Error::new(ErrorKind::Invalid)),
+                    }
+                    start = self.pos;
+                }
+                0..=0x1f => return Err(Error::new(ErrorKind::Invalid)),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let Some(&[a, b, c, d]) = self.n.get(self.pos..self.pos + 4) else {
+            return Err(Error::new(ErrorKind::UnexpectedEof));
+        };
+        self.pos += 4;
+        let mut n = 0;
+        for d in [a, b, c, d] {
+            match hex_to_u8(d) {
+                Some(x) => n = (n << 4) | x as u32,
+                None => return Err(Error::new(ErrorKind::Invalid)),
+            }
+        }
+        Ok(n)
+    }
+}
+
+fn list_of(vec: Vec<Tag>) -> ListTag {
+    let same = vec
+        .iter()
+        .all(|x| core::mem::discriminant(x) == core::mem::discriminant(&vec[0]));
+    if !same {
+        let empty = StringTag::new();
+        return ListTag::Compound(
+            vec.into_iter()
+                .map(|x| CompoundTag::from(alloc::vec![(empty.clone(), x)]))
+                .collect(),
+        );
+    }
+    macro_rules! collect {
+        ($variant:ident) => {
+            ListTag::$variant(
+                vec.into_iter()
+                    .map(|x| match x {
+                        Tag::$variant(y) => y,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            )
+        };
+    }
+    match &vec[0] {
+        Tag::Byte(_) => collect!(Byte),
+        Tag::Short(_) => collect!(Short),
+        Tag::Int(_) => collect!(Int),
+        Tag::Long(_) => collect!(Long),
+        Tag::Float(_) => collect!(Float),
+        Tag::Double(_) => collect!(Double),
+        Tag::String(_) => collect!(String),
+        Tag::ByteArray(_) => collect!(ByteArray),
+        Tag::IntArray(_) => collect!(IntArray),
+        Tag::LongArray(_) => collect!(LongArray),
+        Tag::List(_) => collect!(List),
+        Tag::Compound(_) => collect!(Compound),
+        Tag::End => ListTag::None,
+    }
+}
+
+const TYPE: &str = "type";
+const TEXT: &str = "text";
+const EXTRA: &str = "extra";
+const TRANSLATE_WITH: &str = "with";
+const SEPARATOR: &str = "separator";
+const CLICK_EVENT: &str = "click_event";
+const HOVER_EVENT: &str = "hover_event";
+const LEGACY_CLICK_EVENT: &str = "clickEvent";
+const LEGACY_HOVER_EVENT: &str = "hoverEvent";
+const ACTION: &str = "action";
+const VALUE: &str = "value";
+const CONTENTS: &str = "contents";
+const DIALOG: &str = "dialog";
+const SHOW_DIALOG: &str = "show_dialog";
+const SHOW_TEXT: &str = "show_text";
+const SHOW_ITEM: &str = "show_item";
+const SHOW_ENTITY: &str = "show_entity";
+const ID: &str = "id";
+const NAME: &str = "name";
+
+const TEXT_K: StringTag = key(TEXT);
+const EXTRA_K: StringTag = key(EXTRA);
+const CLICK_EVENT_K: StringTag = key(CLICK_EVENT);
+const HOVER_EVENT_K: StringTag = key(HOVER_EVENT);
+const ACTION_K: StringTag = key(ACTION);
+const VALUE_K: StringTag = key(VALUE);
+const DIALOG_K: StringTag = key(DIALOG);
+const SHOW_DIALOG_K: StringTag = key(SHOW_DIALOG);
+const ID_K: StringTag = key(ID);
+const NAME_K: StringTag = key(NAME);
+const URL_K: StringTag = key("url");
+const PATH_K: StringTag = key("path");
+const COMMAND_K: StringTag = key("command");
+const PAGE_K: StringTag = key("page");
+const COUNT_K: StringTag = key("count");
+const COMPONENTS_K: StringTag = key("components");
+const UUID_K: StringTag = key("uuid");
+
+impl TextComponent {
+    /// Writes the JSON form, collapsing unstyled literals to plain strings.
+    pub fn to_json(&self, w: &mut String) {
+        tag_to_json(&self.to_json_tag(), &component_boolean, w);
+    }
+
+    /// Parses the JSON form, including string and array shorthands and the
+    /// legacy `clickEvent` and `hoverEvent` objects.
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        Self::from_json_tag(json_to_tag(s)?)
+    }
+
+    /// Like [`to_json`](Self::to_json), for embedding in a larger document
+    /// written by [`tag_to_json`].
+    pub fn to_json_tag(&self) -> Tag {
+        match self.serialize() {
+            Tag::Compound(x) => compact(x),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Like [`from_json`](Self::from_json), for a value produced by
+    /// [`json_to_tag`].
+    pub fn from_json_tag(tag: Tag) -> Result<Self, Error> {
+        Self::deserialize(Tag::Compound(component(tag, MAX_DEPTH)?))
+    }
+}
+
+fn compact(c: CompoundTag) -> Tag {
+    if let [(k1, Tag::String(t)), (k2, Tag::String(_))] = c.as_ref()
+        && &**k1 == TYPE
+        && &**t == TEXT
+        && &**k2 == TEXT
+    {
+        let Some((_, Tag::String(text))) = c.into_inner().pop() else {
+            unreachable!()
+        };
+        return Tag::String(text);
+    }
+    let mut out = CompoundTag::with_capacity(c.len());
+    for (k, v) in c {
+        let value = match (&*k, v) {
+            (EXTRA | TRANSLATE_WITH, Tag::List(ListTag::Compound(list))) => {
+                let l = list.into_iter().map(compact).collect::<Vec<_>>();
+                if l.iter().all(|x| matches!(x, Tag::String(_))) {
+                    Tag::List(ListTag::String(
+                        l.into_iter()
+                            .map(|x| match x {
+                                Tag::String(s) => s,
+                                _ => unreachable!(),
+                            })
+                            .collect(),
+                    ))
+                } else {
+                    Tag::List(ListTag::Compound(
+                        l.into_iter()
+                            .map(|x| match x {
+                                Tag::Compound(y) => y,
+                                y => CompoundTag::from(alloc::vec![(StringTag::new(), y)]),
+                            })
+                            .collect(),
+                    ))
+                }
+            }
+            (SEPARATOR, Tag::Compound(x)) => compact(x),
+            (CLICK_EVENT, Tag::Compound(x)) => {
+                let mut event = CompoundTag::with_capacity(2);
+                for (ek, ev) in x {
+                    match (&*ek, ev) {
+                        (VALUE, Tag::Compound(value)) => {
+                            for (vk, vv) in value {
+                                let name = if &*vk == SHOW_DIALOG { DIALOG_K } else { vk };
+                                event.push(name, vv);
+                            }
+                        }
+                        (_, other) => event.push(ek, other),
+                    }
+                }
+                Tag::Compound(event)
+            }
+            (HOVER_EVENT, Tag::Compound(x)) => Tag::Compound(
+                x.into_iter()
+                    .map(|(hk, hv)| match (&*hk, hv) {
+                        (VALUE | NAME, Tag::Compound(y)) => (hk, compact(y)),
+                        (_, other) => (hk, other),
+                    })
+                    .collect(),
+            ),
+            (_, other) => other,
+        };
+        out.push(k, value);
+    }
+    Tag::Compound(out)
+}
+
+fn component(tag: Tag, depth: usize) -> Result<CompoundTag, Error> {
+    if depth == 0 {
+        return Err(Error::new(ErrorKind::TooLong));
+    }
+    match tag {
+        Tag::String(s) => Ok(literal(s)),
+        Tag::List(l) => {
+            let mut iter = list_items(l).into_iter();
+            let Some(first) = iter.next() else {
+                return Ok(literal(StringTag::new()));
+            };
+            let mut c = component(first, depth - 1)?;
+            if iter.len() != 0 {
+                let rest = iter
+                    .map(|x| component(x, depth - 1))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                match c.find_mut(EXTRA) {
+                    Some(Tag::List(ListTag::Compound(extra))) => extra.extend(rest),
+                    _ => c.push(EXTRA_K, Tag::List(ListTag::Compound(rest))),
+                }
+            }
+            Ok(c)
+        }
+        Tag::Compound(wrapped) => {
+            let c = match unwrap_mixed(Tag::Compound(wrapped)) {
+                Tag::Compound(x) => x,
+                x => return component(x, depth),
+            };
+            let mut out = CompoundTag::with_capacity(c.len());
+            for (k, v) in c {
+                match &*k {
+                    EXTRA => {
+                        let l = components(v, depth - 1, false)?;
+                        out.push(k, Tag::List(ListTag::Compound(l)));
+                    }
+                    TRANSLATE_WITH => {
+                        let l = components(v, depth - 1, true)?;
+                        out.push(k, Tag::List(ListTag::Compound(l)));
+                    }
+                    SEPARATOR => out.push(k, Tag::Compound(component(v, depth - 1)?)),
+                    CLICK_EVENT => out.push(CLICK_EVENT_K, click_event(v)?),
+                    LEGACY_CLICK_EVENT => out.push(CLICK_EVENT_K, legacy_click_event(v)?),
+                    HOVER_EVENT => out.push(HOVER_EVENT_K, hover_event(v, depth - 1)?),
+                    LEGACY_HOVER_EVENT => {
+                        out.push(HOVER_EVENT_K, legacy_hover_event(v, depth - 1)?)
+                    }
+                    _ => out.push(k, v),
+                }
+            }
+            Ok(out)
+        }
+        _ => Err(Error::new(ErrorKind::Invalid)),
+    }
+}
+
+/// Translation arguments may also be plain numbers or booleans, which are
+/// kept under an empty key like other elements of a mixed list.
+fn components(tag: Tag, depth: usize, args: bool) -> Result<Vec<CompoundTag>, Error> {
+    let Tag::List(l) = tag else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    list_items(l)
+        .into_iter()
+        .map(|x| match unwrap_mixed(x) {
+            y @ (Tag::Byte(_) | Tag::Int(_) | Tag::Long(_) | Tag::Double(_)) if args => {
+                Ok(CompoundTag::from(alloc::vec![(StringTag::new(), y)]))
+            }
+            y => component(y, depth),
+        })
+        .collect()
+}
+
+/// Unwraps an element of an array whose elements differ in type.
+fn unwrap_mixed(tag: Tag) -> Tag {
+    match tag {
+        Tag::Compound(c) if matches!(c.as_ref(), [(k, _)] if k.is_empty()) => {
+            match c.into_inner().pop() {
+                Some((_, v)) => v,
+                None => unreachable!(),
+            }
+        }
+        x => x,
+    }
+}
+
+fn literal(text: StringTag) -> CompoundTag {
+    CompoundTag::from(alloc::vec![(TEXT_K, Tag::String(text))])
+}
+
+/// Moves the inlined fields of a click event under `value`.
+fn click_event(tag: Tag) -> Result<Tag, Error> {
+    let Tag::Compound(c) = tag else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let mut action = None;
+    let mut value = CompoundTag::new();
+    for (k, v) in c {
+        match &*k {
+            ACTION => action = Some(v),
+            DIALOG => value.push(SHOW_DIALOG_K, v),
+            _ => value.push(k, v),
+        }
+    }
+    let Some(ty) = action else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    Ok(Tag::Compound(CompoundTag::from(alloc::vec![
+        (ACTION_K, ty),
+        (VALUE_K, Tag::Compound(value)),
+    ])))
+}
+
+fn legacy_click_event(tag: Tag) -> Result<Tag, Error> {
+    let Tag::Compound(mut c) = tag else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let (Some(Tag::String(action)), Some(value)) = (c.find_remove(ACTION), c.find_remove(VALUE))
+    else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let field = match &*action {
+        "open_url" => (URL_K, value),
+        "open_file" => (PATH_K, value),
+        "run_command" | "suggest_command" => (COMMAND_K, value),
+        "copy_to_clipboard" => (VALUE_K, value),
+        "change_page" => match value {
+            Tag::String(s) => match s.parse::<i32>() {
+                Ok(page) => (PAGE_K, Tag::Int(page)),
+                Err(_) => return Err(Error::new(ErrorKind::Invalid)),
+            },
+            page => (PAGE_K, page),
+        },
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    Ok(Tag::Compound(CompoundTag::from(alloc::vec![
+        (ACTION_K, Tag::String(action)),
+        (
+            VALUE_K,
+            Tag::Compound(CompoundTag::from(alloc::vec![field]))
+        ),
+    ])))
+}
+
+fn hover_event(tag: Tag, depth: usize) -> Result<Tag, Error> {
+    let Tag::Compound(c) = tag else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let mut out = CompoundTag::with_capacity(c.len());
+    for (k, v) in c {
+        match &*k {
+            VALUE | NAME => out.push(k, Tag::Compound(component(v, depth)?)),
+            _ => out.push(k, v),
+        }
+    }
+    Ok(Tag::Compound(out))
+}
+
+/// Converts `contents`, or the older stringified `value`, to the current
+/// inlined hover event.
+fn legacy_hover_event(tag: Tag, depth: usize) -> Result<Tag, Error> {
+    let Tag::Compound(mut c) = tag else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let Some(Tag::String(action)) = c.find_remove(ACTION) else {
+        return Err(Error::new(ErrorKind::Invalid));
+    };
+    let (contents, legacy) = match (c.find_remove(CONTENTS), c.find_remove(VALUE)) {
+        (Some(x), _) => (x, false),
+        (None, Some(x)) => (x, true),
+        (None, None) => return Err(Error::new(ErrorKind::Invalid)),
+    };
+    let mut out = CompoundTag::from(alloc::vec![(ACTION_K, Tag::String(action.clone()))]);
+    match &*action {
+        SHOW_TEXT => out.push(VALUE_K, Tag::Compound(component(contents, depth)?)),
+        SHOW_ITEM => {
+            let mut item = match contents {
+                Tag::String(id) if !legacy => {
+                    out.push(ID_K, Tag::String(id));
+                    return Ok(Tag::Compound(out));
+                }
+                Tag::String(snbt) if legacy => CompoundStringify::decode(&snbt)?.0,
+                Tag::Compound(x) => x,
+                _ => return Err(Error::new(ErrorKind::Invalid)),
+            };
+            let Some(id) = item.find_remove(ID) else {
+                return Err(Error::new(ErrorKind::Invalid));
+            };
+            out.push(ID_K, id);
+            if let Some(count) = item
+                .find_remove("count")
+                .or_else(|| item.find_remove("Count"))
+            {
+                out.push(COUNT_K, count);
+            }
+            if let Some(components) = item.find_remove("components") {
+                out.push(COMPONENTS_K, components);
+            }
+        }
+        SHOW_ENTITY => {
+            let mut entity = match contents {
+                Tag::String(snbt) if legacy => CompoundStringify::decode(&snbt)?.0,
+                Tag::Compound(x) => x,
+                _ => return Err(Error::new(ErrorKind::Invalid)),
+            };
+            let (Some(ty), Some(uuid)) = (entity.find_remove(TYPE), entity.find_remove(ID)) else {
+                return Err(Error::new(ErrorKind::Invalid));
+            };
+            out.push(ID_K, ty);
+            out.push(UUID_K, uuid);
+            match entity.find_remove(NAME) {
+                Some(Tag::String(json)) if legacy => {
+                    out.push(NAME_K, TextComponent::from_json(&json)?.serialize());
+                }
+                Some(name) => out.push(NAME_K, Tag::Compound(component(name, depth)?)),
+                None => {}
+            }
+        }
+        _ => return Err(Error::new(ErrorKind::Invalid)),
+    }
+    Ok(Tag::Compound(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::{Content, TranslationArg};
+    use crate::hover_event::HoverEvent;
+    use haya_nbt::Serialize;
+
+    #[test]
+    fn test_component_json() {
+        let legacy = r#"["",{"text":"Click","clickEvent":{"action":"change_page","value":"2"},"hoverEvent":{"action":"show_text","contents":["a",{"text":"b","bold":true}]}},{"translate":"chat.type.text","with":["Steve",7,true]}]"#;
+        let c = TextComponent::from_json(legacy).unwrap();
+        assert_eq!(c.siblings.len(), 2);
+        let click = &c.siblings[0];
+        assert!(click.style.click_event.is_some());
+        let Some(HoverEvent::Text(text)) = &click.style.hover_event else {
+            panic!()
+        };
+        assert_eq!(text.value.siblings.len(), 1);
+        let Content::Translatable { args, .. } = &c.siblings[1].content else {
+            panic!()
+        };
+        assert!(matches!(
+            args.as_slice(),
+            [
+                TranslationArg::Component(_),
+                TranslationArg::Int(7),
+                TranslationArg::Byte(1)
+            ]
+        ));
+
+        let mut json = String::new();
+        c.to_json(&mut json);
+        assert!(json.starts_with(r#"{"type":"text","text":"","extra":[{"#));
+        assert!(json.contains(r#""click_event":{"action":"change_page","page":2}"#));
+        assert!(json.contains(r#""with":["Steve",7,true]"#));
+        assert!(json.contains(r#""bold":true"#));
+        let mut again = String::new();
+        TextComponent::from_json(&json).unwrap().to_json(&mut again);
+        assert_eq!(again, json);
+
+        // Arguments survive the NBT form, where they are wrapped in compounds.
+        let nbt = TextComponent::deserialize(c.serialize()).unwrap();
+        let mut from_nbt = String::new();
+        nbt.to_json(&mut from_nbt);
+        assert_eq!(from_nbt, json);
+
+        let mut plain = String::new();
+        TextComponent::from_json(r#"{"text":"hi"}"#)
+            .unwrap()
+            .to_json(&mut plain);
+        assert_eq!(plain, r#""hi""#);
+        let entity = r#"{"text":"","hoverEvent":{"action":"show_entity","value":"{type:\"minecraft:pig\",id:\"069a79f4-44e9-4726-a5be-fca90e38aaf5\",name:\"{\\\"text\\\":\\\"Bob\\\"}\"}"}}"#;
+        let c = TextComponent::from_json(entity).unwrap();
+        assert!(matches!(c.style.hover_event, Some(HoverEvent::Entity(_))));
+        assert!(TextComponent::from_json(r#"{"text":1}"#).is_err());
+        assert!(TextComponent::from_json(r#"{"translate":"a","with":[[1]]}"#).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 #![no_std]
 #![warn(clippy::shadow_reuse, clippy::use_self)]
 
-use haya_nbt::StringTag;
+use alloc::vec::Vec;
+use haya_nbt::{ListTag, StringTag, Tag};
 
 extern crate alloc;
 
@@ -13,6 +14,7 @@ pub mod dialog;
 pub mod hover_event;
 pub mod json;
 pub mod profile;
+pub mod selector;
 
 const fn key(n: &'static str) -> StringTag {
     StringTag::from_ascii_nonzero(n.as_bytes()).unwrap()
@@ -25,11 +27,35 @@ fn capacity_fix(len: usize) -> usize {
 
 fn exact_one<T>(mut iter: alloc::vec::IntoIter<T>) -> Result<T, mser::Error> {
     if iter.len() != 1 {
-        Err(mser::Error)
+        Err(mser::Error::new(mser::ErrorKind::Invalid))
     } else {
         match iter.next() {
             Some(x) => Ok(x),
-            None => Err(mser::Error),
+            None => Err(mser::Error::new(mser::ErrorKind::Invalid)),
         }
     }
 }
+
+/// Splits a list into its elements.
+fn list_items(list: ListTag) -> Vec<Tag> {
+    macro_rules! items {
+        ($l:expr, $variant:ident) => {
+            $l.into_iter().map(Tag::$variant).collect()
+        };
+    }
+    match list {
+        ListTag::None => Vec::new(),
+        ListTag::Byte(l) => items!(l, Byte),
+        ListTag::Short(l) => items!(l, Short),
+        ListTag::Int(l) => items!(l, Int),
+        ListTag::Long(l) => items!(l, Long),
+        ListTag::Float(l) => items!(l, Float),
+        ListTag::Double(l) => items!(l, Double),
+        ListTag::String(l) => items!(l, String),
+        ListTag::ByteArray(l) => items!(l, ByteArray),
+        ListTag::IntArray(l) => items!(l, IntArray),
+        ListTag::LongArray(l) => items!(l, LongArray),
+        ListTag::List(l) => items!(l, List),
+        ListTag::Compound(l) => items!(l, Compound),
+    }
+}
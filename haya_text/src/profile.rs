@@ -3,7 +3,8 @@ use alloc::vec;
 use alloc::vec::Vec;
 use haya_ident::Identifier;
 use haya_nbt::{CompoundTag, Deserialize, ListTag, Serialize, StringTag, Tag};
-use mser::Error;
+use mser::{Error, ErrorKind};
+use mser_macro::{NbtDeserialize, NbtSerialize};
 use uuid::Uuid;
 
 const NAME: &str = "name";
@@ -23,15 +24,6 @@ const TEXTURE: &str = "texture";
 const CAPE: &str = "cape";
 const ELYTRA: &str = "elytra";
 const MODEL: &str = "model";
-const SLIM: &str = "slim";
-const WIDE: &str = "wide";
-
-const TEXTURE_K: StringTag = key(TEXTURE);
-const CAPE_K: StringTag = key(CAPE);
-const ELYTRA_K: StringTag = key(ELYTRA);
-const MODEL_K: StringTag = key(MODEL);
-const SLIM_K: StringTag = key(SLIM);
-const WIDE_K: StringTag = key(WIDE);
 
 #[derive(Clone)]
 pub struct ResolvableProfile {
@@ -46,7 +38,7 @@ impl Deserialize for ResolvableProfile {
         match nbt {
             Tag::String(s) => {
                 if !validate_name(&s) {
-                    return Err(Error);
+                    return Err(Error::new(ErrorKind::Invalid));
                 }
                 Ok(Self {
                     name: Some(s),
@@ -61,6 +53,7 @@ impl Deserialize for ResolvableProfile {
                 })
             }
             Tag::Compound(c) => {
+                let mut patch = CompoundTag::new();
                 let mut x = Self {
                     name: None,
                     id: None,
@@ -77,7 +70,7 @@ impl Deserialize for ResolvableProfile {
                         NAME => {
                             let name = StringTag::deserialize(v)?;
                             if !validate_name(&name) {
-                                return Err(Error);
+                                return Err(Error::new(ErrorKind::Invalid));
                             }
                             x.name = Some(name);
                         }
@@ -89,7 +82,7 @@ impl Deserialize for ResolvableProfile {
                             let props = match v {
                                 Tag::Compound(props) => {
                                     if props.len() > 16 {
-                                        return Err(Error);
+                                        return Err(Error::new(ErrorKind::Invalid));
                                     }
                                     let mut cap = 0;
                                     for (_, v) in props.iter() {
@@ -98,29 +91,33 @@ impl Deserialize for ResolvableProfile {
                                                 cap += s.len();
                                             }
                                             Tag::List(ListTag::None) => {}
-                                            _ => return Err(Error),
+                                            _ => return Err(Error::new(ErrorKind::Invalid)),
                                         }
                                     }
                                     if cap > 512 {
-                                        return Err(Error);
+                                        return Err(Error::new(ErrorKind::Invalid));
                                     }
                                     let mut vec = Vec::with_capacity(capacity_fix(cap));
                                     for (k, v) in props {
                                         if utf16(&k) > 64 {
-                                            return Err(Error);
+                                            return Err(Error::new(ErrorKind::Invalid));
                                         }
                                         match v {
                                             Tag::List(ListTag::String(s)) => {
                                                 if vec.len() == vec.capacity() {
-                                                    return Err(Error);
+                                                    return Err(Error::new(ErrorKind::Invalid));
                                                 }
                                                 if s.len() == 1 {
                                                     let tag = match s.into_iter().next() {
                                                         Some(x) => x,
-                                                        None => return Err(Error),
+                                                        None => {
+                                                            return Err(Error::new(
+                                                                ErrorKind::Invalid,
+                                                            ));
+                                                        }
                                                     };
                                                     if utf16(&tag) > 32767 {
-                                                        return Err(Error);
+                                                        return Err(Error::new(ErrorKind::Invalid));
                                                     }
                                                     vec.push(Property {
                                                         name: k,
@@ -130,7 +127,9 @@ impl Deserialize for ResolvableProfile {
                                                 } else {
                                                     for s in s {
                                                         if utf16(&s) > 32767 {
-                                                            return Err(Error);
+                                                            return Err(Error::new(
+                                                                ErrorKind::Invalid,
+                                                            ));
                                                         }
                                                         vec.push(Property {
                                                             name: k.clone(),
@@ -141,14 +140,14 @@ impl Deserialize for ResolvableProfile {
                                                 }
                                             }
                                             Tag::List(ListTag::None) => {}
-                                            _ => return Err(Error),
+                                            _ => return Err(Error::new(ErrorKind::Invalid)),
                                         }
                                     }
                                     vec
                                 }
                                 Tag::List(ListTag::Compound(props)) => {
                                     if props.len() > 16 {
-                                        return Err(Error);
+                                        return Err(Error::new(ErrorKind::Invalid));
                                     }
                                     let mut v = Vec::with_capacity(capacity_fix(props.len()));
                                     for c in props {
@@ -160,25 +159,25 @@ impl Deserialize for ResolvableProfile {
                                                 PROPERTY_NAME => {
                                                     let n = StringTag::deserialize(v)?;
                                                     if utf16(&n) > 64 {
-                                                        return Err(Error);
+                                                        return Err(Error::new(ErrorKind::Invalid));
                                                     }
                                                     name = Some(n);
                                                 }
                                                 PROPERTY_VALUE => {
                                                     let val = StringTag::deserialize(v)?;
                                                     if utf16(&val) > 32767 {
-                                                        return Err(Error);
+                                                        return Err(Error::new(ErrorKind::Invalid));
                                                     }
                                                     value = Some(val);
                                                 }
                                                 PROPERTY_SIGNATURE => {
                                                     let sig = StringTag::deserialize(v)?;
                                                     if utf16(&sig) > 1024 {
-                                                        return Err(Error);
+                                                        return Err(Error::new(ErrorKind::Invalid));
                                                     }
                                                     signature = Some(sig);
                                                 }
-                                                _ => return Err(Error),
+                                                _ => return Err(Error::new(ErrorKind::Invalid)),
                                             }
                                         }
                                         if let Some(name2) = name
@@ -190,41 +189,26 @@ impl Deserialize for ResolvableProfile {
                                                 signature,
                                             });
                                         } else {
-                                            return Err(Error);
+                                            return Err(Error::new(ErrorKind::Invalid));
                                         }
                                     }
                                     v
                                 }
                                 Tag::List(ListTag::None) => Vec::new(),
-                                _ => return Err(Error),
+                                _ => return Err(Error::new(ErrorKind::Invalid)),
                             };
                             x.properties = PropertyMap(props);
                         }
-                        TEXTURE => {
-                            x.skin_patch.texture = Some(Identifier::deserialize(v)?);
-                        }
-                        CAPE => {
-                            x.skin_patch.cape = Some(Identifier::deserialize(v)?);
-                        }
-                        ELYTRA => {
-                            x.skin_patch.elytra = Some(Identifier::deserialize(v)?);
-                        }
-                        MODEL => {
-                            let model = StringTag::deserialize(v)?;
-                            x.skin_patch.model = match &*model {
-                                SLIM => Some(PlayerModelType::Slim),
-                                WIDE => Some(PlayerModelType::Wide),
-                                _ => return Err(Error),
-                            };
-                        }
+                        TEXTURE | CAPE | ELYTRA | MODEL => patch.push(k, v),
                         _ => {
-                            return Err(Error);
+                            return Err(Error::new(ErrorKind::Invalid));
                         }
                     }
                 }
+                x.skin_patch = PlayerSkinPatch::deserialize(Tag::Compound(patch))?;
                 Ok(x)
             }
-            _ => Err(Error),
+            _ => Err(Error::new(ErrorKind::Invalid)),
         }
     }
 }
@@ -264,23 +248,8 @@ impl Serialize for ResolvableProfile {
             }
             c.push((PROPERTIES_K, Tag::List(ListTag::Compound(list))));
         }
-        if let Some(texture) = self.skin_patch.texture.as_ref() {
-            c.push((TEXTURE_K, texture.serialize()));
-        }
-        if let Some(cape) = self.skin_patch.cape.as_ref() {
-            c.push((CAPE_K, cape.serialize()));
-        }
-        if let Some(elytra) = self.skin_patch.elytra.as_ref() {
-            c.push((ELYTRA_K, elytra.serialize()));
-        }
-        if let Some(model) = self.skin_patch.model {
-            c.push((
-                MODEL_K,
-                match model {
-                    PlayerModelType::Slim => Tag::String(SLIM_K),
-                    PlayerModelType::Wide => Tag::String(WIDE_K),
-                },
-            ));
+        if let Tag::Compound(patch) = self.skin_patch.serialize() {
+            c.extend(patch);
         }
         Tag::Compound(CompoundTag::from(c))
     }
@@ -304,7 +273,7 @@ pub struct Property {
     pub signature: Option<StringTag>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, NbtSerialize, NbtDeserialize)]
 pub struct PlayerSkinPatch {
     pub texture: Option<Identifier>,
     pub cape: Option<Identifier>,
@@ -312,7 +281,7 @@ pub struct PlayerSkinPatch {
     pub model: Option<PlayerModelType>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, NbtSerialize, NbtDeserialize)]
 pub enum PlayerModelType {
     Slim,
     Wide,
@@ -0,0 +1,924 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use haya_ident::{Ident, parse_ident};
+use haya_nbt::{CompoundStringify, CompoundTag};
+use uuid::Uuid;
+
+/// An entity argument: a player name, a UUID or an `@` selector.
+#[derive(Clone, Debug)]
+pub enum EntitySelector<'a> {
+    Player(&'a str),
+    Uuid(Uuid),
+    Select(Selector<'a>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Selector<'a> {
+    pub variable: Variable,
+    pub options: Vec<SelectorOption<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable {
+    /// `@p`
+    NearestPlayer,
+    /// `@a`
+    AllPlayers,
+    /// `@r`
+    RandomPlayer,
+    /// `@s`
+    This,
+    /// `@e`
+    AllEntities,
+    /// `@n`
+    NearestEntity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sort {
+    Nearest,
+    Furthest,
+    Random,
+    Arbitrary,
+}
+
+/// A bound such as `3`, `1..`, `..5` or `1..5`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Range<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: PartialOrd + Copy> Range<T> {
+    pub fn contains(&self, v: T) -> bool {
+        self.min.is_none_or(|min| v >= min) && self.max.is_none_or(|max| v <= max)
+    }
+}
+
+impl Range<f32> {
+    /// Tests an angle against a range of angles, as `WrappedMinMaxBounds`
+    /// does. The bounds default to `0` and `359`, and a range whose min is
+    /// above its max wraps around `180`.
+    pub fn contains_angle(&self, v: f32) -> bool {
+        let min = wrap_degrees(self.min.unwrap_or(0.0));
+        let max = wrap_degrees(self.max.unwrap_or(359.0));
+        let x = wrap_degrees(v);
+        if min > max {
+            x >= min || x <= max
+        } else {
+            x >= min && x <= max
+        }
+    }
+}
+
+/// Wraps an angle in degrees to `[-180, 180)`.
+fn wrap_degrees(v: f32) -> f32 {
+    let x = v % 360.0;
+    if x >= 180.0 {
+        x - 360.0
+    } else if x < -180.0 {
+        x + 360.0
+    } else {
+        x
+    }
+}
+
+/// A filter value preceded by an optional `!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Negatable<T> {
+    pub negated: bool,
+    pub value: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntityType<'a> {
+    /// Whether `id` names a tag written as `#id`.
+    pub tag: bool,
+    pub id: Ident<'a>,
+}
+
+#[derive(Clone, Debug)]
+pub enum SelectorOption<'a> {
+    X(f64),
+    Y(f64),
+    Z(f64),
+    Dx(f64),
+    Dy(f64),
+    Dz(f64),
+    Distance(Range<f64>),
+    XRotation(Range<f32>),
+    YRotation(Range<f32>),
+    Level(Range<i32>),
+    Limit(u32),
+    Sort(Sort),
+    Name(Negatable<Cow<'a, str>>),
+    Type(Negatable<EntityType<'a>>),
+    Tag(Negatable<Cow<'a, str>>),
+    Team(Negatable<Cow<'a, str>>),
+    Gamemode(Negatable<&'a str>),
+    Predicate(Negatable<Ident<'a>>),
+    Nbt(Negatable<CompoundTag>),
+    Scores(Vec<(Cow<'a, str>, Range<i32>)>),
+    /// The unparsed `{...}` of `advancements`.
+    Advancements(&'a str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectorErrorKind {
+    InvalidName,
+    UnknownVariable,
+    UnknownOption,
+    ExpectedValue,
+    ExpectedEnd,
+    InvalidNumber,
+    InvalidRange,
+    InvalidSort,
+    InvalidId,
+    InvalidNbt,
+    UnclosedQuote,
+    /// `@s` with `limit` or `sort`.
+    NotAllowed,
+}
+
+/// A parse failure at a byte offset of the selector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectorError {
+    pub kind: SelectorErrorKind,
+    pub cursor: usize,
+}
+
+struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    const fn error(&self, kind: SelectorErrorKind) -> SelectorError {
+        SelectorError {
+            kind,
+            cursor: self.pos,
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn read_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn read_string(&mut self) -> Result<Cow<'a, str>, SelectorError> {
+        let Some(quote @ (b'"' | b'\'')) = self.peek() else {
+            return Ok(Cow::Borrowed(self.read_while(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b'+')
+            })));
+        };
+        self.pos += 1;
+        let mut out = String::new();
+        let mut escaped = false;
+        let mut run = self.pos;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error(SelectorErrorKind::UnclosedQuote));
+            };
+            if c == quote {
+                let tail = &self.input[run..self.pos];
+                self.pos += 1;
+                if !escaped {
+                    return Ok(Cow::Borrowed(tail));
+                }
+                out.push_str(tail);
+                return Ok(Cow::Owned(out));
+            }
+            if c == b'\\'
+                && matches!(self.input.as_bytes().get(self.pos + 1), Some(&n) if n == quote || n == b'\\')
+            {
+                out.push_str(&self.input[run..self.pos]);
+                escaped = true;
+                self.pos += 1;
+                run = self.pos;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn read_number<T: core::str::FromStr>(&mut self) -> Result<T, SelectorError> {
+        let start = self.pos;
+        let s = self.read_while(|c| c.is_ascii_digit() || c == b'-' || c == b'.');
+        s.parse().map_err(|_| {
+            self.pos = start;
+            self.error(SelectorErrorKind::InvalidNumber)
+        })
+    }
+
+    /// Reads a range of angles wrapped to `[-180, 180)`. Its min may be
+    /// above its max.
+    fn read_angles(&mut self) -> Result<Range<f32>, SelectorError> {
+        let range: Range<f32> = self.read_bounds()?;
+        Ok(Range {
+            min: range.min.map(wrap_degrees),
+            max: range.max.map(wrap_degrees),
+        })
+    }
+
+    fn read_range<T: core::str::FromStr + PartialOrd + Copy>(
+        &mut self,
+    ) -> Result<Range<T>, SelectorError> {
+        let start = self.pos;
+        let range = self.read_bounds()?;
+        if let (Some(a), Some(b)) = (range.min, range.max)
+            && a > b
+        {
+            self.pos = start;
+            return Err(self.error(SelectorErrorKind::InvalidRange));
+        }
+        Ok(range)
+    }
+
+    fn read_bounds<T: core::str::FromStr + Copy>(&mut self) -> Result<Range<T>, SelectorError> {
+        let start = self.pos;
+        let s = self.read_while(|c| c.is_ascii_digit() || c == b'-' || c == b'.');
+        let parse = |x: &str| match x {
+            "" => Ok(None),
+            _ => x.parse().map(Some),
+        };
+        let range = match s.split_once("..") {
+            Some((min, max)) => parse(min).and_then(|a| Ok((a, parse(max)?))),
+            None => parse(s).map(|a| (a, a)),
+        };
+        match range {
+            Ok((min, max)) if min.is_some() || max.is_some() => Ok(Range { min, max }),
+            _ => {
+                self.pos = start;
+                Err(self.error(SelectorErrorKind::InvalidRange))
+            }
+        }
+    }
+
+    fn read_ident(&mut self) -> Result<Ident<'a>, SelectorError> {
+        let start = self.pos;
+        let s = self.read_while(|c| {
+            c.is_ascii_digit()
+                || c.is_ascii_lowercase()
+                || matches!(c, b'_' | b'-' | b'.' | b'/' | b':')
+        });
+        parse_ident(s.as_bytes()).ok_or(SelectorError {
+            kind: SelectorErrorKind::InvalidId,
+            cursor: start,
+        })
+    }
+
+    /// Reads a `{...}` block, skipping quoted strings.
+    fn read_braces(&mut self) -> Result<&'a str, SelectorError> {
+        let start = self.pos;
+        if self.peek() != Some(b'{') {
+            return Err(self.error(SelectorErrorKind::ExpectedValue));
+        }
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                b'"' | b'\'' => {
+                    self.read_string()?;
+                    continue;
+                }
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += 1;
+                        return Ok(&self.input[start..self.pos]);
+                    }
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Err(self.error(SelectorErrorKind::ExpectedEnd))
+    }
+
+    fn read_option(&mut self, key: &str) -> Result<SelectorOption<'a>, SelectorError> {
+        let negated = matches!(
+            key,
+            "name" | "type" | "tag" | "team" | "gamemode" | "predicate" | "nbt"
+        ) && self.eat(b'!');
+        Ok(match key {
+            "x" => SelectorOption::X(self.read_number()?),
+            "y" => SelectorOption::Y(self.read_number()?),
+            "z" => SelectorOption::Z(self.read_number()?),
+            "dx" => SelectorOption::Dx(self.read_number()?),
+            "dy" => SelectorOption::Dy(self.read_number()?),
+            "dz" => SelectorOption::Dz(self.read_number()?),
+            "distance" => {
+                let start = self.pos;
+                let range: Range<f64> = self.read_range()?;
+                if range.min.is_some_and(|x| x < 0.0) || range.max.is_some_and(|x| x < 0.0) {
+                    self.pos = start;
+                    return Err(self.error(SelectorErrorKind::InvalidRange));
+                }
+                SelectorOption::Distance(range)
+            }
+            "x_rotation" => SelectorOption::XRotation(self.read_angles()?),
+            "y_rotation" => SelectorOption::YRotation(self.read_angles()?),
+            "level" => SelectorOption::Level(self.read_range()?),
+            "limit" => {
+                let start = self.pos;
+                match self.read_number()? {
+                    0 => {
+                        self.pos = start;
+                        return Err(self.error(SelectorErrorKind::InvalidNumber));
+                    }
+                    n => SelectorOption::Limit(n),
+                }
+            }
+            "sort" => {
+                let start = self.pos;
+                SelectorOption::Sort(match self.read_while(|c| c.is_ascii_lowercase()) {
+                    "nearest" => Sort::Nearest,
+                    "furthest" => Sort::Furthest,
+                    "random" => Sort::Random,
+                    "arbitrary" => Sort::Arbitrary,
+                    _ => {
+                        self.pos = start;
+                        return Err(self.error(SelectorErrorKind::InvalidSort));
+                    }
+                })
+            }
+            "name" => SelectorOption::Name(Negatable {
+                negated,
+                value: self.read_string()?,
+            }),
+            "tag" => SelectorOption::Tag(Negatable {
+                negated,
+                value: self.read_string()?,
+            }),
+            "team" => SelectorOption::Team(Negatable {
+                negated,
+                value: self.read_string()?,
+            }),
+            "gamemode" => SelectorOption::Gamemode(Negatable {
+                negated,
+                value: self.read_while(|c| c.is_ascii_lowercase()),
+            }),
+            "type" => {
+                let tag = self.eat(b'#');
+                SelectorOption::Type(Negatable {
+                    negated,
+                    value: EntityType {
+                        tag,
+                        id: self.read_ident()?,
+                    },
+                })
+            }
+            "predicate" => SelectorOption::Predicate(Negatable {
+                negated,
+                value: self.read_ident()?,
+            }),
+            "nbt" => {
+                let start = self.pos;
+                let snbt = self.read_braces()?;
+                match CompoundStringify::decode(snbt) {
+                    Ok(nbt) => SelectorOption::Nbt(Negatable {
+                        negated,
+                        value: nbt.0,
+                    }),
+                    Err(_) => {
+                        self.pos = start;
+                        return Err(self.error(SelectorErrorKind::InvalidNbt));
+                    }
+                }
+            }
+            "scores" => {
+                if !self.eat(b'{') {
+                    return Err(self.error(SelectorErrorKind::ExpectedValue));
+                }
+                let mut scores = Vec::new();
+                self.skip_ws();
+                while !self.eat(b'}') {
+                    let objective = self.read_string()?;
+                    self.skip_ws();
+                    if !self.eat(b'=') {
+                        return Err(self.error(SelectorErrorKind::ExpectedValue));
+                    }
+                    self.skip_ws();
+                    scores.push((objective, self.read_range()?));
+                    self.skip_ws();
+                    if !self.eat(b',') && self.peek() != Some(b'}') {
+                        return Err(self.error(SelectorErrorKind::ExpectedEnd));
+                    }
+                    self.skip_ws();
+                }
+                SelectorOption::Scores(scores)
+            }
+            "advancements" => SelectorOption::Advancements(self.read_braces()?),
+            _ => return Err(self.error(SelectorErrorKind::UnknownOption)),
+        })
+    }
+}
+
+impl<'a> EntitySelector<'a> {
+    /// Parses a selector at the start of `input`, returning it with the
+    /// number of bytes read.
+    pub fn parse_prefix(input: &'a str) -> Result<(Self, usize), SelectorError> {
+        let mut cursor = Cursor { input, pos: 0 };
+        if !cursor.eat(b'@') {
+            let name = cursor.read_while(|c| c != b' ');
+            if let Ok(uuid) = Uuid::try_parse(name) {
+                return Ok((Self::Uuid(uuid), cursor.pos));
+            }
+            if name.is_empty() || name.len() > 16 {
+                return Err(SelectorError {
+                    kind: SelectorErrorKind::InvalidName,
+                    cursor: 0,
+                });
+            }
+            return Ok((Self::Player(name), cursor.pos));
+        }
+        let variable = match cursor.peek() {
+            Some(b'p') => Variable::NearestPlayer,
+            Some(b'a') => Variable::AllPlayers,
+            Some(b'r') => Variable::RandomPlayer,
+            Some(b's') => Variable::This,
+            Some(b'e') => Variable::AllEntities,
+            Some(b'n') => Variable::NearestEntity,
+            _ => return Err(cursor.error(SelectorErrorKind::UnknownVariable)),
+        };
+        cursor.pos += 1;
+        let mut options = Vec::new();
+        if cursor.eat(b'[') {
+            cursor.skip_ws();
+            while !cursor.eat(b']') {
+                let start = cursor.pos;
+                let key = cursor.read_while(|c| c.is_ascii_lowercase() || c == b'_');
+                cursor.skip_ws();
+                if key.is_empty() || !cursor.eat(b'=') {
+                    return Err(cursor.error(SelectorErrorKind::ExpectedValue));
+                }
+                cursor.skip_ws();
+                if variable == Variable::This && matches!(key, "limit" | "sort") {
+                    cursor.pos = start;
+                    return Err(cursor.error(SelectorErrorKind::NotAllowed));
+                }
+                match cursor.read_option(key) {
+                    Ok(option) => options.push(option),
+                    Err(e) if e.kind == SelectorErrorKind::UnknownOption => {
+                        return Err(SelectorError {
+                            kind: e.kind,
+                            cursor: start,
+                        });
+                    }
+                    Err(e) => return Err(e),
+                }
+                cursor.skip_ws();
+                if !cursor.eat(b',') && cursor.peek() != Some(b']') {
+                    return Err(cursor.error(SelectorErrorKind::ExpectedEnd));
+                }
+                cursor.skip_ws();
+            }
+        }
+        Ok((Self::Select(Selector { variable, options }), cursor.pos))
+    }
+
+    /// Parses a whole selector, such as the pattern of a selector
+    /// component.
+    pub fn parse(input: &'a str) -> Result<Self, SelectorError> {
+        let (selector, len) = Self::parse_prefix(input)?;
+        if len != input.len() {
+            return Err(SelectorError {
+                kind: SelectorErrorKind::ExpectedEnd,
+                cursor: len,
+            });
+        }
+        Ok(selector)
+    }
+
+    /// Most entities this can select.
+    pub fn max_results(&self) -> u32 {
+        match self {
+            Self::Player(_) | Self::Uuid(_) => 1,
+            Self::Select(s) => s.limit(),
+        }
+    }
+
+    /// Whether only players can be selected.
+    pub fn players_only(&self) -> bool {
+        match self {
+            Self::Player(_) => true,
+            Self::Uuid(_) => false,
+            Self::Select(s) => s.players_only(),
+        }
+    }
+
+    /// Resolves the selected entities in `world`.
+    pub fn select<W: SelectorContext>(&self, world: &mut W) -> Vec<W::Entity> {
+        match self {
+            Self::Player(name) => world.player_by_name(name).into_iter().collect(),
+            Self::Uuid(uuid) => world.entity_by_uuid(*uuid).into_iter().collect(),
+            Self::Select(s) => s.select(world),
+        }
+    }
+}
+
+impl Selector<'_> {
+    pub fn limit(&self) -> u32 {
+        for option in &self.options {
+            if let SelectorOption::Limit(n) = option {
+                return *n;
+            }
+        }
+        match self.variable {
+            Variable::NearestPlayer
+            | Variable::RandomPlayer
+            | Variable::This
+            | Variable::NearestEntity => 1,
+            Variable::AllPlayers | Variable::AllEntities => u32::MAX,
+        }
+    }
+
+    pub fn sort(&self) -> Sort {
+        for option in &self.options {
+            if let SelectorOption::Sort(s) = option {
+                return *s;
+            }
+        }
+        match self.variable {
+            Variable::NearestPlayer | Variable::NearestEntity => Sort::Nearest,
+            Variable::RandomPlayer => Sort::Random,
+            Variable::AllPlayers | Variable::This | Variable::AllEntities => Sort::Arbitrary,
+        }
+    }
+
+    pub fn players_only(&self) -> bool {
+        match self.variable {
+            Variable::NearestPlayer | Variable::AllPlayers | Variable::RandomPlayer => true,
+            Variable::This | Variable::AllEntities | Variable::NearestEntity => {
+                self.options.iter().any(|o| {
+                    matches!(o, SelectorOption::Type(Negatable { negated: false, value })
+                        if !value.tag && value.id.namespace().is_none() && value.id.path() == "player")
+                })
+            }
+        }
+    }
+
+    /// Resolves the selected entities in `world`.
+    pub fn select<W: SelectorContext>(&self, world: &mut W) -> Vec<W::Entity> {
+        let mut origin = world.origin();
+        let mut volume = [None; 3];
+        for option in &self.options {
+            match *option {
+                SelectorOption::X(v) => origin[0] = v,
+                SelectorOption::Y(v) => origin[1] = v,
+                SelectorOption::Z(v) => origin[2] = v,
+                SelectorOption::Dx(v) => volume[0] = Some(v),
+                SelectorOption::Dy(v) => volume[1] = Some(v),
+                SelectorOption::Dz(v) => volume[2] = Some(v),
+                _ => {}
+            }
+        }
+        let players_only = self.players_only();
+        let mut found: Vec<(W::Entity, f64)> = Vec::new();
+        let mut candidates = Vec::new();
+        if self.variable == Variable::This {
+            candidates.extend(world.source());
+        } else {
+            world.entities(players_only, &mut candidates);
+        }
+        for entity in candidates {
+            if players_only && !world.is_player(entity) {
+                continue;
+            }
+            let pos = world.position(entity);
+            if volume.iter().any(Option::is_some) {
+                let inside = (0..3).all(|i| {
+                    let d = volume[i].unwrap_or(0.0);
+                    let (min, max) = if d < 0.0 {
+                        (origin[i] + d, origin[i])
+                    } else {
+                        (origin[i], origin[i] + d)
+                    };
+                    pos[i] >= min && pos[i] <= max + 1.0
+                });
+                if !inside {
+                    continue;
+                }
+            }
+            let dist2 = (0..3)
+                .map(|i| (pos[i] - origin[i]) * (pos[i] - origin[i]))
+                .sum();
+            if self
+                .options
+                .iter()
+                .all(|o| matches(world, entity, dist2, o))
+            {
+                found.push((entity, dist2));
+            }
+        }
+        match self.sort() {
+            Sort::Nearest => found.sort_by(|a, b| a.1.total_cmp(&b.1)),
+            Sort::Furthest => found.sort_by(|a, b| b.1.total_cmp(&a.1)),
+            Sort::Random => {
+                for i in (1..found.len()).rev() {
+                    let j = (world.random() % (i as u64 + 1)) as usize;
+                    found.swap(i, j);
+                }
+            }
+            Sort::Arbitrary => {}
+        }
+        found.truncate(self.limit() as usize);
+        found.into_iter().map(|x| x.0).collect()
+    }
+}
+
+fn matches<W: SelectorContext>(
+    world: &W,
+    entity: W::Entity,
+    dist2: f64,
+    option: &SelectorOption,
+) -> bool {
+    let neg = |n: bool, v: bool| v != n;
+    match option {
+        SelectorOption::Distance(r) => {
+            r.min.is_none_or(|min| dist2 >= min * min) && r.max.is_none_or(|max| dist2 <= max * max)
+        }
+        SelectorOption::XRotation(r) => r.contains_angle(world.rotation(entity)[1]),
+        SelectorOption::YRotation(r) => r.contains_angle(world.rotation(entity)[0]),
+        SelectorOption::Level(r) => world.level(entity).is_some_and(|l| r.contains(l)),
+        SelectorOption::Name(n) => neg(n.negated, world.name(entity) == Some(&*n.value)),
+        SelectorOption::Type(n) => neg(n.negated, world.has_type(entity, n.value)),
+        SelectorOption::Tag(n) => {
+            // `tag=` selects entities without tags.
+            let found = world.has_tag(entity, &n.value);
+            neg(n.negated, found != n.value.is_empty())
+        }
+        SelectorOption::Team(n) => neg(
+            n.negated,
+            match world.team(entity) {
+                Some(team) => team == &*n.value,
+                None => n.value.is_empty(),
+            },
+        ),
+        SelectorOption::Gamemode(n) => match world.gamemode(entity) {
+            Some(mode) => neg(n.negated, mode == n.value),
+            None => false,
+        },
+        SelectorOption::Predicate(n) => neg(n.negated, world.test_predicate(entity, n.value)),
+        SelectorOption::Nbt(n) => neg(n.negated, world.matches_nbt(entity, &n.value)),
+        SelectorOption::Scores(scores) => scores.iter().all(|(objective, range)| {
+            world
+                .score(entity, objective)
+                .is_some_and(|s| range.contains(s))
+        }),
+        SelectorOption::Advancements(a) => world.matches_advancements(entity, a),
+        _ => true,
+    }
+}
+
+/// The world a selector is resolved in, implemented by the host.
+pub trait SelectorContext {
+    type Entity: Copy;
+
+    /// The entity running the command, selected by `@s`.
+    fn source(&self) -> Option<Self::Entity>;
+    /// Position the distance and volume options are measured from.
+    fn origin(&self) -> [f64; 3];
+    /// Appends every loaded entity, or only players if `players_only`.
+    fn entities(&self, players_only: bool, out: &mut Vec<Self::Entity>);
+    fn player_by_name(&self, name: &str) -> Option<Self::Entity>;
+    fn entity_by_uuid(&self, uuid: Uuid) -> Option<Self::Entity>;
+    fn is_player(&self, entity: Self::Entity) -> bool;
+    fn position(&self, entity: Self::Entity) -> [f64; 3];
+    /// Yaw and pitch in degrees.
+    fn rotation(&self, entity: Self::Entity) -> [f32; 2];
+    fn name(&self, entity: Self::Entity) -> Option<&str>;
+    /// Whether the entity has the type or is in the entity type tag.
+    fn has_type(&self, entity: Self::Entity, ty: EntityType) -> bool;
+    /// Whether the entity has `tag`, or any tag when `tag` is empty.
+    fn has_tag(&self, entity: Self::Entity, tag: &str) -> bool;
+    fn team(&self, entity: Self::Entity) -> Option<&str>;
+    fn score(&self, entity: Self::Entity, objective: &str) -> Option<i32>;
+    /// A uniformly distributed number for `@r` and `sort=random`.
+    fn random(&mut self) -> u64;
+
+    /// Experience level of a player.
+    fn level(&self, entity: Self::Entity) -> Option<i32> {
+        let _ = entity;
+        None
+    }
+
+    /// Game mode name of a player, such as `creative`.
+    fn gamemode(&self, entity: Self::Entity) -> Option<&str> {
+        let _ = entity;
+        None
+    }
+
+    fn test_predicate(&self, entity: Self::Entity, predicate: Ident) -> bool {
+        let _ = (entity, predicate);
+        false
+    }
+
+    fn matches_nbt(&self, entity: Self::Entity, nbt: &CompoundTag) -> bool {
+        let _ = (entity, nbt);
+        false
+    }
+
+    fn matches_advancements(&self, entity: Self::Entity, advancements: &str) -> bool {
+        let _ = (entity, advancements);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use haya_nbt::StringTag;
+
+    #[test]
+    fn test_entity_selector() {
+        struct Mob {
+            name: &'static str,
+            player: bool,
+            pos: [f64; 3],
+            tags: &'static [&'static str],
+            score: Option<i32>,
+            yaw: f32,
+        }
+
+        struct World {
+            mobs: Vec<Mob>,
+            seed: u64,
+        }
+
+        impl SelectorContext for World {
+            type Entity = usize;
+
+            fn source(&self) -> Option<usize> {
+                Some(0)
+            }
+            fn origin(&self) -> [f64; 3] {
+                [0.0; 3]
+            }
+            fn entities(&self, players_only: bool, out: &mut Vec<usize>) {
+                out.extend((0..self.mobs.len()).filter(|&i| !players_only || self.mobs[i].player));
+            }
+            fn player_by_name(&self, name: &str) -> Option<usize> {
+                self.mobs.iter().position(|m| m.player && m.name == name)
+            }
+            fn entity_by_uuid(&self, _: Uuid) -> Option<usize> {
+                None
+            }
+            fn is_player(&self, entity: usize) -> bool {
+                self.mobs[entity].player
+            }
+            fn position(&self, entity: usize) -> [f64; 3] {
+                self.mobs[entity].pos
+            }
+            fn rotation(&self, entity: usize) -> [f32; 2] {
+                [self.mobs[entity].yaw, 0.0]
+            }
+            fn name(&self, entity: usize) -> Option<&str> {
+                Some(self.mobs[entity].name)
+            }
+            fn has_type(&self, entity: usize, ty: EntityType) -> bool {
+                let player = self.mobs[entity].player;
+                !ty.tag && ty.id.path() == if player { "player" } else { "zombie" }
+            }
+            fn has_tag(&self, entity: usize, tag: &str) -> bool {
+                let tags = self.mobs[entity].tags;
+                if tag.is_empty() {
+                    !tags.is_empty()
+                } else {
+                    tags.contains(&tag)
+                }
+            }
+            fn team(&self, _: usize) -> Option<&str> {
+                None
+            }
+            fn score(&self, entity: usize, objective: &str) -> Option<i32> {
+                self.mobs[entity].score.filter(|_| objective == "kills")
+            }
+            fn random(&mut self) -> u64 {
+                self.seed = self.seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                self.seed >> 33
+            }
+        }
+
+        let mob = |name, player, x, tags, score| Mob {
+            name,
+            player,
+            pos: [x, 0.0, 0.0],
+            tags,
+            score,
+            yaw: 0.0,
+        };
+        let mut world = World {
+            mobs: vec![
+                mob("Steve", true, 0.0, &[], Some(3)),
+                mob("Alex", true, 4.0, &["red"], None),
+                mob("z1", false, 2.0, &["red"], Some(10)),
+                mob("z2", false, 9.0, &[], None),
+                mob("Notch", true, 20.0, &["blue"], Some(1)),
+            ],
+            seed: 1,
+        };
+
+        world.mobs[3].yaw = 190.0;
+        world.mobs[4].yaw = 175.0;
+
+        let select = |world: &mut World, s| EntitySelector::parse(s).unwrap().select(world);
+        assert_eq!(select(&mut world, "@s"), [0]);
+        assert_eq!(select(&mut world, "@a"), [0, 1, 4]);
+        assert_eq!(
+            select(&mut world, "@e[distance=1..,sort=nearest]"),
+            [2, 1, 3, 4]
+        );
+        assert_eq!(
+            select(
+                &mut world,
+                "@e[type=!minecraft:zombie,sort=furthest,limit=2]"
+            ),
+            [4, 1]
+        );
+        assert_eq!(select(&mut world, "@e[tag=red]"), [1, 2]);
+        assert_eq!(select(&mut world, "@e[tag=]"), [0, 3]);
+        assert_eq!(select(&mut world, "@e[tag=!red, tag=!]"), [4]);
+        assert_eq!(select(&mut world, "@e[scores={kills=..5}]"), [0, 4]);
+        assert_eq!(select(&mut world, "@n[type=zombie]"), [2]);
+        assert_eq!(select(&mut world, "@e[x=10,dx=-3]"), [3]);
+        assert_eq!(select(&mut world, "@a[name=\"Alex\"]"), [1]);
+        assert_eq!(select(&mut world, "Notch"), [4]);
+        assert_eq!(select(&mut world, "@r").len(), 1);
+        assert!(select(&mut world, "@e[nbt={a:1b}]").is_empty());
+        assert_eq!(select(&mut world, "@e[y_rotation=170..-170]"), [3, 4]);
+        assert_eq!(select(&mut world, "@e[y_rotation=-185]"), [4]);
+        assert_eq!(select(&mut world, "@e[y_rotation=..10]"), [0, 1, 2]);
+        assert_eq!(select(&mut world, "@e[x_rotation=-90..90]").len(), 5);
+
+        let Ok(EntitySelector::Select(p)) =
+            EntitySelector::parse("@p[distance=..5,type=!minecraft:zombie,limit=3,sort=nearest]")
+        else {
+            panic!()
+        };
+        assert_eq!((p.limit(), p.sort()), (3, Sort::Nearest));
+        assert!(matches!(
+            p.options[0],
+            SelectorOption::Distance(r) if r.min.is_none() && r.max == Some(5.0)
+        ));
+        assert!(matches!(&p.options[1], SelectorOption::Type(t) if t.negated));
+        let Ok(EntitySelector::Select(p)) = EntitySelector::parse("@e[x_rotation=190..540]") else {
+            panic!()
+        };
+        assert!(matches!(
+            p.options[0],
+            SelectorOption::XRotation(r) if r.min == Some(-170.0) && r.max == Some(-180.0)
+        ));
+        assert!(matches!(
+            EntitySelector::parse("069a79f4-44e9-4726-a5be-fca90e38aaf5"),
+            Ok(EntitySelector::Uuid(_))
+        ));
+
+        let err = |s| EntitySelector::parse(s).unwrap_err();
+        assert_eq!(err("@x").kind, SelectorErrorKind::UnknownVariable);
+        assert_eq!(err("@e[foo=1]").kind, SelectorErrorKind::UnknownOption);
+        assert_eq!(err("@e[foo=1]").cursor, 3);
+        assert_eq!(
+            err("@e[distance=5..1]").kind,
+            SelectorErrorKind::InvalidRange
+        );
+        assert_eq!(err("@e[limit=0]").kind, SelectorErrorKind::InvalidNumber);
+        assert_eq!(err("@s[limit=2]").kind, SelectorErrorKind::NotAllowed);
+        assert_eq!(err("@e[sort=up]").kind, SelectorErrorKind::InvalidSort);
+        assert_eq!(err("@e[nbt={a}]").kind, SelectorErrorKind::InvalidNbt);
+        assert_eq!(err("@e[tag=a").kind, SelectorErrorKind::ExpectedEnd);
+
+        let component = crate::chat::TextComponent {
+            content: crate::chat::Content::Selector {
+                pattern: StringTag::from_utf8("@a[limit=1]"),
+                separator: None,
+            },
+            style: Default::default(),
+            siblings: Vec::new(),
+        };
+        let selector = component.content.selector().unwrap().unwrap();
+        assert_eq!(selector.select(&mut world), [0]);
+    }
+}